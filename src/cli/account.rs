@@ -1,9 +1,26 @@
-use crate::{cmd, context::CommandExecutionContext};
+use crate::{
+    cmd::{
+        self,
+        account::{
+            BalanceAcrossTags, MappingStorageEntry, ProxyStorageEntry, StorageValue,
+            StorageValueType, ACCOUNT_QUERY_CONCURRENCY, BALANCE_SNAPSHOT_TAGS,
+        },
+        lookup::{Lookup, Missing},
+        utils::MappingKey,
+    },
+    context::CommandExecutionContext,
+};
 
-use super::common::{GetAccountArgs, GetBlockByIdArgs, NoArgs};
-use clap::{command, Args, Parser, Subcommand};
-use ethers::types::{Bytes, H256, U256};
+use super::common::{
+    parse_lookup, parse_not_found, GetAccountArgs, GetBlockByIdArgs, NoArgs, NotFoundResult,
+    GET_BLOCK_BY_ID_ARG_GROUP_NAME,
+};
+use std::collections::BTreeMap;
+
+use clap::{builder::PossibleValue, Args, Parser, Subcommand, ValueEnum};
+use ethers::types::{Address, BlockId, BlockNumber, Bytes, NameOrAddress, H256, U256};
 use serde::Serialize;
+use thiserror::Error;
 
 #[derive(Parser, Debug)]
 #[command()]
@@ -14,23 +31,142 @@ pub struct AccountCommand {
     #[clap(flatten)]
     get_block_by_id: GetBlockByIdArgs,
 
+    /// Targets the pending state of the account, same as --tag pending
+    #[arg(long, conflicts_with = GET_BLOCK_BY_ID_ARG_GROUP_NAME)]
+    pending: bool,
+
     #[command(subcommand)]
     command: AccountSubCommand,
 }
 
 #[derive(Args, Debug)]
 pub struct GetStorageAtArgs {
-    /// The storage slot where the target data is stored
+    /// The storage slot where the target data is stored (the mapping base slot if --mapping-key is provided)
     #[arg(short, long)]
     slot: H256,
+
+    /// The key of the mapping entry to read. Can be repeated to read entries of nested mappings
+    #[arg(long, requires = "key_type")]
+    mapping_key: Vec<String>,
+
+    /// The type of the mapping keys. Either a single type for every key or one type per key
+    #[arg(long, requires = "mapping_key")]
+    key_type: Vec<MappingKeyType>,
+
+    /// Interprets the stored word as a value of the provided type instead of returning it as is
+    #[arg(long = "as", value_name = "TYPE")]
+    value_type: Option<StorageValueType>,
+
+    /// Also reports the implementation address if the account is an EIP-1967 proxy
+    #[arg(long, conflicts_with_all = ["mapping_key", "value_type"])]
+    follow_proxy: bool,
+}
+
+impl ValueEnum for StorageValueType {
+    fn value_variants<'a>() -> &'a [Self] {
+        &[Self::U256, Self::Address, Self::Bool, Self::Bytes32]
+    }
+
+    fn to_possible_value(&self) -> Option<PossibleValue> {
+        Some(match self {
+            StorageValueType::U256 => PossibleValue::new("u256"),
+            StorageValueType::Address => {
+                PossibleValue::new("address").help("Reads the low order 20 bytes of the word")
+            }
+            StorageValueType::Bool => {
+                PossibleValue::new("bool").help("True if any bit of the word is set")
+            }
+            StorageValueType::Bytes32 => PossibleValue::new("bytes32"),
+        })
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub enum MappingKeyType {
+    Address,
+    Uint,
+    Bytes32,
+}
+
+impl ValueEnum for MappingKeyType {
+    fn value_variants<'a>() -> &'a [Self] {
+        &[Self::Address, Self::Uint, Self::Bytes32]
+    }
+
+    fn to_possible_value(&self) -> Option<PossibleValue> {
+        Some(match self {
+            MappingKeyType::Address => PossibleValue::new("address"),
+            MappingKeyType::Uint => {
+                PossibleValue::new("uint").help("Decimal or 0x prefixed hex unsigned integer")
+            }
+            MappingKeyType::Bytes32 => PossibleValue::new("bytes32"),
+        })
+    }
+}
+
+#[derive(Error, Debug)]
+pub enum GetStorageAtParserError {
+    #[error("Expected a single key type or one key type per mapping key, got {types} key types for {keys} mapping keys")]
+    MismatchedKeyTypes { keys: usize, types: usize },
+
+    #[error("Invalid mapping key {0} of type {1:?}")]
+    InvalidMappingKey(String, MappingKeyType),
+}
+
+fn parse_mapping_key(
+    value: String,
+    key_type: MappingKeyType,
+) -> Result<MappingKey, GetStorageAtParserError> {
+    let key = match key_type {
+        MappingKeyType::Address => value.parse::<Address>().ok().map(MappingKey::Address),
+        MappingKeyType::Uint => match value.strip_prefix("0x") {
+            Some(hex_value) => U256::from_str_radix(hex_value, 16).ok(),
+            None => U256::from_dec_str(&value).ok(),
+        }
+        .map(MappingKey::Uint),
+        MappingKeyType::Bytes32 => value.parse::<H256>().ok().map(MappingKey::Bytes32),
+    };
+
+    key.ok_or(GetStorageAtParserError::InvalidMappingKey(value, key_type))
+}
+
+impl GetStorageAtArgs {
+    fn mapping_keys(self) -> Result<Vec<MappingKey>, GetStorageAtParserError> {
+        let GetStorageAtArgs {
+            mapping_key,
+            key_type,
+            ..
+        } = self;
+
+        let key_types = match key_type.len() {
+            1 => vec![key_type[0]; mapping_key.len()],
+            len if len == mapping_key.len() => key_type,
+            len => {
+                return Err(GetStorageAtParserError::MismatchedKeyTypes {
+                    keys: mapping_key.len(),
+                    types: len,
+                })
+            }
+        };
+
+        mapping_key
+            .into_iter()
+            .zip(key_types)
+            .map(|(key, key_type)| parse_mapping_key(key, key_type))
+            .collect()
+    }
 }
 
 #[derive(Subcommand, Debug)]
 #[command()]
 pub enum AccountSubCommand {
-    /// Retrieves the account balance in the specified block (defaults to latest)
+    /// Retrieves the account balance in the specified block (defaults to latest). Returns a map of
+    /// address to balance if --address is repeated
     Balance(NoArgs),
 
+    /// Retrieves the account balance at the latest, safe, finalized and pending blocks
+    BalanceAtAllTags(NoArgs),
+
     /// Retrieves the account bytecode in the specified block (defaults to latest)
     Code(NoArgs),
 
@@ -50,6 +186,27 @@ pub enum AccountNamespaceResult {
     Bytecode(Bytes),
     Number(U256),
     Hash(H256),
+    Balances(BTreeMap<Address, U256>),
+    BalanceAcrossTags(BalanceAcrossTags),
+    MappingStorageEntry(MappingStorageEntry),
+    ProxyStorageEntry(ProxyStorageEntry),
+    StorageValue(StorageValue),
+    #[serde(serialize_with = "parse_not_found", rename = "block")]
+    NotFound(),
+}
+
+impl NotFoundResult for AccountNamespaceResult {
+    fn not_found(_what: Missing) -> Self {
+        AccountNamespaceResult::NotFound()
+    }
+}
+
+fn get_block_id(get_block_by_id: GetBlockByIdArgs, pending: bool) -> Option<BlockId> {
+    if pending {
+        return Some(BlockId::Number(BlockNumber::Pending));
+    }
+
+    get_block_by_id.try_into().ok()
 }
 
 pub fn parse(
@@ -59,14 +216,33 @@ pub fn parse(
     let AccountCommand {
         get_account_by_id,
         get_block_by_id,
+        pending,
         command,
     } = sub_command;
 
-    let account_id = get_account_by_id.try_into()?;
+    let block_id = get_block_id(get_block_by_id, pending);
 
-    let block_id = get_block_by_id.try_into().ok();
+    let res = if matches!(command, AccountSubCommand::Balance(_))
+        && get_account_by_id.addresses().len() > 1
+    {
+        get_balances(context, get_account_by_id.addresses().to_vec(), block_id)
+            .map(AccountNamespaceResult::Balances)
+    } else {
+        query_account(context, get_account_by_id, block_id, command)
+    };
 
+    // Nodes reject the queries at a block they don't know
+    Ok(parse_lookup(Lookup::from_block_query(res)?, |res| res))
+}
+
+fn query_account(
+    context: &CommandExecutionContext,
+    get_account_by_id: GetAccountArgs,
+    block_id: Option<BlockId>,
+    command: AccountSubCommand,
+) -> anyhow::Result<AccountNamespaceResult> {
     let node_provider = context.node_provider();
+    let account_id = get_account_by_id.try_into()?;
 
     let res: AccountNamespaceResult = match command {
         AccountSubCommand::Balance(_) => context
@@ -76,6 +252,9 @@ pub fn parse(
                 block_id,
             ))
             .map(AccountNamespaceResult::Number),
+        AccountSubCommand::BalanceAtAllTags(_) => Ok(AccountNamespaceResult::BalanceAcrossTags(
+            balance_across_tags(context, account_id),
+        )),
         AccountSubCommand::Code(_) => context
             .execute(cmd::account::get_code(node_provider, account_id, block_id))
             .map(AccountNamespaceResult::Bytecode),
@@ -89,15 +268,552 @@ pub fn parse(
         AccountSubCommand::Nonce(_) => context
             .execute(cmd::account::get_nonce(node_provider, account_id))
             .map(AccountNamespaceResult::Number),
-        AccountSubCommand::StorageAt(GetStorageAtArgs { slot }) => context
-            .execute(cmd::account::get_storage_at(
-                node_provider,
-                account_id,
-                slot,
-                block_id,
-            ))
-            .map(AccountNamespaceResult::Hash),
+        AccountSubCommand::StorageAt(get_storage_at_args) => {
+            let slot = get_storage_at_args.slot;
+            let value_type = get_storage_at_args.value_type;
+            let follow_proxy = get_storage_at_args.follow_proxy;
+            let mapping_keys = get_storage_at_args.mapping_keys()?;
+
+            match (mapping_keys.is_empty(), value_type) {
+                (true, None) if follow_proxy => context
+                    .execute(cmd::account::get_storage_at_through_proxy(
+                        node_provider,
+                        account_id,
+                        slot,
+                        block_id,
+                    ))
+                    .map(AccountNamespaceResult::ProxyStorageEntry),
+                (true, None) => context
+                    .execute(cmd::account::get_storage_at(
+                        node_provider,
+                        account_id,
+                        slot,
+                        block_id,
+                    ))
+                    .map(AccountNamespaceResult::Hash),
+                (true, Some(value_type)) => context
+                    .execute(cmd::account::get_typed_storage_at(
+                        node_provider,
+                        account_id,
+                        slot,
+                        value_type,
+                        block_id,
+                    ))
+                    .map(AccountNamespaceResult::StorageValue),
+                (false, value_type) => context
+                    .execute(cmd::account::get_mapping_storage_at(
+                        node_provider,
+                        account_id,
+                        slot,
+                        mapping_keys,
+                        value_type.unwrap_or_default(),
+                        block_id,
+                    ))
+                    .map(AccountNamespaceResult::MappingStorageEntry),
+            }
+        }
     }?;
 
     Ok(res)
 }
+
+// eth_getBalance
+fn get_balances(
+    context: &CommandExecutionContext,
+    addresses: Vec<Address>,
+    block_id: Option<BlockId>,
+) -> anyhow::Result<BTreeMap<Address, U256>> {
+    let node_provider = context.node_provider();
+    let block_id = block_id.unwrap_or(BlockId::Number(BlockNumber::Latest));
+
+    // Websocket endpoints and nodes that reject batches get one request per address instead
+    let balances = context
+        .execute(cmd::account::batch_get_balances(
+            node_provider,
+            &addresses,
+            block_id,
+        ))
+        .unwrap_or_else(|_| {
+            context.execute_with_concurrency(
+                addresses.iter().map(|address| {
+                    cmd::account::get_balance(node_provider, (*address).into(), Some(block_id))
+                }),
+                ACCOUNT_QUERY_CONCURRENCY,
+            )
+        });
+
+    cmd::account::collect_balances(addresses, balances)
+}
+
+// eth_getBalance
+fn balance_across_tags(
+    context: &CommandExecutionContext,
+    account_id: NameOrAddress,
+) -> BalanceAcrossTags {
+    let balances = context.execute_many(BALANCE_SNAPSHOT_TAGS.map(|tag| {
+        cmd::account::get_balance(
+            context.node_provider(),
+            account_id.clone(),
+            Some(BlockId::Number(tag)),
+        )
+    }));
+
+    BalanceAcrossTags::new(balances)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::Ordering;
+
+    use clap::Parser;
+    use ethers::types::{BlockId, BlockNumber, NameOrAddress, H256, U256};
+    use serde_json::json;
+
+    use crate::{
+        cli::common::GetAccountParserError,
+        cmd::{
+            helpers::test::{spawn_counting_mock_rpc_server, spawn_mock_rpc_error_server},
+            utils::MappingKey,
+        },
+        config::{get_config, ConfigOverrides},
+        context::CommandExecutionContext,
+    };
+
+    use crate::cmd::account::StorageValueType;
+
+    use super::{
+        get_block_id, parse, AccountCommand, AccountNamespaceResult, AccountSubCommand,
+        GetStorageAtParserError,
+    };
+
+    const ADDRESS: &str = "0xf39Fd6e51aad88F6F4ce6aB8827279cffFb92266";
+    const OTHER_ADDRESS: &str = "0x70997970C51812dc3A010C7d01b50e0d17dc79C8";
+
+    fn setup_execution_context(url: String) -> anyhow::Result<CommandExecutionContext> {
+        let config = get_config(ConfigOverrides::new(None, Some(url), None))?;
+
+        Ok(CommandExecutionContext::new(config)?)
+    }
+
+    #[test]
+    fn should_fall_back_to_a_request_per_address_if_batches_are_not_supported() -> anyhow::Result<()>
+    {
+        // Arrange
+        let (url, requests) = spawn_counting_mock_rpc_server(json!("0x5"));
+        let context = setup_execution_context(url)?;
+
+        let cmd = AccountCommand::try_parse_from([
+            "account",
+            "--address",
+            ADDRESS,
+            "--address",
+            OTHER_ADDRESS,
+            "balance",
+        ])?;
+
+        // Act
+        let res = parse(&context, cmd)?;
+
+        // Assert
+        let AccountNamespaceResult::Balances(balances) = res else {
+            panic!("Should be the balances of the addresses!");
+        };
+        assert_eq!(balances.get(&ADDRESS.parse()?), Some(&U256::from(5)));
+        assert_eq!(balances.get(&OTHER_ADDRESS.parse()?), Some(&U256::from(5)));
+
+        // The rejected batch plus a request per address
+        assert_eq!(requests.load(Ordering::SeqCst), 3);
+
+        Ok(())
+    }
+
+    #[test]
+    fn should_fail_if_a_balance_can_not_be_retrieved() -> anyhow::Result<()> {
+        // Arrange
+        let url = spawn_mock_rpc_error_server(json!({ "code": -32000, "message": "boom" }));
+        let context = setup_execution_context(url)?;
+
+        let cmd = AccountCommand::try_parse_from([
+            "account",
+            "--address",
+            ADDRESS,
+            "--address",
+            OTHER_ADDRESS,
+            "balance",
+        ])?;
+
+        // Act
+        let res = parse(&context, cmd);
+
+        // Assert
+        assert!(res.is_err_and(|err| format!("{err:#}").contains("boom")));
+
+        Ok(())
+    }
+
+    #[test]
+    fn should_report_an_unknown_block_as_not_found() -> anyhow::Result<()> {
+        // Setup
+        let url =
+            spawn_mock_rpc_error_server(json!({ "code": -39001, "message": "Unknown block" }));
+        let test_cases = [
+            vec!["account", "--address", ADDRESS, "--number", "100", "code"],
+            vec![
+                "account",
+                "--address",
+                ADDRESS,
+                "--address",
+                OTHER_ADDRESS,
+                "--number",
+                "100",
+                "balance",
+            ],
+        ];
+
+        for args in test_cases {
+            // Arrange
+            let context = setup_execution_context(url.clone())?;
+            let cmd = AccountCommand::try_parse_from(&args)?;
+
+            // Act
+            let res = parse(&context, cmd)?;
+
+            // Assert
+            assert_eq!(
+                serde_json::to_value(res)?,
+                json!({ "block": null }),
+                "{args:?}"
+            );
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn should_get_the_balance_at_every_tag() -> anyhow::Result<()> {
+        // Arrange
+        let (url, requests) = spawn_counting_mock_rpc_server(json!("0x5"));
+        let context = setup_execution_context(url)?;
+
+        let cmd = AccountCommand::try_parse_from([
+            "account",
+            "--address",
+            ADDRESS,
+            "balance-at-all-tags",
+        ])?;
+
+        // Act
+        let res = parse(&context, cmd)?;
+
+        // Assert
+        let AccountNamespaceResult::BalanceAcrossTags(snapshot) = res else {
+            panic!("Should be the balance snapshot!");
+        };
+        assert_eq!(snapshot.balances.len(), 4);
+        assert!(snapshot.unavailable.is_empty());
+        assert_eq!(requests.load(Ordering::SeqCst), 4);
+
+        Ok(())
+    }
+    const SLOT: &str = "0x0000000000000000000000000000000000000000000000000000000000000001";
+
+    fn parse_mapping_keys(args: &[&str]) -> anyhow::Result<Vec<MappingKey>> {
+        let res = AccountCommand::try_parse_from(
+            [
+                "account",
+                "--address",
+                ADDRESS,
+                "storage-at",
+                "--slot",
+                SLOT,
+            ]
+            .iter()
+            .chain(args),
+        )?;
+
+        let AccountSubCommand::StorageAt(args) = res.command else {
+            panic!("Should be the storage-at subcommand!");
+        };
+
+        Ok(args.mapping_keys()?)
+    }
+
+    #[test]
+    fn should_not_parse_mapping_keys_for_a_plain_slot() -> anyhow::Result<()> {
+        // Act
+        let res = parse_mapping_keys(&[])?;
+
+        // Assert
+        assert!(res.is_empty());
+
+        Ok(())
+    }
+
+    #[test]
+    fn should_apply_a_single_key_type_to_every_mapping_key() -> anyhow::Result<()> {
+        // Act
+        let res = parse_mapping_keys(&[
+            "--mapping-key",
+            "10",
+            "--mapping-key",
+            "0x10",
+            "--key-type",
+            "uint",
+        ])?;
+
+        // Assert
+        assert_eq!(
+            res,
+            vec![
+                MappingKey::Uint(U256::from(10)),
+                MappingKey::Uint(U256::from(16))
+            ]
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn should_parse_one_key_type_per_mapping_key() -> anyhow::Result<()> {
+        // Arrange
+        let bytes32 = H256::repeat_byte(0x11);
+
+        // Act
+        let res = parse_mapping_keys(&[
+            "--mapping-key",
+            ADDRESS,
+            "--key-type",
+            "address",
+            "--mapping-key",
+            &format!("{bytes32:?}"),
+            "--key-type",
+            "bytes32",
+        ])?;
+
+        // Assert
+        assert_eq!(
+            res,
+            vec![
+                MappingKey::Address(ADDRESS.parse()?),
+                MappingKey::Bytes32(bytes32)
+            ]
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn should_reject_mismatched_key_types() {
+        // Act
+        let res = parse_mapping_keys(&[
+            "--mapping-key",
+            "1",
+            "--mapping-key",
+            "2",
+            "--mapping-key",
+            "3",
+            "--key-type",
+            "uint",
+            "--key-type",
+            "uint",
+        ]);
+
+        // Assert
+        assert!(matches!(
+            res.unwrap_err().downcast_ref::<GetStorageAtParserError>(),
+            Some(GetStorageAtParserError::MismatchedKeyTypes { keys: 3, types: 2 })
+        ));
+    }
+
+    #[test]
+    fn should_reject_invalid_mapping_keys() {
+        // Setup
+        let test_cases = [("0x1234", "address"), ("abc", "uint"), ("0x12", "bytes32")];
+
+        for (key, key_type) in test_cases {
+            // Act
+            let res = parse_mapping_keys(&["--mapping-key", key, "--key-type", key_type]);
+
+            // Assert
+            assert!(matches!(
+                res.unwrap_err().downcast_ref::<GetStorageAtParserError>(),
+                Some(GetStorageAtParserError::InvalidMappingKey(..))
+            ));
+        }
+    }
+
+    #[test]
+    fn should_only_follow_the_proxy_of_a_raw_slot() {
+        // Setup
+        let test_cases: [(&[&str], bool); 3] = [
+            (&["--follow-proxy"], true),
+            (&["--follow-proxy", "--as", "u256"], false),
+            (
+                &["--follow-proxy", "--mapping-key", "1", "--key-type", "uint"],
+                false,
+            ),
+        ];
+
+        for (args, is_valid) in test_cases {
+            // Act
+            let res = AccountCommand::try_parse_from(
+                [
+                    "account",
+                    "--address",
+                    ADDRESS,
+                    "storage-at",
+                    "--slot",
+                    SLOT,
+                ]
+                .iter()
+                .chain(args),
+            );
+
+            // Assert
+            assert_eq!(res.is_ok(), is_valid, "{args:?}");
+        }
+    }
+
+    #[test]
+    fn should_require_a_key_type_for_mapping_keys() {
+        // Act
+        let res = parse_mapping_keys(&["--mapping-key", "1"]);
+
+        // Assert
+        assert!(res.is_err());
+    }
+
+    #[test]
+    fn should_parse_the_storage_value_type() -> anyhow::Result<()> {
+        // Setup
+        let test_cases = [
+            ("u256", StorageValueType::U256),
+            ("address", StorageValueType::Address),
+            ("bool", StorageValueType::Bool),
+            ("bytes32", StorageValueType::Bytes32),
+        ];
+
+        for (value_type, expected_res) in test_cases {
+            // Act
+            let res = AccountCommand::try_parse_from([
+                "account",
+                "--address",
+                ADDRESS,
+                "storage-at",
+                "--slot",
+                SLOT,
+                "--as",
+                value_type,
+            ])?;
+
+            // Assert
+            let AccountSubCommand::StorageAt(args) = res.command else {
+                panic!("Should be the storage-at subcommand!");
+            };
+            assert_eq!(args.value_type, Some(expected_res));
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn should_target_the_pending_block_if_pending_is_set() -> anyhow::Result<()> {
+        // Setup
+        let test_cases: [(&[&str], Option<BlockId>); 3] = [
+            (&["--pending"], Some(BlockId::Number(BlockNumber::Pending))),
+            (
+                &["--tag", "pending"],
+                Some(BlockId::Number(BlockNumber::Pending)),
+            ),
+            (&[], None),
+        ];
+
+        for (args, expected_res) in test_cases {
+            let cmd = AccountCommand::try_parse_from(
+                ["account", "--address", ADDRESS]
+                    .iter()
+                    .chain(args)
+                    .chain(&["balance"]),
+            )?;
+
+            // Act
+            let res = get_block_id(cmd.get_block_by_id, cmd.pending);
+
+            // Assert
+            assert_eq!(res, expected_res, "{args:?}");
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn should_not_combine_pending_with_a_block_id() {
+        // Setup
+        let test_cases: [&[&str]; 3] = [
+            &["--pending", "--tag", "latest"],
+            &["--pending", "--number", "1"],
+            &["--pending", "--hash", SLOT],
+        ];
+
+        for args in test_cases {
+            // Act
+            let res = AccountCommand::try_parse_from(
+                ["account", "--address", ADDRESS]
+                    .iter()
+                    .chain(args)
+                    .chain(&["balance"]),
+            );
+
+            // Assert
+            assert!(res.is_err(), "{args:?}");
+        }
+    }
+
+    #[test]
+    fn should_parse_repeated_addresses() -> anyhow::Result<()> {
+        // Arrange
+        let other_address = "0x70997970C51812dc3A010C7d01b50e0d17dc79C8";
+
+        // Act
+        let res = AccountCommand::try_parse_from([
+            "account",
+            "--address",
+            ADDRESS,
+            "--address",
+            other_address,
+            "balance",
+        ])?;
+
+        // Assert
+        assert_eq!(
+            res.get_account_by_id.addresses(),
+            [ADDRESS.parse()?, other_address.parse()?]
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn should_reject_multiple_addresses_for_single_account_commands() -> anyhow::Result<()> {
+        // Arrange
+        let res = AccountCommand::try_parse_from([
+            "account",
+            "--address",
+            ADDRESS,
+            "--address",
+            ADDRESS,
+            "nonce",
+        ])?;
+
+        // Act
+        let res = NameOrAddress::try_from(res.get_account_by_id);
+
+        // Assert
+        assert!(matches!(
+            res,
+            Err(GetAccountParserError::MultipleAccountIds(2))
+        ));
+
+        Ok(())
+    }
+}
@@ -1,13 +1,21 @@
+use std::{collections::BTreeMap, time::Duration};
+
 use crate::{
     cli::common::GetBlockByIdArgs,
-    cmd::block::{self, BlockKind},
+    cmd::{
+        block::{self, BlockKind, CheckedBlock, HeaderFieldDiff, TransactionsSummary},
+        lookup::Missing,
+        transaction::EnrichedReceipt,
+    },
     context::CommandExecutionContext,
 };
-use clap::{command, Args, Parser, Subcommand};
-use ethers::types::{TransactionReceipt, U256, U64};
+use clap::{Args, Parser, Subcommand};
+use ethers::types::{BlockId, U256, U64};
 use serde::Serialize;
 
-use super::common::{parse_not_found, NoArgs};
+use super::common::{
+    parse_lookup, parse_not_found, serialize_with_checksummed_fields, NoArgs, NotFoundResult,
+};
 
 #[derive(Parser, Debug)]
 #[command()]
@@ -19,6 +27,13 @@ pub struct BlockCommand {
     command: BlockSubCommand,
 }
 
+impl BlockCommand {
+    /// Tells if the command keeps running until interrupted.
+    pub fn is_long_running(&self) -> bool {
+        matches!(self.command, BlockSubCommand::Watch(_))
+    }
+}
+
 #[derive(Subcommand, Debug)]
 #[command()]
 pub enum BlockSubCommand {
@@ -35,7 +50,10 @@ pub enum BlockSubCommand {
     UncleCount(NoArgs),
 
     /// Gets the transaction receipts for the block with the provided identifier
-    Receipts(NoArgs),
+    Receipts(ReceiptsArgs),
+
+    /// Writes every new block as it is mined until interrupted
+    Watch(WatchBlocksArgs),
 }
 
 #[derive(Args, Debug)]
@@ -43,19 +61,67 @@ pub struct GetBlockArgs {
     /// Indicates if transactions should be included when getting block
     #[arg(long)]
     include_tx: Option<bool>,
+
+    /// Validates the transactions root and the ommers hash of the block against its contents
+    #[arg(long)]
+    ommers_hash_check: bool,
+
+    /// Compares the block header with the one of the provided block (number, tag or hash)
+    #[arg(long, value_name = "OTHER_BLOCK", conflicts_with_all(["include_tx", "ommers_hash_check"]))]
+    diff: Option<BlockId>,
+
+    /// Returns the transaction count, total value transferred, total gas used and unique senders and receivers instead of the block
+    #[arg(long, conflicts_with_all(["include_tx", "ommers_hash_check", "diff"]))]
+    transactions_summary: bool,
+}
+
+#[derive(Args, Debug)]
+pub struct WatchBlocksArgs {
+    /// Indicates if transactions should be included in the blocks
+    #[arg(long)]
+    include_tx: Option<bool>,
+
+    /// Seconds between checks for new blocks when the node doesn't support subscriptions
+    #[arg(
+        long,
+        value_name = "SECONDS",
+        default_value_t = 12,
+        value_parser = clap::value_parser!(u64).range(1..)
+    )]
+    interval: u64,
+}
+
+#[derive(Args, Debug)]
+pub struct ReceiptsArgs {
+    /// Re-simulates failed transactions to include their revert reason
+    #[arg(long)]
+    explain_failure: bool,
 }
 
 #[derive(Debug, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub enum BlockNamespaceResult {
+    #[serde(serialize_with = "serialize_with_checksummed_fields")]
     Block(BlockKind),
+    CheckedBlock(CheckedBlock),
+    Diff(BTreeMap<&'static str, HeaderFieldDiff>),
     Number(U64),
     Count(U256),
-    TransactionReceipts(Vec<TransactionReceipt>),
+    #[serde(serialize_with = "serialize_with_checksummed_fields")]
+    TransactionReceipts(Vec<EnrichedReceipt>),
+    TransactionsSummary(TransactionsSummary),
+    // The blocks were already written while watching them
+    Watched,
     #[serde(serialize_with = "parse_not_found", rename = "block")]
     NotFound(),
 }
 
+impl NotFoundResult for BlockNamespaceResult {
+    fn not_found(_what: Missing) -> Self {
+        BlockNamespaceResult::NotFound()
+    }
+}
+
 pub fn parse(
     context: &CommandExecutionContext,
     sub_command: BlockCommand,
@@ -68,44 +134,252 @@ pub fn parse(
     let node_provider = context.node_provider();
 
     let res: BlockNamespaceResult = match command {
-        BlockSubCommand::Get(GetBlockArgs { include_tx }) => context
-            .execute(block::get_block(
+        BlockSubCommand::Get(GetBlockArgs {
+            transactions_summary: true,
+            ..
+        }) => parse_lookup(
+            context.execute(block::transactions_summary(
+                node_provider,
+                get_block_by_id.try_into()?,
+            ))?,
+            BlockNamespaceResult::TransactionsSummary,
+        ),
+        BlockSubCommand::Get(GetBlockArgs {
+            diff: Some(other_block_id),
+            ..
+        }) => parse_lookup(
+            context.execute(block::diff_blocks(
+                node_provider,
+                get_block_by_id.try_into()?,
+                other_block_id,
+            ))?,
+            BlockNamespaceResult::Diff,
+        ),
+        BlockSubCommand::Get(GetBlockArgs {
+            include_tx,
+            ommers_hash_check: true,
+            ..
+        }) => parse_lookup(
+            context.execute(block::get_checked_block(
                 node_provider,
                 get_block_by_id.try_into()?,
                 include_tx.unwrap_or_default(),
-            ))?
-            .map_or(
-                BlockNamespaceResult::NotFound(),
-                BlockNamespaceResult::Block,
-            ),
+            ))?,
+            BlockNamespaceResult::CheckedBlock,
+        ),
+        BlockSubCommand::Get(GetBlockArgs { include_tx, .. }) => parse_lookup(
+            context.execute(block::get_block(
+                node_provider,
+                get_block_by_id.try_into()?,
+                include_tx.unwrap_or_default(),
+                context.config().command_timeout(),
+            ))?,
+            BlockNamespaceResult::Block,
+        ),
         BlockSubCommand::Number(_) => context
             .execute(block::get_block_number(node_provider))
             .map(BlockNamespaceResult::Number)?,
-        BlockSubCommand::TransactionCount(_) => context
-            .execute(block::get_transaction_count(
+        BlockSubCommand::TransactionCount(_) => parse_lookup(
+            context.execute(block::get_transaction_count(
                 node_provider,
                 get_block_by_id.try_into()?,
-            ))?
-            .map_or(
-                BlockNamespaceResult::NotFound(),
-                BlockNamespaceResult::Count,
-            ),
-        BlockSubCommand::UncleCount(_) => context
-            .execute(block::get_uncle_block_count(
+            ))?,
+            BlockNamespaceResult::Count,
+        ),
+        BlockSubCommand::UncleCount(_) => parse_lookup(
+            context.execute(block::get_uncle_block_count(
                 node_provider,
                 get_block_by_id.try_into()?,
-            ))
-            .map(BlockNamespaceResult::Count)?,
-        BlockSubCommand::Receipts(_) => context
-            .execute(block::get_block_receipts(
+            ))?,
+            BlockNamespaceResult::Count,
+        ),
+        BlockSubCommand::Receipts(ReceiptsArgs { explain_failure }) => parse_lookup(
+            context.execute(block::get_block_receipts(
                 node_provider,
                 get_block_by_id.try_into()?,
-            ))?
-            .map_or(
-                BlockNamespaceResult::NotFound(),
-                BlockNamespaceResult::TransactionReceipts,
-            ),
+                explain_failure,
+            ))?,
+            BlockNamespaceResult::TransactionReceipts,
+        ),
+        BlockSubCommand::Watch(WatchBlocksArgs {
+            include_tx,
+            interval,
+        }) => context
+            .execute(async {
+                tokio::select! {
+                    res = block::watch_blocks(
+                        node_provider,
+                        Duration::from_secs(interval),
+                        include_tx.unwrap_or_default(),
+                        |block| context.stream_output().write(&BlockNamespaceResult::Block(block)),
+                    ) => res,
+                    res = tokio::signal::ctrl_c() => Ok(res?),
+                }
+            })
+            .map(|_| BlockNamespaceResult::Watched)?,
     };
 
     Ok(res)
 }
+
+#[cfg(test)]
+mod tests {
+    use ethers::types::{Block, H256, U256};
+    use serde_json::json;
+
+    use crate::{
+        cli::common::parse_lookup,
+        cmd::{
+            block::{BlockKind, CheckedBlock, HeaderConsistency},
+            lookup::{Lookup, Missing},
+        },
+    };
+
+    use clap::Parser;
+
+    use super::{BlockCommand, BlockNamespaceResult};
+
+    #[test]
+    fn should_serialize_a_missing_block_as_null() -> anyhow::Result<()> {
+        // Arrange
+        let lookup = Lookup::<U256>::NotFound {
+            what: Missing::Block,
+        };
+
+        // Act
+        let res = serde_json::to_value(parse_lookup(lookup, BlockNamespaceResult::Count))?;
+
+        // Assert
+        assert_eq!(res, json!({ "block": null }));
+
+        Ok(())
+    }
+
+    #[test]
+    fn should_serialize_the_block_addresses_checksummed() -> anyhow::Result<()> {
+        // Arrange
+        let block = BlockKind::RawBlock(Block {
+            author: Some("0xfb6916095ca1df60bb79ce92ce3ea74c37c5d359".parse()?),
+            ..Default::default()
+        });
+
+        // Act
+        let res = serde_json::to_value(BlockNamespaceResult::Block(block))?;
+
+        // Assert
+        assert_eq!(
+            res["block"]["miner"],
+            "0xfB6916095ca1df60bB79Ce92cE3Ea74c37c5d359"
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn should_serialize_the_found_value() -> anyhow::Result<()> {
+        // Arrange
+        let lookup = Lookup::Found(U256::from(2));
+
+        // Act
+        let res = serde_json::to_value(parse_lookup(lookup, BlockNamespaceResult::Count))?;
+
+        // Assert
+        assert_eq!(res, json!({ "count": "0x2" }));
+
+        Ok(())
+    }
+
+    #[test]
+    fn should_serialize_the_header_check_next_to_the_block_fields() -> anyhow::Result<()> {
+        // Arrange
+        let checked_block = CheckedBlock {
+            block: BlockKind::RawBlock(Block {
+                hash: Some(H256::repeat_byte(0xaa)),
+                ..Default::default()
+            }),
+            header_check: HeaderConsistency {
+                consistent: true,
+                checks: vec![],
+                skipped: vec![],
+            },
+        };
+
+        // Act
+        let res = serde_json::to_value(BlockNamespaceResult::CheckedBlock(checked_block))?;
+
+        // Assert
+        assert_eq!(
+            res["checkedBlock"]["hash"],
+            json!(format!("{:?}", H256::repeat_byte(0xaa)))
+        );
+        assert_eq!(
+            res["checkedBlock"]["headerCheck"],
+            json!({ "consistent": true, "checks": [] })
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn should_only_accept_a_positive_watch_interval() {
+        // Setup
+        let test_cases = [
+            (vec!["--interval", "0"], false),
+            (vec!["--interval", "2"], true),
+            (vec![], true),
+        ];
+
+        for (args, is_valid) in test_cases {
+            // Act
+            let res = BlockCommand::try_parse_from(["block", "watch"].into_iter().chain(args));
+
+            // Assert
+            assert_eq!(res.is_ok(), is_valid);
+        }
+    }
+
+    #[test]
+    fn should_not_diff_a_block_while_checking_its_header() {
+        // Setup
+        let test_cases = [
+            (vec!["--diff", "1"], true),
+            (vec!["--diff", "latest"], true),
+            (vec!["--diff", "1", "--ommers-hash-check"], false),
+            (vec!["--diff", "1", "--include-tx", "true"], false),
+        ];
+
+        for (args, is_valid) in test_cases {
+            // Act
+            let res = BlockCommand::try_parse_from(
+                ["block", "--number", "0", "get"].into_iter().chain(args),
+            );
+
+            // Assert
+            assert_eq!(res.is_ok(), is_valid);
+        }
+    }
+
+    #[test]
+    fn should_only_summarize_the_transactions_of_a_plain_block_request() {
+        // Setup
+        let test_cases = [
+            (vec!["--transactions-summary"], true),
+            (
+                vec!["--transactions-summary", "--include-tx", "true"],
+                false,
+            ),
+            (vec!["--transactions-summary", "--ommers-hash-check"], false),
+            (vec!["--transactions-summary", "--diff", "1"], false),
+        ];
+
+        for (args, is_valid) in test_cases {
+            // Act
+            let res = BlockCommand::try_parse_from(
+                ["block", "--number", "0", "get"].into_iter().chain(args),
+            );
+
+            // Assert
+            assert_eq!(res.is_ok(), is_valid);
+        }
+    }
+}
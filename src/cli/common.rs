@@ -1,10 +1,20 @@
 use clap::{builder::PossibleValue, Args, ValueEnum};
-use ethers::types::{
-    Address, BlockId, BlockNumber, Bytes, NameOrAddress, TransactionRequest, H160, H256, U256, U64,
+use ethers::{
+    types::{
+        transaction::eip2718::TypedTransaction, Address, BlockId, BlockNumber, Bytes,
+        Eip1559TransactionRequest, NameOrAddress, TransactionRequest, H160, H256, U256, U64,
+    },
+    utils::to_checksum,
 };
-use serde::Serializer;
+use serde::{ser::Error as _, Serialize, Serializer};
+use serde_json::Value;
 use thiserror::Error;
 
+use crate::cmd::{
+    lookup::{Lookup, Missing},
+    utils::encode_function_call,
+};
+
 #[derive(Args, Debug)]
 pub struct NoArgs;
 
@@ -68,7 +78,7 @@ pub const GET_BLOCK_BY_ID_ARG_GROUP_NAME: &str = "block_by_id";
 #[derive(Args, Debug)]
 pub struct GetBlockByIdArgs {
     /// Hash of the target block
-    #[arg(group=GET_BLOCK_BY_ID_ARG_GROUP_NAME, long, value_name = "BLOCK_HASH",conflicts_with_all(["number","tag"]))]
+    #[arg(group=GET_BLOCK_BY_ID_ARG_GROUP_NAME, long, alias = "block-hash", value_name = "BLOCK_HASH",conflicts_with_all(["number","tag"]))]
     hash: Option<H256>,
 
     /// Number of the target block
@@ -121,6 +131,32 @@ impl TryFrom<GetBlockByIdArgs> for BlockId {
     }
 }
 
+#[derive(Error, Debug)]
+pub enum BytesArgParserError {
+    #[error("Failed to read the file {0}: {1}")]
+    UnreadableFile(String, String),
+
+    #[error("Invalid hex encoded bytes: {0}")]
+    InvalidBytes(String),
+}
+
+/// Parses hex encoded bytes, or reads them from a file when the value is `@<path>`.
+pub fn parse_bytes_arg(value: &str) -> Result<Bytes, BytesArgParserError> {
+    let bytes = match value.strip_prefix('@') {
+        Some(path) => std::fs::read_to_string(path).map_err(|err| {
+            BytesArgParserError::UnreadableFile(path.to_string(), err.to_string())
+        })?,
+        None => value.to_string(),
+    };
+
+    bytes
+        .trim()
+        .parse()
+        .map_err(|err: <Bytes as std::str::FromStr>::Err| {
+            BytesArgParserError::InvalidBytes(err.to_string())
+        })
+}
+
 pub fn parse_not_found<S>(s: S) -> Result<S::Ok, S::Error>
 where
     S: Serializer,
@@ -128,6 +164,73 @@ where
     s.serialize_none()
 }
 
+/// Fields holding an address in the node types, like blocks, logs, receipts, transactions and
+/// proofs.
+const ADDRESS_FIELDS: [&str; 5] = ["address", "from", "to", "contractAddress", "miner"];
+
+/// Serializes the address in its EIP-55 checksummed form.
+pub fn serialize_checksummed<S>(address: &H160, s: S) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    s.serialize_str(&to_checksum(address, None))
+}
+
+/// Serializes the addresses in their EIP-55 checksummed form.
+pub fn serialize_checksummed_list<S>(addresses: &[H160], s: S) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    s.collect_seq(addresses.iter().map(|address| to_checksum(address, None)))
+}
+
+fn checksum_address_fields(value: &mut Value) {
+    match value {
+        Value::Object(fields) => {
+            for (name, field) in fields {
+                match field {
+                    Value::String(field) if ADDRESS_FIELDS.contains(&name.as_str()) => {
+                        if let Ok(address) = field.parse::<H160>() {
+                            *field = to_checksum(&address, None);
+                        }
+                    }
+                    field => checksum_address_fields(field),
+                }
+            }
+        }
+        Value::Array(items) => items.iter_mut().for_each(checksum_address_fields),
+        _ => {}
+    }
+}
+
+/// Serializes the value with the addresses found in its address fields, at any depth, in their
+/// EIP-55 checksummed form. Used for the node types whose fields can't be annotated.
+pub fn serialize_with_checksummed_fields<T, S>(value: &T, s: S) -> Result<S::Ok, S::Error>
+where
+    T: Serialize,
+    S: Serializer,
+{
+    let mut value = serde_json::to_value(value).map_err(S::Error::custom)?;
+    checksum_address_fields(&mut value);
+
+    value.serialize(s)
+}
+
+/// Implemented by the namespace results to represent what a command did not find.
+pub trait NotFoundResult {
+    fn not_found(what: Missing) -> Self;
+}
+
+pub fn parse_lookup<T, R>(lookup: Lookup<T>, found: impl FnOnce(T) -> R) -> R
+where
+    R: NotFoundResult,
+{
+    match lookup {
+        Lookup::Found(value) => found(value),
+        Lookup::NotFound { what } => R::not_found(what),
+    }
+}
+
 #[derive(Args, Debug)]
 pub struct TypedTransactionArgs {
     /// Address of the account from which the transaction will be sent
@@ -145,17 +248,34 @@ pub struct TypedTransactionArgs {
     #[arg(long)]
     gas: Option<U256>,
 
+    /// Gas price of a legacy transaction
     #[arg(long)]
     gas_price: Option<U256>,
 
+    /// Maximum total fee per gas of an EIP-1559 transaction
+    #[arg(long)]
+    max_fee_per_gas: Option<U256>,
+
+    /// Maximum priority fee per gas of an EIP-1559 transaction
+    #[arg(long)]
+    max_priority_fee_per_gas: Option<U256>,
+
     /// Amount of Eth to send
     #[arg(long)]
     value: Option<U256>,
 
-    /// Calldata to send to the target account
-    #[arg(long)]
+    /// Calldata to send to the target account or @<path> to a file containing it
+    #[arg(long, value_name = "BYTES|@FILE", value_parser = parse_bytes_arg)]
     data: Option<Bytes>,
 
+    /// Signature of the function to call, e.g. "transfer(address,uint256)", used with --args to build the calldata
+    #[arg(long)]
+    sig: Option<String>,
+
+    /// Arguments of the function to call
+    #[arg(long, num_args = 1.., requires = "sig")]
+    args: Vec<String>,
+
     #[arg(long)]
     nonce: Option<U256>,
 
@@ -163,14 +283,18 @@ pub struct TypedTransactionArgs {
     chain_id: Option<U64>,
 }
 
-pub const TX_ARGS_FIELD_NAMES: [&str; 9] = [
+pub const TX_ARGS_FIELD_NAMES: [&str; 13] = [
     "from",
     "to",
     "ens_to",
     "gas",
     "gas_price",
+    "max_fee_per_gas",
+    "max_priority_fee_per_gas",
     "value",
     "data",
+    "sig",
+    "args",
     "nonce",
     "chain_id",
 ];
@@ -179,9 +303,18 @@ pub const TX_ARGS_FIELD_NAMES: [&str; 9] = [
 pub enum TypedTransactionParserError {
     #[error("Provided both ens and address")]
     ConflictingTransactionReceiver,
+
+    #[error("Provided both --data and --sig. Either raw calldata or a function signature with its arguments must be provided.")]
+    ConflictingCalldata,
+
+    #[error("Invalid function call: {0}")]
+    InvalidFunctionCall(String),
+
+    #[error("Provided both --gas-price and EIP-1559 fees. Either a legacy gas price or --max-fee-per-gas/--max-priority-fee-per-gas must be provided.")]
+    ConflictingGasPricing,
 }
 
-impl TryFrom<TypedTransactionArgs> for TransactionRequest {
+impl TryFrom<TypedTransactionArgs> for TypedTransaction {
     type Error = TypedTransactionParserError;
 
     fn try_from(value: TypedTransactionArgs) -> Result<Self, Self::Error> {
@@ -191,52 +324,89 @@ impl TryFrom<TypedTransactionArgs> for TransactionRequest {
             ens_to,
             gas,
             gas_price,
+            max_fee_per_gas,
+            max_priority_fee_per_gas,
             value,
             data,
+            sig,
+            args,
             nonce,
             chain_id,
         } = value;
 
-        let mut tx = TransactionRequest::new();
-
         if ens_to.is_some() && to.is_some() {
             return Err(Self::Error::ConflictingTransactionReceiver);
         }
 
+        if data.is_some() && sig.is_some() {
+            return Err(Self::Error::ConflictingCalldata);
+        }
+
+        let is_eip1559 = max_fee_per_gas.is_some() || max_priority_fee_per_gas.is_some();
+
+        if is_eip1559 && gas_price.is_some() {
+            return Err(Self::Error::ConflictingGasPricing);
+        }
+
+        let mut tx = if is_eip1559 {
+            let mut tx = Eip1559TransactionRequest::new();
+
+            if let Some(max_fee_per_gas) = max_fee_per_gas {
+                tx = tx.max_fee_per_gas(max_fee_per_gas)
+            }
+
+            if let Some(max_priority_fee_per_gas) = max_priority_fee_per_gas {
+                tx = tx.max_priority_fee_per_gas(max_priority_fee_per_gas)
+            }
+
+            TypedTransaction::Eip1559(tx)
+        } else {
+            let mut tx = TransactionRequest::new();
+
+            if let Some(gas_price) = gas_price {
+                tx = tx.gas_price(gas_price)
+            }
+
+            TypedTransaction::Legacy(tx)
+        };
+
         if let Some(from) = from {
-            tx = tx.from(from)
+            tx.set_from(from);
         }
 
         if let Some(to) = to {
-            tx = tx.to(to)
+            tx.set_to(to);
         }
 
         if let Some(ens) = ens_to {
-            tx = tx.to(ens)
+            tx.set_to(ens);
         }
 
         if let Some(gas) = gas {
-            tx = tx.gas(gas)
-        }
-
-        if let Some(gas_price) = gas_price {
-            tx = tx.gas_price(gas_price)
+            tx.set_gas(gas);
         }
 
         if let Some(value) = value {
-            tx = tx.value(value)
+            tx.set_value(value);
         }
 
         if let Some(data) = data {
-            tx = tx.data(data)
+            tx.set_data(data);
+        }
+
+        if let Some(sig) = sig {
+            tx.set_data(
+                encode_function_call(&sig, &args)
+                    .map_err(|err| Self::Error::InvalidFunctionCall(err.to_string()))?,
+            );
         }
 
         if let Some(nonce) = nonce {
-            tx = tx.nonce(nonce)
+            tx.set_nonce(nonce);
         }
 
         if let Some(chain_id) = chain_id {
-            tx = tx.chain_id(chain_id)
+            tx.set_chain_id(chain_id);
         }
 
         Ok(tx)
@@ -245,9 +415,9 @@ impl TryFrom<TypedTransactionArgs> for TransactionRequest {
 
 #[derive(Args, Debug)]
 pub struct GetAccountArgs {
-    /// Ethereum address for the account
+    /// Ethereum address for the account. Can be repeated by commands that accept multiple accounts
     #[arg(long, conflicts_with = "ens", required_unless_present = "ens")]
-    address: Option<H160>,
+    address: Vec<H160>,
 
     /// Ens name for the account
     #[arg(long)]
@@ -261,6 +431,16 @@ pub enum GetAccountParserError {
 
     #[error("Missing account identifier. An ens or address must be provided.")]
     MissingAccountId,
+
+    #[error("Provided {0} addresses but the command accepts a single account.")]
+    MultipleAccountIds(usize),
+}
+
+impl GetAccountArgs {
+    /// Addresses provided through repeated --address flags.
+    pub fn addresses(&self) -> &[H160] {
+        &self.address
+    }
 }
 
 impl TryFrom<GetAccountArgs> for NameOrAddress {
@@ -268,12 +448,14 @@ impl TryFrom<GetAccountArgs> for NameOrAddress {
 
     fn try_from(GetAccountArgs { address, ens }: GetAccountArgs) -> Result<Self, Self::Error> {
         // Sanity check
-        if address.is_some() && ens.is_some() {
+        if !address.is_empty() && ens.is_some() {
             return Err(Self::Error::ConflictingAccountId);
         }
 
-        if let Some(address) = address {
-            return Ok(NameOrAddress::Address(address));
+        match address.as_slice() {
+            [] => {}
+            [address] => return Ok(NameOrAddress::Address(*address)),
+            addresses => return Err(Self::Error::MultipleAccountIds(addresses.len())),
         };
 
         if let Some(ens) = ens {
@@ -283,3 +465,93 @@ impl TryFrom<GetAccountArgs> for NameOrAddress {
         Err(Self::Error::MissingAccountId)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::io::Write;
+
+    use ethers::types::{Bytes, TransactionReceipt, H160};
+    use serde::Serialize;
+    use serde_json::json;
+
+    use super::{
+        parse_bytes_arg, serialize_checksummed_list, serialize_with_checksummed_fields,
+        BytesArgParserError,
+    };
+
+    const BYTECODE: &str = "0x602a60005260206000f3";
+
+    #[test]
+    fn should_parse_the_bytes_from_the_argument_or_a_file() -> anyhow::Result<()> {
+        // Arrange
+        let path = std::env::temp_dir().join(format!("yaeth-bytecode-{}", std::process::id()));
+        writeln!(std::fs::File::create(&path)?, "{BYTECODE}")?;
+
+        let file_arg = format!("@{}", path.display());
+        let expected_res: Bytes = BYTECODE.parse()?;
+
+        for test_case in [BYTECODE, &file_arg] {
+            // Act
+            let res = parse_bytes_arg(test_case)?;
+
+            // Assert
+            assert_eq!(res, expected_res);
+        }
+
+        std::fs::remove_file(path)?;
+
+        Ok(())
+    }
+
+    #[test]
+    fn should_not_parse_invalid_bytes() {
+        // Setup
+        let test_cases = ["0xzz", "@/non/existing/file"];
+
+        for test_case in test_cases {
+            // Act
+            let res = parse_bytes_arg(test_case);
+
+            // Assert
+            assert!(matches!(
+                res,
+                Err(BytesArgParserError::InvalidBytes(_) | BytesArgParserError::UnreadableFile(..))
+            ));
+        }
+    }
+
+    #[test]
+    fn should_serialize_the_addresses_checksummed() -> anyhow::Result<()> {
+        // Arrange
+        #[derive(Serialize)]
+        struct Checksummed {
+            #[serde(serialize_with = "serialize_checksummed_list")]
+            accounts: Vec<H160>,
+            #[serde(serialize_with = "serialize_with_checksummed_fields")]
+            receipt: TransactionReceipt,
+        }
+
+        let address: H160 = "0xfb6916095ca1df60bb79ce92ce3ea74c37c5d359".parse()?;
+        let checksummed = "0xfB6916095ca1df60bB79Ce92cE3Ea74c37c5d359";
+
+        let value = Checksummed {
+            accounts: vec![address],
+            receipt: TransactionReceipt {
+                from: address,
+                to: Some(address),
+                ..Default::default()
+            },
+        };
+
+        // Act
+        let res = serde_json::to_value(value)?;
+
+        // Assert
+        assert_eq!(res["accounts"], json!([checksummed]));
+        assert_eq!(res["receipt"]["from"], checksummed);
+        assert_eq!(res["receipt"]["to"], checksummed);
+        assert_eq!(res["receipt"]["contractAddress"], json!(null));
+
+        Ok(())
+    }
+}
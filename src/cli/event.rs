@@ -0,0 +1,1643 @@
+use std::{path::PathBuf, time::Duration};
+
+use crate::{
+    cmd::{
+        self,
+        checkpoint::CheckpointStore,
+        event::{
+            decode_log, decode_logs, parse_event_signature, Erc20Transfer, Erc20TransfersFilter,
+            EventSignature, LogDecoder, LogWithDecoded, LogsExportTotals, LogsSummary, NewBlock,
+            PendingTx, LOGS_EXPORT_CHUNK_SIZE,
+        },
+        lookup::{Lookup, Missing},
+    },
+    context::CommandExecutionContext,
+    run::CsvTable,
+};
+
+use super::common::{
+    parse_lookup, parse_not_found, serialize_with_checksummed_fields, BlockIdParserError,
+    GetBlockByIdArgs, NotFoundResult, GET_BLOCK_BY_ID_ARG_GROUP_NAME,
+};
+use clap::{Args, Parser, Subcommand};
+use ethers::{
+    abi::{AbiParser, ParamType},
+    types::{BlockId, BlockNumber, Bytes, Filter, FilterBlockOption, Log, H160, H256, I256, U256},
+    utils::{keccak256, to_checksum},
+};
+use serde::Serialize;
+use thiserror::Error;
+
+#[derive(Parser, Debug)]
+#[command()]
+pub struct EventCommand {
+    #[command(subcommand)]
+    command: EventSubCommand,
+}
+
+impl EventCommand {
+    /// Tells if the command keeps running until interrupted.
+    pub fn is_long_running(&self) -> bool {
+        match &self.command {
+            EventSubCommand::Logs(args) => args.follow,
+            EventSubCommand::NewBlocks(args) => args.follow,
+            EventSubCommand::PendingTxs(args) => args.follow,
+            _ => false,
+        }
+    }
+}
+
+#[derive(Subcommand, Debug)]
+#[command()]
+pub enum EventSubCommand {
+    /// Gets the logs matching the provided filter
+    #[command(alias = "get")]
+    Logs(GetLogsArgs),
+
+    /// Gets the transfers of an ERC20 token, decoding their sender, recipient and value
+    Erc20Transfers(Erc20TransfersArgs),
+
+    /// Execute operations on the filters installed on the node
+    Filter(FilterCommand),
+
+    /// Gets the transactions entering the node mempool
+    PendingTxs(PendingTxsArgs),
+
+    /// Gets the blocks added to the chain
+    NewBlocks(NewBlocksArgs),
+}
+
+#[derive(Args, Debug)]
+pub struct NewBlocksArgs {
+    /// Keeps polling for new blocks, writing each one as a single json line until interrupted
+    #[arg(long)]
+    follow: bool,
+
+    /// Resolves the block hashes to their number, timestamp and transaction count
+    #[arg(long)]
+    resolve: bool,
+
+    /// Seconds between polls of the new blocks filter (websocket endpoints push the followed blocks instead)
+    #[arg(
+        long,
+        value_name = "SECONDS",
+        default_value_t = 1,
+        value_parser = clap::value_parser!(u64).range(1..)
+    )]
+    interval: u64,
+
+    /// Seconds to wait for at least one new block before giving up
+    #[arg(
+        long,
+        value_name = "SECONDS",
+        default_value_t = 30,
+        conflicts_with = "follow",
+        value_parser = clap::value_parser!(u64).range(1..)
+    )]
+    timeout: u64,
+}
+
+#[derive(Args, Debug)]
+pub struct PendingTxsArgs {
+    /// Keeps polling for new pending transactions, writing each one as a single json line until interrupted
+    #[arg(long)]
+    follow: bool,
+
+    /// Gets the full transactions instead of only their hashes
+    #[arg(long)]
+    full: bool,
+
+    /// Seconds between polls of the pending transactions filter
+    #[arg(
+        long,
+        value_name = "SECONDS",
+        default_value_t = 5,
+        value_parser = clap::value_parser!(u64).range(1..)
+    )]
+    interval: u64,
+}
+
+#[derive(Parser, Debug)]
+#[command()]
+pub struct FilterCommand {
+    #[command(subcommand)]
+    command: FilterSubCommand,
+}
+
+#[derive(Subcommand, Debug)]
+#[command()]
+pub enum FilterSubCommand {
+    /// Gets every log matching the installed filter with the provided id
+    Logs(FilterLogsArgs),
+}
+
+#[derive(Args, Debug)]
+pub struct FilterLogsArgs {
+    /// Id of the installed filter, either decimal or 0x prefixed hex
+    #[arg(value_parser = parse_filter_id)]
+    filter_id: U256,
+}
+
+#[derive(Error, Debug)]
+pub enum FilterIdParserError {
+    #[error("Invalid filter id {0}. Expected a decimal or 0x prefixed hex number.")]
+    InvalidFilterId(String),
+}
+
+fn parse_filter_id(value: &str) -> Result<U256, FilterIdParserError> {
+    match value.strip_prefix("0x") {
+        Some(hex_value) => U256::from_str_radix(hex_value, 16).ok(),
+        None => U256::from_dec_str(value).ok(),
+    }
+    .ok_or(FilterIdParserError::InvalidFilterId(value.to_string()))
+}
+
+#[derive(Args, Debug)]
+pub struct GetLogsArgs {
+    /// First block of the range (number or tag)
+    #[arg(long, value_name = "BLOCK_ID", conflicts_with = GET_BLOCK_BY_ID_ARG_GROUP_NAME)]
+    from_block: Option<BlockNumber>,
+
+    /// Last block of the range (number or tag)
+    #[arg(long, value_name = "BLOCK_ID", conflicts_with = GET_BLOCK_BY_ID_ARG_GROUP_NAME)]
+    to_block: Option<BlockNumber>,
+
+    /// Single block to get the logs from
+    #[clap(flatten)]
+    get_block_by_id: GetBlockByIdArgs,
+
+    /// Address of the contract that emitted the logs. Can be repeated to match any of the addresses
+    #[arg(long)]
+    address: Vec<H160>,
+
+    /// First topic of the logs, usually the event signature hash
+    #[arg(long)]
+    topic0: Option<H256>,
+
+    /// Event signature whose hash is used as first topic, e.g. "Transfer(address indexed from,address indexed to,uint256 value)". The matching logs are decoded with it unless the ABI covers the event
+    #[arg(long, value_name = "SIGNATURE", value_parser = parse_filter_event, conflicts_with = "topic0")]
+    event: Option<EventSignature>,
+
+    /// Value of an indexed parameter of the event, e.g. "from=0xf39f...", encoded into its topic. Can be repeated, values of the same parameter match any of them
+    #[arg(long = "arg", value_name = "NAME=VALUE", value_parser = parse_event_arg, requires = "event")]
+    event_args: Vec<EventArg>,
+
+    /// Second topic of the logs
+    #[arg(long)]
+    topic1: Option<H256>,
+
+    /// Third topic of the logs
+    #[arg(long)]
+    topic2: Option<H256>,
+
+    /// Fourth topic of the logs
+    #[arg(long)]
+    topic3: Option<H256>,
+
+    /// Splits the block range in queries of at most this many blocks, bisecting the ones the node refuses to answer because of too many results
+    #[arg(long, value_name = "BLOCKS", value_parser = clap::value_parser!(u64).range(1..))]
+    chunk_size: Option<u64>,
+
+    /// Json ABI (or compiler artifact) used to decode the logs emitted by its events
+    #[arg(long, value_name = "PATH")]
+    abi: Option<PathBuf>,
+
+    /// Json file mapping 0x prefixed topic0 hashes to event signatures, used to decode the logs that the ABI (if any) doesn't cover
+    #[arg(long, value_name = "PATH")]
+    topics_db: Option<PathBuf>,
+
+    /// Keeps polling for new logs, writing each one as a single json line until interrupted. Websocket endpoints write the logs removed by a reorg again with `"removed": true`
+    #[arg(long, conflicts_with_all = ["to_block", "chunk_size", GET_BLOCK_BY_ID_ARG_GROUP_NAME])]
+    follow: bool,
+
+    /// File recording the last scanned block, so that the next run only gets the logs of the newer blocks
+    #[arg(
+        long,
+        value_name = "PATH",
+        conflicts_with_all = ["follow", GET_BLOCK_BY_ID_ARG_GROUP_NAME]
+    )]
+    since_block_file: Option<PathBuf>,
+
+    /// Appends the logs to this file as json lines one chunk at a time, followed by a line holding the totals, for scans whose logs don't fit in memory
+    #[arg(
+        long,
+        value_name = "PATH",
+        conflicts_with_all = ["follow", "summary", "since_block_file", GET_BLOCK_BY_ID_ARG_GROUP_NAME]
+    )]
+    export: Option<PathBuf>,
+
+    /// Continues an interrupted export from the last block recorded in its file
+    #[arg(long, requires = "export")]
+    resume: bool,
+
+    /// Returns the number of logs, the first and last block and the number of distinct first topics of each contract instead of the logs
+    #[arg(long, conflicts_with_all = ["abi", "topics_db", "follow", "since_block_file"])]
+    summary: bool,
+
+    /// Seconds between polls when following the logs (websocket endpoints push the new logs instead)
+    #[arg(
+        long,
+        value_name = "SECONDS",
+        default_value_t = 5,
+        requires = "follow",
+        value_parser = clap::value_parser!(u64).range(1..)
+    )]
+    interval: u64,
+}
+
+#[derive(Args, Debug)]
+pub struct Erc20TransfersArgs {
+    /// Address of the token contract
+    #[arg(long)]
+    token: H160,
+
+    /// Only includes the transfers sent by this address
+    #[arg(long)]
+    from: Option<H160>,
+
+    /// Only includes the transfers received by this address
+    #[arg(long)]
+    to: Option<H160>,
+
+    /// First block of the range (number or tag)
+    #[arg(long, value_name = "BLOCK_ID")]
+    from_block: BlockNumber,
+
+    /// Last block of the range (number or tag)
+    #[arg(long, value_name = "BLOCK_ID")]
+    to_block: BlockNumber,
+}
+
+// Every transfer of a popular token over a wide range can easily be hundreds of thousands of logs
+const MAX_UNFILTERED_TRANSFERS_BLOCK_RANGE: u64 = 1_000;
+
+#[derive(Error, Debug)]
+pub enum Erc20TransfersParserError {
+    #[error("Block range of {0} blocks is too wide to get every transfer of the token. Provide --from or --to, or narrow the range to at most {MAX_UNFILTERED_TRANSFERS_BLOCK_RANGE} blocks.")]
+    UnfilteredRangeTooWide(u64),
+}
+
+impl TryFrom<Erc20TransfersArgs> for Erc20TransfersFilter {
+    type Error = Erc20TransfersParserError;
+
+    fn try_from(value: Erc20TransfersArgs) -> Result<Self, Self::Error> {
+        let Erc20TransfersArgs {
+            token,
+            from,
+            to,
+            from_block,
+            to_block,
+        } = value;
+
+        // Ranges bounded by tags are only known once resolved, get_logs checks them against the
+        // max block range limit then
+        if let (None, None, Some(from_block), Some(to_block)) =
+            (from, to, from_block.as_number(), to_block.as_number())
+        {
+            let range_len = (to_block.as_u64() + 1).saturating_sub(from_block.as_u64());
+
+            if range_len > MAX_UNFILTERED_TRANSFERS_BLOCK_RANGE {
+                return Err(Self::Error::UnfilteredRangeTooWide(range_len));
+            }
+        }
+
+        Ok(Self {
+            token,
+            from,
+            to,
+            from_block,
+            to_block,
+        })
+    }
+}
+
+#[derive(Error, Debug)]
+pub enum EventSignatureParserError {
+    #[error("Invalid event signature {0}: {1}")]
+    InvalidSignature(String, String),
+}
+
+/// Computes the topic0 of the event with the provided human readable signature. Parameter names,
+/// `indexed` markers and whitespace are ignored, e.g. `Transfer(address indexed from, address to,
+/// uint256)` hashes to the same topic as `Transfer(address,address,uint256)`.
+pub fn parse_event_topic(value: &str) -> Result<H256, EventSignatureParserError> {
+    let signature = value.trim();
+    let signature = signature.strip_prefix("event ").unwrap_or(signature);
+
+    AbiParser::default()
+        .parse_event(&format!("event {signature}"))
+        .map(|event| event.signature())
+        .map_err(|err| {
+            EventSignatureParserError::InvalidSignature(value.to_string(), err.to_string())
+        })
+}
+
+/// Parses the event signature the logs are filtered and decoded with. Unlike the topic only
+/// signatures, parameter names and `indexed` markers are kept to decode the logs.
+pub fn parse_filter_event(value: &str) -> Result<EventSignature, EventSignatureParserError> {
+    parse_event_signature(value).map_err(|err| {
+        EventSignatureParserError::InvalidSignature(value.to_string(), err.to_string())
+    })
+}
+
+/// Value of a named event parameter the logs are filtered on.
+#[derive(Debug, Clone, PartialEq)]
+pub struct EventArg {
+    name: String,
+    value: String,
+}
+
+#[derive(Error, Debug)]
+pub enum EventArgParserError {
+    #[error("Invalid event argument {0}. Expected NAME=VALUE, e.g. from=0xf39f...")]
+    InvalidEventArg(String),
+}
+
+fn parse_event_arg(value: &str) -> Result<EventArg, EventArgParserError> {
+    value
+        .split_once('=')
+        .map(|(name, arg_value)| (name.trim(), arg_value.trim()))
+        .filter(|(name, arg_value)| !name.is_empty() && !arg_value.is_empty())
+        .map(|(name, arg_value)| EventArg {
+            name: name.to_string(),
+            value: arg_value.to_string(),
+        })
+        .ok_or(EventArgParserError::InvalidEventArg(value.to_string()))
+}
+
+/// Encodes the value of an indexed parameter into its topic. Value types are abi encoded while
+/// strings and bytes are matched by the hash of their content, which is what solidity indexes.
+fn encode_topic(kind: &ParamType, value: &str) -> Result<H256, String> {
+    let parse_bytes = || {
+        value
+            .parse::<Bytes>()
+            .map_err(|_| String::from("expected 0x prefixed hex bytes"))
+    };
+
+    match kind {
+        ParamType::Address => value
+            .parse::<H160>()
+            .map(H256::from)
+            .map_err(|_| String::from("expected a 0x prefixed address")),
+        ParamType::Uint(size) => {
+            let number = match value.strip_prefix("0x") {
+                Some(hex_value) => U256::from_str_radix(hex_value, 16).ok(),
+                None => U256::from_dec_str(value).ok(),
+            }
+            .ok_or(String::from("expected a decimal or 0x prefixed hex number"))?;
+
+            if number.bits() > *size {
+                return Err(format!("does not fit in uint{size}"));
+            }
+
+            let mut topic = H256::zero();
+            number.to_big_endian(topic.as_bytes_mut());
+
+            Ok(topic)
+        }
+        ParamType::Int(size) => {
+            let number = match value.strip_prefix("0x") {
+                Some(_) => I256::from_hex_str(value).ok(),
+                None => I256::from_dec_str(value).ok(),
+            }
+            .ok_or(String::from("expected a decimal or 0x prefixed hex number"))?;
+
+            if *size < 256 {
+                let bound = I256::from_raw(U256::one() << (size - 1));
+                if number < -bound || number >= bound {
+                    return Err(format!("does not fit in int{size}"));
+                }
+            }
+
+            let mut topic = H256::zero();
+            number.into_raw().to_big_endian(topic.as_bytes_mut());
+
+            Ok(topic)
+        }
+        ParamType::Bool => match value {
+            "true" => Ok(H256::from_low_u64_be(1)),
+            "false" => Ok(H256::zero()),
+            _ => Err(String::from("expected true or false")),
+        },
+        ParamType::FixedBytes(size) => {
+            let bytes = parse_bytes()?;
+            if bytes.len() != *size {
+                return Err(format!("expected {size} bytes, got {}", bytes.len()));
+            }
+
+            let mut topic = H256::zero();
+            topic[..*size].copy_from_slice(&bytes);
+
+            Ok(topic)
+        }
+        ParamType::String => Ok(H256(keccak256(value))),
+        ParamType::Bytes => Ok(H256(keccak256(parse_bytes()?))),
+        ParamType::Array(_) | ParamType::FixedArray(..) | ParamType::Tuple(_) => Err(format!(
+            "filtering on {kind} values is not supported, use the topic of the value hash instead"
+        )),
+    }
+}
+
+/// Topics the event arguments are encoded into, keyed by their position in the filter. Arguments
+/// can't be matched against the topics already provided explicitly.
+fn encode_event_args(
+    event: &EventSignature,
+    event_args: &[EventArg],
+    topics: &[Option<H256>; 4],
+) -> Result<[Vec<H256>; 4], GetLogsParserError> {
+    let mut arg_topics: [Vec<H256>; 4] = Default::default();
+
+    for EventArg { name, value } in event_args {
+        let (position, param) = event
+            .params
+            .iter()
+            .filter(|param| param.indexed)
+            .enumerate()
+            .find(|(_, param)| param.name == *name)
+            .ok_or_else(
+                || match event.params.iter().any(|param| param.name == *name) {
+                    true => {
+                        GetLogsParserError::EventArgNotIndexed(name.clone(), event.name.clone())
+                    }
+                    false => GetLogsParserError::UnknownEventArg(name.clone(), event.name.clone()),
+                },
+            )?;
+
+        // The first topic holds the event signature hash
+        let position = position + 1;
+        if topics[position].is_some() {
+            return Err(GetLogsParserError::ConflictingEventArg(
+                position,
+                name.clone(),
+            ));
+        }
+
+        let topic = encode_topic(&param.kind, value).map_err(|reason| {
+            GetLogsParserError::InvalidEventArgValue(name.clone(), value.clone(), reason)
+        })?;
+
+        arg_topics[position].push(topic);
+    }
+
+    Ok(arg_topics)
+}
+
+/// Providers reject or time out on filters matching huge address sets.
+const MAX_FILTER_ADDRESSES: usize = 1_000;
+
+#[derive(Error, Debug)]
+pub enum GetLogsParserError {
+    #[error(
+        "Provided multiple block identifiers. Only a block tag, number or hash must be provided."
+    )]
+    ConflictingBlockId,
+    #[error("Provided {0} addresses but a filter can match at most {MAX_FILTER_ADDRESSES}. Split the addresses across several queries")]
+    TooManyAddresses(usize),
+    #[error("Event {1} has no parameter named {0}")]
+    UnknownEventArg(String, String),
+    #[error("Parameter {0} of event {1} is not indexed. Only indexed parameters can be filtered on, mark them as indexed in the event signature or provide the ABI")]
+    EventArgNotIndexed(String, String),
+    #[error("Invalid value {1} for parameter {0}: {2}")]
+    InvalidEventArgValue(String, String, String),
+    #[error("Provided both --topic{0} and the event argument {1}, which are matched against the same topic")]
+    ConflictingEventArg(usize, String),
+}
+
+impl TryFrom<GetLogsArgs> for Filter {
+    type Error = GetLogsParserError;
+
+    fn try_from(value: GetLogsArgs) -> Result<Self, Self::Error> {
+        let GetLogsArgs {
+            from_block,
+            to_block,
+            get_block_by_id,
+            address,
+            topic0,
+            event,
+            event_args,
+            topic1,
+            topic2,
+            topic3,
+            chunk_size: _,
+            abi: _,
+            topics_db: _,
+            follow: _,
+            since_block_file: _,
+            export: _,
+            resume: _,
+            summary: _,
+            interval: _,
+        } = value;
+
+        let mut filter = match BlockId::try_from(get_block_by_id) {
+            Ok(BlockId::Hash(hash)) => Filter::new().at_block_hash(hash),
+            Ok(BlockId::Number(number)) => Filter::new().select(number),
+            Err(BlockIdParserError::ConflictingBlockId) => {
+                return Err(Self::Error::ConflictingBlockId)
+            }
+            Err(BlockIdParserError::MissingBlockId) => {
+                let mut filter = Filter::new();
+
+                if let Some(from_block) = from_block {
+                    filter = filter.from_block(from_block);
+                }
+
+                if let Some(to_block) = to_block {
+                    filter = filter.to_block(to_block);
+                }
+
+                filter
+            }
+        };
+
+        match address.len() {
+            len if len > MAX_FILTER_ADDRESSES => return Err(Self::Error::TooManyAddresses(len)),
+            0 => {}
+            1 => filter = filter.address(address[0]),
+            _ => filter = filter.address(address),
+        }
+
+        let topics = [
+            topic0.or(event.as_ref().map(EventSignature::topic0)),
+            topic1,
+            topic2,
+            topic3,
+        ];
+        let arg_topics = match &event {
+            Some(event) => encode_event_args(event, &event_args, &topics)?,
+            None => Default::default(),
+        };
+
+        for (idx, (topic, mut arg_topics)) in topics.into_iter().zip(arg_topics).enumerate() {
+            filter.topics[idx] = match (topic, arg_topics.len()) {
+                (Some(topic), _) => Some(topic.into()),
+                (None, 0) => None,
+                (None, 1) => arg_topics.pop().map(Into::into),
+                (None, _) => Some(arg_topics.into()),
+            };
+        }
+
+        Ok(filter)
+    }
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum EventNamespaceResult {
+    #[serde(serialize_with = "serialize_with_checksummed_fields")]
+    Logs(Vec<Log>),
+    #[serde(rename = "logs", serialize_with = "serialize_with_checksummed_fields")]
+    DecodedLogs(Vec<LogWithDecoded>),
+    LogsSummary(LogsSummary),
+    #[serde(serialize_with = "serialize_with_checksummed_fields")]
+    Transfers(Vec<Erc20Transfer>),
+    PendingTransactions(Vec<PendingTx>),
+    NewBlocks(Vec<NewBlock>),
+    // The results were already written while following them
+    Followed,
+    Exported(LogsExportTotals),
+    #[serde(serialize_with = "parse_not_found", rename = "block")]
+    NotFound(),
+}
+
+impl NotFoundResult for EventNamespaceResult {
+    fn not_found(_what: Missing) -> Self {
+        EventNamespaceResult::NotFound()
+    }
+}
+
+const LOGS_SUMMARY_CSV_HEADERS: [&str; 5] = [
+    "address",
+    "log_count",
+    "first_block",
+    "last_block",
+    "distinct_topic0_count",
+];
+
+const LOG_CSV_HEADERS: [&str; 10] = [
+    "block_number",
+    "block_hash",
+    "tx_hash",
+    "log_index",
+    "address",
+    "topic0",
+    "topic1",
+    "topic2",
+    "topic3",
+    "data",
+];
+
+impl EventNamespaceResult {
+    /// Flattens the queried logs into one csv row per log, or per contract for a logs summary.
+    pub fn csv_table(&self) -> Option<CsvTable> {
+        if let EventNamespaceResult::LogsSummary(summary) = self {
+            let rows = summary
+                .0
+                .iter()
+                .map(|(address, summary)| {
+                    vec![
+                        to_checksum(address, None),
+                        summary.log_count.to_string(),
+                        summary.first_block.to_string(),
+                        summary.last_block.to_string(),
+                        summary.distinct_topic0_count.to_string(),
+                    ]
+                })
+                .collect();
+
+            return Some(CsvTable::new(
+                LOGS_SUMMARY_CSV_HEADERS.map(String::from).to_vec(),
+                rows,
+            ));
+        }
+
+        let rows = match self {
+            EventNamespaceResult::Logs(logs) => logs.iter().map(log_csv_row).collect(),
+            EventNamespaceResult::DecodedLogs(logs) => {
+                logs.iter().map(|log| log_csv_row(&log.log)).collect()
+            }
+            _ => return None,
+        };
+
+        Some(CsvTable::new(
+            LOG_CSV_HEADERS.map(String::from).to_vec(),
+            rows,
+        ))
+    }
+}
+
+fn log_csv_row(log: &Log) -> Vec<String> {
+    let mut row = vec![
+        log.block_number.map(|n| n.to_string()).unwrap_or_default(),
+        log.block_hash.map(|h| format!("{h:?}")).unwrap_or_default(),
+        log.transaction_hash
+            .map(|h| format!("{h:?}"))
+            .unwrap_or_default(),
+        log.log_index.map(|i| i.to_string()).unwrap_or_default(),
+        to_checksum(&log.address, None),
+    ];
+    row.extend((0..4).map(|i| {
+        log.topics
+            .get(i)
+            .map(|topic| format!("{topic:?}"))
+            .unwrap_or_default()
+    }));
+    row.push(log.data.to_string());
+
+    row
+}
+
+/// Replaces the filtered event with its ABI definition, if any, which tells the parameters that
+/// the event arguments can be matched against.
+fn with_abi_event(mut args: GetLogsArgs, decoder: Option<&LogDecoder>) -> GetLogsArgs {
+    let abi_event = args
+        .event
+        .as_ref()
+        .zip(decoder)
+        .and_then(|(event, decoder)| decoder.abi_event(event.topic0()));
+
+    if abi_event.is_some() {
+        args.event = abi_event;
+    }
+
+    args
+}
+
+pub fn parse(
+    context: &CommandExecutionContext,
+    sub_command: EventCommand,
+) -> Result<EventNamespaceResult, anyhow::Error> {
+    let node_provider = context.node_provider();
+
+    let res: EventNamespaceResult = match sub_command.command {
+        EventSubCommand::Logs(get_logs_args) if get_logs_args.follow => {
+            let interval = Duration::from_secs(get_logs_args.interval);
+            let decoder = LogDecoder::load(
+                get_logs_args.abi.as_deref(),
+                get_logs_args.topics_db.as_deref(),
+                get_logs_args.event.clone(),
+            )?;
+            let filter = with_abi_event(get_logs_args, decoder.as_ref()).try_into()?;
+
+            context
+                .execute(async {
+                    tokio::select! {
+                        res = cmd::event::follow_logs(node_provider, filter, interval, |log| {
+                            match &decoder {
+                                Some(decoder) => {
+                                    context.stream_output().write(&decode_log(decoder, log))
+                                }
+                                None => context.stream_output().write(&log),
+                            }
+                        }) => res,
+                        res = tokio::signal::ctrl_c() => Ok(res?),
+                    }
+                })
+                .map(|_| EventNamespaceResult::Followed)
+        }
+        EventSubCommand::Logs(get_logs_args) if get_logs_args.export.is_some() => {
+            let chunk_size = get_logs_args.chunk_size.unwrap_or(LOGS_EXPORT_CHUNK_SIZE);
+            let resume = get_logs_args.resume;
+            let decoder = LogDecoder::load(
+                get_logs_args.abi.as_deref(),
+                get_logs_args.topics_db.as_deref(),
+                get_logs_args.event.clone(),
+            )?;
+            let path = get_logs_args.export.clone().unwrap_or_default();
+            let filter = with_abi_event(get_logs_args, decoder.as_ref()).try_into()?;
+
+            context
+                .execute(cmd::event::export_logs(
+                    node_provider,
+                    filter,
+                    decoder.as_ref(),
+                    context.config().max_block_range(),
+                    chunk_size,
+                    &path,
+                    resume,
+                ))
+                .map(EventNamespaceResult::Exported)
+        }
+        EventSubCommand::Logs(get_logs_args) if get_logs_args.summary => {
+            let chunk_size = get_logs_args.chunk_size;
+            let filter = get_logs_args.try_into()?;
+
+            context
+                .execute(cmd::event::get_logs_summary(
+                    node_provider,
+                    filter,
+                    context.config().max_block_range(),
+                    chunk_size,
+                ))
+                .map(EventNamespaceResult::LogsSummary)
+        }
+        EventSubCommand::Logs(get_logs_args) => {
+            let chunk_size = get_logs_args.chunk_size;
+            let decoder = LogDecoder::load(
+                get_logs_args.abi.as_deref(),
+                get_logs_args.topics_db.as_deref(),
+                get_logs_args.event.clone(),
+            )?;
+            let checkpoint_store = get_logs_args
+                .since_block_file
+                .as_deref()
+                .map(CheckpointStore::new);
+            let filter: Filter = with_abi_event(get_logs_args, decoder.as_ref()).try_into()?;
+            let max_block_range = context.config().max_block_range();
+
+            context
+                .execute(async {
+                    match (&checkpoint_store, &filter.block_option) {
+                        (Some(checkpoint_store), _) => cmd::event::get_logs_since_checkpoint(
+                            node_provider,
+                            filter,
+                            checkpoint_store,
+                            max_block_range,
+                            chunk_size,
+                        )
+                        .await
+                        .map(Lookup::Found),
+                        (None, FilterBlockOption::AtBlockHash(_)) => {
+                            cmd::event::get_logs_at_block_hash(node_provider, filter).await
+                        }
+                        (None, FilterBlockOption::Range { .. }) => {
+                            cmd::event::get_logs(node_provider, filter, max_block_range, chunk_size)
+                                .await
+                                .map(Lookup::Found)
+                        }
+                    }
+                })
+                .map(|lookup| {
+                    parse_lookup(lookup, |logs| match decoder {
+                        Some(decoder) => {
+                            EventNamespaceResult::DecodedLogs(decode_logs(&decoder, logs))
+                        }
+                        None => EventNamespaceResult::Logs(logs),
+                    })
+                })
+        }
+        EventSubCommand::Erc20Transfers(erc20_transfers_args) => context
+            .execute(cmd::event::get_erc20_transfers(
+                node_provider,
+                erc20_transfers_args.try_into()?,
+                context.config().max_block_range(),
+            ))
+            .map(EventNamespaceResult::Transfers),
+        EventSubCommand::Filter(FilterCommand {
+            command: FilterSubCommand::Logs(FilterLogsArgs { filter_id }),
+        }) => context
+            .execute(cmd::event::get_filter_logs(node_provider, filter_id))
+            .map(EventNamespaceResult::Logs),
+        EventSubCommand::PendingTxs(PendingTxsArgs {
+            follow: true,
+            full,
+            interval,
+        }) => context
+            .execute(cmd::event::follow_pending_transactions(
+                node_provider,
+                Duration::from_secs(interval),
+                full,
+                async { Ok(tokio::signal::ctrl_c().await?) },
+                |tx| context.stream_output().write(&tx),
+            ))
+            .map(|_| EventNamespaceResult::Followed),
+        EventSubCommand::PendingTxs(PendingTxsArgs {
+            follow: false,
+            full,
+            interval,
+        }) => context
+            .execute(cmd::event::get_pending_transactions(
+                node_provider,
+                Duration::from_secs(interval),
+                full,
+            ))
+            .map(EventNamespaceResult::PendingTransactions),
+        EventSubCommand::NewBlocks(NewBlocksArgs {
+            follow: true,
+            resolve,
+            interval,
+            timeout: _,
+        }) => context
+            .execute(cmd::event::follow_new_blocks(
+                node_provider,
+                Duration::from_secs(interval),
+                resolve,
+                async { Ok(tokio::signal::ctrl_c().await?) },
+                |block| context.stream_output().write(&block),
+            ))
+            .map(|_| EventNamespaceResult::Followed),
+        EventSubCommand::NewBlocks(NewBlocksArgs {
+            follow: false,
+            resolve,
+            interval,
+            timeout,
+        }) => context
+            .execute(cmd::event::get_new_blocks(
+                node_provider,
+                Duration::from_secs(interval),
+                Duration::from_secs(timeout),
+                resolve,
+            ))
+            .map(EventNamespaceResult::NewBlocks),
+    }?;
+
+    Ok(res)
+}
+
+#[cfg(test)]
+mod tests {
+    use clap::Parser;
+    use ethers::{
+        abi::ParamType,
+        types::{BlockNumber, Filter, FilterBlockOption, Log, ValueOrArray, H160, H256, U256},
+        utils::keccak256,
+    };
+
+    use crate::cmd::event::Erc20TransfersFilter;
+
+    use super::{
+        encode_topic, parse_event_topic, parse_filter_id, EventCommand, EventNamespaceResult,
+        EventSignatureParserError, EventSubCommand, FilterCommand, FilterSubCommand,
+        MAX_FILTER_ADDRESSES,
+    };
+
+    const TOPIC: &str = "0xddf252ad1be2c89b69c2b068fc378daa952ba7f163c4a11628f55a4df523b3ef";
+
+    #[test]
+    fn should_serialize_the_log_addresses_checksummed() -> anyhow::Result<()> {
+        // Arrange
+        let log = Log {
+            address: "0xfb6916095ca1df60bb79ce92ce3ea74c37c5d359".parse()?,
+            ..Default::default()
+        };
+
+        // Act
+        let res = serde_json::to_value(EventNamespaceResult::Logs(vec![log]))?;
+
+        // Assert
+        assert_eq!(
+            res["logs"][0]["address"],
+            "0xfB6916095ca1df60bB79Ce92cE3Ea74c37c5d359"
+        );
+
+        Ok(())
+    }
+
+    fn parse_filter(args: &[&str]) -> anyhow::Result<Filter> {
+        let res = EventCommand::try_parse_from(["event", "logs"].iter().chain(args))?;
+
+        let EventSubCommand::Logs(args) = res.command else {
+            anyhow::bail!("Expected the logs command");
+        };
+
+        Ok(args.try_into()?)
+    }
+
+    #[test]
+    fn should_build_a_block_range_filter() -> anyhow::Result<()> {
+        // Act
+        let res = parse_filter(&["--from-block", "10", "--to-block", "latest"])?;
+
+        // Assert
+        assert_eq!(
+            res.block_option,
+            FilterBlockOption::Range {
+                from_block: Some(BlockNumber::Number(10.into())),
+                to_block: Some(BlockNumber::Latest),
+            }
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn should_build_a_block_hash_filter() -> anyhow::Result<()> {
+        // Arrange
+        let block_hash = H256::repeat_byte(0xaa);
+
+        // Act
+        let res = parse_filter(&["--block-hash", &format!("{block_hash:?}")])?;
+        let conflicting_res = parse_filter(&[
+            "--block-hash",
+            &format!("{block_hash:?}"),
+            "--to-block",
+            "latest",
+        ]);
+
+        // Assert
+        assert_eq!(res.block_option, FilterBlockOption::AtBlockHash(block_hash));
+        assert!(conflicting_res.is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn should_serialize_an_unknown_block_as_not_found() -> anyhow::Result<()> {
+        // Act
+        let res = serde_json::to_value(EventNamespaceResult::NotFound())?;
+
+        // Assert
+        assert_eq!(res, serde_json::json!({ "block": null }));
+
+        Ok(())
+    }
+
+    #[test]
+    fn should_not_summarize_followed_or_decoded_logs() {
+        // Setup
+        let test_cases = [
+            vec!["--summary", "--follow"],
+            vec!["--summary", "--abi", "abi.json"],
+            vec!["--summary", "--topics-db", "topics.json"],
+            vec!["--summary", "--since-block-file", "checkpoint.json"],
+        ];
+
+        for args in test_cases {
+            // Act
+            let res = EventCommand::try_parse_from(["event", "logs"].iter().chain(args.iter()));
+
+            // Assert
+            assert!(res.is_err());
+        }
+    }
+
+    #[test]
+    fn should_build_a_single_block_filter() -> anyhow::Result<()> {
+        // Arrange
+        let hash = H256::repeat_byte(0x01);
+
+        // Act
+        let number_filter = parse_filter(&["--number", "10"])?;
+        let hash_filter = parse_filter(&["--hash", &format!("{hash:?}")])?;
+
+        // Assert
+        assert_eq!(
+            number_filter.block_option,
+            FilterBlockOption::Range {
+                from_block: Some(BlockNumber::Number(10.into())),
+                to_block: Some(BlockNumber::Number(10.into())),
+            }
+        );
+        assert_eq!(
+            hash_filter.block_option,
+            FilterBlockOption::AtBlockHash(hash)
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn should_build_the_topic_filters() -> anyhow::Result<()> {
+        // Act
+        let res = parse_filter(&["--topic0", TOPIC, "--topic2", TOPIC])?;
+
+        // Assert
+        let topic: H256 = TOPIC.parse()?;
+
+        assert_eq!(res.topics[0], Some(topic.into()));
+        assert_eq!(res.topics[1], None);
+        assert_eq!(res.topics[2], Some(topic.into()));
+        assert_eq!(res.topics[3], None);
+
+        Ok(())
+    }
+
+    #[test]
+    fn should_not_mix_a_single_block_with_a_block_range() {
+        // Act
+        let res = parse_filter(&["--number", "10", "--from-block", "1"]);
+
+        // Assert
+        assert!(res.is_err());
+    }
+
+    #[test]
+    fn should_build_the_address_filter() -> anyhow::Result<()> {
+        // Arrange
+        let token = H160::repeat_byte(0x01);
+        let other_token = H160::repeat_byte(0x02);
+
+        // Act
+        let single_address_filter = parse_filter(&["--address", &format!("{token:?}")])?;
+        let multiple_addresses_filter = parse_filter(&[
+            "--address",
+            &format!("{token:?}"),
+            "--address",
+            &format!("{other_token:?}"),
+        ])?;
+        let no_address_filter = parse_filter(&[])?;
+
+        // Assert
+        assert_eq!(
+            single_address_filter.address,
+            Some(ValueOrArray::Value(token))
+        );
+        assert_eq!(
+            multiple_addresses_filter.address,
+            Some(ValueOrArray::Array(vec![token, other_token]))
+        );
+        assert_eq!(no_address_filter.address, None);
+
+        Ok(())
+    }
+
+    #[test]
+    fn should_not_build_a_filter_above_the_address_cap() -> anyhow::Result<()> {
+        // Setup
+        let test_cases = [
+            (MAX_FILTER_ADDRESSES, true),
+            (MAX_FILTER_ADDRESSES + 1, false),
+        ];
+
+        for (address_count, expected_ok) in test_cases {
+            let addresses: Vec<String> = (0..address_count)
+                .map(|idx| format!("{:?}", H160::from_low_u64_be(idx as u64)))
+                .collect();
+            let args: Vec<&str> = addresses
+                .iter()
+                .flat_map(|address| ["--address", address.as_str()])
+                .collect();
+
+            // Act
+            let res = parse_filter(&args);
+
+            // Assert
+            assert_eq!(res.is_ok(), expected_ok);
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn should_only_accept_a_positive_chunk_size() {
+        // Setup
+        let test_cases = [("0", false), ("1", true), ("2000", true)];
+
+        for (chunk_size, is_valid) in test_cases {
+            // Act
+            let res = EventCommand::try_parse_from(["event", "logs", "--chunk-size", chunk_size]);
+
+            // Assert
+            assert_eq!(res.is_ok(), is_valid);
+        }
+    }
+
+    #[test]
+    fn should_only_follow_open_ended_block_ranges() {
+        // Setup
+        let test_cases: [(&[&str], bool); 7] = [
+            (&["--follow"], true),
+            (&["--follow", "--from-block", "10", "--interval", "2"], true),
+            (&["--interval", "2"], false),
+            (&["--follow", "--interval", "0"], false),
+            (&["--follow", "--to-block", "10"], false),
+            (&["--follow", "--number", "10"], false),
+            (&["--follow", "--chunk-size", "10"], false),
+        ];
+
+        for (args, is_valid) in test_cases {
+            // Act
+            let res = EventCommand::try_parse_from(["event", "logs"].iter().chain(args));
+
+            // Assert
+            assert_eq!(res.is_ok(), is_valid, "{args:?}");
+        }
+    }
+
+    #[test]
+    fn should_only_time_out_when_not_following_new_blocks() {
+        // Setup
+        let test_cases: [(&[&str], bool); 5] = [
+            (&[], true),
+            (&["--timeout", "5", "--resolve"], true),
+            (&["--follow", "--interval", "2"], true),
+            (&["--follow", "--timeout", "5"], false),
+            (&["--timeout", "0"], false),
+        ];
+
+        for (args, is_valid) in test_cases {
+            // Act
+            let res = EventCommand::try_parse_from(["event", "new-blocks"].iter().chain(args));
+
+            // Assert
+            assert_eq!(res.is_ok(), is_valid, "{args:?}");
+        }
+    }
+
+    #[test]
+    fn should_only_resume_block_range_scans() {
+        // Setup
+        let test_cases: [(&[&str], bool); 5] = [
+            (&["--since-block-file", "checkpoint.json"], true),
+            (
+                &[
+                    "--since-block-file",
+                    "checkpoint.json",
+                    "--from-block",
+                    "10",
+                    "--to-block",
+                    "20",
+                ],
+                true,
+            ),
+            (
+                &["--since-block-file", "checkpoint.json", "--follow"],
+                false,
+            ),
+            (
+                &["--since-block-file", "checkpoint.json", "--number", "10"],
+                false,
+            ),
+            (&["--since-block-file"], false),
+        ];
+
+        for (args, is_valid) in test_cases {
+            // Act
+            let res = EventCommand::try_parse_from(["event", "logs"].iter().chain(args));
+
+            // Assert
+            assert_eq!(res.is_ok(), is_valid, "{args:?}");
+        }
+    }
+
+    #[test]
+    fn should_hash_the_normalized_event_signature() -> anyhow::Result<()> {
+        // Arrange
+        let expected_topic: H256 = TOPIC.parse()?;
+
+        let signatures = [
+            "Transfer(address,address,uint256)",
+            "Transfer(address indexed from, address indexed to, uint256 value)",
+            "  event Transfer( address from,address to , uint value )",
+        ];
+
+        for signature in signatures {
+            // Act
+            let res = parse_event_topic(signature);
+
+            // Assert
+            assert_eq!(res?, expected_topic, "{signature}");
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn should_not_hash_an_invalid_event_signature() {
+        // Arrange
+        let signatures = [
+            "Transfer(address,address,uint256",
+            "Transfer(adress,address,uint256)",
+            "Transfer",
+            "",
+        ];
+
+        for signature in signatures {
+            // Act
+            let res = parse_event_topic(signature);
+
+            // Assert
+            assert!(
+                matches!(res, Err(EventSignatureParserError::InvalidSignature(..))),
+                "{signature}"
+            );
+        }
+    }
+
+    #[test]
+    fn should_use_the_event_signature_hash_as_first_topic() -> anyhow::Result<()> {
+        // Act
+        let res = parse_filter(&["--event", "Transfer(address,address,uint256)"])?;
+
+        // Assert
+        assert_eq!(
+            res.topics[0],
+            Some(ValueOrArray::Value(Some(TOPIC.parse()?)))
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn should_ignore_the_parameter_names_in_the_first_topic() -> anyhow::Result<()> {
+        // Act
+        let res = parse_filter(&[
+            "--event",
+            "Transfer(address indexed from, address indexed to, uint value)",
+        ])?;
+
+        // Assert
+        assert_eq!(
+            res.topics[0],
+            Some(ValueOrArray::Value(Some(TOPIC.parse()?)))
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn should_not_accept_an_invalid_event() {
+        // Setup
+        let events = [
+            "Transfer(address,address,uint256",
+            "Transfer(adress)",
+            "Transfer",
+        ];
+
+        for event in events {
+            // Act
+            let res = EventCommand::try_parse_from(["event", "logs", "--event", event]);
+
+            // Assert
+            assert!(res.is_err(), "{event}");
+        }
+    }
+
+    #[test]
+    fn should_only_resume_a_range_export() {
+        // Setup
+        let test_cases: [(&[&str], bool); 6] = [
+            (&["--export", "logs.jsonl"], true),
+            (&["--export", "logs.jsonl", "--resume"], true),
+            (&["--resume"], false),
+            (&["--export", "logs.jsonl", "--follow"], false),
+            (&["--export", "logs.jsonl", "--summary"], false),
+            (&["--export", "logs.jsonl", "--number", "10"], false),
+        ];
+
+        for (args, is_valid) in test_cases {
+            // Act
+            let res = EventCommand::try_parse_from(["event", "logs"].iter().chain(args));
+
+            // Assert
+            assert_eq!(res.is_ok(), is_valid, "{args:?}");
+        }
+    }
+
+    #[test]
+    fn should_not_accept_both_the_event_and_the_first_topic() {
+        // Act
+        let res = EventCommand::try_parse_from([
+            "event",
+            "logs",
+            "--event",
+            "Transfer(address,address,uint256)",
+            "--topic0",
+            TOPIC,
+        ]);
+
+        // Assert
+        assert!(res.is_err());
+    }
+
+    const TRANSFER_EVENT: &str =
+        "Transfer(address indexed from, address indexed to, uint256 value)";
+
+    #[test]
+    fn should_encode_the_indexed_values_into_topics() -> anyhow::Result<()> {
+        // Setup
+        let test_cases = [
+            (
+                ParamType::Address,
+                "0xf39fd6e51aad88f6f4ce6ab8827279cfffb92266",
+                "0x000000000000000000000000f39fd6e51aad88f6f4ce6ab8827279cfffb92266",
+            ),
+            (
+                ParamType::Uint(256),
+                "1000",
+                "0x00000000000000000000000000000000000000000000000000000000000003e8",
+            ),
+            (
+                ParamType::Uint(8),
+                "0xff",
+                "0x00000000000000000000000000000000000000000000000000000000000000ff",
+            ),
+            (
+                ParamType::Int(256),
+                "-1",
+                "0xffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff",
+            ),
+            (
+                ParamType::Bool,
+                "true",
+                "0x0000000000000000000000000000000000000000000000000000000000000001",
+            ),
+            (ParamType::FixedBytes(32), TOPIC, TOPIC),
+            (
+                ParamType::FixedBytes(4),
+                "0xa9059cbb",
+                "0xa9059cbb00000000000000000000000000000000000000000000000000000000",
+            ),
+        ];
+
+        for (kind, value, expected) in test_cases {
+            // Act
+            let res = encode_topic(&kind, value);
+
+            // Assert
+            assert_eq!(res, Ok(expected.parse()?), "{kind} {value}");
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn should_encode_the_hash_of_the_indexed_dynamic_values() -> anyhow::Result<()> {
+        // Act
+        let string_res = encode_topic(&ParamType::String, "yaeth");
+        let bytes_res = encode_topic(&ParamType::Bytes, "0x0102");
+
+        // Assert
+        assert_eq!(string_res, Ok(H256(keccak256("yaeth"))));
+        assert_eq!(bytes_res, Ok(H256(keccak256([1u8, 2]))));
+
+        Ok(())
+    }
+
+    #[test]
+    fn should_not_encode_an_invalid_indexed_value() {
+        // Setup
+        let test_cases = [
+            (ParamType::Address, "0xf39f"),
+            (ParamType::Uint(256), "-1"),
+            (ParamType::Uint(8), "256"),
+            (ParamType::Int(8), "128"),
+            (ParamType::Int(8), "-129"),
+            (ParamType::Bool, "yes"),
+            (ParamType::FixedBytes(32), "0xa9059cbb"),
+            (ParamType::Bytes, "0xzz"),
+            (ParamType::Array(Box::new(ParamType::Address)), "[]"),
+        ];
+
+        for (kind, value) in test_cases {
+            // Act
+            let res = encode_topic(&kind, value);
+
+            // Assert
+            assert!(res.is_err(), "{kind} {value}");
+        }
+    }
+
+    #[test]
+    fn should_match_the_event_args_against_their_topics() -> anyhow::Result<()> {
+        // Arrange
+        let from = "0xf39fd6e51aad88f6f4ce6ab8827279cfffb92266";
+
+        // Act
+        let res = parse_filter(&["--event", TRANSFER_EVENT, "--arg", &format!("from={from}")])?;
+
+        // Assert
+        assert_eq!(
+            res.topics[0],
+            Some(ValueOrArray::Value(Some(TOPIC.parse()?)))
+        );
+        assert_eq!(
+            res.topics[1],
+            Some(ValueOrArray::Value(Some(H256::from(from.parse::<H160>()?))))
+        );
+        assert_eq!(res.topics[2], None);
+
+        Ok(())
+    }
+
+    #[test]
+    fn should_match_any_of_the_values_of_a_repeated_event_arg() -> anyhow::Result<()> {
+        // Arrange
+        let to = [H160::repeat_byte(0x01), H160::repeat_byte(0x02)];
+
+        // Act
+        let res = parse_filter(&[
+            "--event",
+            TRANSFER_EVENT,
+            "--arg",
+            &format!("to={:?}", to[0]),
+            "--arg",
+            &format!("to={:?}", to[1]),
+        ])?;
+
+        // Assert
+        assert_eq!(res.topics[1], None);
+        assert_eq!(
+            res.topics[2],
+            Some(ValueOrArray::Array(vec![
+                Some(H256::from(to[0])),
+                Some(H256::from(to[1]))
+            ]))
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn should_not_match_an_invalid_event_arg() {
+        // Setup
+        let test_cases = [
+            (
+                ["--arg", "value=1"].as_slice(),
+                "Parameter value of event Transfer is not indexed. Only indexed parameters can be filtered on, mark them as indexed in the event signature or provide the ABI",
+            ),
+            (
+                ["--arg", "amount=1"].as_slice(),
+                "Event Transfer has no parameter named amount",
+            ),
+            (
+                ["--arg", "from=1"].as_slice(),
+                "Invalid value 1 for parameter from: expected a 0x prefixed address",
+            ),
+            (
+                [
+                    "--arg",
+                    "to=0xf39fd6e51aad88f6f4ce6ab8827279cfffb92266",
+                    "--topic2",
+                    TOPIC,
+                ]
+                .as_slice(),
+                "Provided both --topic2 and the event argument to, which are matched against the same topic",
+            ),
+        ];
+
+        for (args, expected_err) in test_cases {
+            // Act
+            let res = parse_filter(&[["--event", TRANSFER_EVENT].as_slice(), args].concat());
+
+            // Assert
+            assert_eq!(
+                res.map_err(|err| err.to_string()).err().as_deref(),
+                Some(expected_err),
+                "{args:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn should_not_accept_an_event_arg_without_an_event() {
+        // Setup
+        let args = [
+            ["--arg", "from=0xf39fd6e51aad88f6f4ce6ab8827279cfffb92266"].as_slice(),
+            ["--event", TRANSFER_EVENT, "--arg", "from"].as_slice(),
+            ["--event", TRANSFER_EVENT, "--arg", "=1"].as_slice(),
+        ];
+
+        for args in args {
+            // Act
+            let res = EventCommand::try_parse_from(["event", "logs"].iter().chain(args));
+
+            // Assert
+            assert!(res.is_err(), "{args:?}");
+        }
+    }
+
+    fn parse_erc20_transfers_filter(args: &[&str]) -> anyhow::Result<Erc20TransfersFilter> {
+        let res = EventCommand::try_parse_from(
+            ["event", "erc20-transfers", "--token", TOKEN]
+                .iter()
+                .chain(args),
+        )?;
+
+        let EventSubCommand::Erc20Transfers(args) = res.command else {
+            anyhow::bail!("Expected the erc20-transfers command");
+        };
+
+        Ok(args.try_into()?)
+    }
+
+    const TOKEN: &str = "0x6b175474e89094c44da98b954eedeac495271d0f";
+
+    #[test]
+    fn should_build_the_erc20_transfers_filter() -> anyhow::Result<()> {
+        // Arrange
+        let sender = H160::repeat_byte(0x01);
+
+        // Act
+        let res = parse_erc20_transfers_filter(&[
+            "--from",
+            &format!("{sender:?}"),
+            "--from-block",
+            "10",
+            "--to-block",
+            "latest",
+        ])?;
+
+        // Assert
+        assert_eq!(
+            res,
+            Erc20TransfersFilter {
+                token: TOKEN.parse()?,
+                from: Some(sender),
+                to: None,
+                from_block: BlockNumber::Number(10.into()),
+                to_block: BlockNumber::Latest,
+            }
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn should_not_get_every_transfer_over_a_wide_range() {
+        // Setup
+        let recipient = format!("{:?}", H160::repeat_byte(0x01));
+
+        let test_cases: [(&[&str], bool); 5] = [
+            (&["--from-block", "0", "--to-block", "999"], true),
+            (&["--from-block", "0", "--to-block", "1000"], false),
+            (
+                &[
+                    "--from-block",
+                    "0",
+                    "--to-block",
+                    "1000",
+                    "--to",
+                    &recipient,
+                ],
+                true,
+            ),
+            (&["--from-block", "0", "--to-block", "latest"], true),
+            (&["--from-block", "0"], false),
+        ];
+
+        for (args, is_valid) in test_cases {
+            // Act
+            let res = parse_erc20_transfers_filter(args);
+
+            // Assert
+            assert_eq!(res.is_ok(), is_valid, "{args:?}");
+        }
+    }
+
+    #[test]
+    fn should_parse_decimal_and_hex_filter_ids() {
+        // Setup
+        let test_cases = [
+            ("16", Some(U256::from(16))),
+            ("0x10", Some(U256::from(16))),
+            ("0xg", None),
+            ("ten", None),
+        ];
+
+        for (filter_id, expected_res) in test_cases {
+            // Act
+            let res = parse_filter_id(filter_id);
+
+            // Assert
+            assert_eq!(res.ok(), expected_res, "{filter_id}");
+        }
+    }
+
+    #[test]
+    fn should_parse_the_filter_logs_command() -> anyhow::Result<()> {
+        // Act
+        let res = EventCommand::try_parse_from(["event", "filter", "logs", "0x1f"])?;
+
+        // Assert
+        let EventSubCommand::Filter(FilterCommand {
+            command: FilterSubCommand::Logs(args),
+        }) = res.command
+        else {
+            anyhow::bail!("Expected the filter logs command");
+        };
+        assert_eq!(args.filter_id, U256::from(0x1f));
+
+        Ok(())
+    }
+}
@@ -1,9 +1,22 @@
-use crate::{cmd, context::CommandExecutionContext};
+use crate::{
+    cmd::{
+        self,
+        gas::{
+            FeeSuggestion, GasEstimateBreakdown, GasEstimateVariance, GasOracle,
+            FEE_SUGGESTION_BLOCK_COUNT, MAX_FEE_HISTORY_BLOCK_COUNT,
+        },
+        lookup::Missing,
+    },
+    context::CommandExecutionContext,
+};
 
-use super::common::{GetBlockByIdArgs, NoArgs, TypedTransactionArgs};
-use clap::{command, Args, Parser, Subcommand};
-use ethers::types::{FeeHistory, U256};
+use super::common::{
+    parse_lookup, parse_not_found, GetBlockByIdArgs, NoArgs, NotFoundResult, TypedTransactionArgs,
+};
+use clap::{Args, Parser, Subcommand};
+use ethers::types::{BlockId, FeeHistory, U256};
 use serde::Serialize;
+use thiserror::Error;
 
 #[derive(Parser, Debug)]
 #[command()]
@@ -18,6 +31,9 @@ pub enum GasSubCommand {
     /// Estimates the gas used by the provided transaction
     Estimate(EstimateGasArgs),
 
+    /// Estimates the gas used by the provided transaction for each block in the specified range
+    Variance(EstimateVarianceArgs),
+
     /// Gets the transaction base fee per gas and effective priority fee per gas for the specified block range
     History(GetFeeHistoryArgs),
 
@@ -26,6 +42,36 @@ pub enum GasSubCommand {
 
     /// Gets the current estimated max priority gas fee
     Fee(NoArgs),
+
+    /// Suggests fees for a transaction to be included within the provided number of blocks
+    Suggest(SuggestFeesArgs),
+
+    /// Recommends slow, standard and fast EIP-1559 fees based on the priority fees paid in the recent blocks
+    Oracle(GasOracleArgs),
+
+    /// Gets the average gas price paid in the recent blocks, as the base fee plus the median priority fee of each block
+    AveragePrice(AveragePriceArgs),
+}
+
+#[derive(Args, Debug)]
+pub struct AveragePriceArgs {
+    /// Number of recent blocks whose gas prices are averaged
+    #[arg(long, default_value_t = FEE_SUGGESTION_BLOCK_COUNT, value_parser = clap::value_parser!(u64).range(1..=MAX_FEE_HISTORY_BLOCK_COUNT))]
+    blocks: u64,
+}
+
+#[derive(Args, Debug)]
+pub struct GasOracleArgs {
+    /// Number of recent blocks whose priority fees are analyzed
+    #[arg(long, default_value_t = FEE_SUGGESTION_BLOCK_COUNT, value_parser = clap::value_parser!(u64).range(1..=MAX_FEE_HISTORY_BLOCK_COUNT))]
+    blocks: u64,
+}
+
+#[derive(Args, Debug)]
+pub struct SuggestFeesArgs {
+    /// Number of blocks within which the transaction should be included, higher values suggest lower fees
+    #[arg(long, default_value_t = 3, value_parser = clap::value_parser!(u64).range(1..))]
+    within: u64,
 }
 
 #[derive(Args, Debug)]
@@ -37,13 +83,36 @@ pub struct EstimateGasArgs {
     // Block id args
     #[clap(flatten)]
     get_block_by_id: GetBlockByIdArgs,
+
+    /// Breaks the estimate down in base, calldata (4 gas per zero byte and 16 per non zero byte) and execution gas
+    #[arg(long)]
+    include_calldata_cost: bool,
+}
+
+#[derive(Args, Debug)]
+pub struct EstimateVarianceArgs {
+    // Typed Tx args
+    #[clap(flatten)]
+    typed_tx: TypedTransactionArgs,
+
+    /// First block of the range (number, tag or hash)
+    #[arg(long, value_name = "BLOCK_ID")]
+    from_block: BlockId,
+
+    /// Last block of the range (number, tag or hash)
+    #[arg(long, value_name = "BLOCK_ID")]
+    to_block: BlockId,
 }
 
 #[derive(Args, Debug)]
 pub struct GetFeeHistoryArgs {
-    /// The number of blocks to include in the requested range
-    #[clap()]
-    count: U256,
+    /// The number of blocks to include in the requested range (at most 1024 unless --allow-large is set)
+    #[clap(value_parser = clap::value_parser!(u64).range(1..))]
+    count: u64,
+
+    /// Allow more than 1024 blocks by splitting the request in multiple fee history calls
+    #[arg(long)]
+    allow_large: bool,
 
     /// The highest block of the requested range
     #[clap(flatten)]
@@ -54,13 +123,42 @@ pub struct GetFeeHistoryArgs {
     percentiles: Vec<f64>,
 }
 
+#[derive(Error, Debug)]
+pub enum GetFeeHistoryParserError {
+    #[error("Block count {0} exceeds the maximum of {MAX_FEE_HISTORY_BLOCK_COUNT} blocks. Use --allow-large to split the request in multiple calls.")]
+    BlockCountTooLarge(u64),
+}
+
+impl GetFeeHistoryArgs {
+    fn block_count(&self) -> Result<u64, GetFeeHistoryParserError> {
+        if self.count > MAX_FEE_HISTORY_BLOCK_COUNT && !self.allow_large {
+            return Err(GetFeeHistoryParserError::BlockCountTooLarge(self.count));
+        }
+
+        Ok(self.count)
+    }
+}
+
 #[derive(Debug, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub enum GasNamespaceResult {
     Estimate(U256),
+    EstimateBreakdown(GasEstimateBreakdown),
+    Variance(GasEstimateVariance),
     Price(U256),
     Fee(U256),
-    GetFeeHistory(Option<FeeHistory>),
+    GetFeeHistory(FeeHistory),
+    Suggestion(FeeSuggestion),
+    Oracle(GasOracle),
+    AveragePrice(U256),
+    #[serde(serialize_with = "parse_not_found", rename = "block")]
+    NotFound(),
+}
+
+impl NotFoundResult for GasNamespaceResult {
+    fn not_found(_what: Missing) -> Self {
+        GasNamespaceResult::NotFound()
+    }
 }
 
 pub fn parse(
@@ -73,6 +171,18 @@ pub fn parse(
         GasSubCommand::Estimate(EstimateGasArgs {
             get_block_by_id,
             typed_tx,
+            include_calldata_cost: true,
+        }) => context
+            .execute(cmd::gas::estimate_gas_breakdown(
+                node_provider,
+                typed_tx.try_into()?,
+                get_block_by_id.try_into().ok(),
+            ))
+            .map(GasNamespaceResult::EstimateBreakdown),
+        GasSubCommand::Estimate(EstimateGasArgs {
+            get_block_by_id,
+            typed_tx,
+            include_calldata_cost: false,
         }) => context
             .execute(cmd::gas::estimate_gas(
                 node_provider,
@@ -80,25 +190,256 @@ pub fn parse(
                 get_block_by_id.try_into().ok(),
             ))
             .map(GasNamespaceResult::Estimate),
-        GasSubCommand::History(GetFeeHistoryArgs {
-            count,
-            last_block,
-            percentiles,
+        GasSubCommand::Variance(EstimateVarianceArgs {
+            typed_tx,
+            from_block,
+            to_block,
         }) => context
-            .execute(cmd::gas::get_fee_history(
+            .execute(cmd::gas::estimate_gas_variance(
                 node_provider,
-                count,
-                last_block.try_into()?,
-                percentiles,
+                typed_tx.try_into()?,
+                from_block,
+                to_block,
+                context.config().max_block_range(),
             ))
-            .map(GasNamespaceResult::GetFeeHistory),
+            .map(GasNamespaceResult::Variance),
+        GasSubCommand::History(get_fee_history_args) => {
+            let count = get_fee_history_args.block_count()?;
+            let GetFeeHistoryArgs {
+                last_block,
+                percentiles,
+                ..
+            } = get_fee_history_args;
+
+            context
+                .execute(cmd::gas::get_fee_history(
+                    node_provider,
+                    count,
+                    last_block.try_into()?,
+                    percentiles,
+                    context.config().max_block_range(),
+                    context.fee_history_cache(),
+                ))
+                .map(|fee_history| parse_lookup(fee_history, GasNamespaceResult::GetFeeHistory))
+        }
         GasSubCommand::Price(_) => context
             .execute(cmd::gas::gas_price(node_provider))
             .map(GasNamespaceResult::Price),
         GasSubCommand::Fee(_) => context
             .execute(cmd::gas::get_max_priority_fee(node_provider))
             .map(GasNamespaceResult::Fee),
+        GasSubCommand::Suggest(SuggestFeesArgs { within }) => context
+            .execute(cmd::gas::suggest_fees(
+                node_provider,
+                within,
+                context.fee_history_cache(),
+            ))
+            .map(GasNamespaceResult::Suggestion),
+        GasSubCommand::Oracle(GasOracleArgs { blocks }) => context
+            .execute(cmd::gas::gas_oracle(
+                node_provider,
+                blocks,
+                context.fee_history_cache(),
+            ))
+            .map(GasNamespaceResult::Oracle),
+        GasSubCommand::AveragePrice(AveragePriceArgs { blocks }) => context
+            .execute(cmd::gas::average_price(
+                node_provider,
+                blocks,
+                context.fee_history_cache(),
+            ))
+            .map(GasNamespaceResult::AveragePrice),
     }?;
 
     Ok(res)
 }
+
+#[cfg(test)]
+mod tests {
+    use clap::Parser;
+
+    use serde_json::json;
+
+    use crate::{
+        cli::common::parse_lookup,
+        cmd::{
+            gas::{
+                calldata_cost, GasEstimateBreakdown, FEE_SUGGESTION_BLOCK_COUNT,
+                MAX_FEE_HISTORY_BLOCK_COUNT,
+            },
+            lookup::{Lookup, Missing},
+        },
+    };
+
+    use super::{GasCommand, GasNamespaceResult, GasSubCommand, GetFeeHistoryParserError};
+
+    fn parse_block_count(args: &[&str]) -> anyhow::Result<u64> {
+        let res = GasCommand::try_parse_from(["gas", "history"].iter().chain(args))?;
+
+        let GasSubCommand::History(args) = res.command else {
+            panic!("Should be the history subcommand!");
+        };
+
+        Ok(args.block_count()?)
+    }
+
+    #[test]
+    fn should_label_the_gas_estimate_breakdown() -> anyhow::Result<()> {
+        // Arrange
+        let breakdown = GasEstimateBreakdown {
+            total: 30_000.into(),
+            base: 21_000.into(),
+            calldata: calldata_cost(&[0x00, 0x12]),
+            execution: 8_980.into(),
+        };
+
+        // Act
+        let res = serde_json::to_value(GasNamespaceResult::EstimateBreakdown(breakdown))?;
+
+        // Assert
+        assert_eq!(
+            res,
+            json!({
+                "estimateBreakdown": {
+                    "total": "0x7530",
+                    "base": "0x5208",
+                    "calldata": { "zeroBytes": 1, "nonZeroBytes": 1, "gas": "0x14" },
+                    "execution": "0x2314"
+                }
+            })
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn should_analyze_the_last_blocks_for_the_oracle() -> anyhow::Result<()> {
+        // Setup
+        let test_cases: [(&[&str], u64); 2] = [
+            (&[], FEE_SUGGESTION_BLOCK_COUNT),
+            (&["--blocks", "100"], 100),
+        ];
+
+        for (args, expected) in test_cases {
+            // Act
+            let res = GasCommand::try_parse_from(["gas", "oracle"].iter().chain(args))?;
+
+            // Assert
+            let GasSubCommand::Oracle(args) = res.command else {
+                panic!("Should be the oracle subcommand!");
+            };
+            assert_eq!(args.blocks, expected);
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn should_not_analyze_an_invalid_number_of_blocks_for_the_oracle() {
+        // Setup
+        let too_many_blocks = (MAX_FEE_HISTORY_BLOCK_COUNT + 1).to_string();
+        let test_cases = ["0", too_many_blocks.as_str()];
+
+        for blocks in test_cases {
+            // Act
+            let res = GasCommand::try_parse_from(["gas", "oracle", "--blocks", blocks]);
+
+            // Assert
+            assert!(res.is_err(), "{blocks}");
+        }
+    }
+
+    #[test]
+    fn should_serialize_the_fee_history_of_a_missing_block_as_null() -> anyhow::Result<()> {
+        // Act
+        let res = serde_json::to_value(parse_lookup(
+            Lookup::NotFound {
+                what: Missing::Block,
+            },
+            GasNamespaceResult::GetFeeHistory,
+        ))?;
+
+        // Assert
+        assert_eq!(res, json!({ "block": null }));
+
+        Ok(())
+    }
+
+    #[test]
+    fn should_accept_a_block_count_up_to_the_maximum() -> anyhow::Result<()> {
+        // Act
+        let res =
+            parse_block_count(&[&MAX_FEE_HISTORY_BLOCK_COUNT.to_string(), "--tag", "latest"])?;
+
+        // Assert
+        assert_eq!(res, MAX_FEE_HISTORY_BLOCK_COUNT);
+
+        Ok(())
+    }
+
+    #[test]
+    fn should_reject_a_block_count_above_the_maximum() {
+        // Act
+        let res = parse_block_count(&["1025", "--tag", "latest"]);
+
+        // Assert
+        assert!(matches!(
+            res.unwrap_err().downcast_ref::<GetFeeHistoryParserError>(),
+            Some(GetFeeHistoryParserError::BlockCountTooLarge(1025))
+        ));
+    }
+
+    #[test]
+    fn should_accept_a_block_count_above_the_maximum_with_allow_large() -> anyhow::Result<()> {
+        // Act
+        let res = parse_block_count(&["5000", "--allow-large", "--tag", "latest"])?;
+
+        // Assert
+        assert_eq!(res, 5000);
+
+        Ok(())
+    }
+
+    #[test]
+    fn should_reject_invalid_block_counts() {
+        // Setup
+        let test_cases = ["0", "-1", "0x10", "99999999999999999999999"];
+
+        for test_case in test_cases {
+            // Act
+            let res = parse_block_count(&[test_case, "--tag", "latest"]);
+
+            // Assert
+            assert!(res.is_err());
+        }
+    }
+
+    #[test]
+    fn should_parse_the_target_inclusion_block_count() -> anyhow::Result<()> {
+        // Arrange
+        let test_cases: [(&[&str], u64); 2] = [(&[], 3), (&["--within", "10"], 10)];
+
+        for (args, expected) in test_cases {
+            // Act
+            let res = GasCommand::try_parse_from(["gas", "suggest"].iter().chain(args))?;
+
+            // Assert
+            let GasSubCommand::Suggest(args) = res.command else {
+                panic!("Should be the suggest subcommand!");
+            };
+
+            assert_eq!(args.within, expected);
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn should_reject_a_zero_target_inclusion_block_count() {
+        // Act
+        let res = GasCommand::try_parse_from(["gas", "suggest", "--within", "0"]);
+
+        // Assert
+        assert!(res.is_err());
+    }
+}
@@ -1,6 +1,9 @@
 pub mod account;
 pub mod block;
 mod common;
+pub mod event;
 pub mod gas;
+pub mod spec;
+pub mod trace;
 pub mod transaction;
 pub mod utils;
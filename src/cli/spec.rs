@@ -0,0 +1,262 @@
+use std::{collections::BTreeMap, io::Read};
+
+use serde::Deserialize;
+use serde_json::Value;
+use thiserror::Error;
+
+/// Whole invocation described as json, e.g.
+/// `{"options": {"rpc-url": "http://localhost:8545"}, "namespace": "block", "subcommand": "number"}`.
+/// Arguments are keyed by their long flag name, in kebab or snake case.
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct CommandSpec {
+    /// Global options, placed before the namespace
+    #[serde(default)]
+    options: BTreeMap<String, Value>,
+
+    namespace: String,
+
+    /// Arguments of the namespace, placed before the subcommand
+    #[serde(default)]
+    namespace_args: BTreeMap<String, Value>,
+
+    subcommand: String,
+
+    /// Arguments of the subcommand
+    #[serde(default)]
+    args: BTreeMap<String, Value>,
+
+    /// Positional arguments of the subcommand
+    #[serde(default)]
+    positional: Vec<Value>,
+}
+
+#[derive(Error, Debug)]
+pub enum CommandSpecParserError {
+    #[error("Invalid json command spec: {0}")]
+    InvalidSpec(serde_json::Error),
+
+    #[error("Unsupported value for the {0} argument. Expected a string, a number, a boolean or an array of them.")]
+    UnsupportedValue(String),
+
+    #[error("A json command batch must hold at least one command spec")]
+    EmptyBatch,
+
+    #[error("The command specs of a json batch must share the same options")]
+    MixedOptions,
+}
+
+/// Formats a scalar argument value as it would be typed on the command line.
+fn format_value(name: &str, value: &Value) -> Result<String, CommandSpecParserError> {
+    match value {
+        Value::String(value) => Ok(value.clone()),
+        Value::Number(value) => Ok(value.to_string()),
+        Value::Bool(value) => Ok(value.to_string()),
+        _ => Err(CommandSpecParserError::UnsupportedValue(name.to_string())),
+    }
+}
+
+/// Turns the named arguments into flags: `true` enables a switch, `false` and `null` omit the
+/// flag and arrays repeat it once per item. Values are attached with `=` so that values starting
+/// with a dash are not mistaken for flags.
+fn push_flags(
+    args: &mut Vec<String>,
+    flags: &BTreeMap<String, Value>,
+) -> Result<(), CommandSpecParserError> {
+    for (name, value) in flags {
+        let flag = format!("--{}", name.replace('_', "-"));
+
+        match value {
+            Value::Bool(true) => args.push(flag),
+            Value::Bool(false) | Value::Null => {}
+            Value::Array(values) => {
+                for value in values {
+                    args.push(format!("{flag}={}", format_value(name, value)?));
+                }
+            }
+            value => args.push(format!("{flag}={}", format_value(name, value)?)),
+        }
+    }
+
+    Ok(())
+}
+
+impl CommandSpec {
+    /// Reads a single command spec or an array of them. The commands of an array are run in turn
+    /// on the same node connection, so their specs must share the same options.
+    pub fn from_reader(reader: impl Read) -> Result<Vec<Self>, CommandSpecParserError> {
+        let specs =
+            match serde_json::from_reader(reader).map_err(CommandSpecParserError::InvalidSpec)? {
+                Value::Array(specs) => specs
+                    .into_iter()
+                    .map(serde_json::from_value)
+                    .collect::<Result<Vec<Self>, _>>(),
+                spec => serde_json::from_value(spec).map(|spec| vec![spec]),
+            }
+            .map_err(CommandSpecParserError::InvalidSpec)?;
+
+        if specs.is_empty() {
+            return Err(CommandSpecParserError::EmptyBatch);
+        }
+
+        if specs
+            .windows(2)
+            .any(|pair| pair[0].options != pair[1].options)
+        {
+            return Err(CommandSpecParserError::MixedOptions);
+        }
+
+        Ok(specs)
+    }
+
+    /// Builds the command line equivalent to the spec, starting with the binary name.
+    pub fn to_args(&self) -> Result<Vec<String>, CommandSpecParserError> {
+        let mut args = vec!["yaeth".to_string()];
+
+        push_flags(&mut args, &self.options)?;
+        args.push(self.namespace.clone());
+        push_flags(&mut args, &self.namespace_args)?;
+        args.push(self.subcommand.clone());
+        push_flags(&mut args, &self.args)?;
+
+        if !self.positional.is_empty() {
+            args.push("--".to_string());
+
+            for value in &self.positional {
+                args.push(format_value("positional", value)?);
+            }
+        }
+
+        Ok(args)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{CommandSpec, CommandSpecParserError};
+
+    fn to_args(spec: &str) -> Result<Vec<String>, CommandSpecParserError> {
+        CommandSpec::from_reader(spec.as_bytes())?[0].to_args()
+    }
+
+    #[test]
+    fn should_build_the_command_line_of_the_spec() -> anyhow::Result<()> {
+        // Setup
+        let test_cases = [
+            (
+                r#"{"namespace": "block", "subcommand": "number"}"#,
+                vec!["yaeth", "block", "number"],
+            ),
+            (
+                r#"{
+                    "options": {"rpc_url": "http://localhost:8545", "raw-output": true, "no-pretty": false},
+                    "namespace": "account",
+                    "namespaceArgs": {"address": ["0x01", "0x02"], "number": 10},
+                    "subcommand": "balance"
+                }"#,
+                vec![
+                    "yaeth",
+                    "--raw-output",
+                    "--rpc-url=http://localhost:8545",
+                    "account",
+                    "--address=0x01",
+                    "--address=0x02",
+                    "--number=10",
+                    "balance",
+                ],
+            ),
+            (
+                r#"{
+                    "namespace": "utils",
+                    "subcommand": "abi-encode",
+                    "positional": ["transfer(address,int256)", "0x01", -1]
+                }"#,
+                vec![
+                    "yaeth",
+                    "utils",
+                    "abi-encode",
+                    "--",
+                    "transfer(address,int256)",
+                    "0x01",
+                    "-1",
+                ],
+            ),
+        ];
+
+        for (spec, expected_res) in test_cases {
+            // Act
+            let res = to_args(spec)?;
+
+            // Assert
+            assert_eq!(res, expected_res, "{spec}");
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn should_reject_invalid_specs() {
+        // Setup
+        let test_cases = [
+            r#"{"subcommand": "number"}"#,
+            r#"{"namespace": "block", "subcommand": "number", "unknown": 1}"#,
+            r#"{"namespace": "block", "subcommand": "get", "args": {"number": {"value": 1}}}"#,
+            r#"{"namespace": "block", "subcommand": "get", "positional": [[1]]}"#,
+            "block number",
+        ];
+
+        for spec in test_cases {
+            // Act
+            let res = to_args(spec);
+
+            // Assert
+            assert!(res.is_err(), "{spec}");
+        }
+    }
+
+    #[test]
+    fn should_read_a_single_spec_or_a_batch_of_them() -> anyhow::Result<()> {
+        // Setup
+        let test_cases = [
+            (r#"{"namespace": "block", "subcommand": "number"}"#, 1),
+            (
+                r#"[
+                    {"options": {"rpc-url": "http://localhost:8545"}, "namespace": "gas", "subcommand": "suggest"},
+                    {"options": {"rpc-url": "http://localhost:8545"}, "namespace": "gas", "subcommand": "average-price"}
+                ]"#,
+                2,
+            ),
+        ];
+
+        for (spec, expected_len) in test_cases {
+            // Act
+            let res = CommandSpec::from_reader(spec.as_bytes())?;
+
+            // Assert
+            assert_eq!(res.len(), expected_len, "{spec}");
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn should_reject_invalid_batches() {
+        // Setup
+        let test_cases = [
+            "[]",
+            r#"[{"namespace": "block", "subcommand": "number"}, {"subcommand": "number"}]"#,
+            r#"[
+                {"options": {"rpc-url": "http://localhost:8545"}, "namespace": "gas", "subcommand": "suggest"},
+                {"options": {"rpc-url": "http://localhost:8546"}, "namespace": "gas", "subcommand": "suggest"}
+            ]"#,
+        ];
+
+        for spec in test_cases {
+            // Act
+            let res = CommandSpec::from_reader(spec.as_bytes());
+
+            // Assert
+            assert!(res.is_err(), "{spec}");
+        }
+    }
+}
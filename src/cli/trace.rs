@@ -0,0 +1,122 @@
+use crate::{
+    cmd::{
+        self,
+        trace::{Tracer, TransactionTrace},
+    },
+    context::CommandExecutionContext,
+};
+
+use clap::{builder::PossibleValue, Args, Parser, Subcommand, ValueEnum};
+use ethers::types::H256;
+use serde::Serialize;
+
+#[derive(Parser, Debug)]
+#[command()]
+pub struct TraceCommand {
+    #[command(subcommand)]
+    command: TraceSubCommand,
+}
+
+#[derive(Subcommand, Debug)]
+#[command()]
+pub enum TraceSubCommand {
+    /// Re-executes the transaction with the provided hash and returns its trace
+    Transaction(TraceTransactionArgs),
+}
+
+#[derive(Args, Debug)]
+pub struct TraceTransactionArgs {
+    /// Hash of the target transaction
+    #[arg(value_name = "TRANSACTION_HASH")]
+    tx_hash: H256,
+
+    /// Tracer run by the node over the transaction execution
+    #[arg(long, default_value = "structLogger")]
+    tracer: Tracer,
+}
+
+impl ValueEnum for Tracer {
+    fn value_variants<'a>() -> &'a [Self] {
+        &[Self::StructLogger, Self::CallTracer]
+    }
+
+    fn to_possible_value(&self) -> Option<PossibleValue> {
+        Some(match self {
+            Tracer::StructLogger => {
+                PossibleValue::new("structLogger").help("Opcode level execution steps")
+            }
+            Tracer::CallTracer => {
+                PossibleValue::new("callTracer").help("Tree of the calls made by the transaction")
+            }
+        })
+    }
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum TraceNamespaceResult {
+    Trace(TransactionTrace),
+}
+
+pub fn parse(
+    context: &CommandExecutionContext,
+    sub_command: TraceCommand,
+) -> Result<TraceNamespaceResult, anyhow::Error> {
+    let node_provider = context.node_provider();
+
+    let res: TraceNamespaceResult = match sub_command.command {
+        TraceSubCommand::Transaction(TraceTransactionArgs { tx_hash, tracer }) => context
+            .execute(cmd::trace::trace_transaction(
+                node_provider,
+                tx_hash,
+                tracer,
+            ))
+            .map(TraceNamespaceResult::Trace)?,
+    };
+
+    Ok(res)
+}
+
+#[cfg(test)]
+mod tests {
+    use clap::Parser;
+
+    use crate::cmd::trace::Tracer;
+
+    use super::{TraceCommand, TraceSubCommand};
+
+    const TX_HASH: &str = "0x79202697c177e951ea2bdfc283ef9a44108c41e2023cf56c4fd233a589da2e6a";
+
+    #[test]
+    fn should_parse_the_tracer() -> anyhow::Result<()> {
+        // Setup
+        let test_cases = [
+            (vec![], Tracer::StructLogger),
+            (vec!["--tracer", "structLogger"], Tracer::StructLogger),
+            (vec!["--tracer", "callTracer"], Tracer::CallTracer),
+        ];
+
+        for (args, expected_tracer) in test_cases {
+            // Act
+            let res = TraceCommand::try_parse_from(
+                ["trace", "transaction", TX_HASH].into_iter().chain(args),
+            )?;
+
+            // Assert
+            let TraceSubCommand::Transaction(args) = res.command;
+            assert_eq!(args.tracer, expected_tracer);
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn should_not_accept_an_unknown_tracer() {
+        // Act
+        let res =
+            TraceCommand::try_parse_from(["trace", "transaction", TX_HASH, "--tracer", "4byte"]);
+
+        // Assert
+        assert!(res.is_err());
+    }
+}
@@ -1,34 +1,135 @@
 use crate::{
     cmd::{
         self,
+        lookup::Missing,
         transaction::{
-            GetTransaction, SendTransactionOptions, SendTxResult, SimulateTransactionOptions,
-            TransactionKind,
+            load_batch_transfers, BatchTxResult, BlockCallResult, DecodedTransaction,
+            EnrichedReceipt, GetTransaction, SendTransactionOptions, SendTxResult,
+            SimulateTransactionOptions, TransactionKind, TransactionStatus,
+            CONFIRMATIONS_POLL_INTERVAL,
         },
     },
     context::CommandExecutionContext,
+    run::{stdio_output, EntryPoint},
 };
 
 use super::common::{
-    parse_not_found, BlockIdParserError, GetBlockByIdArgs, NoArgs, TypedTransactionArgs,
-    TypedTransactionParserError, GET_BLOCK_BY_ID_ARG_GROUP_NAME, TX_ARGS_FIELD_NAMES,
+    parse_lookup, parse_not_found, serialize_with_checksummed_fields, BlockIdParserError,
+    GetBlockByIdArgs, NotFoundResult, TypedTransactionArgs, TypedTransactionParserError,
+    GET_BLOCK_BY_ID_ARG_GROUP_NAME, TX_ARGS_FIELD_NAMES,
+};
+use clap::{Args, CommandFactory, Parser, Subcommand};
+use ethers::types::{
+    transaction::eip2718::TypedTransaction, Address, BlockId, Bytes, Transaction, H256,
 };
-use clap::{arg, command, Args, Parser, Subcommand};
-use ethers::types::{Bytes, Transaction, TransactionReceipt, H256};
 use serde::Serialize;
+use std::{ffi::OsString, path::PathBuf, time::Duration};
 use thiserror::Error;
 
 #[derive(Parser, Debug)]
 #[command()]
 pub struct TransactionCommand {
-    /// Transaction hash. Required if using the receipt subcommand or get without options
-    #[arg(long, value_name = "TRANSACTION_HASH")]
-    hash: Option<H256>,
-
     #[command(subcommand)]
     command: TransactionSubCommand,
 }
 
+const TRANSACTION_NAMESPACE: &str = "transaction";
+const DEPRECATED_HASH_ARG: &str = "--hash";
+
+/// Rewrites the deprecated namespace level hash argument (`transaction --hash <HASH> <COMMAND>`)
+/// into the positional hash argument of the subcommand (`transaction <COMMAND> <HASH>`).
+/// This keeps old invocations working for one more release.
+pub fn migrate_deprecated_hash_arg<I>(args: I) -> Vec<OsString>
+where
+    I: IntoIterator<Item = OsString>,
+{
+    let mut args: Vec<OsString> = args.into_iter().collect();
+
+    let Some(namespace_idx) = namespace_position(&args) else {
+        return args;
+    };
+
+    if args[namespace_idx] != TRANSACTION_NAMESPACE {
+        return args;
+    }
+
+    let hash_idx = namespace_idx + 1;
+    let Some(arg) = args
+        .get(hash_idx)
+        .map(|arg| arg.to_string_lossy().into_owned())
+    else {
+        return args;
+    };
+
+    let hash = if arg == DEPRECATED_HASH_ARG && hash_idx + 1 < args.len() {
+        args.drain(hash_idx..hash_idx + 2).nth(1)
+    } else if let Some(hash) = arg.strip_prefix(&format!("{DEPRECATED_HASH_ARG}=")) {
+        args.remove(hash_idx);
+        Some(hash.into())
+    } else {
+        None
+    };
+
+    if let Some(hash) = hash {
+        // Failing to write the warning must not prevent the command from running
+        let _ = stdio_output().progress(
+            "Warning: `transaction --hash <HASH> <COMMAND>` is deprecated, use `transaction <COMMAND> <HASH>` instead",
+        );
+
+        let subcommand_idx = (hash_idx + 1).min(args.len());
+        args.insert(subcommand_idx, hash);
+    }
+
+    args
+}
+
+/// Finds the namespace of the invocation, the first positional argument after the global
+/// options and their values.
+fn namespace_position(args: &[OsString]) -> Option<usize> {
+    let command = EntryPoint::command();
+    let takes_value = |arg: &str| {
+        command.get_arguments().any(|option| {
+            let matches = match arg.strip_prefix("--") {
+                Some(long) => {
+                    option.get_long() == Some(long)
+                        || option
+                            .get_all_aliases()
+                            .is_some_and(|aliases| aliases.contains(&long))
+                }
+                None => {
+                    let mut short = arg.chars().skip(1);
+                    short
+                        .next()
+                        .is_some_and(|short| option.get_short() == Some(short))
+                        && short.next().is_none()
+                }
+            };
+
+            matches && option.get_action().takes_values()
+        })
+    };
+
+    let mut idx = 1;
+    while let Some(arg) = args.get(idx) {
+        let arg = arg.to_string_lossy();
+        if !arg.starts_with('-') {
+            return Some(idx);
+        }
+
+        if arg == "--" {
+            return None;
+        }
+
+        idx += if !arg.contains('=') && takes_value(&arg) {
+            2
+        } else {
+            1
+        };
+    }
+
+    None
+}
+
 #[derive(Subcommand, Debug)]
 #[command()]
 pub enum TransactionSubCommand {
@@ -36,25 +137,112 @@ pub enum TransactionSubCommand {
     Get(GetTransactionArgs),
 
     /// Gets a transaction receipt by transaction hash
-    Receipt(NoArgs),
+    Receipt(ReceiptArgs),
+
+    /// Reports whether a transaction is pending, mined or not found, optionally waiting for its confirmations
+    Status(StatusArgs),
 
     /// Sends a transaction
     Send(SendTransactionArgs),
 
+    /// Sends the transfers listed in a csv file of to,value[,data] rows one after the other
+    SendBatch(SendBatchArgs),
+
     /// Simulates a transaction without using any gas
     Call(SimulateTransactionArgs),
+
+    /// Decodes a signed raw transaction and recovers its sender without broadcasting it
+    Decode(DecodeTransactionArgs),
+}
+
+impl TransactionCommand {
+    /// Tells if the command can run without reaching the node.
+    pub fn is_offline(&self) -> bool {
+        matches!(self.command, TransactionSubCommand::Decode(_))
+    }
+}
+
+#[derive(Args, Debug)]
+pub struct DecodeTransactionArgs {
+    /// Rlp encoded signed transaction
+    #[arg(long)]
+    raw: Bytes,
 }
 
 #[derive(Args, Debug)]
 pub struct GetTransactionArgs {
+    /// Hash of the target transaction
+    #[arg(value_name = "TRANSACTION_HASH", conflicts_with_all = [GET_BLOCK_BY_ID_ARG_GROUP_NAME, "index"])]
+    tx_hash: Option<H256>,
+
     #[clap(flatten)]
     get_block_by_id: GetBlockByIdArgs,
 
     /// Index of the transaction in the block
-    #[arg(long, value_name = "TRANSACTION_INDEX", requires = GET_BLOCK_BY_ID_ARG_GROUP_NAME)]
+    #[arg(long, value_name = "TRANSACTION_INDEX")]
     index: Option<u64>,
 }
 
+#[derive(Args, Debug)]
+pub struct ReceiptArgs {
+    /// Hash of the target transaction
+    #[arg(value_name = "TRANSACTION_HASH", required_unless_present = "hash_arg")]
+    hash: Option<H256>,
+
+    /// Hash of the target transaction
+    #[arg(
+        id = "hash_arg",
+        long = "hash",
+        value_name = "TRANSACTION_HASH",
+        conflicts_with = "hash"
+    )]
+    hash_arg: Option<H256>,
+
+    /// Re-simulates failed transactions to include their revert reason
+    #[arg(long)]
+    explain_failure: bool,
+
+    /// Traces the transaction to include the internal calls and ETH transfers made by contracts
+    #[arg(long)]
+    follow_internal: bool,
+}
+
+#[derive(Args, Debug)]
+pub struct StatusArgs {
+    /// Hash of the target transaction
+    #[arg(value_name = "TRANSACTION_HASH", required_unless_present = "hash_arg")]
+    hash: Option<H256>,
+
+    /// Hash of the target transaction
+    #[arg(
+        id = "hash_arg",
+        long = "hash",
+        value_name = "TRANSACTION_HASH",
+        conflicts_with = "hash"
+    )]
+    hash_arg: Option<H256>,
+
+    /// Waits until the transaction is mined with at least this number of confirmations
+    #[arg(long, value_parser = clap::value_parser!(u64).range(1..))]
+    confirmations: Option<u64>,
+
+    /// Stops waiting for the confirmations after the provided number of seconds (defaults to 300)
+    #[arg(long, value_name = "SECONDS", requires = "confirmations", value_parser = clap::value_parser!(u64).range(1..))]
+    timeout: Option<u64>,
+}
+
+impl StatusArgs {
+    fn hash(&self) -> Option<H256> {
+        self.hash.or(self.hash_arg)
+    }
+}
+
+impl ReceiptArgs {
+    fn hash(&self) -> Option<H256> {
+        self.hash.or(self.hash_arg)
+    }
+}
+
 #[derive(Args, Debug)]
 pub struct SendTransactionArgs {
     // Raw tx args
@@ -70,6 +258,33 @@ pub struct SendTransactionArgs {
     /// Wait for the transaction receipt
     #[arg(long)]
     wait: Option<bool>,
+
+    /// Block explorer base url used to link the mined transaction (defaults to a known explorer for the chain id)
+    #[arg(long)]
+    explorer_base: Option<String>,
+
+    /// Re-simulates the transaction if it fails to include its revert reason
+    #[arg(long)]
+    explain_failure: bool,
+
+    /// Refuses to send the transaction if the target account has no code
+    #[arg(long, conflicts_with = "raw")]
+    only_if_contract: bool,
+
+    /// Sends the transaction from the unlocked node account at this index of eth_accounts
+    #[arg(long, conflicts_with_all = ["raw", "from"])]
+    from_index: Option<usize>,
+}
+
+#[derive(Args, Debug)]
+pub struct SendBatchArgs {
+    /// Csv file with one to,value[,data] row per transfer, the value being expressed in wei
+    #[arg(long, value_name = "PATH")]
+    file: PathBuf,
+
+    /// Address of the account sending the transfers (defaults to the configured private key)
+    #[arg(long)]
+    from: Option<Address>,
 }
 
 #[derive(Error, Debug)]
@@ -92,6 +307,10 @@ impl TryFrom<SendTransactionArgs> for SendTransactionOptions {
             raw,
             typed_tx,
             wait,
+            explorer_base,
+            explain_failure,
+            only_if_contract,
+            from_index,
         } = value;
 
         if raw.is_some() && typed_tx.is_some() {
@@ -99,7 +318,9 @@ impl TryFrom<SendTransactionArgs> for SendTransactionOptions {
         }
 
         if let Some(raw) = raw {
-            return Ok(Self::new(TransactionKind::RawTransaction(raw), wait));
+            return Ok(Self::new(TransactionKind::RawTransaction(raw), wait)
+                .with_explorer_base(explorer_base)
+                .with_explain_failure(explain_failure));
         }
 
         if let Some(typed_tx) = typed_tx {
@@ -108,7 +329,11 @@ impl TryFrom<SendTransactionArgs> for SendTransactionOptions {
                     typed_tx.try_into().map_err(Self::Error::InvalidTypedTx)?,
                 ),
                 wait,
-            ));
+            )
+            .with_explorer_base(explorer_base)
+            .with_explain_failure(explain_failure)
+            .with_only_if_contract(only_if_contract)
+            .with_from_index(from_index));
         }
 
         Err(Self::Error::MissingTxData)
@@ -122,6 +347,15 @@ pub enum GetTransactionParserError {
 
     #[error("Missing transaction index.")]
     MissingIndex,
+
+    #[error("An index requires --hash/--number/--tag for the containing block.")]
+    IndexWithoutBlockId,
+
+    #[error("Provided multiple transaction identifiers. Either a transaction hash or a block identifier and index must be provided.")]
+    ConflictingTransactionId,
+
+    #[error("Missing transaction identifier. A transaction hash or a block identifier and index must be provided.")]
+    MissingTransactionId,
 }
 
 impl TryFrom<GetTransactionArgs> for GetTransaction {
@@ -129,21 +363,42 @@ impl TryFrom<GetTransactionArgs> for GetTransaction {
 
     fn try_from(value: GetTransactionArgs) -> Result<Self, Self::Error> {
         let GetTransactionArgs {
+            tx_hash,
             get_block_by_id,
             index,
         } = value;
 
-        let idx = index.ok_or(Self::Error::MissingIndex)?;
+        let block_id: Result<BlockId, BlockIdParserError> = get_block_by_id.try_into();
 
-        Ok(Self::BlockIdAndIdx(
-            get_block_by_id
-                .try_into()
-                .map_err(Self::Error::InvalidBlockId)?,
-            idx as usize,
-        ))
+        match (tx_hash, block_id, index) {
+            (Some(hash), Err(BlockIdParserError::MissingBlockId), None) => {
+                Ok(Self::TransactionHash(hash))
+            }
+            (Some(_), _, _) => Err(Self::Error::ConflictingTransactionId),
+            (None, Ok(block_id), Some(idx)) => Ok(Self::BlockIdAndIdx(block_id, idx as usize)),
+            (None, Ok(_), None) => Err(Self::Error::MissingIndex),
+            (None, Err(BlockIdParserError::MissingBlockId), None) => {
+                Err(Self::Error::MissingTransactionId)
+            }
+            (None, Err(BlockIdParserError::MissingBlockId), Some(_)) => {
+                Err(Self::Error::IndexWithoutBlockId)
+            }
+            (None, Err(err), _) => Err(Self::Error::InvalidBlockId(err)),
+        }
     }
 }
 
+const RAW_DATA_ONLY_CONFLICTING_FIELD_NAMES: [&str; 8] = [
+    "from",
+    "gas",
+    "gas_price",
+    "max_fee_per_gas",
+    "max_priority_fee_per_gas",
+    "value",
+    "nonce",
+    "chain_id",
+];
+
 #[derive(Args, Debug)]
 pub struct SimulateTransactionArgs {
     #[clap(flatten)]
@@ -151,12 +406,32 @@ pub struct SimulateTransactionArgs {
 
     #[clap(flatten)]
     get_block_by_id: GetBlockByIdArgs,
+
+    /// Only send the target account and calldata
+    #[arg(long, requires = "data", conflicts_with_all = RAW_DATA_ONLY_CONFLICTING_FIELD_NAMES)]
+    raw_data_only: bool,
+
+    /// Refuses to simulate the call if the target account has no code
+    #[arg(long)]
+    only_if_contract: bool,
+
+    /// Comma separated block numbers, tags or hashes to run the same call at, returning the output at each of them
+    #[arg(
+        long,
+        value_name = "BLOCK_IDS",
+        value_delimiter = ',',
+        conflicts_with = GET_BLOCK_BY_ID_ARG_GROUP_NAME
+    )]
+    multi_block: Vec<BlockId>,
 }
 
 #[derive(Error, Debug)]
 pub enum SimulateTransactionParserError {
     #[error("{0}")]
     TypedTxParserError(TypedTransactionParserError),
+
+    #[error("Missing call target. An address or ens must be provided when using raw data only.")]
+    MissingCallTarget,
 }
 
 impl TryFrom<SimulateTransactionArgs> for SimulateTransactionOptions {
@@ -166,71 +441,885 @@ impl TryFrom<SimulateTransactionArgs> for SimulateTransactionOptions {
         let SimulateTransactionArgs {
             typed_tx,
             get_block_by_id,
+            raw_data_only,
+            only_if_contract,
+            multi_block: _,
         } = value;
 
-        Ok(SimulateTransactionOptions::new(
-            typed_tx
-                .try_into()
-                .map_err(Self::Error::TypedTxParserError)?,
-            get_block_by_id.try_into().ok(),
-        ))
+        let tx: TypedTransaction = typed_tx
+            .try_into()
+            .map_err(Self::Error::TypedTxParserError)?;
+
+        let block_id = get_block_by_id.try_into().ok();
+
+        if raw_data_only {
+            let to = tx.to().ok_or(Self::Error::MissingCallTarget)?.clone();
+
+            return Ok(SimulateTransactionOptions::raw(
+                to,
+                tx.data().cloned().unwrap_or_default(),
+                block_id,
+            )
+            .with_only_if_contract(only_if_contract));
+        }
+
+        Ok(SimulateTransactionOptions::new(tx, block_id).with_only_if_contract(only_if_contract))
     }
 }
 
 #[derive(Debug, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub enum TransactionNamespaceResult {
+    #[serde(serialize_with = "serialize_with_checksummed_fields")]
     Transaction(Transaction),
     SentTransaction(SendTxResult),
-    Receipt(TransactionReceipt),
+    SentBatch(Vec<BatchTxResult>),
+    #[serde(serialize_with = "serialize_with_checksummed_fields")]
+    Receipt(EnrichedReceipt),
+    #[serde(serialize_with = "serialize_with_checksummed_fields")]
+    Status(TransactionStatus),
     Call(Bytes),
+    MultiBlockCall(Vec<BlockCallResult>),
+    Decoded(DecodedTransaction),
     #[serde(serialize_with = "parse_not_found", rename = "transaction")]
     NotFound(),
+    #[serde(serialize_with = "parse_not_found", rename = "receipt")]
+    ReceiptNotFound(),
+}
+
+impl NotFoundResult for TransactionNamespaceResult {
+    fn not_found(what: Missing) -> Self {
+        match what {
+            Missing::Receipt => TransactionNamespaceResult::ReceiptNotFound(),
+            Missing::Transaction | Missing::Block => TransactionNamespaceResult::NotFound(),
+        }
+    }
 }
 
 pub fn parse(
     context: &CommandExecutionContext,
     sub_command: TransactionCommand,
 ) -> Result<TransactionNamespaceResult, anyhow::Error> {
-    let TransactionCommand { hash, command } = sub_command;
+    let TransactionCommand { command } = sub_command;
 
     let node_provider = context.node_provider();
 
     let res: TransactionNamespaceResult = match command {
-        TransactionSubCommand::Get(get_transaction_args) => context
-            .execute(cmd::transaction::get_transaction(
+        TransactionSubCommand::Get(get_transaction_args) => parse_lookup(
+            context.execute(cmd::transaction::get_transaction(
                 node_provider,
-                hash.map(GetTransaction::TransactionHash)
-                    .map_or_else(|| get_transaction_args.try_into(), Ok)?,
-            ))?
-            .map_or_else(
-                TransactionNamespaceResult::NotFound,
-                TransactionNamespaceResult::Transaction,
-            ),
-        TransactionSubCommand::Receipt(_) => context
-            .execute(cmd::transaction::get_transaction_receipt(
+                get_transaction_args.try_into()?,
+            ))?,
+            TransactionNamespaceResult::Transaction,
+        ),
+        TransactionSubCommand::Receipt(receipt_args) => parse_lookup(
+            context.execute(cmd::transaction::get_transaction_receipt(
                 node_provider,
-                hash.ok_or(anyhow::anyhow!(
+                receipt_args.hash().ok_or(anyhow::anyhow!(
                     "Missing required argument transaction hash"
                 ))?,
-            ))?
-            .map_or_else(
-                TransactionNamespaceResult::NotFound,
-                TransactionNamespaceResult::Receipt,
-            ),
+                receipt_args.explain_failure,
+                receipt_args.follow_internal,
+            ))?,
+            TransactionNamespaceResult::Receipt,
+        ),
+        TransactionSubCommand::Status(status_args) => context
+            .execute(cmd::transaction::wait_for_confirmations(
+                node_provider,
+                status_args.hash().ok_or(anyhow::anyhow!(
+                    "Missing required argument transaction hash"
+                ))?,
+                status_args.confirmations,
+                CONFIRMATIONS_POLL_INTERVAL,
+                status_args
+                    .timeout
+                    .map(Duration::from_secs)
+                    .or(context.config().command_timeout()),
+            ))
+            .map(TransactionNamespaceResult::Status)?,
         TransactionSubCommand::Send(send_transaction_args) => context
             .execute(cmd::transaction::send_transaction(
                 node_provider,
-                send_transaction_args.try_into()?,
+                SendTransactionOptions::try_from(send_transaction_args)?
+                    .with_timeout(context.config().command_timeout()),
             ))
             .map(TransactionNamespaceResult::SentTransaction)?,
+        TransactionSubCommand::SendBatch(SendBatchArgs { file, from }) => context
+            .execute(cmd::transaction::send_batch(
+                node_provider,
+                from,
+                load_batch_transfers(&file)?,
+            ))
+            .map(TransactionNamespaceResult::SentBatch)?,
+        TransactionSubCommand::Call(simulate_transaction_args)
+            if !simulate_transaction_args.multi_block.is_empty() =>
+        {
+            let block_ids = simulate_transaction_args.multi_block.clone();
+
+            context
+                .execute(cmd::transaction::call_multi_block(
+                    node_provider,
+                    SimulateTransactionOptions::try_from(simulate_transaction_args)?
+                        .with_timeout(context.config().command_timeout()),
+                    block_ids,
+                ))
+                .map(TransactionNamespaceResult::MultiBlockCall)?
+        }
         TransactionSubCommand::Call(simulate_transaction_args) => context
             .execute(cmd::transaction::call(
                 node_provider,
-                simulate_transaction_args.try_into()?,
+                SimulateTransactionOptions::try_from(simulate_transaction_args)?
+                    .with_timeout(context.config().command_timeout()),
             ))
             .map(TransactionNamespaceResult::Call)?,
+        command @ TransactionSubCommand::Decode(_) => {
+            parse_offline(&TransactionCommand { command })?
+        }
     };
 
     Ok(res)
 }
+
+/// Runs the commands that don't need a node, see `TransactionCommand::is_offline`.
+pub fn parse_offline(
+    sub_command: &TransactionCommand,
+) -> Result<TransactionNamespaceResult, anyhow::Error> {
+    let res = match &sub_command.command {
+        TransactionSubCommand::Decode(DecodeTransactionArgs { raw }) => {
+            TransactionNamespaceResult::Decoded(cmd::transaction::decode_raw_transaction(
+                raw.clone(),
+            )?)
+        }
+        command => anyhow::bail!("The {command:?} command requires a node"),
+    };
+
+    Ok(res)
+}
+
+#[cfg(test)]
+mod tests {
+    use clap::Parser;
+    use ethers::types::{
+        transaction::eip2718::TypedTransaction, BlockId, BlockNumber, Bytes, H160, H256,
+    };
+
+    use serde_json::json;
+
+    use crate::{
+        cli::common::{parse_lookup, TypedTransactionParserError},
+        cmd::{
+            lookup::{Lookup, Missing},
+            transaction::{GetTransaction, SendTransactionOptions, SimulateTransactionOptions},
+        },
+    };
+
+    use super::{
+        migrate_deprecated_hash_arg, parse_offline, GetTransactionParserError,
+        SendTransactionParserError, SimulateTransactionParserError, TransactionCommand,
+        TransactionNamespaceResult, TransactionSubCommand,
+    };
+
+    const ADDRESS: &str = "0xf39fd6e51aad88f6f4ce6ab8827279cfffb92266";
+    const TX_HASH: &str = "0x79202697c177e951ea2bdfc283ef9a44108c41e2023cf56c4fd233a589da2e6a";
+
+    fn parse(args: &[&str]) -> Result<TransactionCommand, clap::Error> {
+        TransactionCommand::try_parse_from(["transaction"].iter().chain(args))
+    }
+
+    #[test]
+    fn should_only_decode_raw_transactions_offline() -> anyhow::Result<()> {
+        // Arrange
+        let decode = parse(&["decode", "--raw", "0x02c0"])?;
+        let get = parse(&["get", TX_HASH])?;
+
+        // Act
+        let res = parse_offline(&decode);
+
+        // Assert
+        assert!(decode.is_offline());
+        assert!(!get.is_offline());
+        assert!(res
+            .unwrap_err()
+            .to_string()
+            .contains("Invalid raw transaction"));
+        assert!(parse_offline(&get).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn should_serialize_a_missing_transaction_or_receipt_as_null() -> anyhow::Result<()> {
+        // Setup
+        let test_cases = [
+            (Missing::Transaction, json!({ "transaction": null })),
+            (Missing::Receipt, json!({ "receipt": null })),
+        ];
+
+        for (what, expected_res) in test_cases {
+            // Act
+            let res = serde_json::to_value(parse_lookup(
+                Lookup::NotFound { what },
+                TransactionNamespaceResult::Transaction,
+            ))?;
+
+            // Assert
+            assert_eq!(res, expected_res);
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn should_get_a_transaction_by_hash() -> anyhow::Result<()> {
+        // Act
+        let res = parse(&["get", TX_HASH])?;
+
+        // Assert
+        let TransactionSubCommand::Get(args) = res.command else {
+            panic!("Should be the get subcommand!");
+        };
+
+        let get_by: GetTransaction = args.try_into()?;
+        assert!(
+            matches!(get_by, GetTransaction::TransactionHash(hash) if hash == TX_HASH.parse::<H256>()?)
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn should_get_a_transaction_by_block_and_index() -> anyhow::Result<()> {
+        // Act
+        let res = parse(&["get", "--number", "1", "--index", "0"])?;
+
+        // Assert
+        let TransactionSubCommand::Get(args) = res.command else {
+            panic!("Should be the get subcommand!");
+        };
+
+        let get_by: GetTransaction = args.try_into()?;
+        assert!(matches!(get_by, GetTransaction::BlockIdAndIdx(_, 0)));
+
+        Ok(())
+    }
+
+    #[test]
+    fn should_not_get_a_transaction_with_a_hash_and_block_and_index() {
+        // Act
+        let res = parse(&["get", TX_HASH, "--number", "1", "--index", "0"]);
+
+        // Assert
+        assert!(res.is_err());
+    }
+
+    #[test]
+    fn should_not_get_a_transaction_without_identifiers() -> anyhow::Result<()> {
+        // Act
+        let res = parse(&["get"])?;
+
+        // Assert
+        let TransactionSubCommand::Get(args) = res.command else {
+            panic!("Should be the get subcommand!");
+        };
+
+        let res: Result<GetTransaction, GetTransactionParserError> = args.try_into();
+        assert!(matches!(
+            res,
+            Err(GetTransactionParserError::MissingTransactionId)
+        ));
+
+        Ok(())
+    }
+
+    #[test]
+    fn should_not_get_a_transaction_with_an_index_but_no_block() -> anyhow::Result<()> {
+        // Act
+        let res = parse(&["get", "--index", "2"])?;
+
+        // Assert
+        let TransactionSubCommand::Get(args) = res.command else {
+            panic!("Should be the get subcommand!");
+        };
+
+        let res: Result<GetTransaction, GetTransactionParserError> = args.try_into();
+        assert!(matches!(
+            res,
+            Err(GetTransactionParserError::IndexWithoutBlockId)
+        ));
+
+        Ok(())
+    }
+
+    #[test]
+    fn should_not_get_a_transaction_with_a_block_but_no_index() -> anyhow::Result<()> {
+        // Act
+        let res = parse(&["get", "--tag", "pending"])?;
+
+        // Assert
+        let TransactionSubCommand::Get(args) = res.command else {
+            panic!("Should be the get subcommand!");
+        };
+
+        let res: Result<GetTransaction, GetTransactionParserError> = args.try_into();
+        assert!(matches!(res, Err(GetTransactionParserError::MissingIndex)));
+
+        Ok(())
+    }
+
+    #[test]
+    fn should_get_a_transaction_by_pending_tag_and_index() -> anyhow::Result<()> {
+        // Act
+        let res = parse(&["get", "--tag", "pending", "--index", "1"])?;
+
+        // Assert
+        let TransactionSubCommand::Get(args) = res.command else {
+            panic!("Should be the get subcommand!");
+        };
+
+        let get_by: GetTransaction = args.try_into()?;
+        assert!(matches!(
+            get_by,
+            GetTransaction::BlockIdAndIdx(BlockId::Number(BlockNumber::Pending), 1)
+        ));
+
+        Ok(())
+    }
+
+    #[test]
+    fn should_get_a_receipt_with_a_positional_or_named_hash() -> anyhow::Result<()> {
+        // Setup
+        let test_cases: [&[&str]; 2] = [&["receipt", TX_HASH], &["receipt", "--hash", TX_HASH]];
+
+        for test_case in test_cases {
+            // Act
+            let res = parse(test_case)?;
+
+            // Assert
+            let TransactionSubCommand::Receipt(args) = res.command else {
+                panic!("Should be the receipt subcommand!");
+            };
+
+            assert_eq!(args.hash(), Some(TX_HASH.parse()?));
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn should_require_a_hash_for_the_receipt_subcommand() {
+        // Setup
+        let test_cases: [&[&str]; 2] = [&["receipt"], &["receipt", TX_HASH, "--hash", TX_HASH]];
+
+        for test_case in test_cases {
+            // Act
+            let res = parse(test_case);
+
+            // Assert
+            assert!(res.is_err());
+        }
+    }
+
+    #[test]
+    fn should_parse_the_status_confirmations_and_timeout() -> anyhow::Result<()> {
+        // Setup
+        let test_cases: [(&[&str], Option<u64>, Option<u64>); 3] = [
+            (&["status", TX_HASH], None, None),
+            (
+                &["status", "--hash", TX_HASH, "--confirmations", "3"],
+                Some(3),
+                None,
+            ),
+            (
+                &["status", TX_HASH, "--confirmations", "3", "--timeout", "60"],
+                Some(3),
+                Some(60),
+            ),
+        ];
+
+        for (args, expected_confirmations, expected_timeout) in test_cases {
+            // Act
+            let res = parse(args)?;
+
+            // Assert
+            let TransactionSubCommand::Status(status_args) = res.command else {
+                panic!("Should be the status subcommand!");
+            };
+
+            assert_eq!(status_args.hash(), Some(TX_HASH.parse()?));
+            assert_eq!(status_args.confirmations, expected_confirmations);
+            assert_eq!(status_args.timeout, expected_timeout);
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn should_reject_invalid_status_arguments() {
+        // Setup
+        let test_cases: [&[&str]; 4] = [
+            &["status"],
+            &["status", TX_HASH, "--confirmations", "0"],
+            &["status", TX_HASH, "--timeout", "60"],
+            &["status", TX_HASH, "--hash", TX_HASH],
+        ];
+
+        for test_case in test_cases {
+            // Act
+            let res = parse(test_case);
+
+            // Assert
+            assert!(res.is_err(), "{test_case:?}");
+        }
+    }
+
+    #[test]
+    fn should_reject_a_hash_for_the_send_and_call_subcommands() {
+        // Setup
+        let test_cases: [&[&str]; 3] = [
+            &["send", TX_HASH, "--raw", "0x00"],
+            &["send", "--raw", "0x00", "--hash", TX_HASH],
+            &[
+                "call",
+                TX_HASH,
+                "--to",
+                "0xf39fd6e51aad88f6f4ce6ab8827279cfffb92266",
+            ],
+        ];
+
+        for test_case in test_cases {
+            // Act
+            let res = parse(test_case);
+
+            // Assert
+            assert!(res.is_err());
+        }
+    }
+
+    #[test]
+    fn should_parse_the_send_and_call_subcommands_without_a_hash() {
+        // Setup
+        let test_cases: [&[&str]; 2] = [
+            &["send", "--raw", "0x00"],
+            &["call", "--to", "0xf39fd6e51aad88f6f4ce6ab8827279cfffb92266"],
+        ];
+
+        for test_case in test_cases {
+            // Act
+            let res = parse(test_case);
+
+            // Assert
+            assert!(res.is_ok());
+        }
+    }
+
+    #[test]
+    fn should_only_accept_the_target_and_data_with_raw_data_only() {
+        // Setup
+        let test_cases: [(&[&str], bool); 4] = [
+            (
+                &[
+                    "call",
+                    "--raw-data-only",
+                    "--to",
+                    ADDRESS,
+                    "--data",
+                    "0x1234",
+                ],
+                true,
+            ),
+            (&["call", "--raw-data-only", "--to", ADDRESS], false),
+            (
+                &[
+                    "call",
+                    "--raw-data-only",
+                    "--to",
+                    ADDRESS,
+                    "--data",
+                    "0x1234",
+                    "--from",
+                    ADDRESS,
+                ],
+                false,
+            ),
+            (
+                &[
+                    "call",
+                    "--raw-data-only",
+                    "--to",
+                    ADDRESS,
+                    "--data",
+                    "0x1234",
+                    "--value",
+                    "1",
+                ],
+                false,
+            ),
+        ];
+
+        for (args, is_valid) in test_cases {
+            // Act
+            let res = parse(args);
+
+            // Assert
+            assert_eq!(res.is_ok(), is_valid);
+        }
+    }
+
+    #[test]
+    fn should_not_build_a_raw_data_only_call_without_a_target() -> anyhow::Result<()> {
+        // Arrange
+        let res = parse(&["call", "--raw-data-only", "--data", "0x1234"])?;
+
+        let TransactionSubCommand::Call(args) = res.command else {
+            panic!("Should be the call subcommand!");
+        };
+
+        // Act
+        let res: Result<SimulateTransactionOptions, SimulateTransactionParserError> =
+            args.try_into();
+
+        // Assert
+        assert!(matches!(
+            res,
+            Err(SimulateTransactionParserError::MissingCallTarget)
+        ));
+
+        Ok(())
+    }
+
+    #[test]
+    fn should_not_send_both_calldata_and_a_function_call() -> anyhow::Result<()> {
+        // Arrange
+        let res = parse(&[
+            "send",
+            "--to",
+            ADDRESS,
+            "--data",
+            "0x1234",
+            "--sig",
+            "decimals()",
+        ])?;
+
+        let TransactionSubCommand::Send(args) = res.command else {
+            panic!("Should be the send subcommand!");
+        };
+
+        // Act
+        let res: Result<SendTransactionOptions, SendTransactionParserError> = args.try_into();
+
+        // Assert
+        assert!(matches!(
+            res,
+            Err(SendTransactionParserError::InvalidTypedTx(
+                TypedTransactionParserError::ConflictingCalldata
+            ))
+        ));
+
+        Ok(())
+    }
+
+    #[test]
+    fn should_encode_the_function_call_as_the_calldata() -> anyhow::Result<()> {
+        // Arrange
+        let res = parse(&[
+            "send",
+            "--to",
+            ADDRESS,
+            "--sig",
+            "transfer(address,uint256)",
+            "--args",
+            ADDRESS,
+            "1",
+        ])?;
+
+        let TransactionSubCommand::Send(args) = res.command else {
+            panic!("Should be the send subcommand!");
+        };
+
+        let expected_data: Bytes = format!(
+            "0xa9059cbb000000000000000000000000{}{:064x}",
+            &ADDRESS[2..],
+            1
+        )
+        .parse()?;
+
+        // Act
+        let res: Result<TypedTransaction, TypedTransactionParserError> =
+            args.typed_tx.unwrap().try_into();
+
+        // Assert
+        assert!(res.is_ok());
+        assert_eq!(res.unwrap().data(), Some(&expected_data));
+
+        Ok(())
+    }
+
+    fn parse_send_typed_tx(
+        args: &[&str],
+    ) -> anyhow::Result<Result<TypedTransaction, TypedTransactionParserError>> {
+        let res = parse(&[["send", "--to", ADDRESS].as_slice(), args].concat())?;
+
+        let TransactionSubCommand::Send(args) = res.command else {
+            panic!("Should be the send subcommand!");
+        };
+
+        Ok(args.typed_tx.unwrap().try_into())
+    }
+
+    #[test]
+    fn should_build_an_eip1559_transaction_with_the_eip1559_fees() -> anyhow::Result<()> {
+        // Act
+        let res = parse_send_typed_tx(&[
+            "--max-fee-per-gas",
+            "64",
+            "--max-priority-fee-per-gas",
+            "2",
+            "--nonce",
+            "7",
+        ])?;
+
+        // Assert
+        let Ok(TypedTransaction::Eip1559(tx)) = res else {
+            panic!("Should be an EIP-1559 transaction!");
+        };
+
+        assert_eq!(tx.max_fee_per_gas, Some(0x64.into()));
+        assert_eq!(tx.max_priority_fee_per_gas, Some(2.into()));
+        assert_eq!(tx.nonce, Some(7.into()));
+        assert_eq!(tx.to, Some(ADDRESS.parse::<H160>()?.into()));
+
+        Ok(())
+    }
+
+    #[test]
+    fn should_build_a_legacy_transaction_with_the_gas_price() -> anyhow::Result<()> {
+        // Act
+        let res = parse_send_typed_tx(&["--gas-price", "64"])?;
+
+        // Assert
+        let Ok(TypedTransaction::Legacy(tx)) = res else {
+            panic!("Should be a legacy transaction!");
+        };
+
+        assert_eq!(tx.gas_price, Some(0x64.into()));
+
+        Ok(())
+    }
+
+    #[test]
+    fn should_not_build_a_transaction_with_both_a_gas_price_and_eip1559_fees() -> anyhow::Result<()>
+    {
+        // Setup
+        let test_cases: [&[&str]; 2] = [
+            &["--gas-price", "100", "--max-fee-per-gas", "100"],
+            &["--gas-price", "100", "--max-priority-fee-per-gas", "2"],
+        ];
+
+        for test_case in test_cases {
+            // Act
+            let res = parse_send_typed_tx(test_case)?;
+
+            // Assert
+            assert!(matches!(
+                res,
+                Err(TypedTransactionParserError::ConflictingGasPricing)
+            ));
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn should_only_guard_typed_transactions_with_only_if_contract() {
+        // Setup
+        let test_cases: [(&[&str], bool); 3] = [
+            (&["send", "--to", ADDRESS, "--only-if-contract"], true),
+            (&["call", "--to", ADDRESS, "--only-if-contract"], true),
+            (&["send", "--raw", "0x00", "--only-if-contract"], false),
+        ];
+
+        for (args, is_valid) in test_cases {
+            // Act
+            let res = parse(args);
+
+            // Assert
+            assert_eq!(res.is_ok(), is_valid);
+        }
+    }
+
+    #[test]
+    fn should_only_accept_from_index_for_typed_transactions_without_from() {
+        // Setup
+        let test_cases: [(&[&str], bool); 4] = [
+            (&["send", "--to", ADDRESS, "--from-index", "1"], true),
+            (&["send", "--to", ADDRESS, "--from-index", "-1"], false),
+            (
+                &[
+                    "send",
+                    "--to",
+                    ADDRESS,
+                    "--from",
+                    ADDRESS,
+                    "--from-index",
+                    "1",
+                ],
+                false,
+            ),
+            (&["send", "--raw", "0x00", "--from-index", "1"], false),
+        ];
+
+        for (args, is_valid) in test_cases {
+            // Act
+            let res = parse(args);
+
+            // Assert
+            assert_eq!(res.is_ok(), is_valid, "{args:?}");
+        }
+    }
+
+    #[test]
+    fn should_parse_the_blocks_to_compare_the_call_at() -> anyhow::Result<()> {
+        // Arrange
+        let block_hash = format!("0x{}", "11".repeat(32));
+        let block_ids = format!("10,0x14,latest,{block_hash}");
+
+        // Act
+        let res = parse(&["call", "--to", ADDRESS, "--multi-block", &block_ids])?;
+
+        // Assert
+        let TransactionSubCommand::Call(args) = res.command else {
+            panic!("Should be the call subcommand!");
+        };
+        assert_eq!(
+            args.multi_block,
+            vec![
+                BlockId::Number(BlockNumber::Number(10.into())),
+                BlockId::Number(BlockNumber::Number(20.into())),
+                BlockId::Number(BlockNumber::Latest),
+                BlockId::Hash(block_hash.parse()?),
+            ]
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn should_not_compare_a_call_across_invalid_blocks() {
+        // Setup
+        let test_cases: [&[&str]; 3] = [
+            &["--multi-block", "10,newest"],
+            &["--multi-block", "10,20", "--number", "10"],
+            &["--multi-block", ""],
+        ];
+
+        for test_case in test_cases {
+            // Act
+            let res = parse(&[["call", "--to", ADDRESS].as_slice(), test_case].concat());
+
+            // Assert
+            assert!(res.is_err(), "{test_case:?}");
+        }
+    }
+
+    #[test]
+    fn should_only_migrate_the_hash_argument_of_the_transaction_namespace() {
+        // Setup
+        let hash_arg = format!("--hash={TX_HASH}");
+        let test_cases: [Vec<&str>; 4] = [
+            vec!["yaeth", "trace", "transaction", "--hash", TX_HASH],
+            vec!["yaeth", "trace", "transaction", &hash_arg],
+            vec!["yaeth", "--file", "transaction", "block", "number"],
+            vec![
+                "yaeth",
+                "-f",
+                "transaction",
+                "block",
+                "get",
+                "--hash",
+                TX_HASH,
+            ],
+        ];
+
+        for args in test_cases {
+            // Act
+            let res = migrate_deprecated_hash_arg(args.iter().map(Into::into));
+
+            // Assert
+            assert_eq!(res, args);
+        }
+    }
+
+    #[test]
+    fn should_not_accept_function_arguments_without_a_signature() {
+        // Act
+        let res = parse(&["send", "--to", ADDRESS, "--args", "1"]);
+
+        // Assert
+        assert!(res.is_err());
+    }
+
+    #[test]
+    fn should_move_the_deprecated_hash_argument_after_the_subcommand() {
+        // Setup
+        let hash_arg = format!("--hash={TX_HASH}");
+        let test_cases: [(Vec<&str>, Vec<&str>); 5] = [
+            (
+                vec!["yaeth", "transaction", "--hash", TX_HASH, "receipt"],
+                vec!["yaeth", "transaction", "receipt", TX_HASH],
+            ),
+            (
+                vec!["yaeth", "transaction", &hash_arg, "get"],
+                vec!["yaeth", "transaction", "get", TX_HASH],
+            ),
+            (
+                vec![
+                    "yaeth",
+                    "transaction",
+                    "--hash",
+                    TX_HASH,
+                    "send",
+                    "--raw",
+                    "0x00",
+                ],
+                vec!["yaeth", "transaction", "send", TX_HASH, "--raw", "0x00"],
+            ),
+            (
+                vec![
+                    "yaeth",
+                    "--rpc-url",
+                    "http://localhost:8545",
+                    "-o",
+                    "json",
+                    "--raw-output",
+                    "transaction",
+                    "--hash",
+                    TX_HASH,
+                    "status",
+                ],
+                vec![
+                    "yaeth",
+                    "--rpc-url",
+                    "http://localhost:8545",
+                    "-o",
+                    "json",
+                    "--raw-output",
+                    "transaction",
+                    "status",
+                    TX_HASH,
+                ],
+            ),
+            (
+                vec!["yaeth", "transaction", "receipt", TX_HASH],
+                vec!["yaeth", "transaction", "receipt", TX_HASH],
+            ),
+        ];
+
+        for (args, expected_args) in test_cases {
+            // Act
+            let res = migrate_deprecated_hash_arg(args.into_iter().map(Into::into));
+
+            // Assert
+            assert_eq!(res, expected_args);
+        }
+    }
+}
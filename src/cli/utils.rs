@@ -1,14 +1,34 @@
+use std::path::PathBuf;
+
 use crate::{
-    cmd::utils::{self, SignTransactionData},
+    cmd::{
+        signatures,
+        utils::{
+            self, BytecodeVerification, CalldataFunction, ChainInfo, ConvertedAmount, DecodedCall,
+            MathOperation, PermitOptions, PermitSignature, ProtocolVersion, SelectorCache,
+            SignTransactionData, SignatureComponents, DEFAULT_SIGNATURE_DATABASE_URL,
+        },
+    },
     context::CommandExecutionContext,
 };
-use clap::{command, Args, Parser, Subcommand};
-use ethers::types::{Bytes, EIP1186ProofResponse, Signature, SyncingStatus, H160, H256, U256};
+use clap::{builder::PossibleValue, Args, Parser, Subcommand, ValueEnum};
+use ethers::{
+    types::{
+        Address, Bytes, EIP1186ProofResponse, NameOrAddress, Selector, Signature, SyncingStatus,
+        H160, H256, U256,
+    },
+    utils::Units,
+};
 use serde::Serialize;
+use thiserror::Error;
 
-use super::common::{
-    GetAccountArgs, GetBlockByIdArgs, NoArgs, TypedTransactionArgs, TypedTransactionParserError,
-    TX_ARGS_FIELD_NAMES,
+use super::{
+    common::{
+        parse_bytes_arg, serialize_checksummed, serialize_checksummed_list,
+        serialize_with_checksummed_fields, GetAccountArgs, GetAccountParserError, GetBlockByIdArgs,
+        NoArgs, TypedTransactionArgs, TypedTransactionParserError, TX_ARGS_FIELD_NAMES,
+    },
+    event::parse_event_topic,
 };
 
 #[derive(Parser, Debug)]
@@ -27,17 +47,436 @@ pub enum UtilsSubCommand {
     /// Gets the chain id from the node
     ChainId(NoArgs),
 
+    /// Gets the chain id from the node along with the chain name and native currency
+    ChainInfo(NoArgs),
+
     /// Gets the EIP-1186 proof for the provided input
     Proof(GetProofArgs),
 
     /// Gets the ethereum protocol version
-    ProtocolVersion(NoArgs),
+    ProtocolVersion(ProtocolVersionArgs),
 
     /// Signs the given transaction or data
     Sign(SignArgs),
 
     /// Gets the current sync status for the node
     SyncStatus(NoArgs),
+
+    /// Encodes the provided bytes to base58
+    Base58Encode(BytesArgs),
+
+    /// Decodes the provided base58 string to bytes
+    Base58Decode(EncodedDataArgs),
+
+    /// Encodes the provided bytes to base64
+    Base64Encode(BytesArgs),
+
+    /// Decodes the provided base64 string to bytes
+    Base64Decode(EncodedDataArgs),
+
+    /// Splits a 65 bytes signature in its r, s and v components
+    SplitSignature(SplitSignatureArgs),
+
+    /// Joins the r, s and v components in a 65 bytes signature
+    JoinSignature(JoinSignatureArgs),
+
+    /// Compares the code deployed at the provided account with the expected bytecode
+    VerifyBytecode(VerifyBytecodeArgs),
+
+    /// Signs an EIP-2612 permit allowing the spender to transfer the owner tokens
+    #[command(alias = "sign-permit")]
+    Permit(PermitArgs),
+
+    /// Converts an amount expressed in the provided unit to wei
+    ToWei(ConvertUnitsArgs),
+
+    /// Converts an amount expressed in the provided unit to gwei
+    ToGwei(ConvertUnitsArgs),
+
+    /// Converts an amount of wei to the provided unit
+    FromWei(ConvertUnitsArgs),
+
+    /// Converts an amount between wei, kwei, mwei, gwei, szabo, finney and ether
+    Convert(ConvertArgs),
+
+    /// Computes the topic hash of the provided event signature
+    EventTopic(EventTopicArgs),
+
+    /// ABI encodes a call to the provided function, e.g. to send it with transaction send --data
+    AbiEncode(AbiEncodeArgs),
+
+    /// Decodes the arguments of the provided calldata with a function signature or an ABI
+    AbiDecode(AbiDecodeArgs),
+
+    /// Derives the address of the provided secp256k1 public key
+    #[command(alias = "address")]
+    AddrFromPubkey(AddrFromPubkeyArgs),
+
+    /// Normalizes an address, with or without 0x prefix, in any case or as a zero padded 32 bytes word, to its checksummed form
+    ToAddress(ToAddressArgs),
+
+    /// Gets the EIP-55 checksummed form of the provided address
+    Checksum(ChecksumArgs),
+
+    /// Resolves the provided ENS name to its address
+    EnsResolve(EnsResolveArgs),
+
+    /// Gets the primary ENS name of the provided address
+    EnsLookup(EnsLookupArgs),
+
+    /// Gets the function signatures matching the provided selector from a signature database
+    SelectorLookup(SelectorLookupArgs),
+
+    /// Gets the function signatures matching the provided selector from a built in table of common signatures
+    Selector(SelectorArgs),
+
+    /// Applies an arithmetic operation to two uint256 values, failing on overflow instead of wrapping around
+    #[command(alias = "wei-math")]
+    Math(MathArgs),
+}
+
+impl UtilsCommand {
+    /// Tells if the command is a pure conversion that can run without reaching the node.
+    pub fn is_offline(&self) -> bool {
+        matches!(
+            self.command,
+            UtilsSubCommand::ToWei(_)
+                | UtilsSubCommand::ToGwei(_)
+                | UtilsSubCommand::FromWei(_)
+                | UtilsSubCommand::Convert(_)
+                | UtilsSubCommand::EventTopic(_)
+                | UtilsSubCommand::AbiEncode(_)
+                | UtilsSubCommand::AbiDecode(_)
+                | UtilsSubCommand::AddrFromPubkey(_)
+                | UtilsSubCommand::ToAddress(_)
+                | UtilsSubCommand::Checksum(_)
+                | UtilsSubCommand::Math(_)
+                | UtilsSubCommand::Selector(SelectorArgs { remote: false, .. })
+        )
+    }
+}
+
+#[derive(Args, Debug)]
+pub struct MathArgs {
+    /// Operation applied to the operands
+    operation: MathOperation,
+
+    /// Left operand, either decimal or 0x prefixed hex
+    #[arg(value_parser = parse_uint)]
+    lhs: U256,
+
+    /// Right operand, either decimal or 0x prefixed hex
+    #[arg(value_parser = parse_uint)]
+    rhs: U256,
+}
+
+impl ValueEnum for MathOperation {
+    fn value_variants<'a>() -> &'a [Self] {
+        &[Self::Add, Self::Sub, Self::Mul, Self::Div, Self::Mod]
+    }
+
+    fn to_possible_value(&self) -> Option<PossibleValue> {
+        Some(match self {
+            MathOperation::Add => PossibleValue::new("add"),
+            MathOperation::Sub => PossibleValue::new("sub"),
+            MathOperation::Mul => PossibleValue::new("mul"),
+            MathOperation::Div => PossibleValue::new("div").help("Rounds the quotient down"),
+            MathOperation::Mod => PossibleValue::new("mod"),
+        })
+    }
+}
+
+#[derive(Error, Debug)]
+pub enum UintParserError {
+    #[error(
+        "Invalid uint256 {0}. Expected a decimal or 0x prefixed hex number that fits in 256 bits"
+    )]
+    InvalidUint(String),
+}
+
+fn parse_uint(value: &str) -> Result<U256, UintParserError> {
+    match value.strip_prefix("0x") {
+        Some(hex_value) => U256::from_str_radix(hex_value, 16).ok(),
+        None => U256::from_dec_str(value).ok(),
+    }
+    .ok_or(UintParserError::InvalidUint(value.to_string()))
+}
+
+#[derive(Args, Debug)]
+pub struct EnsResolveArgs {
+    /// ENS name to resolve, e.g. "vitalik.eth"
+    name: String,
+}
+
+#[derive(Args, Debug)]
+pub struct EnsLookupArgs {
+    /// Address whose reverse record is looked up
+    address: H160,
+}
+
+#[derive(Args, Debug)]
+pub struct SelectorLookupArgs {
+    /// Hex encoded function selector, e.g. 0xa9059cbb
+    #[arg(value_parser = parse_selector)]
+    selector: Selector,
+
+    /// Base url of the 4byte-directory compatible signature database
+    #[arg(long, value_name = "URL", default_value = DEFAULT_SIGNATURE_DATABASE_URL)]
+    api_url: String,
+
+    /// File caching the signatures found between runs (defaults to a file in the temp directory)
+    #[arg(long, value_name = "PATH")]
+    cache_file: Option<PathBuf>,
+}
+
+#[derive(Args, Debug)]
+pub struct SelectorArgs {
+    /// Hex encoded function selector, e.g. 0xa9059cbb
+    #[arg(value_parser = parse_selector)]
+    selector: Selector,
+
+    /// Also queries the signature database, keeping the built in matches if it can't be reached
+    #[arg(long)]
+    remote: bool,
+
+    /// Base url of the 4byte-directory compatible signature database
+    #[arg(long, value_name = "URL", requires = "remote")]
+    api_url: Option<String>,
+
+    /// File caching the signatures found between runs (defaults to a file in the temp directory)
+    #[arg(long, value_name = "PATH", requires = "remote")]
+    cache_file: Option<PathBuf>,
+}
+
+#[derive(Error, Debug)]
+pub enum SelectorParserError {
+    #[error("Invalid function selector {0}. Expected 4 hex encoded bytes, e.g. 0xa9059cbb")]
+    InvalidSelector(String),
+}
+
+fn parse_selector(value: &str) -> Result<Selector, SelectorParserError> {
+    hex::decode(value.strip_prefix("0x").unwrap_or(value))
+        .ok()
+        .and_then(|bytes| bytes.try_into().ok())
+        .ok_or(SelectorParserError::InvalidSelector(value.to_string()))
+}
+
+#[derive(Args, Debug)]
+pub struct ToAddressArgs {
+    /// Address or zero padded 32 bytes word to normalize
+    value: String,
+}
+
+#[derive(Args, Debug)]
+pub struct ChecksumArgs {
+    /// Address to checksum, with or without 0x prefix
+    #[arg(value_parser = parse_hex_address)]
+    address: String,
+
+    /// Fails if the provided mixed case address is not correctly checksummed
+    #[arg(long)]
+    verify: bool,
+}
+
+#[derive(Error, Debug)]
+pub enum ChecksumParserError {
+    #[error("Invalid address {0}. Expected 40 hex chars, with or without 0x prefix")]
+    InvalidAddress(String),
+}
+
+/// Strips the 0x prefix of the address, keeping the case of the hex chars to verify the checksum.
+fn parse_hex_address(value: &str) -> Result<String, ChecksumParserError> {
+    let hex_address = value.strip_prefix("0x").unwrap_or(value);
+
+    if hex_address.len() != 40 || !hex_address.chars().all(|c| c.is_ascii_hexdigit()) {
+        return Err(ChecksumParserError::InvalidAddress(value.to_string()));
+    }
+
+    Ok(hex_address.to_string())
+}
+
+#[derive(Args, Debug)]
+pub struct AddrFromPubkeyArgs {
+    /// Hex encoded public key, either compressed (0x02/0x03...), uncompressed (0x04...) or the raw 64 bytes
+    #[arg(long)]
+    pubkey: Bytes,
+}
+
+#[derive(Args, Debug)]
+pub struct EventTopicArgs {
+    /// Human readable event signature, e.g. "Transfer(address,address,uint256)"
+    #[arg(value_name = "SIGNATURE", value_parser = parse_event_topic)]
+    topic: H256,
+}
+
+#[derive(Args, Debug)]
+pub struct AbiEncodeArgs {
+    /// Human readable function signature, e.g. "transfer(address,uint256)"
+    signature: String,
+
+    /// Function arguments in order. Uints can be decimal or 0x prefixed hex and arrays are written as [a,b]
+    #[arg(allow_hyphen_values = true)]
+    args: Vec<String>,
+}
+
+#[derive(Args, Debug)]
+pub struct AbiDecodeArgs {
+    /// Human readable signature of the called function, e.g. "transfer(address,uint256)"
+    #[arg(long, conflicts_with = "abi", required_unless_present = "abi")]
+    sig: Option<String>,
+
+    /// ABI file, or compiler artifact with an abi field, holding the called function
+    #[arg(long, value_name = "PATH")]
+    abi: Option<PathBuf>,
+
+    /// Hex encoded calldata, including the 4 bytes selector, or @<path> to a file containing it
+    #[arg(value_name = "BYTES|@FILE", value_parser = parse_bytes_arg)]
+    data: Bytes,
+}
+
+#[derive(Error, Debug)]
+pub enum AbiDecodeParserError {
+    #[error("Missing function. Either a signature or an ABI must be provided.")]
+    MissingFunction,
+}
+
+impl AbiDecodeArgs {
+    fn function(&self) -> Result<CalldataFunction, AbiDecodeParserError> {
+        match (&self.sig, &self.abi) {
+            (Some(sig), _) => Ok(CalldataFunction::Signature(sig.clone())),
+            (None, Some(abi)) => Ok(CalldataFunction::Abi(abi.clone())),
+            (None, None) => Err(AbiDecodeParserError::MissingFunction),
+        }
+    }
+}
+
+#[derive(Args, Debug)]
+pub struct ConvertUnitsArgs {
+    /// Decimal amount to convert
+    #[arg(allow_hyphen_values = true)]
+    amount: String,
+
+    /// Unit of the amount, or of the result for from-wei (wei, gwei, ether or a number of decimals)
+    #[arg(long, default_value = "ether")]
+    unit: Units,
+}
+
+#[derive(Args, Debug)]
+pub struct ConvertArgs {
+    /// Decimal amount to convert
+    #[arg(allow_hyphen_values = true)]
+    amount: String,
+
+    /// Unit of the amount (wei, kwei, mwei, gwei, szabo, finney, eth, ether or a number of decimals)
+    #[arg(long)]
+    from: Units,
+
+    /// Unit of the result (wei, kwei, mwei, gwei, szabo, finney, eth, ether or a number of decimals)
+    #[arg(long)]
+    to: Units,
+}
+
+#[derive(Args, Debug)]
+pub struct PermitArgs {
+    /// Address of the EIP-2612 token
+    #[arg(long)]
+    token: Address,
+
+    /// Address of the token owner signing the permit
+    #[arg(long)]
+    owner: Address,
+
+    /// Address allowed to spend the owner tokens
+    #[arg(long)]
+    spender: Address,
+
+    /// Amount of tokens the spender is allowed to transfer
+    #[arg(long)]
+    value: U256,
+
+    /// Unix timestamp after which the permit expires
+    #[arg(long)]
+    deadline: U256,
+}
+
+impl From<PermitArgs> for PermitOptions {
+    fn from(value: PermitArgs) -> Self {
+        let PermitArgs {
+            token,
+            owner,
+            spender,
+            value,
+            deadline,
+        } = value;
+
+        Self {
+            token,
+            owner,
+            spender,
+            value,
+            deadline,
+        }
+    }
+}
+
+#[derive(Args, Debug)]
+pub struct ProtocolVersionArgs {
+    /// Fails instead of reporting the method as not supported when the node does not implement it
+    #[arg(long)]
+    strict: bool,
+}
+
+#[derive(Args, Debug)]
+pub struct BytesArgs {
+    /// Hex encoded data
+    #[arg()]
+    data: Bytes,
+}
+
+#[derive(Args, Debug)]
+pub struct EncodedDataArgs {
+    /// Encoded data
+    #[arg()]
+    data: String,
+}
+
+#[derive(Args, Debug)]
+pub struct SplitSignatureArgs {
+    /// Hex encoded 65 bytes signature
+    #[arg()]
+    signature: Bytes,
+}
+
+#[derive(Args, Debug)]
+pub struct JoinSignatureArgs {
+    #[arg(long)]
+    r: H256,
+
+    #[arg(long)]
+    s: H256,
+
+    /// Recovery id, either 0/1 or 27/28
+    #[arg(long)]
+    v: u64,
+}
+
+#[derive(Args, Debug)]
+pub struct VerifyBytecodeArgs {
+    #[clap(flatten)]
+    get_account_by_id: GetAccountArgs,
+
+    /// Hex encoded expected bytecode or @<path> to a file containing it
+    #[arg(long, value_name = "BYTES|@FILE", value_parser = parse_bytes_arg)]
+    expected: Bytes,
+
+    /// Ignore the trailing solc metadata of both bytecodes. Immutable variables are written into the
+    /// runtime code at deployment, so the expected bytecode must hold the deployed values of the
+    /// immutables for them not to be reported as mismatches
+    #[arg(long)]
+    ignore_metadata: bool,
+
+    #[clap(flatten)]
+    get_block_by_id: GetBlockByIdArgs,
 }
 
 #[derive(Args, Debug)]
@@ -52,15 +491,43 @@ pub struct GetProofArgs {
     get_block_by_id: GetBlockByIdArgs,
 }
 
+#[derive(Args, Debug)]
+pub struct SignerArgs {
+    /// Ethereum address of the signing account
+    #[arg(
+        long,
+        conflicts_with_all = ["ens", "from_index"],
+        required_unless_present_any = ["ens", "from_index"]
+    )]
+    address: Option<H160>,
+
+    /// Ens name of the signing account
+    #[arg(long, conflicts_with = "from_index")]
+    ens: Option<String>,
+
+    /// Signs with the unlocked node account at this index of eth_accounts
+    #[arg(long)]
+    from_index: Option<usize>,
+}
+
 #[derive(Args, Debug)]
 pub struct SignArgs {
     #[clap(flatten)]
-    get_account_by_id: GetAccountArgs,
+    signer: SignerArgs,
 
     /// Raw byte data to sign
     #[clap(conflicts_with_all = TX_ARGS_FIELD_NAMES)]
     raw: Option<Bytes>,
 
+    /// Json file with the EIP-712 domain, types and message to sign
+    #[arg(
+        long,
+        value_name = "PATH",
+        conflicts_with = "raw",
+        conflicts_with_all = TX_ARGS_FIELD_NAMES
+    )]
+    typed_data_file: Option<PathBuf>,
+
     #[clap(flatten)]
     typed_tx: TypedTransactionArgs,
 }
@@ -76,12 +543,37 @@ impl TryFrom<TypedTransactionArgs> for SignTransactionData {
 #[derive(Debug, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub enum UtilsNamespaceResult {
+    #[serde(serialize_with = "serialize_checksummed_list")]
     Accounts(Vec<H160>),
     ChainId(U256),
+    ChainInfo(ChainInfo),
+    #[serde(serialize_with = "serialize_with_checksummed_fields")]
     Proof(EIP1186ProofResponse),
-    ProtocolVersion(U256),
     Sign(Signature),
     SyncStatus(SyncingStatus),
+    Encoded(String),
+    Decoded(Bytes),
+    BytecodeVerification(BytecodeVerification),
+    Permit(PermitSignature),
+    SignatureComponents(SignatureComponents),
+    JoinedSignature(Bytes),
+    UnitConversion(String),
+    Converted(ConvertedAmount),
+    EventTopic(H256),
+    Calldata(Bytes),
+    DecodedCall(DecodedCall),
+    #[serde(serialize_with = "serialize_checksummed")]
+    Address(Address),
+    NormalizedAddress(String),
+    ChecksummedAddress(String),
+    #[serde(serialize_with = "serialize_checksummed")]
+    ResolvedAddress(Address),
+    EnsName(String),
+    Signatures(Vec<String>),
+    Math(String),
+    // Serialized as is to report the support flag next to the version, untagged variants must stay last
+    #[serde(untagged)]
+    ProtocolVersion(ProtocolVersion),
 }
 
 pub fn parse(
@@ -97,6 +589,42 @@ pub fn parse(
         UtilsSubCommand::ChainId(_) => context
             .execute(utils::get_chain_id(node_provider))
             .map(UtilsNamespaceResult::ChainId),
+        UtilsSubCommand::ChainInfo(_) => context
+            .execute(utils::get_chain_info(node_provider))
+            .map(UtilsNamespaceResult::ChainInfo),
+        UtilsSubCommand::EnsResolve(EnsResolveArgs { name }) => context
+            .execute(utils::resolve_ens_name(node_provider, name))
+            .map(UtilsNamespaceResult::ResolvedAddress),
+        UtilsSubCommand::EnsLookup(EnsLookupArgs { address }) => context
+            .execute(utils::lookup_ens_name(node_provider, address))
+            .map(UtilsNamespaceResult::EnsName),
+        UtilsSubCommand::SelectorLookup(SelectorLookupArgs {
+            selector,
+            api_url,
+            cache_file,
+        }) => {
+            let cache = SelectorCache::new(cache_file.unwrap_or_else(SelectorCache::default_path));
+
+            context
+                .execute(utils::lookup_selector(&api_url, &cache, selector))
+                .map(UtilsNamespaceResult::Signatures)
+        }
+        UtilsSubCommand::Selector(SelectorArgs {
+            selector,
+            remote: true,
+            api_url,
+            cache_file,
+        }) => {
+            let cache = SelectorCache::new(cache_file.unwrap_or_else(SelectorCache::default_path));
+
+            context
+                .execute(utils::lookup_selector_with_known_signatures(
+                    api_url.as_deref().unwrap_or(DEFAULT_SIGNATURE_DATABASE_URL),
+                    &cache,
+                    selector,
+                ))
+                .map(UtilsNamespaceResult::Signatures)
+        }
         UtilsSubCommand::Proof(GetProofArgs {
             get_account_by_id,
             storage_locations,
@@ -109,25 +637,649 @@ pub fn parse(
                 get_block_by_id.try_into().ok(),
             ))
             .map(UtilsNamespaceResult::Proof),
-        UtilsSubCommand::ProtocolVersion(_) => context
-            .execute(utils::get_protocol_version(node_provider))
+        UtilsSubCommand::ProtocolVersion(ProtocolVersionArgs { strict }) => context
+            .execute(utils::get_protocol_version(node_provider, strict))
             .map(UtilsNamespaceResult::ProtocolVersion),
         UtilsSubCommand::Sign(SignArgs {
-            get_account_by_id,
-            raw: data,
+            signer:
+                SignerArgs {
+                    address,
+                    ens,
+                    from_index,
+                },
+            raw,
+            typed_data_file,
             typed_tx: tx,
+        }) => {
+            let data = match (raw, typed_data_file) {
+                (Some(raw), _) => SignTransactionData::Raw(raw),
+                (None, Some(path)) => {
+                    SignTransactionData::TypedData(utils::load_typed_data(&path)?)
+                }
+                (None, None) => tx.try_into()?,
+            };
+
+            context
+                .execute(async {
+                    let from = match (address, ens, from_index) {
+                        (Some(address), _, _) => NameOrAddress::Address(address),
+                        (None, Some(ens), _) => NameOrAddress::Name(ens),
+                        (None, None, Some(index)) => NameOrAddress::Address(
+                            utils::get_account_at(node_provider, index).await?,
+                        ),
+                        (None, None, None) => Err(GetAccountParserError::MissingAccountId)?,
+                    };
+
+                    utils::sign(node_provider, from, data).await
+                })
+                .map(UtilsNamespaceResult::Sign)
+        }
+        UtilsSubCommand::SyncStatus(_) => context
+            .execute(utils::get_sync_status(node_provider))
+            .map(UtilsNamespaceResult::SyncStatus),
+        UtilsSubCommand::Base58Encode(BytesArgs { data }) => {
+            Ok(UtilsNamespaceResult::Encoded(utils::base58_encode(data)))
+        }
+        UtilsSubCommand::Base58Decode(EncodedDataArgs { data }) => {
+            utils::base58_decode(&data).map(UtilsNamespaceResult::Decoded)
+        }
+        UtilsSubCommand::Base64Encode(BytesArgs { data }) => {
+            Ok(UtilsNamespaceResult::Encoded(utils::base64_encode(data)))
+        }
+        UtilsSubCommand::Base64Decode(EncodedDataArgs { data }) => {
+            utils::base64_decode(&data).map(UtilsNamespaceResult::Decoded)
+        }
+        UtilsSubCommand::SplitSignature(SplitSignatureArgs { signature }) => {
+            utils::split_signature(signature).map(UtilsNamespaceResult::SignatureComponents)
+        }
+        UtilsSubCommand::JoinSignature(JoinSignatureArgs { r, s, v }) => {
+            utils::join_signature(r, s, v).map(UtilsNamespaceResult::JoinedSignature)
+        }
+        UtilsSubCommand::VerifyBytecode(VerifyBytecodeArgs {
+            get_account_by_id,
+            expected,
+            ignore_metadata,
+            get_block_by_id,
         }) => context
-            .execute(utils::sign(
+            .execute(utils::verify_bytecode(
                 node_provider,
                 get_account_by_id.try_into()?,
-                data.map(SignTransactionData::Raw)
-                    .map_or_else(|| tx.try_into(), Ok)?,
+                expected,
+                ignore_metadata,
+                get_block_by_id.try_into().ok(),
             ))
-            .map(UtilsNamespaceResult::Sign),
-        UtilsSubCommand::SyncStatus(_) => context
-            .execute(utils::get_sync_status(node_provider))
-            .map(UtilsNamespaceResult::SyncStatus),
+            .map(UtilsNamespaceResult::BytecodeVerification),
+        UtilsSubCommand::Permit(permit_args) => context
+            .execute(utils::sign_permit(node_provider, permit_args.into()))
+            .map(UtilsNamespaceResult::Permit),
+        command @ (UtilsSubCommand::ToWei(_)
+        | UtilsSubCommand::ToGwei(_)
+        | UtilsSubCommand::FromWei(_)
+        | UtilsSubCommand::Convert(_)
+        | UtilsSubCommand::EventTopic(_)
+        | UtilsSubCommand::AbiEncode(_)
+        | UtilsSubCommand::AbiDecode(_)
+        | UtilsSubCommand::AddrFromPubkey(_)
+        | UtilsSubCommand::ToAddress(_)
+        | UtilsSubCommand::Checksum(_)
+        | UtilsSubCommand::Math(_)
+        | UtilsSubCommand::Selector(SelectorArgs { remote: false, .. })) => {
+            parse_offline(&UtilsCommand { command })
+        }
     }?;
 
     Ok(res)
 }
+
+/// Runs the commands that don't need a node, see `UtilsCommand::is_offline`.
+pub fn parse_offline(sub_command: &UtilsCommand) -> Result<UtilsNamespaceResult, anyhow::Error> {
+    let res = match &sub_command.command {
+        UtilsSubCommand::ToWei(ConvertUnitsArgs { amount, unit }) => {
+            UtilsNamespaceResult::UnitConversion(utils::to_wei(amount, *unit)?.to_string())
+        }
+        UtilsSubCommand::ToGwei(ConvertUnitsArgs { amount, unit }) => {
+            UtilsNamespaceResult::UnitConversion(utils::to_gwei(amount, *unit)?)
+        }
+        UtilsSubCommand::FromWei(ConvertUnitsArgs { amount, unit }) => {
+            UtilsNamespaceResult::UnitConversion(utils::from_wei(amount, *unit)?)
+        }
+        UtilsSubCommand::Convert(ConvertArgs { amount, from, to }) => {
+            UtilsNamespaceResult::Converted(utils::convert_units(amount, *from, *to)?)
+        }
+        UtilsSubCommand::EventTopic(EventTopicArgs { topic }) => {
+            UtilsNamespaceResult::EventTopic(*topic)
+        }
+        UtilsSubCommand::AbiEncode(AbiEncodeArgs { signature, args }) => {
+            UtilsNamespaceResult::Calldata(utils::encode_function_call(signature, args)?)
+        }
+        UtilsSubCommand::AbiDecode(abi_decode_args) => UtilsNamespaceResult::DecodedCall(
+            utils::decode_function_call(abi_decode_args.function()?, &abi_decode_args.data)?,
+        ),
+        UtilsSubCommand::AddrFromPubkey(AddrFromPubkeyArgs { pubkey }) => {
+            UtilsNamespaceResult::Address(utils::address_from_public_key(pubkey.clone())?)
+        }
+        UtilsSubCommand::ToAddress(ToAddressArgs { value }) => {
+            UtilsNamespaceResult::NormalizedAddress(utils::normalize_address(value)?)
+        }
+        UtilsSubCommand::Checksum(ChecksumArgs { address, verify }) => {
+            UtilsNamespaceResult::ChecksummedAddress(utils::checksum_address(address, *verify)?)
+        }
+        UtilsSubCommand::Math(MathArgs {
+            operation,
+            lhs,
+            rhs,
+        }) => UtilsNamespaceResult::Math(utils::wei_math(*operation, *lhs, *rhs)?.to_string()),
+        UtilsSubCommand::Selector(SelectorArgs {
+            selector,
+            remote: false,
+            ..
+        }) => UtilsNamespaceResult::Signatures(signatures::find_known_signatures(*selector)),
+        command => anyhow::bail!("The {command:?} command requires a node"),
+    };
+
+    Ok(res)
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use crate::cmd::utils::ProtocolVersion;
+
+    use clap::Parser;
+
+    use super::{
+        parse_hex_address, parse_offline, parse_selector, UtilsCommand, UtilsNamespaceResult,
+    };
+
+    #[test]
+    fn should_serialize_the_protocol_version_with_its_support_flag() -> anyhow::Result<()> {
+        // Arrange
+        let test_cases = [
+            (
+                ProtocolVersion {
+                    protocol_version: Some(0x41.into()),
+                    supported: true,
+                },
+                json!({ "protocolVersion": "0x41", "supported": true }),
+            ),
+            (
+                ProtocolVersion {
+                    protocol_version: None,
+                    supported: false,
+                },
+                json!({ "protocolVersion": null, "supported": false }),
+            ),
+        ];
+
+        for (protocol_version, expected) in test_cases {
+            // Act
+            let res =
+                serde_json::to_value(UtilsNamespaceResult::ProtocolVersion(protocol_version))?;
+
+            // Assert
+            assert_eq!(res, expected);
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn should_convert_units_without_a_node() -> anyhow::Result<()> {
+        // Arrange
+        let cmd = UtilsCommand::try_parse_from([
+            "utils",
+            "from-wei",
+            "1000000000000000000",
+            "--unit",
+            "ether",
+        ])?;
+
+        // Act
+        let res = parse_offline(&cmd)?;
+
+        // Assert
+        assert!(cmd.is_offline());
+        assert_eq!(serde_json::to_value(res)?, json!({ "unitConversion": "1" }));
+
+        Ok(())
+    }
+
+    #[test]
+    fn should_compute_wei_math_without_a_node() -> anyhow::Result<()> {
+        // Setup
+        let test_cases = [
+            (
+                ["utils", "math", "mul", "3", "1000000000000000000"],
+                "3000000000000000000",
+            ),
+            (
+                ["utils", "math", "div", "0xde0b6b3a7640000", "1000"],
+                "1000000000000000",
+            ),
+            (["utils", "wei-math", "mod", "10", "3"], "1"),
+        ];
+
+        for (args, expected) in test_cases {
+            // Act
+            let cmd = UtilsCommand::try_parse_from(args)?;
+            let res = parse_offline(&cmd)?;
+
+            // Assert
+            assert!(cmd.is_offline());
+            assert_eq!(serde_json::to_value(res)?, json!({ "math": expected }));
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn should_not_accept_an_invalid_math_operand() {
+        // Setup
+        let max_plus_one =
+            "115792089237316195423570985008687907853269984665640564039457584007913129639936";
+        let test_cases = [
+            ["utils", "math", "add", "1", "-1"],
+            ["utils", "math", "add", "1", "1.5"],
+            ["utils", "math", "add", "1", max_plus_one],
+            ["utils", "math", "pow", "1", "1"],
+        ];
+
+        for args in test_cases {
+            // Act
+            let res = UtilsCommand::try_parse_from(args);
+
+            // Assert
+            assert!(res.is_err(), "{args:?}");
+        }
+    }
+
+    #[test]
+    fn should_report_an_overflowing_math_operation() -> anyhow::Result<()> {
+        // Arrange
+        let cmd = UtilsCommand::try_parse_from([
+            "utils",
+            "math",
+            "mul",
+            "0x8000000000000000000000000000000000000000000000000000000000000000",
+            "2",
+        ])?;
+
+        // Act
+        let res = parse_offline(&cmd);
+
+        // Assert
+        assert!(res.is_err_and(|err| err.to_string().contains("overflows uint256")));
+
+        Ok(())
+    }
+
+    #[test]
+    fn should_checksum_an_address_without_a_node() -> anyhow::Result<()> {
+        // Setup
+        let test_cases = [
+            [
+                "utils",
+                "checksum",
+                "0xfb6916095ca1df60bb79ce92ce3ea74c37c5d359",
+            ],
+            [
+                "utils",
+                "checksum",
+                "fb6916095ca1df60bb79ce92ce3ea74c37c5d359",
+            ],
+            [
+                "utils",
+                "checksum",
+                "0xFB6916095CA1DF60BB79CE92CE3EA74C37C5D359",
+            ],
+        ];
+
+        for args in test_cases {
+            // Act
+            let cmd = UtilsCommand::try_parse_from(args)?;
+            let res = parse_offline(&cmd)?;
+
+            // Assert
+            assert!(cmd.is_offline());
+            assert_eq!(
+                serde_json::to_value(res)?,
+                json!({ "checksummedAddress": "0xfB6916095ca1df60bB79Ce92cE3Ea74c37c5d359" })
+            );
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn should_fail_the_checksum_verification_of_a_wrong_mixed_case_address() -> anyhow::Result<()> {
+        // Arrange
+        let cmd = UtilsCommand::try_parse_from([
+            "utils",
+            "checksum",
+            "--verify",
+            "0xFb6916095ca1df60bB79Ce92cE3Ea74c37c5d359",
+        ])?;
+
+        // Act
+        let res = parse_offline(&cmd);
+
+        // Assert
+        assert!(res.is_err_and(|err| err.to_string().contains("EIP-55")));
+
+        Ok(())
+    }
+
+    #[test]
+    fn should_only_parse_addresses_of_40_hex_chars() {
+        // Setup
+        let test_cases = [
+            ("0xfb6916095ca1df60bb79ce92ce3ea74c37c5d359", true),
+            ("fb6916095ca1df60bb79ce92ce3ea74c37c5d359", true),
+            ("0xfb6916095ca1df60bb79ce92ce3ea74c37c5d3", false),
+            ("0xfb6916095ca1df60bb79ce92ce3ea74c37c5d35900", false),
+            ("0xzb6916095ca1df60bb79ce92ce3ea74c37c5d359", false),
+            ("vitalik.eth", false),
+        ];
+
+        for (value, expected) in test_cases {
+            // Act
+            let res = parse_hex_address(value);
+
+            // Assert
+            assert_eq!(res.is_ok(), expected, "{value}");
+        }
+    }
+
+    #[test]
+    fn should_not_run_the_node_commands_offline() -> anyhow::Result<()> {
+        // Arrange
+        let cmd = UtilsCommand::try_parse_from(["utils", "chain-id"])?;
+
+        // Act
+        let res = parse_offline(&cmd);
+
+        // Assert
+        assert!(!cmd.is_offline());
+        assert!(res.is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn should_parse_a_four_bytes_selector() {
+        // Setup
+        let test_cases = [
+            ("0xa9059cbb", Some([0xa9, 0x05, 0x9c, 0xbb])),
+            ("a9059cbb", Some([0xa9, 0x05, 0x9c, 0xbb])),
+            ("0xa9059c", None),
+            ("0xa9059cbb00", None),
+            ("transfer", None),
+        ];
+
+        for (value, expected) in test_cases {
+            // Act
+            let res = parse_selector(value);
+
+            // Assert
+            assert_eq!(res.ok(), expected, "{value}");
+        }
+    }
+
+    #[test]
+    fn should_sign_either_raw_data_typed_data_or_a_transaction() {
+        // Setup
+        let address = "0xf39fd6e51aad88f6f4ce6ab8827279cfffb92267";
+        let test_cases: [(&[&str], bool); 4] = [
+            (&["--typed-data-file", "mail.json"], true),
+            (&["--typed-data-file", "mail.json", "0x1234"], false),
+            (&["--typed-data-file", "mail.json", "--value", "1"], false),
+            (&["--typed-data-file"], false),
+        ];
+
+        for (args, is_valid) in test_cases {
+            // Act
+            let res = UtilsCommand::try_parse_from(
+                ["utils", "sign", "--address", address].iter().chain(args),
+            );
+
+            // Assert
+            assert_eq!(res.is_ok(), is_valid, "{args:?}");
+        }
+    }
+
+    #[test]
+    fn should_require_exactly_one_signer() {
+        // Setup
+        let address = "0xf39fd6e51aad88f6f4ce6ab8827279cfffb92267";
+        let test_cases: [(&[&str], bool); 5] = [
+            (&["--address", address], true),
+            (&["--ens", "vitalik.eth"], true),
+            (&["--from-index", "1"], true),
+            (&["--address", address, "--from-index", "1"], false),
+            (&[], false),
+        ];
+
+        for (args, is_valid) in test_cases {
+            // Act
+            let res = UtilsCommand::try_parse_from(
+                ["utils", "sign"].iter().chain(args).chain(&["0x1234"]),
+            );
+
+            // Assert
+            assert_eq!(res.is_ok(), is_valid, "{args:?}");
+        }
+    }
+
+    #[test]
+    fn should_look_up_known_selectors_without_a_node() -> anyhow::Result<()> {
+        // Arrange
+        let cmd = UtilsCommand::try_parse_from(["utils", "selector", "0xa9059cbb"])?;
+
+        // Act
+        let res = parse_offline(&cmd)?;
+
+        // Assert
+        assert!(cmd.is_offline());
+        assert_eq!(
+            serde_json::to_value(res)?,
+            json!({ "signatures": ["transfer(address,uint256)"] })
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn should_only_look_up_remote_selectors_with_a_database() {
+        // Setup
+        let test_cases: [(&[&str], bool); 3] = [
+            (&["--remote"], true),
+            (&["--remote", "--api-url", "http://localhost:8080"], true),
+            (&["--api-url", "http://localhost:8080"], false),
+        ];
+
+        for (args, is_valid) in test_cases {
+            // Act
+            let res = UtilsCommand::try_parse_from(
+                ["utils", "selector", "0xa9059cbb"].iter().chain(args),
+            );
+
+            // Assert
+            assert_eq!(res.is_ok(), is_valid, "{args:?}");
+            if let Ok(cmd) = res {
+                assert!(!cmd.is_offline());
+            }
+        }
+    }
+
+    #[test]
+    fn should_normalize_an_address_without_a_node() -> anyhow::Result<()> {
+        // Arrange
+        let cmd = UtilsCommand::try_parse_from([
+            "utils",
+            "to-address",
+            "0x0000000000000000000000007e5f4552091a69125d5dfcb7b8c2659029395bdf",
+        ])?;
+
+        // Act
+        let res = parse_offline(&cmd)?;
+
+        // Assert
+        assert!(cmd.is_offline());
+        assert_eq!(
+            serde_json::to_value(res)?,
+            json!({ "normalizedAddress": "0x7E5F4552091A69125d5DfCb7b8C2659029395Bdf" })
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn should_convert_between_units_without_a_node() -> anyhow::Result<()> {
+        // Arrange
+        let cmd = UtilsCommand::try_parse_from([
+            "utils", "convert", "1.5", "--from", "eth", "--to", "gwei",
+        ])?;
+
+        // Act
+        let res = parse_offline(&cmd)?;
+
+        // Assert
+        assert!(cmd.is_offline());
+        assert_eq!(
+            serde_json::to_value(res)?,
+            json!({ "converted": { "value": "1500000000", "unit": "gwei" } })
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn should_compute_the_event_topic_without_a_node() -> anyhow::Result<()> {
+        // Arrange
+        let cmd = UtilsCommand::try_parse_from([
+            "utils",
+            "event-topic",
+            "Approval(address indexed owner, address indexed spender, uint256 value)",
+        ])?;
+
+        // Act
+        let res = parse_offline(&cmd)?;
+
+        // Assert
+        assert!(cmd.is_offline());
+        assert_eq!(
+            serde_json::to_value(res)?,
+            json!({
+                "eventTopic": "0x8c5be1e5ebec7d5bd14f71427d1e84f3dd0314c0f7b2291e5b200ac8c7c3b925"
+            })
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn should_derive_the_address_without_a_node() -> anyhow::Result<()> {
+        // Arrange
+        let cmd = UtilsCommand::try_parse_from([
+            "utils",
+            "address",
+            "--pubkey",
+            "0x0279be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959f2815b16f81798",
+        ])?;
+
+        // Act
+        let res = parse_offline(&cmd)?;
+
+        // Assert
+        assert!(cmd.is_offline());
+        assert_eq!(
+            serde_json::to_value(res)?,
+            json!({ "address": "0x7E5F4552091A69125d5DfCb7b8C2659029395Bdf" })
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn should_abi_encode_the_function_call_without_a_node() -> anyhow::Result<()> {
+        // Arrange
+        let cmd = UtilsCommand::try_parse_from([
+            "utils",
+            "abi-encode",
+            "transfer(address,uint256)",
+            "0x000000000000000000000000000000000000dead",
+            "0xde0b6b3a7640000",
+        ])?;
+
+        // Act
+        let res = parse_offline(&cmd)?;
+
+        // Assert
+        assert!(cmd.is_offline());
+        assert_eq!(
+            serde_json::to_value(res)?,
+            json!({
+                "calldata": "0xa9059cbb000000000000000000000000000000000000000000000000000000000000dead0000000000000000000000000000000000000000000000000de0b6b3a7640000"
+            })
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn should_abi_decode_the_calldata_without_a_node() -> anyhow::Result<()> {
+        // Arrange
+        let cmd = UtilsCommand::try_parse_from([
+            "utils",
+            "abi-decode",
+            "--sig",
+            "transfer(address,uint256)",
+            "0xa9059cbb000000000000000000000000000000000000000000000000000000000000dead0000000000000000000000000000000000000000000000000de0b6b3a7640000",
+        ])?;
+
+        // Act
+        let res = parse_offline(&cmd)?;
+
+        // Assert
+        assert!(cmd.is_offline());
+        assert_eq!(
+            serde_json::to_value(res)?,
+            json!({
+                "decodedCall": {
+                    "function": "transfer(address,uint256)",
+                    "selector": "0xa9059cbb",
+                    "params": [
+                        { "name": "arg0", "type": "address", "value": "0x000000000000000000000000000000000000dEaD" },
+                        { "name": "arg1", "type": "uint256", "value": "1000000000000000000" },
+                    ]
+                }
+            })
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn should_require_either_a_signature_or_an_abi_to_decode() {
+        // Setup
+        let test_cases = [
+            (vec![], false),
+            (vec!["--sig", "f()"], true),
+            (vec!["--abi", "abi.json"], true),
+            (vec!["--sig", "f()", "--abi", "abi.json"], false),
+        ];
+
+        for (args, is_valid) in test_cases {
+            // Act
+            let res = UtilsCommand::try_parse_from(
+                ["utils", "abi-decode", "0x12345678"]
+                    .into_iter()
+                    .chain(args),
+            );
+
+            // Assert
+            assert_eq!(res.is_ok(), is_valid);
+        }
+    }
+}
@@ -1,9 +1,108 @@
+use std::collections::BTreeMap;
+
+use anyhow::Context;
+
 use ethers::{
     providers::Middleware,
-    types::{BlockId, BlockNumber, Bytes, NameOrAddress, H256, U256},
+    types::{Address, BlockId, BlockNumber, Bytes, NameOrAddress, H256, U256},
+};
+use serde::Serialize;
+
+use crate::{
+    cmd::utils::{derive_mapping_slot, MappingKey},
+    context::NodeProvider,
 };
 
-use crate::context::NodeProvider;
+/// Maximum number of account requests in flight when a command fans out.
+pub const ACCOUNT_QUERY_CONCURRENCY: usize = 4;
+
+pub const BALANCE_SNAPSHOT_TAGS: [BlockNumber; 4] = [
+    BlockNumber::Latest,
+    BlockNumber::Safe,
+    BlockNumber::Finalized,
+    BlockNumber::Pending,
+];
+
+/// EIP-1967 slot holding the implementation address of a proxy:
+/// `bytes32(uint256(keccak256("eip1967.proxy.implementation")) - 1)`.
+pub const EIP1967_IMPLEMENTATION_SLOT: H256 = H256([
+    0x36, 0x08, 0x94, 0xa1, 0x3b, 0xa1, 0xa3, 0x21, 0x06, 0x67, 0xc8, 0x28, 0x49, 0x2d, 0xb9, 0x8d,
+    0xca, 0x3e, 0x20, 0x76, 0xcc, 0x37, 0x35, 0xa9, 0x20, 0xa3, 0xca, 0x50, 0x5d, 0x38, 0x2b, 0xbc,
+]);
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MappingStorageEntry {
+    /// The storage slot derived from the mapping base slot and keys
+    pub slot: H256,
+    pub value: StorageValue,
+}
+
+/// Solidity type of the value stored in a storage slot.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum StorageValueType {
+    U256,
+    Address,
+    Bool,
+    #[default]
+    Bytes32,
+}
+
+/// Storage word interpreted as its solidity type, serialized as the plain value.
+#[derive(Debug, Serialize, PartialEq, Eq)]
+#[serde(untagged)]
+pub enum StorageValue {
+    U256(U256),
+    Address(Address),
+    Bool(bool),
+    Bytes32(H256),
+}
+
+impl StorageValue {
+    /// Interprets the storage word as a value of the provided type. Values shorter than a word are
+    /// read from its low order bytes, as solidity stores them right aligned.
+    pub fn decode(word: H256, value_type: StorageValueType) -> Self {
+        match value_type {
+            StorageValueType::U256 => Self::U256(U256::from_big_endian(word.as_bytes())),
+            StorageValueType::Address => Self::Address(Address::from(word)),
+            StorageValueType::Bool => Self::Bool(!word.is_zero()),
+            StorageValueType::Bytes32 => Self::Bytes32(word),
+        }
+    }
+}
+
+#[derive(Debug, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BalanceAcrossTags {
+    /// The account balance labeled by block tag
+    pub balances: BTreeMap<String, U256>,
+    /// The reason why the balance could not be retrieved, labeled by block tag
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub unavailable: BTreeMap<String, String>,
+}
+
+impl BalanceAcrossTags {
+    /// Labels the balances queried at each of the `BALANCE_SNAPSHOT_TAGS`, in the same order.
+    pub fn new(balances: Vec<anyhow::Result<U256>>) -> Self {
+        let mut snapshot = Self::default();
+
+        for (tag, balance) in BALANCE_SNAPSHOT_TAGS.iter().zip(balances) {
+            // Nodes that don't support a tag (e.g. pre-merge chains with safe or finalized) reject the request
+            match balance {
+                Ok(balance) => {
+                    snapshot.balances.insert(tag.to_string(), balance);
+                }
+                Err(err) => {
+                    snapshot
+                        .unavailable
+                        .insert(tag.to_string(), err.to_string());
+                }
+            }
+        }
+
+        snapshot
+    }
+}
 
 // eth_getBalance
 pub async fn get_balance(
@@ -16,6 +115,48 @@ pub async fn get_balance(
     Ok(balance)
 }
 
+// eth_getBalance
+/// Gets the balances of the addresses in a single batch request. Fails if the node or the
+/// transport (e.g. websockets) doesn't support batches.
+pub async fn batch_get_balances(
+    node_provider: &NodeProvider,
+    addresses: &[Address],
+    block_id: BlockId,
+) -> anyhow::Result<Vec<anyhow::Result<U256>>> {
+    let params: Vec<_> = addresses
+        .iter()
+        .map(|address| (address, block_id))
+        .collect();
+
+    let balances = node_provider
+        .provider()
+        .as_ref()
+        .batch_request("eth_getBalance", &params)
+        .await?;
+
+    Ok(balances
+        .into_iter()
+        .map(|balance| balance.map_err(anyhow::Error::from))
+        .collect())
+}
+
+/// Labels the balances with their address, failing if any of them could not be retrieved.
+pub fn collect_balances(
+    addresses: Vec<Address>,
+    balances: Vec<anyhow::Result<U256>>,
+) -> anyhow::Result<BTreeMap<Address, U256>> {
+    addresses
+        .into_iter()
+        .zip(balances)
+        .map(|(address, balance)| {
+            let balance =
+                balance.with_context(|| format!("Failed to get the balance of {address:?}"))?;
+
+            Ok((address, balance))
+        })
+        .collect()
+}
+
 // eth_getCode
 pub async fn get_code(
     node_provider: &NodeProvider,
@@ -53,7 +194,6 @@ pub async fn get_nonce(
 }
 
 // eth_getStorageAt
-// TODO: Implement a variant that recieves the expected storage slot type and parses the result based on that
 pub async fn get_storage_at(
     node_provider: &NodeProvider,
     account_id: NameOrAddress,
@@ -67,6 +207,82 @@ pub async fn get_storage_at(
     Ok(storage_data)
 }
 
+// eth_getStorageAt
+/// Gets the implementation address of the EIP-1967 proxy, or None if the account isn't one.
+pub async fn get_proxy_implementation(
+    node_provider: &NodeProvider,
+    account_id: NameOrAddress,
+    block_id: Option<BlockId>,
+) -> anyhow::Result<Option<Address>> {
+    let word = get_storage_at(
+        node_provider,
+        account_id,
+        EIP1967_IMPLEMENTATION_SLOT,
+        block_id,
+    )
+    .await?;
+
+    Ok(Some(Address::from(word)).filter(|address| !address.is_zero()))
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProxyStorageEntry {
+    /// The word stored in the slot of the proxy, which holds the state of the implementation
+    pub value: H256,
+    /// The implementation address of the proxy, or null if the account isn't an EIP-1967 proxy
+    pub implementation: Option<Address>,
+}
+
+// eth_getStorageAt
+/// Gets the value stored in the slot along with the implementation address of the proxy that
+/// delegates to the contract whose logic reads it.
+pub async fn get_storage_at_through_proxy(
+    node_provider: &NodeProvider,
+    account_id: NameOrAddress,
+    slot: H256,
+    block_id: Option<BlockId>,
+) -> anyhow::Result<ProxyStorageEntry> {
+    let (value, implementation) = futures::try_join!(
+        get_storage_at(node_provider, account_id.clone(), slot, block_id),
+        get_proxy_implementation(node_provider, account_id, block_id)
+    )?;
+
+    Ok(ProxyStorageEntry {
+        value,
+        implementation,
+    })
+}
+
+// eth_getStorageAt
+/// Gets the value stored in the slot interpreted as the provided type.
+pub async fn get_typed_storage_at(
+    node_provider: &NodeProvider,
+    account_id: NameOrAddress,
+    slot: H256,
+    value_type: StorageValueType,
+    block_id: Option<BlockId>,
+) -> anyhow::Result<StorageValue> {
+    let word = get_storage_at(node_provider, account_id, slot, block_id).await?;
+
+    Ok(StorageValue::decode(word, value_type))
+}
+
+pub async fn get_mapping_storage_at(
+    node_provider: &NodeProvider,
+    account_id: NameOrAddress,
+    base_slot: H256,
+    keys: Vec<MappingKey>,
+    value_type: StorageValueType,
+    block_id: Option<BlockId>,
+) -> anyhow::Result<MappingStorageEntry> {
+    let slot = derive_mapping_slot(base_slot, &keys);
+
+    let value = get_typed_storage_at(node_provider, account_id, slot, value_type, block_id).await?;
+
+    Ok(MappingStorageEntry { slot, value })
+}
+
 #[cfg(test)]
 mod tests {
 
@@ -80,7 +296,7 @@ mod tests {
             // Arrange
             let (node_provider, anvil) = setup_test().await?;
 
-            let account = *anvil.addresses().get(0).unwrap();
+            let account = *anvil.addresses().first().unwrap();
 
             // Default account balance in Anvil
             let expected_balance = parse_ether(10_000)?;
@@ -98,6 +314,127 @@ mod tests {
         }
     }
 
+    mod batch_get_balances {
+        use std::sync::atomic::Ordering;
+
+        use ethers::{
+            types::{BlockId, BlockNumber, U256},
+            utils::parse_ether,
+        };
+        use serde_json::json;
+
+        use crate::cmd::{
+            account::{batch_get_balances, collect_balances},
+            helpers::test::{
+                mock_node_provider, setup_test, spawn_counting_mock_rpc_server,
+                spawn_mock_http_server,
+            },
+        };
+
+        const LATEST: BlockId = BlockId::Number(BlockNumber::Latest);
+
+        #[tokio::test]
+        async fn should_get_the_balance_of_every_address() -> anyhow::Result<()> {
+            // Arrange
+            let (node_provider, anvil) = setup_test().await?;
+
+            let addresses = anvil.addresses()[..3].to_vec();
+
+            // Default account balance in Anvil
+            let expected_balance = parse_ether(10_000)?;
+
+            // Act
+            let res = batch_get_balances(&node_provider, &addresses, LATEST).await?;
+
+            // Assert
+            let balances = collect_balances(addresses.clone(), res)?;
+            assert_eq!(balances.len(), addresses.len());
+            assert!(balances
+                .values()
+                .all(|balance| *balance == expected_balance));
+
+            Ok(())
+        }
+
+        #[tokio::test]
+        async fn should_send_a_single_batch_request() -> anyhow::Result<()> {
+            // Arrange
+            let addresses = vec![[1u8; 20].into(), [2u8; 20].into()];
+
+            // Responses out of order on purpose, as nodes are free to reorder them
+            let (url, requests) = spawn_mock_http_server(json!([
+                { "jsonrpc": "2.0", "id": 1, "result": "0x2" },
+                { "jsonrpc": "2.0", "id": 0, "result": "0x1" },
+            ]));
+            let node_provider = mock_node_provider(url).await?;
+
+            // Act
+            let res = batch_get_balances(&node_provider, &addresses, LATEST).await?;
+
+            // Assert
+            let balances = collect_balances(addresses.clone(), res)?;
+            assert_eq!(balances.get(&addresses[0]), Some(&U256::from(1)));
+            assert_eq!(balances.get(&addresses[1]), Some(&U256::from(2)));
+            assert_eq!(requests.load(Ordering::SeqCst), 1);
+
+            Ok(())
+        }
+
+        #[tokio::test]
+        async fn should_fail_if_the_node_does_not_support_batches() -> anyhow::Result<()> {
+            // Arrange
+            let (url, _) = spawn_counting_mock_rpc_server(json!("0x5"));
+            let node_provider = mock_node_provider(url).await?;
+
+            // Act
+            let res = batch_get_balances(&node_provider, &[[1u8; 20].into()], LATEST).await;
+
+            // Assert
+            assert!(res.is_err());
+
+            Ok(())
+        }
+
+        #[test]
+        fn should_fail_if_a_balance_can_not_be_retrieved() {
+            // Act
+            let res = collect_balances(
+                vec![[1u8; 20].into(), [2u8; 20].into()],
+                vec![Ok(U256::one()), Err(anyhow::anyhow!("boom"))],
+            );
+
+            // Assert
+            assert!(res.is_err());
+            assert!(format!("{:#}", res.unwrap_err()).contains("boom"));
+        }
+    }
+
+    mod balance_across_tags {
+        use ethers::types::U256;
+
+        use crate::cmd::account::BalanceAcrossTags;
+
+        #[test]
+        fn should_label_the_balances_by_tag() {
+            // Act
+            let res = BalanceAcrossTags::new(vec![
+                Ok(U256::from(1)),
+                Err(anyhow::anyhow!("unknown block")),
+                Ok(U256::from(3)),
+                Ok(U256::from(4)),
+            ]);
+
+            // Assert
+            assert_eq!(res.balances.get("latest"), Some(&U256::from(1)));
+            assert_eq!(res.balances.get("finalized"), Some(&U256::from(3)));
+            assert_eq!(res.balances.get("pending"), Some(&U256::from(4)));
+            assert_eq!(
+                res.unavailable.get("safe").map(String::as_str),
+                Some("unknown block")
+            );
+        }
+    }
+
     mod get_code {
         use crate::cmd::{account::get_code, helpers::test::setup_test};
 
@@ -106,7 +443,7 @@ mod tests {
             // Arrange
             let (node_provider, anvil) = setup_test().await?;
 
-            let account = *anvil.addresses().get(0).unwrap();
+            let account = *anvil.addresses().first().unwrap();
 
             // Act
             let res = get_code(&node_provider, account.into(), None).await;
@@ -131,7 +468,7 @@ mod tests {
             // Arrange
             let (node_provider, anvil) = setup_test().await?;
 
-            let account = *anvil.addresses().get(0).unwrap();
+            let account = *anvil.addresses().first().unwrap();
 
             // Act
             let res = get_transaction_count(&node_provider, account.into(), None).await;
@@ -158,7 +495,7 @@ mod tests {
             // Arrange
             let (node_provider, anvil) = setup_test().await?;
 
-            let account = *anvil.addresses().get(0).unwrap();
+            let account = *anvil.addresses().first().unwrap();
 
             // Act
             let res = get_storage_at(&node_provider, account.into(), H256::default(), None).await;
@@ -172,4 +509,243 @@ mod tests {
             Ok(())
         }
     }
+
+    mod get_storage_at_through_proxy {
+        use ethers::{
+            types::{Bytes, H160, H256, U256},
+            utils::keccak256,
+        };
+
+        use crate::cmd::{
+            account::{get_storage_at_through_proxy, EIP1967_IMPLEMENTATION_SLOT},
+            helpers::test::{deploy_contract_helper, setup_test},
+        };
+
+        #[test]
+        fn should_derive_the_implementation_slot_from_its_label() {
+            // Act
+            let slot = U256::from(keccak256("eip1967.proxy.implementation")) - 1;
+
+            // Assert
+            assert_eq!(U256::from(EIP1967_IMPLEMENTATION_SLOT.as_bytes()), slot);
+        }
+
+        #[tokio::test]
+        async fn should_get_the_proxy_slot_and_its_implementation() -> anyhow::Result<()> {
+            // Arrange
+            let (node_provider, anvil) = setup_test().await?;
+
+            let deployer = *anvil.addresses().first().unwrap();
+            let implementation = H160::repeat_byte(0x11);
+
+            // Fixture proxy whose constructor stores the implementation in the EIP-1967 slot and
+            // 42 in slot 0: PUSH20 implementation, PUSH32 slot, SSTORE, PUSH1 42, PUSH1 0, SSTORE,
+            // STOP
+            let mut init_code = vec![0x73];
+            init_code.extend_from_slice(implementation.as_bytes());
+            init_code.push(0x7f);
+            init_code.extend_from_slice(EIP1967_IMPLEMENTATION_SLOT.as_bytes());
+            init_code.extend_from_slice(&[0x55, 0x60, 0x2a, 0x60, 0x00, 0x55, 0x00]);
+
+            let proxy =
+                deploy_contract_helper(&node_provider, deployer, Bytes::from(init_code)).await?;
+
+            // Act
+            let res =
+                get_storage_at_through_proxy(&node_provider, proxy.into(), H256::zero(), None)
+                    .await?;
+
+            // Assert
+            assert_eq!(res.value, H256::from_low_u64_be(42));
+            assert_eq!(res.implementation, Some(implementation));
+
+            Ok(())
+        }
+
+        #[tokio::test]
+        async fn should_not_find_the_implementation_of_a_regular_account() -> anyhow::Result<()> {
+            // Arrange
+            let (node_provider, anvil) = setup_test().await?;
+
+            let account = *anvil.addresses().first().unwrap();
+
+            // Act
+            let res =
+                get_storage_at_through_proxy(&node_provider, account.into(), H256::zero(), None)
+                    .await?;
+
+            // Assert
+            assert_eq!(res.value, H256::zero());
+            assert_eq!(res.implementation, None);
+
+            Ok(())
+        }
+    }
+
+    mod get_typed_storage_at {
+        use ethers::types::{Bytes, H256, U256};
+
+        use crate::cmd::{
+            account::{get_typed_storage_at, StorageValue, StorageValueType},
+            helpers::test::{deploy_contract_helper, setup_test},
+        };
+
+        #[tokio::test]
+        async fn should_get_the_storage_data_as_the_provided_type() -> anyhow::Result<()> {
+            // Arrange
+            let (node_provider, anvil) = setup_test().await?;
+
+            let deployer = *anvil.addresses().first().unwrap();
+
+            // Fixture contract whose constructor stores 42 in slot 0: PUSH1 42, PUSH1 0, SSTORE, STOP
+            let init_code = Bytes::from(vec![0x60, 0x2a, 0x60, 0x00, 0x55, 0x00]);
+            let contract = deploy_contract_helper(&node_provider, deployer, init_code).await?;
+
+            // Act
+            let res = get_typed_storage_at(
+                &node_provider,
+                contract.into(),
+                H256::zero(),
+                StorageValueType::U256,
+                None,
+            )
+            .await;
+
+            // Assert
+            assert!(res.is_ok());
+            assert_eq!(res.unwrap(), StorageValue::U256(U256::from(42)));
+
+            Ok(())
+        }
+    }
+
+    mod storage_value {
+        use ethers::types::{Address, H256, U256};
+        use serde_json::json;
+
+        use crate::cmd::account::{StorageValue, StorageValueType};
+
+        #[test]
+        fn should_interpret_the_storage_word_as_the_provided_type() {
+            // Arrange
+            let address = Address::repeat_byte(0xaa);
+            let address_word = H256::from(address);
+            let number_word = H256::from_low_u64_be(42);
+
+            let test_cases = [
+                (
+                    number_word,
+                    StorageValueType::U256,
+                    StorageValue::U256(U256::from(42)),
+                ),
+                (
+                    address_word,
+                    StorageValueType::Address,
+                    StorageValue::Address(address),
+                ),
+                (
+                    number_word,
+                    StorageValueType::Bool,
+                    StorageValue::Bool(true),
+                ),
+                (
+                    H256::zero(),
+                    StorageValueType::Bool,
+                    StorageValue::Bool(false),
+                ),
+                (
+                    number_word,
+                    StorageValueType::Bytes32,
+                    StorageValue::Bytes32(number_word),
+                ),
+            ];
+
+            for (word, value_type, expected_res) in test_cases {
+                // Act
+                let res = StorageValue::decode(word, value_type);
+
+                // Assert
+                assert_eq!(res, expected_res);
+            }
+        }
+
+        #[test]
+        fn should_only_keep_the_low_order_bytes_of_an_address() {
+            // Arrange
+            let mut word = [0xff; 32];
+            word[12..].copy_from_slice(Address::repeat_byte(0x11).as_bytes());
+
+            // Act
+            let res = StorageValue::decode(H256(word), StorageValueType::Address);
+
+            // Assert
+            assert_eq!(res, StorageValue::Address(Address::repeat_byte(0x11)));
+        }
+
+        #[test]
+        fn should_serialize_the_plain_value() -> anyhow::Result<()> {
+            // Act
+            let uint = serde_json::to_value(StorageValue::U256(U256::from(42)))?;
+            let boolean = serde_json::to_value(StorageValue::Bool(true))?;
+
+            // Assert
+            assert_eq!(uint, json!("0x2a"));
+            assert_eq!(boolean, json!(true));
+
+            Ok(())
+        }
+    }
+
+    mod get_mapping_storage_at {
+        use ethers::types::{Bytes, H256};
+
+        use crate::cmd::{
+            account::{get_mapping_storage_at, StorageValue, StorageValueType},
+            helpers::test::{deploy_contract_helper, setup_test},
+            utils::MappingKey,
+        };
+
+        const MAPPING_VALUE: u64 = 42;
+
+        #[tokio::test]
+        async fn should_get_the_storage_data_of_a_mapping_entry() -> anyhow::Result<()> {
+            // Arrange
+            let (node_provider, anvil) = setup_test().await?;
+
+            let deployer = *anvil.addresses().first().unwrap();
+            let holder = *anvil.addresses().get(1).unwrap();
+
+            // Fixture contract whose constructor sets balances[holder] = 42 with balances in slot 0:
+            // PUSH20 holder, PUSH1 0, MSTORE, PUSH1 0, PUSH1 32, MSTORE, PUSH1 64, PUSH1 0, SHA3,
+            // PUSH1 42, SWAP1, SSTORE, STOP
+            let mut init_code = vec![0x73];
+            init_code.extend_from_slice(holder.as_bytes());
+            init_code.extend_from_slice(&[
+                0x60, 0x00, 0x52, 0x60, 0x00, 0x60, 0x20, 0x52, 0x60, 0x40, 0x60, 0x00, 0x20, 0x60,
+                0x2a, 0x90, 0x55, 0x00,
+            ]);
+
+            let contract =
+                deploy_contract_helper(&node_provider, deployer, Bytes::from(init_code)).await?;
+
+            // Act
+            let res = get_mapping_storage_at(
+                &node_provider,
+                contract.into(),
+                H256::zero(),
+                vec![MappingKey::Address(holder)],
+                StorageValueType::U256,
+                None,
+            )
+            .await;
+
+            // Assert
+            assert!(res.is_ok());
+
+            let entry = res.unwrap();
+            assert_eq!(entry.value, StorageValue::U256(MAPPING_VALUE.into()));
+
+            Ok(())
+        }
+    }
 }
@@ -1,13 +1,29 @@
+use std::{
+    collections::{BTreeMap, HashSet},
+    pin::pin,
+    time::Duration,
+};
+
 use crate::context::NodeProvider;
 use anyhow::Ok;
 use ethers::{
-    providers::Middleware,
-    types::{Block, BlockId, Transaction, TransactionReceipt, H256, U256, U64},
+    providers::{Middleware, StreamExt},
+    types::{Block, BlockId, Transaction, H256, U256, U64},
+    utils::{keccak256, rlp::RlpStream},
 };
 use serde::Serialize;
 
-use super::helpers::{get_block_number_by_block_id, get_raw_block};
+use super::{
+    helpers::{
+        fetch_blocks_stream, get_block_number_by_block_id, get_raw_block, with_timeout,
+        BLOCK_FETCH_CONCURRENCY,
+    },
+    lookup::{Lookup, Missing},
+    transaction::{enrich_receipt, EnrichedReceipt},
+};
 
+/// Both variants serialize as the plain rpc block object, where `transactions` holds either the
+/// transaction hashes or the full transaction objects, like `eth_getBlockByNumber` does.
 #[derive(Debug, Serialize)]
 #[serde(untagged)]
 pub enum BlockKind {
@@ -15,23 +31,407 @@ pub enum BlockKind {
     BlockWithTransaction(Block<Transaction>),
 }
 
+/// Default time limit of a single block request.
+pub const GET_BLOCK_TIMEOUT: Duration = Duration::from_secs(30);
+
 // eth_getBlockByHash || eth_getBlockByNumber
 pub async fn get_block(
     node_provider: &NodeProvider,
     block_id: BlockId,
     include_tx: bool,
-) -> Result<Option<BlockKind>, anyhow::Error> {
-    let res = if include_tx {
-        get_block_with_txs(node_provider, block_id)
-            .await?
-            .map(BlockKind::BlockWithTransaction)
+    timeout: Option<Duration>,
+) -> Result<Lookup<BlockKind>, anyhow::Error> {
+    let res = with_timeout(
+        timeout.unwrap_or(GET_BLOCK_TIMEOUT),
+        "block request",
+        async {
+            let res = if include_tx {
+                get_block_with_txs(node_provider, block_id)
+                    .await?
+                    .map(BlockKind::BlockWithTransaction)
+            } else {
+                get_raw_block(node_provider, block_id)
+                    .await?
+                    .map(BlockKind::RawBlock)
+            };
+
+            Ok(res)
+        },
+    )
+    .await?;
+
+    Ok(Lookup::from_option(res, Missing::Block))
+}
+
+/// Block along with the result of recomputing its header commitments from its contents.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CheckedBlock {
+    #[serde(flatten)]
+    pub block: BlockKind,
+    pub header_check: HeaderConsistency,
+}
+
+#[derive(Debug, Serialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct HeaderFieldCheck {
+    pub field: &'static str,
+    pub header: H256,
+    pub computed: H256,
+    pub matches: bool,
+}
+
+#[derive(Debug, Serialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct HeaderConsistency {
+    pub consistent: bool,
+    pub checks: Vec<HeaderFieldCheck>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub skipped: Vec<String>,
+}
+
+// eth_getBlockByHash || eth_getBlockByNumber
+/// Gets a block and validates its transactions root and ommers hash against its contents.
+pub async fn get_checked_block(
+    node_provider: &NodeProvider,
+    block_id: BlockId,
+    include_tx: bool,
+) -> Result<Lookup<CheckedBlock>, anyhow::Error> {
+    // The full transactions are always required to recompute the transactions root
+    let Some(full_block) = get_block_with_txs(node_provider, block_id).await? else {
+        return Ok(Lookup::NotFound {
+            what: Missing::Block,
+        });
+    };
+
+    let uncles = get_uncles(node_provider, &full_block).await?;
+    let header_check = check_header_consistency(&full_block, &uncles);
+
+    let block = if include_tx {
+        BlockKind::BlockWithTransaction(full_block)
     } else {
-        get_raw_block(node_provider, block_id)
+        match full_block.hash {
+            Some(hash) => get_raw_block(node_provider, hash.into()).await?,
+            None => get_raw_block(node_provider, block_id).await?,
+        }
+        .map(BlockKind::RawBlock)
+        .ok_or(anyhow::anyhow!("Block disappeared while being fetched"))?
+    };
+
+    Ok(Lookup::Found(CheckedBlock {
+        block,
+        header_check,
+    }))
+}
+
+// eth_getUncleByBlockHashAndIndex
+async fn get_uncles(
+    node_provider: &NodeProvider,
+    block: &Block<Transaction>,
+) -> Result<Vec<Block<H256>>, anyhow::Error> {
+    let Some(block_hash) = block.hash else {
+        return Ok(vec![]);
+    };
+
+    let mut uncles = Vec::with_capacity(block.uncles.len());
+
+    for idx in 0..block.uncles.len() {
+        let uncle = node_provider
+            .get_uncle(block_hash, U64::from(idx))
             .await?
-            .map(BlockKind::RawBlock)
+            .ok_or(anyhow::anyhow!(
+                "Uncle {idx} of block {block_hash:?} not found"
+            ))?;
+
+        uncles.push(uncle);
+    }
+
+    Ok(uncles)
+}
+
+/// Recomputes the transactions root and the ommers hash of the block and compares them with the
+/// values committed in its header.
+pub fn check_header_consistency(
+    block: &Block<Transaction>,
+    uncles: &[Block<H256>],
+) -> HeaderConsistency {
+    let mut checks = Vec::new();
+    let mut skipped = Vec::new();
+
+    match compute_transactions_root(&block.transactions) {
+        std::result::Result::Ok(computed) => checks.push(HeaderFieldCheck {
+            field: "transactionsRoot",
+            header: block.transactions_root,
+            computed,
+            matches: computed == block.transactions_root,
+        }),
+        Err(reason) => skipped.push(format!("transactionsRoot: {reason}")),
+    }
+
+    if uncles.len() == block.uncles.len() {
+        let computed = compute_ommers_hash(uncles);
+
+        checks.push(HeaderFieldCheck {
+            field: "sha3Uncles",
+            header: block.uncles_hash,
+            computed,
+            matches: computed == block.uncles_hash,
+        });
+    } else {
+        skipped.push("sha3Uncles: missing uncle headers".to_string());
+    }
+
+    HeaderConsistency {
+        consistent: checks.iter().all(|check| check.matches),
+        checks,
+        skipped,
+    }
+}
+
+fn compute_transactions_root(transactions: &[Transaction]) -> Result<H256, String> {
+    let mut encoded_transactions = Vec::with_capacity(transactions.len());
+
+    for tx in transactions {
+        // Transaction::rlp only knows how to encode legacy, EIP-2930 and EIP-1559 transactions
+        match tx.transaction_type.map(|tx_type| tx_type.as_u64()) {
+            None | Some(0..=2) => encoded_transactions.push(tx.rlp().to_vec()),
+            Some(tx_type) => return Err(format!("unsupported transaction type {tx_type:#x}")),
+        }
+    }
+
+    std::result::Result::Ok(ordered_trie_root(&encoded_transactions))
+}
+
+fn compute_ommers_hash(uncles: &[Block<H256>]) -> H256 {
+    let mut stream = RlpStream::new_list(uncles.len());
+
+    for uncle in uncles {
+        stream.append_raw(&encode_header(uncle), 1);
+    }
+
+    keccak256(stream.out()).into()
+}
+
+/// Rlp encodes the header fields of a pre-merge block, which is the only kind of block that can
+/// be referenced as an uncle.
+fn encode_header(header: &Block<H256>) -> Vec<u8> {
+    let mut stream = RlpStream::new();
+    stream.begin_unbounded_list();
+
+    stream.append(&header.parent_hash);
+    stream.append(&header.uncles_hash);
+    stream.append(&header.author.unwrap_or_default());
+    stream.append(&header.state_root);
+    stream.append(&header.transactions_root);
+    stream.append(&header.receipts_root);
+    stream.append(&header.logs_bloom.unwrap_or_default());
+    stream.append(&header.difficulty);
+    stream.append(&header.number.unwrap_or_default());
+    stream.append(&header.gas_limit);
+    stream.append(&header.gas_used);
+    stream.append(&header.timestamp);
+    stream.append(&header.extra_data.as_ref());
+    stream.append(&header.mix_hash.unwrap_or_default());
+    stream.append(&header.nonce.unwrap_or_default());
+
+    if let Some(base_fee_per_gas) = header.base_fee_per_gas {
+        stream.append(&base_fee_per_gas);
+    }
+
+    stream.finalize_unbounded_list();
+
+    stream.out().to_vec()
+}
+
+/// Computes the root of a merkle patricia trie whose keys are the rlp encoded indexes of the
+/// provided values.
+fn ordered_trie_root(values: &[Vec<u8>]) -> H256 {
+    trie_root(
+        values
+            .iter()
+            .enumerate()
+            .map(|(idx, value)| (ethers::utils::rlp::encode(&idx).to_vec(), value.as_slice()))
+            .collect(),
+    )
+}
+
+fn trie_root(entries: Vec<(Vec<u8>, &[u8])>) -> H256 {
+    let mut entries: Vec<(Vec<u8>, &[u8])> = entries
+        .into_iter()
+        .map(|(key, value)| (to_nibbles(&key), value))
+        .collect();
+
+    entries.sort();
+
+    keccak256(encode_trie_node(&entries, 0)).into()
+}
+
+fn to_nibbles(bytes: &[u8]) -> Vec<u8> {
+    bytes
+        .iter()
+        .flat_map(|byte| [byte >> 4, byte & 0x0f])
+        .collect()
+}
+
+/// Hex prefix encoding of a nibble path as defined in the yellow paper.
+fn encode_path(nibbles: &[u8], is_leaf: bool) -> Vec<u8> {
+    let flag = if is_leaf { 0x20 } else { 0x00 };
+
+    let mut encoded = Vec::with_capacity(nibbles.len() / 2 + 1);
+
+    let rest = if nibbles.len() % 2 == 1 {
+        encoded.push(flag | 0x10 | nibbles[0]);
+        &nibbles[1..]
+    } else {
+        encoded.push(flag);
+        nibbles
     };
 
-    Ok(res)
+    encoded.extend(rest.chunks(2).map(|pair| (pair[0] << 4) | pair[1]));
+
+    encoded
+}
+
+/// Rlp encodes the trie node holding the provided sorted entries, ignoring the first `depth`
+/// nibbles of their keys.
+fn encode_trie_node(entries: &[(Vec<u8>, &[u8])], depth: usize) -> Vec<u8> {
+    match entries {
+        [] => ethers::utils::rlp::NULL_RLP.to_vec(),
+        [(key, value)] => {
+            let mut stream = RlpStream::new_list(2);
+            stream.append(&encode_path(&key[depth..], true));
+            stream.append(value);
+
+            stream.out().to_vec()
+        }
+        [(first_key, _), .., (last_key, _)] => {
+            // Entries are sorted, so the shared prefix of the first and last keys is shared by all
+            let shared = first_key[depth..]
+                .iter()
+                .zip(&last_key[depth..])
+                .take_while(|(a, b)| a == b)
+                .count();
+
+            if shared > 0 {
+                let mut stream = RlpStream::new_list(2);
+                stream.append(&encode_path(&first_key[depth..depth + shared], false));
+                append_child(&mut stream, encode_trie_node(entries, depth + shared));
+
+                return stream.out().to_vec();
+            }
+
+            let mut stream = RlpStream::new_list(17);
+            let mut branch_value: &[u8] = &[];
+            let mut remaining = entries;
+
+            if let Some(((key, value), rest)) = remaining.split_first() {
+                if key.len() == depth {
+                    branch_value = value;
+                    remaining = rest;
+                }
+            }
+
+            for nibble in 0..16 {
+                let child_len = remaining
+                    .iter()
+                    .take_while(|(key, _)| key[depth] == nibble)
+                    .count();
+                let (child, rest) = remaining.split_at(child_len);
+
+                if child.is_empty() {
+                    stream.append_empty_data();
+                } else {
+                    append_child(&mut stream, encode_trie_node(child, depth + 1));
+                }
+
+                remaining = rest;
+            }
+
+            stream.append(&branch_value);
+
+            stream.out().to_vec()
+        }
+    }
+}
+
+/// Nodes shorter than 32 bytes are embedded in their parent, longer nodes are referenced by hash.
+fn append_child(stream: &mut RlpStream, node: Vec<u8>) {
+    if node.len() < 32 {
+        stream.append_raw(&node, 1);
+    } else {
+        stream.append(&keccak256(node).as_slice());
+    }
+}
+
+/// Header fields compared by `diff_blocks`, named as in the rpc block object.
+const HEADER_FIELDS: [&str; 18] = [
+    "hash",
+    "parentHash",
+    "sha3Uncles",
+    "miner",
+    "stateRoot",
+    "transactionsRoot",
+    "receiptsRoot",
+    "logsBloom",
+    "difficulty",
+    "number",
+    "gasLimit",
+    "gasUsed",
+    "timestamp",
+    "extraData",
+    "mixHash",
+    "nonce",
+    "baseFeePerGas",
+    "withdrawalsRoot",
+];
+
+#[derive(Debug, Serialize, PartialEq)]
+pub struct HeaderFieldDiff {
+    pub old: serde_json::Value,
+    pub new: serde_json::Value,
+}
+
+// eth_getBlockByHash || eth_getBlockByNumber
+/// Gets two blocks and reports the header fields whose values differ, keyed by field name.
+pub async fn diff_blocks(
+    node_provider: &NodeProvider,
+    block_id: BlockId,
+    other_block_id: BlockId,
+) -> Result<Lookup<BTreeMap<&'static str, HeaderFieldDiff>>, anyhow::Error> {
+    let (block, other_block) = futures::try_join!(
+        get_raw_block(node_provider, block_id),
+        get_raw_block(node_provider, other_block_id)
+    )?;
+
+    let (Some(block), Some(other_block)) = (block, other_block) else {
+        return Ok(Lookup::NotFound {
+            what: Missing::Block,
+        });
+    };
+
+    Ok(Lookup::Found(diff_block_headers(&block, &other_block)?))
+}
+
+/// Compares the header fields of both blocks. Fields missing from one of the blocks, like the base
+/// fee before London, are reported as null.
+pub fn diff_block_headers(
+    block: &Block<H256>,
+    other_block: &Block<H256>,
+) -> Result<BTreeMap<&'static str, HeaderFieldDiff>, anyhow::Error> {
+    let old = serde_json::to_value(block)?;
+    let new = serde_json::to_value(other_block)?;
+
+    let diff = HEADER_FIELDS
+        .into_iter()
+        .filter_map(|field| {
+            let old = old.get(field).cloned().unwrap_or_default();
+            let new = new.get(field).cloned().unwrap_or_default();
+
+            (old != new).then_some((field, HeaderFieldDiff { old, new }))
+        })
+        .collect();
+
+    Ok(diff)
 }
 
 async fn get_block_with_txs(
@@ -54,40 +454,191 @@ pub async fn get_block_number(node_provider: &NodeProvider) -> Result<U64, anyho
     Ok(block_number)
 }
 
+/// Calls `on_block` with the latest block and then with every new block in block number order.
+/// New heads are pushed by the node through `eth_subscribe` when supported, otherwise the block
+/// number is polled every `interval`.
+pub async fn watch_blocks(
+    node_provider: &NodeProvider,
+    interval: Duration,
+    include_tx: bool,
+    mut on_block: impl FnMut(BlockKind) -> anyhow::Result<()>,
+) -> anyhow::Result<()> {
+    let latest_block = get_block_number(node_provider).await?;
+    let mut next_block = latest_block;
+
+    emit_blocks_until(
+        node_provider,
+        &mut next_block,
+        latest_block,
+        include_tx,
+        &mut on_block,
+    )
+    .await?;
+
+    if node_provider.supports_subscriptions() {
+        let mut new_heads = node_provider.subscribe_blocks().await?;
+
+        while let Some(head) = new_heads.next().await {
+            let Some(head_number) = head.number else {
+                continue;
+            };
+
+            emit_blocks_until(
+                node_provider,
+                &mut next_block,
+                head_number,
+                include_tx,
+                &mut on_block,
+            )
+            .await?;
+        }
+
+        anyhow::bail!("The node closed the new heads subscription");
+    }
+
+    loop {
+        tokio::time::sleep(interval).await;
+
+        let latest_block = get_block_number(node_provider).await?;
+
+        emit_blocks_until(
+            node_provider,
+            &mut next_block,
+            latest_block,
+            include_tx,
+            &mut on_block,
+        )
+        .await?;
+    }
+}
+
+/// Emits the blocks from `next_block` up to `last_block`, so that no block is skipped when
+/// several of them are mined between two checks.
+async fn emit_blocks_until(
+    node_provider: &NodeProvider,
+    next_block: &mut U64,
+    last_block: U64,
+    include_tx: bool,
+    on_block: &mut impl FnMut(BlockKind) -> anyhow::Result<()>,
+) -> anyhow::Result<()> {
+    let mut blocks = pin!(fetch_blocks_stream(
+        node_provider,
+        next_block.as_u64()..=last_block.as_u64(),
+        include_tx,
+        BLOCK_FETCH_CONCURRENCY,
+        |_, _| {},
+    ));
+
+    while let Some(block) = blocks.next().await {
+        on_block(block?)?;
+
+        *next_block += U64::one();
+    }
+
+    Ok(())
+}
+
 // eth_getBlockTransactionCountByHash || eth_getBlockTransactionCountByNumber
 pub async fn get_transaction_count(
     node_provider: &NodeProvider,
     block_id: BlockId,
-) -> Result<Option<U256>, anyhow::Error> {
-    if let Some(block) = get_raw_block(node_provider, block_id).await? {
-        return Ok(Some(U256::from(block.transactions.len())));
-    }
+) -> Result<Lookup<U256>, anyhow::Error> {
+    let block = get_raw_block(node_provider, block_id).await?;
 
-    Ok(None)
+    Ok(
+        Lookup::from_option(block, Missing::Block)
+            .map(|block| U256::from(block.transactions.len())),
+    )
 }
 
 // eth_getUncleCountByBlockHash || eth_getUncleCountByBlockNumber
 pub async fn get_uncle_block_count(
     node_provider: &NodeProvider,
     block_id: BlockId,
-) -> Result<U256, anyhow::Error> {
+) -> Result<Lookup<U256>, anyhow::Error> {
+    // Nodes report 0 uncles for blocks that do not exist
+    if get_raw_block(node_provider, block_id).await?.is_none() {
+        return Ok(Lookup::NotFound {
+            what: Missing::Block,
+        });
+    }
+
     let count = node_provider.get_uncle_count(block_id).await?;
 
-    Ok(count)
+    Ok(Lookup::Found(count))
+}
+
+/// Aggregated stats of the transactions included in a block.
+#[derive(Debug, Default, Serialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct TransactionsSummary {
+    pub transaction_count: usize,
+    /// Sum of the ether transferred by the transactions, in wei
+    pub total_value: U256,
+    /// Sum of the gas used by the transactions according to their receipts
+    pub total_gas_used: U256,
+    pub unique_senders: usize,
+    /// Contract creations don't have a receiver and are not counted
+    pub unique_receivers: usize,
+}
+
+// eth_getBlockByHash || eth_getBlockByNumber && eth_getBlockReceipts
+pub async fn transactions_summary(
+    node_provider: &NodeProvider,
+    block_id: BlockId,
+) -> Result<Lookup<TransactionsSummary>, anyhow::Error> {
+    let Some(block) = get_block_with_txs(node_provider, block_id).await? else {
+        return Ok(Lookup::NotFound {
+            what: Missing::Block,
+        });
+    };
+
+    let receipts = match block.number {
+        Some(number) if !block.transactions.is_empty() => {
+            node_provider.get_block_receipts(number).await?
+        }
+        _ => vec![],
+    };
+
+    let senders: HashSet<_> = block.transactions.iter().map(|tx| tx.from).collect();
+    let receivers: HashSet<_> = block.transactions.iter().filter_map(|tx| tx.to).collect();
+
+    Ok(Lookup::Found(TransactionsSummary {
+        transaction_count: block.transactions.len(),
+        total_value: block
+            .transactions
+            .iter()
+            .fold(U256::zero(), |total, tx| total + tx.value),
+        total_gas_used: receipts
+            .iter()
+            .filter_map(|receipt| receipt.gas_used)
+            .fold(U256::zero(), |total, gas_used| total + gas_used),
+        unique_senders: senders.len(),
+        unique_receivers: receivers.len(),
+    }))
 }
 
 // eth_getBlockReceipts
 pub async fn get_block_receipts(
     node_provider: &NodeProvider,
     block_id: BlockId,
-) -> Result<Option<Vec<TransactionReceipt>>, anyhow::Error> {
+    explain_failure: bool,
+) -> Result<Lookup<Vec<EnrichedReceipt>>, anyhow::Error> {
     if let Some(block_number) = get_block_number_by_block_id(node_provider, block_id).await? {
         let receipts = node_provider.get_block_receipts(block_number).await?;
 
-        return Ok(Some(receipts));
+        let mut enriched_receipts = Vec::with_capacity(receipts.len());
+
+        for receipt in receipts {
+            enriched_receipts.push(enrich_receipt(node_provider, receipt, explain_failure).await?);
+        }
+
+        return Ok(Lookup::Found(enriched_receipts));
     }
 
-    Ok(None)
+    Ok(Lookup::NotFound {
+        what: Missing::Block,
+    })
 }
 
 #[cfg(test)]
@@ -111,6 +662,7 @@ mod tests {
                 &node_provider,
                 BlockId::Number(BlockNumber::Number(100.into())),
                 false,
+                None,
             )
             .await;
 
@@ -118,7 +670,7 @@ mod tests {
             assert!(res.is_ok());
 
             let maybe_block = res.unwrap();
-            assert!(maybe_block.is_none());
+            assert!(maybe_block.is_not_found());
 
             Ok(())
         }
@@ -129,13 +681,19 @@ mod tests {
             let (node_provider, _anvil) = setup_test().await?;
 
             // Act
-            let res = get_block(&node_provider, BlockId::Number(BlockNumber::Latest), false).await;
+            let res = get_block(
+                &node_provider,
+                BlockId::Number(BlockNumber::Latest),
+                false,
+                None,
+            )
+            .await;
 
             // Assert
             assert!(res.is_ok());
 
             let maybe_block = res.unwrap();
-            assert!(maybe_block.is_some());
+            assert!(maybe_block.is_found());
 
             Ok(())
         }
@@ -146,15 +704,21 @@ mod tests {
             let (node_provider, _anvil) = setup_test().await?;
 
             // Act
-            let res = get_block(&node_provider, BlockId::Number(BlockNumber::Latest), false).await;
+            let res = get_block(
+                &node_provider,
+                BlockId::Number(BlockNumber::Latest),
+                false,
+                None,
+            )
+            .await;
 
             // Assert
             assert!(res.is_ok());
 
             let maybe_block = res.unwrap();
-            assert!(maybe_block.is_some());
+            assert!(maybe_block.is_found());
 
-            assert!(matches!(maybe_block.unwrap(), BlockKind::RawBlock(_)));
+            assert!(matches!(maybe_block.found(), Some(BlockKind::RawBlock(_))));
 
             Ok(())
         }
@@ -165,23 +729,225 @@ mod tests {
             let (node_provider, _anvil) = setup_test().await?;
 
             // Act
-            let res = get_block(&node_provider, BlockId::Number(BlockNumber::Latest), true).await;
+            let res = get_block(
+                &node_provider,
+                BlockId::Number(BlockNumber::Latest),
+                true,
+                None,
+            )
+            .await;
 
             // Assert
             assert!(res.is_ok());
 
             let maybe_block = res.unwrap();
-            assert!(maybe_block.is_some());
+            assert!(maybe_block.is_found());
 
             assert!(matches!(
-                maybe_block.unwrap(),
-                BlockKind::BlockWithTransaction(_)
+                maybe_block.found(),
+                Some(BlockKind::BlockWithTransaction(_))
             ));
 
             Ok(())
         }
     }
 
+    mod diff_blocks {
+        use ethers::{
+            types::{Block, BlockId, BlockNumber, H256, U256, U64},
+            utils::parse_ether,
+        };
+        use serde_json::json;
+
+        use crate::cmd::{
+            block::{diff_block_headers, diff_blocks, HeaderFieldDiff},
+            helpers::test::{send_tx_helper, setup_test},
+        };
+
+        #[test]
+        fn should_only_report_the_changed_header_fields() -> anyhow::Result<()> {
+            // Arrange
+            let block: Block<H256> = Block {
+                number: Some(U64::from(1)),
+                gas_limit: U256::from(30_000_000),
+                base_fee_per_gas: Some(U256::from(7)),
+                ..Default::default()
+            };
+            let other_block = Block {
+                number: Some(U64::from(2)),
+                ..block.clone()
+            };
+
+            // Act
+            let res = diff_block_headers(&block, &other_block)?;
+
+            // Assert
+            assert_eq!(res.len(), 1);
+            assert_eq!(
+                res.get("number"),
+                Some(&HeaderFieldDiff {
+                    old: json!("0x1"),
+                    new: json!("0x2"),
+                })
+            );
+
+            Ok(())
+        }
+
+        #[test]
+        fn should_report_a_missing_field_as_null() -> anyhow::Result<()> {
+            // Arrange
+            let block: Block<H256> = Block::default();
+            let other_block = Block {
+                base_fee_per_gas: Some(U256::from(7)),
+                ..block.clone()
+            };
+
+            // Act
+            let res = diff_block_headers(&block, &other_block)?;
+
+            // Assert
+            assert_eq!(
+                res.get("baseFeePerGas"),
+                Some(&HeaderFieldDiff {
+                    old: json!(null),
+                    new: json!("0x7"),
+                })
+            );
+
+            Ok(())
+        }
+
+        #[tokio::test]
+        async fn should_diff_two_consecutive_blocks() -> anyhow::Result<()> {
+            // Arrange
+            let (node_provider, anvil) = setup_test().await?;
+
+            let sender = *anvil.addresses().first().unwrap();
+            let receiver = *anvil.addresses().last().unwrap();
+            send_tx_helper(&node_provider, sender, receiver, parse_ether(1)?).await?;
+
+            // Act
+            let res = diff_blocks(
+                &node_provider,
+                BlockId::Number(BlockNumber::Number(0.into())),
+                BlockId::Number(BlockNumber::Number(1.into())),
+            )
+            .await;
+
+            // Assert
+            assert!(res.is_ok());
+
+            let diff = res.unwrap().found().unwrap();
+            assert_eq!(
+                diff.get("number"),
+                Some(&HeaderFieldDiff {
+                    old: json!("0x0"),
+                    new: json!("0x1"),
+                })
+            );
+            assert!(diff.contains_key("hash"));
+            assert!(!diff.contains_key("gasLimit"));
+
+            Ok(())
+        }
+
+        #[tokio::test]
+        async fn should_not_diff_a_non_existing_block() -> anyhow::Result<()> {
+            // Arrange
+            let (node_provider, _anvil) = setup_test().await?;
+
+            // Act
+            let res = diff_blocks(
+                &node_provider,
+                BlockId::Number(BlockNumber::Latest),
+                BlockId::Number(BlockNumber::Number(100.into())),
+            )
+            .await;
+
+            // Assert
+            assert!(res.is_ok());
+            assert!(res.unwrap().is_not_found());
+
+            Ok(())
+        }
+    }
+
+    mod transactions_summary {
+        use ethers::{
+            providers::Middleware,
+            types::{BlockId, BlockNumber, TransactionRequest, U256},
+            utils::parse_ether,
+        };
+
+        use crate::cmd::{block::transactions_summary, helpers::test::setup_test};
+
+        #[tokio::test]
+        async fn should_aggregate_the_transactions_of_the_block() -> anyhow::Result<()> {
+            // Arrange
+            let (node_provider, anvil) = setup_test().await?;
+
+            let sender = *anvil.addresses().first().unwrap();
+            let receivers = &anvil.addresses()[1..3];
+            let values = [parse_ether(1)?, parse_ether(2)?];
+
+            // Include both transfers in the same block
+            node_provider
+                .provider()
+                .request::<_, ()>("evm_setAutomine", [false])
+                .await?;
+
+            for (receiver, value) in receivers.iter().zip(values) {
+                let tx = TransactionRequest::new()
+                    .value(value)
+                    .from(sender)
+                    .to(*receiver);
+                node_provider.send_transaction(tx, None).await?;
+            }
+
+            node_provider
+                .provider()
+                .request::<_, ()>("evm_mine", ())
+                .await?;
+
+            // Act
+            let res =
+                transactions_summary(&node_provider, BlockId::Number(BlockNumber::Latest)).await;
+
+            // Assert
+            assert!(res.is_ok());
+
+            let summary = res.unwrap().found().unwrap();
+            assert_eq!(summary.transaction_count, 2);
+            assert_eq!(summary.total_value, values[0] + values[1]);
+            // Plain transfers cost 21000 gas each
+            assert_eq!(summary.total_gas_used, U256::from(2 * 21_000));
+            assert_eq!(summary.unique_senders, 1);
+            assert_eq!(summary.unique_receivers, 2);
+
+            Ok(())
+        }
+
+        #[tokio::test]
+        async fn should_not_summarize_a_non_existing_block() -> anyhow::Result<()> {
+            // Arrange
+            let (node_provider, _anvil) = setup_test().await?;
+
+            // Act
+            let res = transactions_summary(
+                &node_provider,
+                BlockId::Number(BlockNumber::Number(100.into())),
+            )
+            .await;
+
+            // Assert
+            assert!(res.is_ok());
+            assert!(res.unwrap().is_not_found());
+
+            Ok(())
+        }
+    }
+
     mod get_block_number {
         use ethers::types::U64;
 
@@ -205,6 +971,131 @@ mod tests {
         }
     }
 
+    mod watch_blocks {
+        use std::time::Duration;
+
+        use ethers::{types::U64, utils::Anvil};
+
+        use crate::{
+            cmd::{
+                block::{emit_blocks_until, watch_blocks, BlockKind},
+                helpers::test::{send_tx_helper, setup_test},
+            },
+            config::{get_config, ConfigOverrides},
+            context::NodeProvider,
+        };
+
+        fn block_number(block: &BlockKind) -> U64 {
+            match block {
+                BlockKind::RawBlock(block) => block.number.unwrap(),
+                BlockKind::BlockWithTransaction(block) => block.number.unwrap(),
+            }
+        }
+
+        async fn watch_while_mining_helper(
+            node_provider: &NodeProvider,
+            sender: ethers::types::H160,
+            receiver: ethers::types::H160,
+        ) -> anyhow::Result<Vec<U64>> {
+            let mut blocks = Vec::new();
+
+            let (res, mined) = tokio::join!(
+                tokio::time::timeout(
+                    Duration::from_secs(2),
+                    watch_blocks(node_provider, Duration::from_millis(100), false, |block| {
+                        blocks.push(block_number(&block));
+
+                        Ok(())
+                    }),
+                ),
+                async {
+                    tokio::time::sleep(Duration::from_millis(500)).await;
+
+                    send_tx_helper(node_provider, sender, receiver, 1.into()).await?;
+                    send_tx_helper(node_provider, sender, receiver, 1.into()).await
+                }
+            );
+
+            assert!(res.is_err(), "Should keep watching until interrupted");
+            mined?;
+
+            Ok(blocks)
+        }
+
+        #[tokio::test]
+        async fn should_poll_the_new_blocks() -> anyhow::Result<()> {
+            // Arrange
+            let (node_provider, anvil) = setup_test().await?;
+
+            let sender = *anvil.addresses().first().unwrap();
+            let receiver = *anvil.addresses().get(1).unwrap();
+
+            // Act
+            let res = watch_while_mining_helper(&node_provider, sender, receiver).await?;
+
+            // Assert
+            assert_eq!(res, vec![0.into(), 1.into(), 2.into()]);
+
+            Ok(())
+        }
+
+        #[tokio::test]
+        async fn should_subscribe_to_the_new_blocks_over_websockets() -> anyhow::Result<()> {
+            // Arrange
+            let anvil = Anvil::new().spawn();
+
+            let config = get_config(ConfigOverrides::new(None, Some(anvil.ws_endpoint()), None))?;
+            let node_provider = NodeProvider::new(&config).await?;
+
+            let sender = *anvil.addresses().first().unwrap();
+            let receiver = *anvil.addresses().get(1).unwrap();
+
+            // Act
+            let res = watch_while_mining_helper(&node_provider, sender, receiver).await?;
+
+            // Assert
+            assert_eq!(res, vec![0.into(), 1.into(), 2.into()]);
+
+            Ok(())
+        }
+
+        #[tokio::test]
+        async fn should_emit_the_missed_blocks_in_order() -> anyhow::Result<()> {
+            // Arrange
+            let (node_provider, anvil) = setup_test().await?;
+
+            let sender = *anvil.addresses().first().unwrap();
+            let receiver = *anvil.addresses().get(1).unwrap();
+
+            for _ in 0..3 {
+                send_tx_helper(&node_provider, sender, receiver, 1.into()).await?;
+            }
+
+            let mut next_block = U64::one();
+            let mut blocks = Vec::new();
+
+            // Act
+            emit_blocks_until(
+                &node_provider,
+                &mut next_block,
+                3.into(),
+                true,
+                &mut |block| {
+                    blocks.push(block_number(&block));
+
+                    Ok(())
+                },
+            )
+            .await?;
+
+            // Assert
+            assert_eq!(blocks, vec![1.into(), 2.into(), 3.into()]);
+            assert_eq!(next_block, 4.into());
+
+            Ok(())
+        }
+    }
+
     mod get_transaction_count {
         use ethers::types::{BlockId, BlockNumber, U256};
 
@@ -223,9 +1114,9 @@ mod tests {
             assert!(res.is_ok());
 
             let maybe_transaction_count = res.unwrap();
-            assert!(maybe_transaction_count.is_some());
+            assert!(maybe_transaction_count.is_found());
 
-            let transaction_count = maybe_transaction_count.unwrap();
+            let transaction_count = maybe_transaction_count.found().unwrap();
             assert_eq!(transaction_count, U256::default());
 
             Ok(())
@@ -248,7 +1139,7 @@ mod tests {
             assert!(res.is_ok());
 
             let maybe_transaction_count = res.unwrap();
-            assert!(maybe_transaction_count.is_none());
+            assert!(maybe_transaction_count.is_not_found());
 
             Ok(())
         }
@@ -257,7 +1148,11 @@ mod tests {
     mod get_uncle_block_count {
         use ethers::types::{BlockId, BlockNumber, U256};
 
-        use crate::cmd::{block::get_uncle_block_count, helpers::test::setup_test};
+        use crate::cmd::{
+            block::get_uncle_block_count,
+            helpers::test::{generate_random_h256, setup_test},
+            lookup::{Lookup, Missing},
+        };
 
         #[tokio::test]
         async fn should_get_uncle_block_count() -> anyhow::Result<()> {
@@ -272,11 +1167,361 @@ mod tests {
             assert!(res.is_ok());
 
             let uncle_block_count = res.unwrap();
-            assert_eq!(uncle_block_count, U256::default());
+            assert_eq!(uncle_block_count, Lookup::Found(U256::default()));
+
+            Ok(())
+        }
+
+        #[tokio::test]
+        async fn should_not_find_the_uncle_block_count_of_a_non_existing_block(
+        ) -> anyhow::Result<()> {
+            // Arrange
+            let (node_provider, _anvil) = setup_test().await?;
+
+            // Act
+            let res = get_uncle_block_count(
+                &node_provider,
+                BlockId::Number(BlockNumber::Number(999_999_999.into())),
+            )
+            .await;
+
+            // Assert
+            assert!(res.is_ok());
+            assert_eq!(
+                res.unwrap(),
+                Lookup::NotFound {
+                    what: Missing::Block
+                }
+            );
+
+            Ok(())
+        }
+
+        #[tokio::test]
+        async fn should_not_find_the_uncle_block_count_of_a_non_existing_block_hash(
+        ) -> anyhow::Result<()> {
+            // Arrange
+            let (node_provider, _anvil) = setup_test().await?;
+
+            // Act
+            let res =
+                get_uncle_block_count(&node_provider, BlockId::Hash(generate_random_h256())).await;
+
+            // Assert
+            assert!(res.is_ok());
+            assert_eq!(
+                res.unwrap(),
+                Lookup::NotFound {
+                    what: Missing::Block
+                }
+            );
 
             Ok(())
         }
     }
 
     // Not testing  get_block_receipts because anvil does not support it
+
+    mod block_kind {
+        use ethers::types::{Block, Transaction, H256};
+        use serde_json::{json, Value};
+
+        use crate::{
+            cli::block::BlockNamespaceResult,
+            cmd::{
+                block::{get_block, BlockKind},
+                helpers::test::{send_tx_helper, setup_test},
+            },
+        };
+
+        fn block_with_transactions<TX: Default>(transactions: Vec<TX>) -> Block<TX> {
+            Block {
+                hash: Some(H256::repeat_byte(0xaa)),
+                parent_hash: H256::repeat_byte(0xbb),
+                number: Some(1.into()),
+                transactions,
+                ..Default::default()
+            }
+        }
+
+        fn assert_rpc_block_shape(block: &Value) {
+            assert_eq!(
+                block["hash"],
+                json!(format!("{:?}", H256::repeat_byte(0xaa)))
+            );
+            assert_eq!(
+                block["parentHash"],
+                json!(format!("{:?}", H256::repeat_byte(0xbb)))
+            );
+            assert_eq!(block["number"], json!("0x1"));
+            assert!(block.get("RawBlock").is_none());
+            assert!(block.get("BlockWithTransaction").is_none());
+        }
+
+        #[test]
+        fn should_serialize_a_raw_block_like_the_rpc_block() -> anyhow::Result<()> {
+            // Arrange
+            let tx_hash = H256::repeat_byte(0x01);
+            let block = BlockKind::RawBlock(block_with_transactions(vec![tx_hash]));
+
+            // Act
+            let res = serde_json::to_value(&block)?;
+
+            // Assert
+            assert_rpc_block_shape(&res);
+            assert_eq!(res["transactions"], json!([format!("{tx_hash:?}")]));
+
+            Ok(())
+        }
+
+        #[test]
+        fn should_serialize_a_block_with_transactions_like_the_rpc_block() -> anyhow::Result<()> {
+            // Arrange
+            let tx = Transaction {
+                hash: H256::repeat_byte(0x01),
+                ..Default::default()
+            };
+            let block = BlockKind::BlockWithTransaction(block_with_transactions(vec![tx.clone()]));
+
+            // Act
+            let res = serde_json::to_value(&block)?;
+
+            // Assert
+            assert_rpc_block_shape(&res);
+            assert_eq!(res["transactions"][0], serde_json::to_value(&tx)?);
+
+            Ok(())
+        }
+
+        #[test]
+        fn should_wrap_both_variants_in_the_same_namespace_envelope() -> anyhow::Result<()> {
+            // Setup
+            let test_cases = [
+                BlockKind::RawBlock(block_with_transactions(vec![])),
+                BlockKind::BlockWithTransaction(block_with_transactions(vec![])),
+            ];
+
+            for block in test_cases {
+                // Act
+                let res = serde_json::to_value(BlockNamespaceResult::Block(block))?;
+
+                // Assert
+                let res = res.as_object().unwrap();
+                assert_eq!(res.keys().collect::<Vec<_>>(), ["block"]);
+                assert_rpc_block_shape(&res["block"]);
+            }
+
+            Ok(())
+        }
+
+        #[tokio::test]
+        async fn should_expose_the_first_transaction_at_the_same_path_in_both_modes(
+        ) -> anyhow::Result<()> {
+            // Arrange
+            let (node_provider, anvil) = setup_test().await?;
+
+            let sender = *anvil.addresses().first().unwrap();
+            let receiver = *anvil.addresses().get(1).unwrap();
+
+            let tx_receipt = send_tx_helper(&node_provider, sender, receiver, 1.into()).await?;
+            let block_id = tx_receipt.block_hash.unwrap().into();
+            let tx_hash = json!(format!("{:?}", tx_receipt.transaction_hash));
+
+            for include_tx in [false, true] {
+                // Act
+                let res = get_block(&node_provider, block_id, include_tx, None).await?;
+
+                // Assert
+                let res = serde_json::to_value(res.found().unwrap())?;
+
+                // Equivalent to the `.transactions[0]` jq filter
+                let first_tx = res.pointer("/transactions/0").unwrap();
+
+                if include_tx {
+                    assert_eq!(first_tx["hash"], tx_hash);
+                } else {
+                    assert_eq!(*first_tx, tx_hash);
+                }
+            }
+
+            Ok(())
+        }
+    }
+
+    mod trie_root {
+        use ethers::types::H256;
+
+        use crate::cmd::block::{ordered_trie_root, trie_root};
+
+        #[test]
+        fn should_compute_the_root_of_an_empty_trie() -> anyhow::Result<()> {
+            // Act
+            let res = ordered_trie_root(&[]);
+
+            // Assert
+            assert_eq!(
+                res,
+                "0x56e81f171bcc55a6ff8345e692c0f86e5b48e01b996cadc001622fb5e363b421"
+                    .parse::<H256>()?
+            );
+
+            Ok(())
+        }
+
+        #[test]
+        fn should_compute_the_root_of_the_reference_tries() -> anyhow::Result<()> {
+            // Arrange
+            let test_cases: [(&[(&str, &str)], &str); 2] = [
+                (
+                    &[
+                        ("do", "verb"),
+                        ("horse", "stallion"),
+                        ("doge", "coin"),
+                        ("dog", "puppy"),
+                    ],
+                    "0x5991bb8c6514148a29db676a14ac506cd2cd5775ace63c30a4fe457715e9ac84",
+                ),
+                (
+                    &[
+                        ("doe", "reindeer"),
+                        ("dog", "puppy"),
+                        ("dogglesworth", "cat"),
+                    ],
+                    "0x8aad789dff2f538bca5d8ea56e8abe10f4c7ba3a5dea95fea4cd6e7c3a1168d3",
+                ),
+            ];
+
+            for (entries, expected) in test_cases {
+                // Act
+                let res = trie_root(
+                    entries
+                        .iter()
+                        .map(|(key, value)| (key.as_bytes().to_vec(), value.as_bytes()))
+                        .collect(),
+                );
+
+                // Assert
+                assert_eq!(res, expected.parse::<H256>()?);
+            }
+
+            Ok(())
+        }
+    }
+
+    mod check_header_consistency {
+        use ethers::{
+            providers::Middleware,
+            types::{Block, Transaction, H256},
+            utils::parse_ether,
+        };
+
+        use crate::cmd::{
+            block::{check_header_consistency, get_checked_block, BlockKind},
+            helpers::test::{generate_random_h256, send_tx_helper, setup_test},
+        };
+
+        const EMPTY_TRIE_ROOT: &str =
+            "0x56e81f171bcc55a6ff8345e692c0f86e5b48e01b996cadc001622fb5e363b421";
+        const EMPTY_OMMERS_HASH: &str =
+            "0x1dcc4de8dec75d7aab85b567b6ccd41ad312451b948a7413f0a142fd40d49347";
+
+        fn empty_block() -> anyhow::Result<Block<Transaction>> {
+            Ok(Block {
+                transactions_root: EMPTY_TRIE_ROOT.parse()?,
+                uncles_hash: EMPTY_OMMERS_HASH.parse()?,
+                ..Default::default()
+            })
+        }
+
+        #[test]
+        fn should_accept_a_consistent_empty_block() -> anyhow::Result<()> {
+            // Arrange
+            let block = empty_block()?;
+
+            // Act
+            let res = check_header_consistency(&block, &[]);
+
+            // Assert
+            assert!(res.consistent);
+            assert_eq!(res.checks.len(), 2);
+            assert!(res.skipped.is_empty());
+
+            Ok(())
+        }
+
+        #[test]
+        fn should_report_a_tampered_transactions_root() -> anyhow::Result<()> {
+            // Arrange
+            let tampered_root = generate_random_h256();
+            let block = Block {
+                transactions_root: tampered_root,
+                ..empty_block()?
+            };
+
+            // Act
+            let res = check_header_consistency(&block, &[]);
+
+            // Assert
+            assert!(!res.consistent);
+
+            let mismatches: Vec<_> = res.checks.iter().filter(|check| !check.matches).collect();
+            assert_eq!(mismatches.len(), 1);
+            assert_eq!(mismatches[0].field, "transactionsRoot");
+            assert_eq!(mismatches[0].header, tampered_root);
+            assert_eq!(mismatches[0].computed, EMPTY_TRIE_ROOT.parse::<H256>()?);
+
+            Ok(())
+        }
+
+        #[test]
+        fn should_skip_the_ommers_hash_without_the_uncle_headers() -> anyhow::Result<()> {
+            // Arrange
+            let block = Block {
+                uncles: vec![generate_random_h256()],
+                ..empty_block()?
+            };
+
+            // Act
+            let res = check_header_consistency(&block, &[]);
+
+            // Assert
+            assert_eq!(res.checks.len(), 1);
+            assert_eq!(res.skipped.len(), 1);
+
+            Ok(())
+        }
+
+        #[tokio::test]
+        async fn should_validate_a_genuine_block_and_reject_a_tampered_one() -> anyhow::Result<()> {
+            // Arrange
+            let (node_provider, anvil) = setup_test().await?;
+
+            let sender = *anvil.addresses().first().unwrap();
+            let receiver = *anvil.addresses().get(1).unwrap();
+
+            let receipt = send_tx_helper(&node_provider, sender, receiver, parse_ether(1)?).await?;
+            let block_id = receipt.block_hash.unwrap().into();
+
+            // Act
+            let res = get_checked_block(&node_provider, block_id, true).await;
+
+            // Assert
+            assert!(res.is_ok());
+
+            let checked_block = res.unwrap().found().unwrap();
+            assert!(checked_block.header_check.consistent);
+            assert!(matches!(
+                checked_block.block,
+                BlockKind::BlockWithTransaction(_)
+            ));
+
+            let mut tampered_block = node_provider.get_block_with_txs(block_id).await?.unwrap();
+            tampered_block.transactions[0].value += 1.into();
+
+            let tampered_res = check_header_consistency(&tampered_block, &[]);
+            assert!(!tampered_res.consistent);
+
+            Ok(())
+        }
+    }
 }
@@ -0,0 +1,201 @@
+use std::{
+    fs,
+    io::ErrorKind,
+    path::{Path, PathBuf},
+};
+
+use ethers::{providers::Middleware, types::H256};
+use serde::{Deserialize, Serialize};
+
+use crate::context::NodeProvider;
+
+/// Number of blocks scanned again when the checkpointed block was reorged out of the chain.
+pub const REORG_OVERLAP_BLOCKS: u64 = 12;
+
+/// Last block processed by a scan.
+#[derive(Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct Checkpoint {
+    pub block_number: u64,
+    pub block_hash: H256,
+}
+
+/// Json file holding the checkpoint of a scan between runs.
+pub struct CheckpointStore {
+    path: PathBuf,
+}
+
+impl CheckpointStore {
+    pub fn new(path: impl AsRef<Path>) -> Self {
+        Self {
+            path: path.as_ref().to_path_buf(),
+        }
+    }
+
+    /// Reads the recorded checkpoint, if the scan ran before.
+    pub fn load(&self) -> anyhow::Result<Option<Checkpoint>> {
+        let content = match fs::read_to_string(&self.path) {
+            Ok(content) => content,
+            Err(err) if err.kind() == ErrorKind::NotFound => return Ok(None),
+            Err(err) => return Err(err.into()),
+        };
+
+        let checkpoint = serde_json::from_str(&content).map_err(|err| {
+            anyhow::anyhow!("Invalid checkpoint file {}: {err}", self.path.display())
+        })?;
+
+        Ok(Some(checkpoint))
+    }
+
+    pub fn save(&self, checkpoint: &Checkpoint) -> anyhow::Result<()> {
+        fs::write(&self.path, serde_json::to_string(checkpoint)?)?;
+
+        Ok(())
+    }
+}
+
+// eth_getBlockByNumber
+/// Returns the first block to scan after the checkpoint: the next block if the checkpointed one is
+/// still part of the chain, or a few blocks before it otherwise so that reorged logs are picked up.
+pub async fn resume_block(
+    node_provider: &NodeProvider,
+    checkpoint: &Checkpoint,
+) -> anyhow::Result<u64> {
+    let block_hash = node_provider
+        .get_block(checkpoint.block_number)
+        .await?
+        .and_then(|block| block.hash);
+
+    if block_hash == Some(checkpoint.block_hash) {
+        Ok(checkpoint.block_number + 1)
+    } else {
+        Ok((checkpoint.block_number + 1).saturating_sub(REORG_OVERLAP_BLOCKS))
+    }
+}
+
+// eth_getBlockByNumber
+/// Builds the checkpoint of the provided block.
+pub async fn checkpoint_at(
+    node_provider: &NodeProvider,
+    block_number: u64,
+) -> anyhow::Result<Checkpoint> {
+    let block_hash = node_provider
+        .get_block(block_number)
+        .await?
+        .and_then(|block| block.hash)
+        .ok_or(anyhow::anyhow!("Block {block_number} not found"))?;
+
+    Ok(Checkpoint {
+        block_number,
+        block_hash,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+
+    mod checkpoint_store {
+        use ethers::types::H256;
+
+        use crate::cmd::checkpoint::{Checkpoint, CheckpointStore};
+
+        #[test]
+        fn should_not_load_a_checkpoint_before_the_first_run() -> anyhow::Result<()> {
+            // Arrange
+            let store = CheckpointStore::new(std::env::temp_dir().join("yaeth-checkpoint-missing"));
+
+            // Act
+            let res = store.load()?;
+
+            // Assert
+            assert_eq!(res, None);
+
+            Ok(())
+        }
+
+        #[test]
+        fn should_load_the_saved_checkpoint() -> anyhow::Result<()> {
+            // Arrange
+            let path =
+                std::env::temp_dir().join(format!("yaeth-checkpoint-saved-{}", std::process::id()));
+            let store = CheckpointStore::new(&path);
+            let checkpoint = Checkpoint {
+                block_number: 42,
+                block_hash: H256::repeat_byte(0x42),
+            };
+
+            // Act
+            store.save(&checkpoint)?;
+            let res = store.load();
+            std::fs::remove_file(path)?;
+
+            // Assert
+            assert_eq!(res?, Some(checkpoint));
+
+            Ok(())
+        }
+
+        #[test]
+        fn should_reject_an_invalid_checkpoint_file() -> anyhow::Result<()> {
+            // Arrange
+            let path = std::env::temp_dir()
+                .join(format!("yaeth-checkpoint-invalid-{}", std::process::id()));
+            std::fs::write(&path, "not a checkpoint")?;
+            let store = CheckpointStore::new(&path);
+
+            // Act
+            let res = store.load();
+            std::fs::remove_file(path)?;
+
+            // Assert
+            assert!(res.is_err());
+
+            Ok(())
+        }
+    }
+
+    mod resume_block {
+        use ethers::types::H256;
+
+        use crate::cmd::{
+            checkpoint::{checkpoint_at, resume_block, Checkpoint, REORG_OVERLAP_BLOCKS},
+            helpers::test::setup_test,
+        };
+
+        #[tokio::test]
+        async fn should_resume_after_the_checkpointed_block() -> anyhow::Result<()> {
+            // Arrange
+            let (node_provider, _anvil) = setup_test().await?;
+
+            let checkpoint = checkpoint_at(&node_provider, 0).await?;
+
+            // Act
+            let res = resume_block(&node_provider, &checkpoint).await;
+
+            // Assert
+            assert_eq!(res?, 1);
+
+            Ok(())
+        }
+
+        #[tokio::test]
+        async fn should_scan_an_overlap_when_the_checkpointed_block_was_reorged(
+        ) -> anyhow::Result<()> {
+            // Arrange
+            let (node_provider, _anvil) = setup_test().await?;
+
+            let checkpoint = Checkpoint {
+                block_number: 20,
+                block_hash: H256::repeat_byte(0xaa),
+            };
+
+            // Act
+            let res = resume_block(&node_provider, &checkpoint).await;
+
+            // Assert
+            assert_eq!(res?, 21 - REORG_OVERLAP_BLOCKS);
+
+            Ok(())
+        }
+    }
+}
@@ -0,0 +1,3678 @@
+use std::{
+    collections::{BTreeMap, HashMap, HashSet},
+    fs::OpenOptions,
+    future::Future,
+    io::{BufRead, BufReader, Write},
+    path::Path,
+    pin::pin,
+    time::Duration,
+};
+
+use ethers::{
+    abi::{self, Abi, Event, ParamType, RawLog, Token},
+    providers::{FilterKind, Middleware},
+    types::{
+        Address, Block, BlockId, BlockNumber, Filter, FilterBlockOption, Log, Transaction, H256,
+        I256, U256, U64,
+    },
+    utils::{format_units, keccak256, to_checksum},
+};
+use futures::{StreamExt, TryStreamExt};
+use serde::{Deserialize, Serialize};
+
+use crate::context::NodeProvider;
+
+use super::{
+    block::{get_block, BlockKind},
+    checkpoint::{checkpoint_at, resume_block, CheckpointStore},
+    helpers::{
+        as_json_rpc_error, buffered_fetch, ensure_block_range_within_limit, fetch_blocks_stream,
+        get_block_number_value, is_method_not_found, BLOCK_FETCH_CONCURRENCY,
+    },
+    lookup::Lookup,
+    utils::{call_token, decode_single},
+};
+
+const LOG_CHUNK_CONCURRENCY: usize = 4;
+
+// Providers that cap the size of eth_getLogs responses don't share a common error code
+const TOO_MANY_RESULTS_MESSAGES: [&str; 4] = [
+    "query returned more than",
+    "response size exceeded",
+    "query exceeds max results",
+    "too many results",
+];
+
+// eth_getLogs
+pub async fn get_logs(
+    node_provider: &NodeProvider,
+    filter: Filter,
+    max_block_range: u64,
+    chunk_size: Option<u64>,
+) -> anyhow::Result<Vec<Log>> {
+    let FilterBlockOption::Range {
+        from_block,
+        to_block,
+    } = filter.block_option
+    else {
+        return Ok(node_provider.get_logs(&filter).await?);
+    };
+
+    // Tags are resolved first so that a range like earliest..latest is subject to the limit too
+    let from_block = get_range_bound(node_provider, from_block).await?;
+    let to_block = get_range_bound(node_provider, to_block).await?;
+
+    if let Some(chunk_size) = chunk_size {
+        return get_logs_in_chunks(
+            node_provider,
+            &filter,
+            from_block,
+            to_block,
+            max_block_range,
+            chunk_size,
+        )
+        .await;
+    }
+
+    if to_block >= from_block {
+        ensure_block_range_within_limit(to_block - from_block + 1, max_block_range)?;
+    }
+
+    let filter = filter.from_block(from_block).to_block(to_block);
+
+    Ok(node_provider.get_logs(&filter).await?)
+}
+
+// eth_getLogs
+/// Gets the logs of the block selected by the hash of the filter. A block unknown to the node is
+/// reported as not found rather than as an error, which would be indistinguishable from a block
+/// without matching logs if the node answered with an empty list.
+pub async fn get_logs_at_block_hash(
+    node_provider: &NodeProvider,
+    filter: Filter,
+) -> anyhow::Result<Lookup<Vec<Log>>> {
+    Lookup::from_block_query(
+        node_provider
+            .get_logs(&filter)
+            .await
+            .map_err(anyhow::Error::from),
+    )
+}
+
+// eth_getFilterLogs
+/// Gets every log matching the filter installed on the node with the provided id.
+pub async fn get_filter_logs(
+    node_provider: &NodeProvider,
+    filter_id: U256,
+) -> anyhow::Result<Vec<Log>> {
+    let logs = node_provider
+        .inner()
+        .request("eth_getFilterLogs", [filter_id])
+        .await?;
+
+    Ok(logs)
+}
+
+// eth_getLogs
+/// Gets the logs matching the filter from the block after the checkpoint (or the filter starting
+/// block on the first run) up to the filter ending block, then records the latter as checkpoint so
+/// that the next run resumes from there.
+pub async fn get_logs_since_checkpoint(
+    node_provider: &NodeProvider,
+    filter: Filter,
+    checkpoint_store: &CheckpointStore,
+    max_block_range: u64,
+    chunk_size: Option<u64>,
+) -> anyhow::Result<Vec<Log>> {
+    let FilterBlockOption::Range {
+        from_block,
+        to_block,
+    } = filter.block_option
+    else {
+        anyhow::bail!("Logs can only be resumed over a block range");
+    };
+
+    let from_block = match checkpoint_store.load()? {
+        Some(checkpoint) => resume_block(node_provider, &checkpoint).await?,
+        None => {
+            get_range_bound(
+                node_provider,
+                Some(from_block.unwrap_or(BlockNumber::Earliest)),
+            )
+            .await?
+        }
+    };
+    let to_block = get_range_bound(node_provider, to_block).await?;
+
+    if from_block > to_block {
+        return Ok(vec![]);
+    }
+
+    let checkpoint = checkpoint_at(node_provider, to_block).await?;
+
+    let logs = get_logs(
+        node_provider,
+        filter.from_block(from_block).to_block(to_block),
+        max_block_range,
+        chunk_size,
+    )
+    .await?;
+
+    checkpoint_store.save(&checkpoint)?;
+
+    Ok(logs)
+}
+
+/// Splits the range in queries of at most `chunk_size` blocks, merging their logs in block order.
+async fn get_logs_in_chunks(
+    node_provider: &NodeProvider,
+    filter: &Filter,
+    from_block: u64,
+    to_block: u64,
+    max_block_range: u64,
+    chunk_size: u64,
+) -> anyhow::Result<Vec<Log>> {
+    fold_logs_in_chunks(
+        node_provider,
+        filter,
+        from_block,
+        to_block,
+        max_block_range,
+        chunk_size,
+        |mut logs: Vec<Log>, chunk_logs| {
+            logs.extend(chunk_logs);
+
+            Ok(logs)
+        },
+    )
+    .await
+}
+
+/// Splits the range in queries of at most `chunk_size` blocks, folding the logs of each chunk
+/// into the accumulator in block order as soon as they are fetched.
+async fn fold_logs_in_chunks<T: Default>(
+    node_provider: &NodeProvider,
+    filter: &Filter,
+    from_block: u64,
+    to_block: u64,
+    max_block_range: u64,
+    chunk_size: u64,
+    mut fold: impl FnMut(T, Vec<Log>) -> anyhow::Result<T>,
+) -> anyhow::Result<T> {
+    if from_block > to_block {
+        anyhow::bail!(
+            "Invalid block range. The starting block {from_block} is after the ending block {to_block}"
+        );
+    }
+
+    ensure_block_range_within_limit(to_block - from_block + 1, max_block_range)?;
+
+    let chunks = split_block_range(from_block, to_block, chunk_size);
+
+    buffered_fetch(
+        0..=(chunks.len() as u64 - 1),
+        LOG_CHUNK_CONCURRENCY,
+        |idx| {
+            let (from_block, to_block) = chunks[idx as usize];
+
+            get_logs_bisecting(from_block, to_block, move |from_block, to_block| {
+                let filter = filter.clone().from_block(from_block).to_block(to_block);
+
+                async move { Ok(node_provider.get_logs(&filter).await?) }
+            })
+        },
+        |_, _| {},
+    )
+    .try_fold(T::default(), |acc, chunk_logs| {
+        futures::future::ready(fold(acc, chunk_logs))
+    })
+    .await
+}
+
+/// Logs emitted by a single contract over the scanned range.
+#[derive(Debug, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct AddressLogsSummary {
+    pub log_count: u64,
+    pub first_block: u64,
+    pub last_block: u64,
+    pub distinct_topic0_count: usize,
+    #[serde(skip)]
+    topic0s: HashSet<H256>,
+}
+
+/// Number of logs emitted by each contract over the scanned range.
+#[derive(Debug, Default, Serialize)]
+#[serde(transparent)]
+pub struct LogsSummary(pub BTreeMap<Address, AddressLogsSummary>);
+
+impl LogsSummary {
+    /// Adds the logs to the summary of the contracts that emitted them.
+    pub fn extend(&mut self, logs: &[Log]) {
+        for log in logs {
+            let block_number = log.block_number.unwrap_or_default().as_u64();
+
+            let summary = self
+                .0
+                .entry(log.address)
+                .or_insert_with(|| AddressLogsSummary {
+                    log_count: 0,
+                    first_block: block_number,
+                    last_block: block_number,
+                    distinct_topic0_count: 0,
+                    topic0s: HashSet::new(),
+                });
+
+            summary.log_count += 1;
+            summary.first_block = summary.first_block.min(block_number);
+            summary.last_block = summary.last_block.max(block_number);
+
+            if let Some(topic0) = log.topics.first() {
+                summary.topic0s.insert(*topic0);
+                summary.distinct_topic0_count = summary.topic0s.len();
+            }
+        }
+    }
+}
+
+// eth_getLogs
+/// Summarizes the logs matching the filter per contract. Logs are aggregated one chunk at a time
+/// (the whole range being a single chunk if no chunk size is provided), so they are never all held
+/// in memory.
+pub async fn get_logs_summary(
+    node_provider: &NodeProvider,
+    filter: Filter,
+    max_block_range: u64,
+    chunk_size: Option<u64>,
+) -> anyhow::Result<LogsSummary> {
+    let FilterBlockOption::Range {
+        from_block,
+        to_block,
+    } = filter.block_option
+    else {
+        let mut summary = LogsSummary::default();
+        summary.extend(&node_provider.get_logs(&filter).await?);
+
+        return Ok(summary);
+    };
+
+    let from_block = get_range_bound(node_provider, from_block).await?;
+    let to_block = get_range_bound(node_provider, to_block).await?;
+    let chunk_size = chunk_size.unwrap_or(to_block.saturating_sub(from_block) + 1);
+
+    fold_logs_in_chunks(
+        node_provider,
+        &filter,
+        from_block,
+        to_block,
+        max_block_range,
+        chunk_size,
+        |mut summary: LogsSummary, chunk_logs| {
+            summary.extend(&chunk_logs);
+
+            Ok(summary)
+        },
+    )
+    .await
+}
+
+/// Blocks per query of an export without an explicit chunk size.
+pub const LOGS_EXPORT_CHUNK_SIZE: u64 = 2_000;
+
+/// Totals of a logs export, written as the last line of the export file.
+#[derive(Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct LogsExportTotals {
+    pub from_block: u64,
+    pub to_block: u64,
+    pub logs: u64,
+}
+
+#[derive(Serialize)]
+struct LogsExportFooter<'a> {
+    totals: &'a LogsExportTotals,
+}
+
+/// Fields of an export file line needed to resume the export: the block of a log or the totals of
+/// a completed export.
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct LogsExportLine {
+    block_number: Option<U64>,
+    totals: Option<LogsExportTotals>,
+}
+
+/// Progress found in an existing export file.
+#[derive(Debug, PartialEq, Eq)]
+enum LogsExportProgress {
+    Started,
+    Interrupted { block: u64, logs: u64 },
+    Completed(LogsExportTotals),
+}
+
+// eth_getLogs
+/// Appends the logs matching the filter to the export file as json lines, one chunk at a time as
+/// soon as it is fetched, followed by a line holding the totals. The whole range is never held in
+/// memory. With `resume`, an interrupted export continues from the last block recorded in the
+/// file, whose logs are fetched again as they might have been partially written.
+pub async fn export_logs(
+    node_provider: &NodeProvider,
+    filter: Filter,
+    decoder: Option<&LogDecoder>,
+    max_block_range: u64,
+    chunk_size: u64,
+    path: &Path,
+    resume: bool,
+) -> anyhow::Result<LogsExportTotals> {
+    let FilterBlockOption::Range {
+        from_block,
+        to_block,
+    } = filter.block_option
+    else {
+        anyhow::bail!("Logs can only be exported over a block range");
+    };
+
+    let from_block = get_range_bound(node_provider, from_block).await?;
+    let to_block = get_range_bound(node_provider, to_block).await?;
+
+    let (start_block, exported_logs) = match load_logs_export_progress(path, resume)? {
+        LogsExportProgress::Started => (from_block, 0),
+        LogsExportProgress::Interrupted { block, logs } => (block.max(from_block), logs),
+        LogsExportProgress::Completed(totals) => return Ok(totals),
+    };
+
+    let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+
+    let new_logs = match start_block <= to_block {
+        true => {
+            fold_logs_in_chunks(
+                node_provider,
+                &filter,
+                start_block,
+                to_block,
+                max_block_range,
+                chunk_size,
+                |written: u64, chunk_logs| {
+                    let logs = chunk_logs.len() as u64;
+                    let mut lines = String::new();
+
+                    for log in chunk_logs {
+                        let line = match decoder {
+                            Some(decoder) => serde_json::to_string(&decode_log(decoder, log))?,
+                            None => serde_json::to_string(&log)?,
+                        };
+
+                        lines.push_str(&line);
+                        lines.push('\n');
+                    }
+
+                    file.write_all(lines.as_bytes())?;
+
+                    Ok(written + logs)
+                },
+            )
+            .await?
+        }
+        false => 0,
+    };
+
+    let totals = LogsExportTotals {
+        from_block,
+        to_block,
+        logs: exported_logs + new_logs,
+    };
+
+    writeln!(
+        file,
+        "{}",
+        serde_json::to_string(&LogsExportFooter { totals: &totals })?
+    )?;
+
+    Ok(totals)
+}
+
+/// Reads the progress of an existing export file. An interrupted export is truncated before the
+/// logs of its last recorded block, so that they can be fetched again without duplicates.
+fn load_logs_export_progress(path: &Path, resume: bool) -> anyhow::Result<LogsExportProgress> {
+    if !path.exists() {
+        return Ok(LogsExportProgress::Started);
+    }
+
+    if !resume {
+        anyhow::bail!(
+            "The export file {} already exists. Use --resume to continue an interrupted export",
+            path.display()
+        );
+    }
+
+    let file = OpenOptions::new().read(true).write(true).open(path)?;
+    let mut reader = BufReader::new(&file);
+
+    let mut line = String::new();
+    let mut offset = 0;
+    let mut logs = 0;
+    // Block of the last recorded log, along with the offset and log count before its first log
+    let mut last_block: Option<(u64, u64, u64)> = None;
+
+    loop {
+        line.clear();
+        let read = reader.read_line(&mut line)?;
+
+        // A line without newline was being written when the export was interrupted
+        if read == 0 || !line.ends_with('\n') {
+            break;
+        }
+
+        let parsed: LogsExportLine = serde_json::from_str(&line)
+            .map_err(|err| anyhow::anyhow!("Invalid export file {}: {err}", path.display()))?;
+
+        if let Some(totals) = parsed.totals {
+            return Ok(LogsExportProgress::Completed(totals));
+        }
+
+        let block = parsed
+            .block_number
+            .ok_or(anyhow::anyhow!(
+                "Invalid export file {}: found a log without block number",
+                path.display()
+            ))?
+            .as_u64();
+
+        if last_block.is_none_or(|(last_block, ..)| last_block != block) {
+            last_block = Some((block, offset, logs));
+        }
+
+        offset += read as u64;
+        logs += 1;
+    }
+
+    match last_block {
+        Some((block, offset, logs)) => {
+            file.set_len(offset)?;
+
+            Ok(LogsExportProgress::Interrupted { block, logs })
+        }
+        None => {
+            file.set_len(0)?;
+
+            Ok(LogsExportProgress::Started)
+        }
+    }
+}
+
+/// Calls `on_log` with every log matching the filter from its starting block onward, in block
+/// order. New logs are pushed by the node through `eth_subscribe` when supported, subscribing again
+/// with a backoff and catching up from the last seen block whenever the socket drops. The pushed
+/// logs that a reorg removed are reported again with `removed` set if they were reported before.
+/// Otherwise the node is polled every `interval`, querying the last seen block again so that logs
+/// of reorged blocks are picked up while the already seen ones are skipped.
+pub async fn follow_logs(
+    node_provider: &NodeProvider,
+    filter: Filter,
+    interval: Duration,
+    mut on_log: impl FnMut(Log) -> anyhow::Result<()>,
+) -> anyhow::Result<()> {
+    let FilterBlockOption::Range { from_block, .. } = filter.block_option else {
+        anyhow::bail!("Logs can only be followed over a block range");
+    };
+
+    let mut from_block = get_range_bound(node_provider, from_block).await?;
+    let mut seen_logs = SeenLogs::default();
+
+    if node_provider.supports_subscriptions() {
+        // Subscriptions only report the logs of the blocks mined after they start
+        let subscription_filter = Filter {
+            block_option: FilterBlockOption::default(),
+            ..filter.clone()
+        };
+
+        let mut backoff = ResubscribeBackoff::default();
+
+        loop {
+            // Subscribing before catching up so that the logs emitted in between are not missed
+            let mut new_logs = node_provider.subscribe_logs(&subscription_filter).await?;
+            let mut pushed = false;
+
+            emit_logs_since(
+                node_provider,
+                &filter,
+                &mut from_block,
+                &mut seen_logs,
+                &mut on_log,
+            )
+            .await?;
+
+            while let Some(log) = new_logs.next().await {
+                pushed = true;
+
+                // Reorged out logs are only reported to retract the ones reported before
+                if log.removed == Some(true) {
+                    if seen_logs.remove(&log) {
+                        on_log(log)?;
+                    }
+
+                    continue;
+                }
+
+                if let Some(block_number) = log.block_number {
+                    from_block = block_number.as_u64();
+                    seen_logs.prune(from_block);
+                }
+
+                if seen_logs.insert(&log) {
+                    on_log(log)?;
+                }
+            }
+
+            tokio::time::sleep(backoff.next_delay(pushed)).await;
+        }
+    }
+
+    loop {
+        emit_logs_since(
+            node_provider,
+            &filter,
+            &mut from_block,
+            &mut seen_logs,
+            &mut on_log,
+        )
+        .await?;
+
+        tokio::time::sleep(interval).await;
+    }
+}
+
+// eth_blockNumber, eth_getLogs
+/// Emits the logs not seen yet from `from_block` up to the latest block, which becomes the next
+/// starting block.
+async fn emit_logs_since(
+    node_provider: &NodeProvider,
+    filter: &Filter,
+    from_block: &mut u64,
+    seen_logs: &mut SeenLogs,
+    on_log: &mut impl FnMut(Log) -> anyhow::Result<()>,
+) -> anyhow::Result<()> {
+    let latest_block = node_provider.get_block_number().await?.as_u64();
+
+    if latest_block < *from_block {
+        return Ok(());
+    }
+
+    let range_filter = filter
+        .clone()
+        .from_block(*from_block)
+        .to_block(latest_block);
+
+    for log in node_provider.get_logs(&range_filter).await? {
+        if seen_logs.insert(&log) {
+            on_log(log)?;
+        }
+    }
+
+    *from_block = latest_block;
+    seen_logs.prune(latest_block);
+
+    Ok(())
+}
+
+/// Pending transaction reported by the pending transactions filter.
+#[derive(Debug, Serialize)]
+#[serde(untagged)]
+pub enum PendingTx {
+    Hash(H256),
+    Full(Transaction),
+}
+
+// eth_newPendingTransactionFilter, eth_uninstallFilter
+/// Installs a pending transactions filter and polls it every `interval`, calling `on_tx` with every
+/// transaction that entered the mempool until `shutdown` completes. The filter is uninstalled
+/// before returning so that it isn't left on the node.
+pub async fn follow_pending_transactions(
+    node_provider: &NodeProvider,
+    interval: Duration,
+    full: bool,
+    shutdown: impl Future<Output = anyhow::Result<()>>,
+    on_tx: impl FnMut(PendingTx) -> anyhow::Result<()>,
+) -> anyhow::Result<()> {
+    follow_filter_changes(
+        node_provider,
+        FilterKind::PendingTransactions,
+        interval,
+        shutdown,
+        |filter_id| get_pending_transaction_changes(node_provider, filter_id, full),
+        on_tx,
+    )
+    .await
+}
+
+// eth_newPendingTransactionFilter, eth_uninstallFilter
+/// Gets the transactions that entered the mempool during the provided interval.
+pub async fn get_pending_transactions(
+    node_provider: &NodeProvider,
+    interval: Duration,
+    full: bool,
+) -> anyhow::Result<Vec<PendingTx>> {
+    let filter_id = install_filter(node_provider, FilterKind::PendingTransactions).await?;
+
+    tokio::time::sleep(interval).await;
+    let res = get_pending_transaction_changes(node_provider, filter_id, full).await;
+
+    uninstall_filter_after(node_provider, filter_id, res).await
+}
+
+// eth_getFilterChanges, eth_getTransactionByHash
+async fn get_pending_transaction_changes(
+    node_provider: &NodeProvider,
+    filter_id: U256,
+    full: bool,
+) -> anyhow::Result<Vec<PendingTx>> {
+    let hashes: Vec<H256> = node_provider.get_filter_changes(filter_id).await?;
+
+    if !full {
+        return Ok(hashes.into_iter().map(PendingTx::Hash).collect());
+    }
+
+    let mut txs = Vec::with_capacity(hashes.len());
+    for hash in hashes {
+        // The transaction may have already been dropped from the mempool
+        txs.push(match node_provider.get_transaction(hash).await? {
+            Some(tx) => PendingTx::Full(tx),
+            None => PendingTx::Hash(hash),
+        });
+    }
+
+    Ok(txs)
+}
+
+/// Block reported by the new blocks filter, summarized when resolved.
+#[derive(Debug, Serialize)]
+#[serde(untagged)]
+pub enum NewBlock {
+    Hash(H256),
+    #[serde(rename_all = "camelCase")]
+    Summary {
+        hash: H256,
+        number: Option<U64>,
+        timestamp: U256,
+        transaction_count: usize,
+    },
+}
+
+// eth_newBlockFilter, eth_uninstallFilter
+/// Calls `on_block` with every new block until `shutdown` completes. New heads are pushed by the
+/// node through `eth_subscribe` when supported, otherwise a new blocks filter is installed and
+/// polled every `interval`, then uninstalled before returning.
+pub async fn follow_new_blocks(
+    node_provider: &NodeProvider,
+    interval: Duration,
+    resolve: bool,
+    shutdown: impl Future<Output = anyhow::Result<()>>,
+    mut on_block: impl FnMut(NewBlock) -> anyhow::Result<()>,
+) -> anyhow::Result<()> {
+    if node_provider.supports_subscriptions() {
+        return tokio::select! {
+            res = subscribe_new_blocks(node_provider, resolve, &mut on_block) => res,
+            res = shutdown => res,
+        };
+    }
+
+    follow_filter_changes(
+        node_provider,
+        FilterKind::NewBlocks,
+        interval,
+        shutdown,
+        |filter_id| get_new_block_changes(node_provider, filter_id, resolve),
+        on_block,
+    )
+    .await
+}
+
+// eth_subscribe
+/// Reports the new heads pushed by the node. Whenever the socket drops, the blocks mined since the
+/// last seen one are reported before subscribing again.
+async fn subscribe_new_blocks(
+    node_provider: &NodeProvider,
+    resolve: bool,
+    on_block: &mut impl FnMut(NewBlock) -> anyhow::Result<()>,
+) -> anyhow::Result<()> {
+    let mut last_block: Option<U64> = None;
+
+    let mut backoff = ResubscribeBackoff::default();
+
+    loop {
+        // Subscribing before catching up so that the blocks mined in between are not missed
+        let mut new_heads = node_provider.subscribe_blocks().await?;
+        let mut caught_up_block = None;
+        let mut pushed = false;
+
+        if let Some(from_block) = last_block {
+            let latest_block = node_provider.get_block_number().await?;
+
+            let mut blocks = pin!(fetch_blocks_stream(
+                node_provider,
+                (from_block.as_u64() + 1)..=latest_block.as_u64(),
+                false,
+                BLOCK_FETCH_CONCURRENCY,
+                |_, _| {},
+            ));
+
+            while let Some(block) = blocks.next().await {
+                if let BlockKind::RawBlock(block) = block? {
+                    let hash = block.hash.unwrap_or_default();
+
+                    on_block(if resolve {
+                        new_block_summary(hash, &block)
+                    } else {
+                        NewBlock::Hash(hash)
+                    })?;
+                }
+            }
+
+            last_block = Some(latest_block);
+            caught_up_block = Some(latest_block);
+        }
+
+        while let Some(head) = new_heads.next().await {
+            pushed = true;
+
+            let Some(hash) = head.hash else {
+                continue;
+            };
+
+            // The heads pushed while catching up were already reported
+            if head.number.is_some() && head.number <= caught_up_block {
+                continue;
+            }
+
+            last_block = head.number.or(last_block);
+
+            // Pushed heads don't include the block transactions
+            on_block(if resolve {
+                resolve_new_block(node_provider, hash).await?
+            } else {
+                NewBlock::Hash(hash)
+            })?;
+        }
+
+        tokio::time::sleep(backoff.next_delay(pushed)).await;
+    }
+}
+
+const INITIAL_RESUBSCRIBE_BACKOFF: Duration = Duration::from_secs(1);
+
+const MAX_RESUBSCRIBE_BACKOFF: Duration = Duration::from_secs(30);
+
+/// Delay before subscribing again once the socket drops, doubled for each consecutive
+/// subscription that ended without pushing anything so that a node dropping it right away is not
+/// flooded with subscriptions.
+struct ResubscribeBackoff(Duration);
+
+impl Default for ResubscribeBackoff {
+    fn default() -> Self {
+        Self(INITIAL_RESUBSCRIBE_BACKOFF)
+    }
+}
+
+impl ResubscribeBackoff {
+    /// Returns the delay to wait before the next subscription, starting over if the ended one
+    /// `pushed` anything.
+    fn next_delay(&mut self, pushed: bool) -> Duration {
+        if pushed {
+            self.0 = INITIAL_RESUBSCRIBE_BACKOFF;
+        }
+
+        let delay = self.0;
+        self.0 = (self.0 * 2).min(MAX_RESUBSCRIBE_BACKOFF);
+
+        delay
+    }
+}
+
+// eth_newBlockFilter, eth_uninstallFilter
+/// Polls a new blocks filter every `interval` until it reports at least one block or `timeout`
+/// elapses, in which case no blocks are returned.
+pub async fn get_new_blocks(
+    node_provider: &NodeProvider,
+    interval: Duration,
+    timeout: Duration,
+    resolve: bool,
+) -> anyhow::Result<Vec<NewBlock>> {
+    let filter_id = install_filter(node_provider, FilterKind::NewBlocks).await?;
+
+    let res = tokio::time::timeout(timeout, async {
+        loop {
+            tokio::time::sleep(interval).await;
+
+            let blocks = get_new_block_changes(node_provider, filter_id, resolve).await?;
+            if !blocks.is_empty() {
+                return Ok(blocks);
+            }
+        }
+    })
+    .await
+    .unwrap_or(Ok(vec![]));
+
+    uninstall_filter_after(node_provider, filter_id, res).await
+}
+
+// eth_getFilterChanges, eth_getBlockByHash
+async fn get_new_block_changes(
+    node_provider: &NodeProvider,
+    filter_id: U256,
+    resolve: bool,
+) -> anyhow::Result<Vec<NewBlock>> {
+    let hashes: Vec<H256> = node_provider.get_filter_changes(filter_id).await?;
+
+    if !resolve {
+        return Ok(hashes.into_iter().map(NewBlock::Hash).collect());
+    }
+
+    let mut blocks = Vec::with_capacity(hashes.len());
+    for hash in hashes {
+        blocks.push(resolve_new_block(node_provider, hash).await?);
+    }
+
+    Ok(blocks)
+}
+
+// eth_getBlockByHash
+async fn resolve_new_block(node_provider: &NodeProvider, hash: H256) -> anyhow::Result<NewBlock> {
+    // The block may have already been reorged out of the chain
+    let block = get_block(node_provider, BlockId::Hash(hash), false, None)
+        .await?
+        .found();
+
+    Ok(match block {
+        Some(BlockKind::RawBlock(block)) => new_block_summary(hash, &block),
+        _ => NewBlock::Hash(hash),
+    })
+}
+
+fn new_block_summary(hash: H256, block: &Block<H256>) -> NewBlock {
+    NewBlock::Summary {
+        hash,
+        number: block.number,
+        timestamp: block.timestamp,
+        transaction_count: block.transactions.len(),
+    }
+}
+
+/// Installs the filter and polls its changes every `interval`, calling `on_change` with each of
+/// them until `shutdown` completes, then uninstalls it.
+async fn follow_filter_changes<T, F>(
+    node_provider: &NodeProvider,
+    kind: FilterKind<'_>,
+    interval: Duration,
+    shutdown: impl Future<Output = anyhow::Result<()>>,
+    get_changes: impl Fn(U256) -> F,
+    mut on_change: impl FnMut(T) -> anyhow::Result<()>,
+) -> anyhow::Result<()>
+where
+    F: Future<Output = anyhow::Result<Vec<T>>>,
+{
+    let filter_id = install_filter(node_provider, kind).await?;
+
+    let res = tokio::select! {
+        res = async {
+            loop {
+                tokio::time::sleep(interval).await;
+
+                for change in get_changes(filter_id).await? {
+                    on_change(change)?;
+                }
+            }
+        } => res,
+        res = shutdown => res,
+    };
+
+    uninstall_filter_after(node_provider, filter_id, res).await
+}
+
+/// Installs the filter, explaining when the node does not implement the filter kind instead of
+/// surfacing the raw json rpc error.
+async fn install_filter(
+    node_provider: &NodeProvider,
+    kind: FilterKind<'_>,
+) -> anyhow::Result<U256> {
+    let (filter_name, method) = match kind {
+        FilterKind::PendingTransactions => {
+            ("pending transaction", "eth_newPendingTransactionFilter")
+        }
+        FilterKind::NewBlocks => ("new block", "eth_newBlockFilter"),
+        FilterKind::Logs(_) => ("log", "eth_newFilter"),
+    };
+
+    node_provider.new_filter(kind).await.map_err(|err| {
+        let err = anyhow::Error::from(err);
+
+        if is_method_not_found(&err) {
+            anyhow::anyhow!("The node does not support {filter_name} filters ({method})")
+        } else {
+            err
+        }
+    })
+}
+
+/// Uninstalls the filter so that it isn't left on the node, then reports the outcome of the
+/// operation that used it.
+async fn uninstall_filter_after<T>(
+    node_provider: &NodeProvider,
+    filter_id: U256,
+    res: anyhow::Result<T>,
+) -> anyhow::Result<T> {
+    let uninstalled = node_provider.uninstall_filter(filter_id).await;
+    let res = res?;
+    uninstalled?;
+
+    Ok(res)
+}
+
+/// Logs already reported while following, identified by their block hash and log index.
+#[derive(Default)]
+struct SeenLogs(HashMap<(H256, U256), u64>);
+
+impl SeenLogs {
+    /// Records the log, telling if it was not seen before. Logs not yet included in a block are
+    /// never recorded.
+    fn insert(&mut self, log: &Log) -> bool {
+        let (Some(block_hash), Some(log_index), Some(block_number)) =
+            (log.block_hash, log.log_index, log.block_number)
+        else {
+            return true;
+        };
+
+        self.0
+            .insert((block_hash, log_index), block_number.as_u64())
+            .is_none()
+    }
+
+    /// Forgets the log, telling if it was seen before.
+    fn remove(&mut self, log: &Log) -> bool {
+        let (Some(block_hash), Some(log_index)) = (log.block_hash, log.log_index) else {
+            return false;
+        };
+
+        self.0.remove(&(block_hash, log_index)).is_some()
+    }
+
+    /// Forgets the logs of the blocks before the provided one, as they won't be queried again.
+    fn prune(&mut self, from_block: u64) {
+        self.0.retain(|_, block_number| *block_number >= from_block);
+    }
+}
+
+/// Log along with its parameters decoded with the provided ABI or topics database, if one of
+/// their events matches.
+#[derive(Debug, Serialize)]
+pub struct LogWithDecoded {
+    #[serde(flatten)]
+    pub log: Log,
+    pub decoded: Option<DecodedLog>,
+}
+
+#[derive(Debug, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct DecodedLog {
+    pub event_name: String,
+    #[serde(serialize_with = "serialize_params")]
+    pub params: Vec<(String, String)>,
+}
+
+/// Serializes the parameters as a map from their name to their value, keeping the event order.
+fn serialize_params<S: serde::Serializer>(
+    params: &[(String, String)],
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    serializer.collect_map(params.iter().map(|(name, value)| (name, value)))
+}
+
+/// Unnamed parameters are named after their position so that they can be told apart.
+pub(crate) fn param_name(name: &str, idx: usize) -> String {
+    match name {
+        "" => format!("arg{idx}"),
+        name => name.to_string(),
+    }
+}
+
+/// Loads an ABI from a json file holding either the ABI itself or a compiler artifact with an
+/// `abi` field, as produced by hardhat and foundry.
+pub fn load_abi(path: &Path) -> anyhow::Result<Abi> {
+    let content = std::fs::read_to_string(path)
+        .map_err(|err| anyhow::anyhow!("Failed to read the ABI file {}: {err}", path.display()))?;
+
+    let mut json: serde_json::Value = serde_json::from_str(&content)?;
+
+    let abi = match json.get_mut("abi") {
+        Some(abi) => serde_json::from_value(abi.take())?,
+        None => serde_json::from_value(json)?,
+    };
+
+    Ok(abi)
+}
+
+/// Event signature parsed from its human readable form, e.g.
+/// `Transfer(address indexed from, address to, uint256)`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct EventSignature {
+    pub name: String,
+    pub params: Vec<EventSignatureParam>,
+}
+
+impl From<&Event> for EventSignature {
+    fn from(event: &Event) -> Self {
+        Self {
+            name: event.name.clone(),
+            params: event
+                .inputs
+                .iter()
+                .map(|param| EventSignatureParam {
+                    name: param.name.clone(),
+                    kind: param.kind.clone(),
+                    indexed: param.indexed,
+                })
+                .collect(),
+        }
+    }
+}
+
+impl EventSignature {
+    /// Hash of the canonical signature, i.e. without parameter names and `indexed` markers.
+    pub fn topic0(&self) -> H256 {
+        let types: Vec<String> = self
+            .params
+            .iter()
+            .map(|param| param.kind.to_string())
+            .collect();
+
+        H256(keccak256(format!("{}({})", self.name, types.join(","))))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct EventSignatureParam {
+    pub name: String,
+    pub kind: ParamType,
+    pub indexed: bool,
+}
+
+/// Parses a human readable event signature. Parameters can be named and marked as `indexed`,
+/// and their types can be any solidity elementary type, array or tuple.
+pub fn parse_event_signature(signature: &str) -> anyhow::Result<EventSignature> {
+    let signature = signature.trim();
+    let signature = signature.strip_prefix("event ").unwrap_or(signature);
+
+    let (name, params) = signature
+        .strip_suffix(')')
+        .and_then(|signature| signature.split_once('('))
+        .ok_or(anyhow::anyhow!(
+            "Expected a signature like Name(type1,type2)"
+        ))?;
+
+    let name = name.trim();
+    if name.is_empty() || !name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') {
+        anyhow::bail!("Invalid event name {name:?}");
+    }
+
+    let params = split_top_level(params)?
+        .into_iter()
+        .map(parse_event_signature_param)
+        .collect::<anyhow::Result<_>>()?;
+
+    Ok(EventSignature {
+        name: name.to_string(),
+        params,
+    })
+}
+
+fn parse_event_signature_param(param: &str) -> anyhow::Result<EventSignatureParam> {
+    let (kind, rest) = split_param_type(param);
+    let mut rest = rest.split_whitespace();
+
+    let mut indexed = false;
+    let mut name = rest.next();
+    if name == Some("indexed") {
+        indexed = true;
+        name = rest.next();
+    }
+
+    if let Some(unexpected) = rest.next() {
+        anyhow::bail!("Unexpected {unexpected:?} in parameter {param:?}");
+    }
+
+    Ok(EventSignatureParam {
+        name: name.unwrap_or_default().to_string(),
+        kind: parse_param_type(kind)?,
+        indexed,
+    })
+}
+
+/// Parses a solidity type, e.g. `uint256`, `bytes32[2][]` or `(address,uint256)[]`.
+pub fn parse_param_type(kind: &str) -> anyhow::Result<ParamType> {
+    let kind = kind.trim();
+
+    if let Some(array) = kind.strip_suffix(']') {
+        let (inner, size) = array
+            .rsplit_once('[')
+            .ok_or(anyhow::anyhow!("Invalid array type {kind:?}"))?;
+        let inner = Box::new(parse_param_type(inner)?);
+
+        return match size {
+            "" => Ok(ParamType::Array(inner)),
+            size => size
+                .parse()
+                .map(|size| ParamType::FixedArray(inner, size))
+                .map_err(|_| anyhow::anyhow!("Invalid array size in {kind:?}")),
+        };
+    }
+
+    if let Some(components) = kind
+        .strip_prefix("tuple")
+        .unwrap_or(kind)
+        .strip_prefix('(')
+        .and_then(|components| components.strip_suffix(')'))
+    {
+        // Tuple components can be named like the event parameters
+        let components = split_top_level(components)?
+            .into_iter()
+            .map(|component| parse_param_type(split_param_type(component).0))
+            .collect::<anyhow::Result<_>>()?;
+
+        return Ok(ParamType::Tuple(components));
+    }
+
+    let sized = |prefix: &str, max: usize, step: usize| {
+        kind.strip_prefix(prefix)
+            .and_then(|size| size.parse::<usize>().ok())
+            .filter(|size| *size > 0 && *size <= max && size % step == 0)
+    };
+
+    match kind {
+        "address" => Ok(ParamType::Address),
+        "bool" => Ok(ParamType::Bool),
+        "string" => Ok(ParamType::String),
+        "bytes" => Ok(ParamType::Bytes),
+        "uint" => Ok(ParamType::Uint(256)),
+        "int" => Ok(ParamType::Int(256)),
+        _ => {
+            if let Some(size) = sized("bytes", 32, 1) {
+                Ok(ParamType::FixedBytes(size))
+            } else if let Some(size) = sized("uint", 256, 8) {
+                Ok(ParamType::Uint(size))
+            } else if let Some(size) = sized("int", 256, 8) {
+                Ok(ParamType::Int(size))
+            } else {
+                anyhow::bail!("Unsupported type {kind:?}")
+            }
+        }
+    }
+}
+
+/// Splits a parameter into its type and the rest, e.g. the `indexed` marker and the name. Tuple
+/// types can hold whitespace so the type ends at the first whitespace outside parentheses.
+fn split_param_type(param: &str) -> (&str, &str) {
+    let param = param.trim();
+    let mut depth = 0usize;
+
+    for (idx, c) in param.char_indices() {
+        match c {
+            '(' => depth += 1,
+            ')' => depth = depth.saturating_sub(1),
+            c if c.is_whitespace() && depth == 0 => return (&param[..idx], &param[idx..]),
+            _ => {}
+        }
+    }
+
+    (param, "")
+}
+
+/// Splits a comma separated list ignoring the commas nested in tuples.
+fn split_top_level(list: &str) -> anyhow::Result<Vec<&str>> {
+    if list.trim().is_empty() {
+        return Ok(vec![]);
+    }
+
+    let mut items = vec![];
+    let mut depth = 0usize;
+    let mut start = 0;
+
+    for (idx, c) in list.char_indices() {
+        match c {
+            '(' => depth += 1,
+            ')' => {
+                depth = depth
+                    .checked_sub(1)
+                    .ok_or(anyhow::anyhow!("Unbalanced parentheses in {list:?}"))?
+            }
+            ',' if depth == 0 => {
+                items.push(&list[start..idx]);
+                start = idx + 1;
+            }
+            _ => {}
+        }
+    }
+
+    if depth != 0 {
+        anyhow::bail!("Unbalanced parentheses in {list:?}");
+    }
+
+    items.push(&list[start..]);
+
+    Ok(items)
+}
+
+/// Event signatures keyed by their first topic, used to decode the logs of contracts whose ABI
+/// is unknown.
+#[derive(Debug, Default)]
+pub struct TopicsDb(HashMap<H256, EventSignature>);
+
+/// Loads a json file mapping 0x prefixed topic0 hashes to event signatures.
+pub fn load_topics_db(path: &Path) -> anyhow::Result<TopicsDb> {
+    let content = std::fs::read_to_string(path).map_err(|err| {
+        anyhow::anyhow!("Failed to read the topics file {}: {err}", path.display())
+    })?;
+
+    let entries: HashMap<String, String> = serde_json::from_str(&content)
+        .map_err(|err| anyhow::anyhow!("Invalid topics file {}: {err}", path.display()))?;
+
+    entries
+        .into_iter()
+        .map(|(topic, signature)| {
+            let topic0 = topic
+                .parse::<H256>()
+                .map_err(|err| anyhow::anyhow!("Invalid topic {topic}: {err}"))?;
+            let signature = parse_event_signature(&signature).map_err(|err| {
+                anyhow::anyhow!("Invalid event signature {signature} for topic {topic}: {err}")
+            })?;
+
+            Ok((topic0, signature))
+        })
+        .collect::<anyhow::Result<_>>()
+        .map(TopicsDb)
+}
+
+/// Sources the logs are decoded with: the events of an ABI and, for the logs that none of them
+/// matches, the signatures of a topics database and of the filtered event.
+#[derive(Debug, Default)]
+pub struct LogDecoder {
+    abi: Option<Abi>,
+    topics_db: Option<TopicsDb>,
+}
+
+impl LogDecoder {
+    /// Loads the provided ABI and topics database, adding the filtered event signature to the
+    /// latter. Returns None if none of them is provided.
+    pub fn load(
+        abi: Option<&Path>,
+        topics_db: Option<&Path>,
+        event: Option<EventSignature>,
+    ) -> anyhow::Result<Option<Self>> {
+        if abi.is_none() && topics_db.is_none() && event.is_none() {
+            return Ok(None);
+        }
+
+        let mut topics_db = topics_db.map(load_topics_db).transpose()?;
+        if let Some(event) = event {
+            topics_db
+                .get_or_insert_with(TopicsDb::default)
+                .0
+                .insert(event.topic0(), event);
+        }
+
+        Ok(Some(Self {
+            abi: abi.map(load_abi).transpose()?,
+            topics_db,
+        }))
+    }
+
+    /// Signature of the ABI event with the provided first topic, which unlike a signature without
+    /// `indexed` markers tells which parameters are indexed.
+    pub fn abi_event(&self, topic0: H256) -> Option<EventSignature> {
+        self.abi
+            .as_ref()?
+            .events()
+            .find(|event| !event.anonymous && event.signature() == topic0)
+            .map(EventSignature::from)
+    }
+}
+
+impl From<Abi> for LogDecoder {
+    fn from(abi: Abi) -> Self {
+        Self {
+            abi: Some(abi),
+            topics_db: None,
+        }
+    }
+}
+
+impl From<TopicsDb> for LogDecoder {
+    fn from(topics_db: TopicsDb) -> Self {
+        Self {
+            abi: None,
+            topics_db: Some(topics_db),
+        }
+    }
+}
+
+pub fn decode_logs(decoder: &LogDecoder, logs: Vec<Log>) -> Vec<LogWithDecoded> {
+    logs.into_iter()
+        .map(|log| decode_log(decoder, log))
+        .collect()
+}
+
+/// Decodes the log with the ABI event whose signature matches its first topic, falling back to
+/// the topics database. Logs that don't match any event are returned without decoded parameters.
+pub fn decode_log(decoder: &LogDecoder, log: Log) -> LogWithDecoded {
+    let decoded = log.topics.first().and_then(|topic0| {
+        decoder
+            .abi
+            .as_ref()
+            .and_then(|abi| decode_log_with_abi(abi, *topic0, &log))
+            .or_else(|| {
+                decoder
+                    .topics_db
+                    .as_ref()
+                    .and_then(|topics_db| topics_db.0.get(topic0))
+                    .and_then(|signature| decode_log_with_signature(signature, &log))
+            })
+    });
+
+    LogWithDecoded { log, decoded }
+}
+
+fn decode_log_with_abi(abi: &Abi, topic0: H256, log: &Log) -> Option<DecodedLog> {
+    abi.events()
+        .filter(|event| !event.anonymous && event.signature() == topic0)
+        .find_map(|event| {
+            let parsed = event
+                .parse_log(RawLog {
+                    topics: log.topics.clone(),
+                    data: log.data.to_vec(),
+                })
+                .ok()?;
+
+            Some(DecodedLog {
+                event_name: event.name.clone(),
+                params: parsed
+                    .params
+                    .into_iter()
+                    .enumerate()
+                    .map(|(idx, param)| (param_name(&param.name, idx), format_token(&param.value)))
+                    .collect(),
+            })
+        })
+}
+
+/// Decodes the log with a signature of the topics database. Signatures without `indexed` markers
+/// are assumed to index their leading parameters, one per topic after the first. Indexed dynamic
+/// values are only known by their hash, which is returned as is.
+fn decode_log_with_signature(signature: &EventSignature, log: &Log) -> Option<DecodedLog> {
+    let topics = log.topics.get(1..)?;
+
+    let indexed: Vec<bool> = if signature.params.iter().any(|param| param.indexed) {
+        signature.params.iter().map(|param| param.indexed).collect()
+    } else {
+        (0..signature.params.len())
+            .map(|idx| idx < topics.len())
+            .collect()
+    };
+
+    if indexed.iter().filter(|indexed| **indexed).count() != topics.len() {
+        return None;
+    }
+
+    let data_types: Vec<ParamType> = signature
+        .params
+        .iter()
+        .zip(&indexed)
+        .filter(|(_, indexed)| !**indexed)
+        .map(|(param, _)| param.kind.clone())
+        .collect();
+
+    let mut data_tokens = abi::decode(&data_types, &log.data).ok()?.into_iter();
+    let mut topics = topics.iter();
+
+    let params = signature
+        .params
+        .iter()
+        .zip(indexed)
+        .enumerate()
+        .map(|(idx, (param, indexed))| {
+            let value = if indexed {
+                format_indexed_value(&param.kind, topics.next()?)
+            } else {
+                format_token(&data_tokens.next()?)
+            };
+
+            Some((param_name(&param.name, idx), value))
+        })
+        .collect::<Option<_>>()?;
+
+    Some(DecodedLog {
+        event_name: signature.name.clone(),
+        params,
+    })
+}
+
+fn format_indexed_value(kind: &ParamType, topic: &H256) -> String {
+    if kind.is_dynamic() || matches!(kind, ParamType::FixedArray(..) | ParamType::Tuple(_)) {
+        return format!("{topic:?}");
+    }
+
+    abi::decode(std::slice::from_ref(kind), topic.as_bytes())
+        .ok()
+        .and_then(|tokens| tokens.first().map(format_token))
+        .unwrap_or_else(|| format!("{topic:?}"))
+}
+
+pub(crate) fn format_token(token: &Token) -> String {
+    match token {
+        Token::Address(address) => to_checksum(address, None),
+        Token::Bytes(bytes) | Token::FixedBytes(bytes) => format!("0x{}", hex::encode(bytes)),
+        Token::Int(value) => I256::from_raw(*value).to_string(),
+        Token::Uint(value) => value.to_string(),
+        Token::Bool(value) => value.to_string(),
+        Token::String(value) => value.clone(),
+        Token::Array(tokens) | Token::FixedArray(tokens) => {
+            format!(
+                "[{}]",
+                tokens
+                    .iter()
+                    .map(format_token)
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            )
+        }
+        Token::Tuple(tokens) => {
+            format!(
+                "({})",
+                tokens
+                    .iter()
+                    .map(format_token)
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            )
+        }
+    }
+}
+
+// Transfer(address,address,uint256)
+const TRANSFER_EVENT_TOPIC: H256 = H256([
+    0xdd, 0xf2, 0x52, 0xad, 0x1b, 0xe2, 0xc8, 0x9b, 0x69, 0xc2, 0xb0, 0x68, 0xfc, 0x37, 0x8d, 0xaa,
+    0x95, 0x2b, 0xa7, 0xf1, 0x63, 0xc4, 0xa1, 0x16, 0x28, 0xf5, 0x5a, 0x4d, 0xf5, 0x23, 0xb3, 0xef,
+]);
+// decimals()
+const DECIMALS_SELECTOR: [u8; 4] = [0x31, 0x3c, 0xe5, 0x67];
+
+/// Transfers of `token` in the block range, optionally restricted to a sender and/or a recipient.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Erc20TransfersFilter {
+    pub token: Address,
+    pub from: Option<Address>,
+    pub to: Option<Address>,
+    pub from_block: BlockNumber,
+    pub to_block: BlockNumber,
+}
+
+impl From<&Erc20TransfersFilter> for Filter {
+    fn from(value: &Erc20TransfersFilter) -> Self {
+        let mut filter = Filter::new()
+            .address(value.token)
+            .topic0(TRANSFER_EVENT_TOPIC)
+            .from_block(value.from_block)
+            .to_block(value.to_block);
+
+        if let Some(from) = value.from {
+            filter = filter.topic1(H256::from(from));
+        }
+
+        if let Some(to) = value.to {
+            filter = filter.topic2(H256::from(to));
+        }
+
+        filter
+    }
+}
+
+#[derive(Debug, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct Erc20Transfer {
+    pub block_number: Option<U64>,
+    pub transaction_hash: Option<H256>,
+    pub log_index: Option<U256>,
+    pub from: Address,
+    pub to: Address,
+    pub value: U256,
+    /// Value scaled by the token decimals, omitted if the token doesn't implement `decimals()`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub formatted_value: Option<String>,
+}
+
+// eth_getLogs + eth_call
+/// Gets the transfers of an ERC20 token. Transfer logs that don't follow the ERC20 layout, like
+/// the ERC721 ones sharing the same signature, are skipped.
+pub async fn get_erc20_transfers(
+    node_provider: &NodeProvider,
+    filter: Erc20TransfersFilter,
+    max_block_range: u64,
+) -> anyhow::Result<Vec<Erc20Transfer>> {
+    let (logs, decimals) = futures::try_join!(
+        get_logs(node_provider, (&filter).into(), max_block_range, None),
+        get_token_decimals(node_provider, filter.token)
+    )?;
+
+    Ok(logs
+        .iter()
+        .filter_map(|log| decode_erc20_transfer(log, decimals))
+        .collect())
+}
+
+async fn get_token_decimals(
+    node_provider: &NodeProvider,
+    token: Address,
+) -> anyhow::Result<Option<u32>> {
+    let Some(res) = call_token(node_provider, token, &DECIMALS_SELECTOR, &[]).await? else {
+        return Ok(None);
+    };
+
+    match decode_single(ParamType::Uint(8), &res)? {
+        Token::Uint(decimals) => Ok(decimals.try_into().ok()),
+        _ => Ok(None),
+    }
+}
+
+pub fn decode_erc20_transfer(log: &Log, decimals: Option<u32>) -> Option<Erc20Transfer> {
+    let [topic0, from, to] = log.topics.as_slice() else {
+        return None;
+    };
+
+    if *topic0 != TRANSFER_EVENT_TOPIC || log.data.len() != 32 {
+        return None;
+    }
+
+    let value = U256::from_big_endian(&log.data);
+
+    Some(Erc20Transfer {
+        block_number: log.block_number,
+        transaction_hash: log.transaction_hash,
+        log_index: log.log_index,
+        from: Address::from(*from),
+        to: Address::from(*to),
+        value,
+        formatted_value: decimals.and_then(|decimals| format_units(value, decimals).ok()),
+    })
+}
+
+/// Resolves a bound of the block range to its block number. A missing bound defaults to the
+/// latest block like the node does.
+async fn get_range_bound(
+    node_provider: &NodeProvider,
+    block_number: Option<BlockNumber>,
+) -> anyhow::Result<u64> {
+    let block_number = match block_number.unwrap_or(BlockNumber::Latest) {
+        BlockNumber::Number(block_number) => block_number,
+        tag => get_block_number_value(node_provider, BlockId::Number(tag))
+            .await?
+            .ok_or(anyhow::anyhow!("Block not found for the block tag {tag}"))?,
+    };
+
+    Ok(block_number.as_u64())
+}
+
+/// Splits the inclusive `[from_block, to_block]` range in consecutive ranges of at most
+/// `chunk_size` blocks.
+fn split_block_range(from_block: u64, to_block: u64, chunk_size: u64) -> Vec<(u64, u64)> {
+    let chunk_size = chunk_size.max(1);
+
+    (from_block..=to_block)
+        .step_by(chunk_size as usize)
+        .map(|chunk_start| {
+            (
+                chunk_start,
+                chunk_start.saturating_add(chunk_size - 1).min(to_block),
+            )
+        })
+        .collect()
+}
+
+/// Fetches the logs of the range, splitting it in halves whenever the node refuses to return
+/// that many logs at once. The logs are returned in block order.
+async fn get_logs_bisecting<F, Fut>(
+    from_block: u64,
+    to_block: u64,
+    fetch: F,
+) -> anyhow::Result<Vec<Log>>
+where
+    F: Fn(U64, U64) -> Fut,
+    Fut: Future<Output = anyhow::Result<Vec<Log>>>,
+{
+    let mut logs = Vec::new();
+    let mut pending = vec![(from_block, to_block)];
+
+    while let Some((from_block, to_block)) = pending.pop() {
+        match fetch(from_block.into(), to_block.into()).await {
+            Ok(range_logs) => logs.extend(range_logs),
+            Err(err) if from_block < to_block && is_too_many_results(&err) => {
+                let middle = from_block + (to_block - from_block) / 2;
+
+                pending.push((middle + 1, to_block));
+                pending.push((from_block, middle));
+            }
+            Err(err) => return Err(err),
+        }
+    }
+
+    Ok(logs)
+}
+
+fn is_too_many_results(err: &anyhow::Error) -> bool {
+    as_json_rpc_error(err).is_some_and(|err| {
+        let message = err.message.to_lowercase();
+
+        TOO_MANY_RESULTS_MESSAGES
+            .iter()
+            .any(|pattern| message.contains(pattern))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+
+    mod split_block_range {
+        use crate::cmd::event::split_block_range;
+
+        #[test]
+        fn should_split_the_range_in_chunks() {
+            // Setup
+            let test_cases = [
+                ((0, 9), 5, vec![(0, 4), (5, 9)]),
+                ((10, 20), 4, vec![(10, 13), (14, 17), (18, 20)]),
+                ((7, 7), 2_000, vec![(7, 7)]),
+                ((0, 2), 1, vec![(0, 0), (1, 1), (2, 2)]),
+            ];
+
+            for ((from_block, to_block), chunk_size, expected_res) in test_cases {
+                // Act
+                let res = split_block_range(from_block, to_block, chunk_size);
+
+                // Assert
+                assert_eq!(res, expected_res);
+            }
+        }
+    }
+
+    mod resubscribe_backoff {
+        use std::time::Duration;
+
+        use crate::cmd::event::{
+            ResubscribeBackoff, INITIAL_RESUBSCRIBE_BACKOFF, MAX_RESUBSCRIBE_BACKOFF,
+        };
+
+        #[test]
+        fn should_double_the_delay_up_to_the_max_while_nothing_is_pushed() {
+            // Arrange
+            let mut backoff = ResubscribeBackoff::default();
+
+            // Act
+            let res: Vec<Duration> = (0..7).map(|_| backoff.next_delay(false)).collect();
+
+            // Assert
+            assert_eq!(res[0], INITIAL_RESUBSCRIBE_BACKOFF);
+            assert_eq!(res[1], INITIAL_RESUBSCRIBE_BACKOFF * 2);
+            assert_eq!(res[4], INITIAL_RESUBSCRIBE_BACKOFF * 16);
+            assert_eq!(res[5], MAX_RESUBSCRIBE_BACKOFF);
+            assert_eq!(res[6], MAX_RESUBSCRIBE_BACKOFF);
+        }
+
+        #[test]
+        fn should_start_over_once_a_subscription_pushed_something() {
+            // Arrange
+            let mut backoff = ResubscribeBackoff::default();
+            backoff.next_delay(false);
+            backoff.next_delay(false);
+
+            // Act
+            let res = backoff.next_delay(true);
+
+            // Assert
+            assert_eq!(res, INITIAL_RESUBSCRIBE_BACKOFF);
+        }
+    }
+
+    mod seen_logs {
+        use ethers::types::{Log, H256};
+
+        use crate::cmd::event::SeenLogs;
+
+        fn log(block_number: u64, block_hash: H256, log_index: u64) -> Log {
+            Log {
+                block_number: Some(block_number.into()),
+                block_hash: Some(block_hash),
+                log_index: Some(log_index.into()),
+                ..Default::default()
+            }
+        }
+
+        #[test]
+        fn should_skip_the_logs_already_seen() {
+            // Arrange
+            let mut seen_logs = SeenLogs::default();
+            let block_hash = H256::repeat_byte(0x01);
+            let reorged_block_hash = H256::repeat_byte(0x02);
+
+            // Act
+            let first = seen_logs.insert(&log(10, block_hash, 0));
+            let duplicate = seen_logs.insert(&log(10, block_hash, 0));
+            let next_log = seen_logs.insert(&log(10, block_hash, 1));
+            let reorged = seen_logs.insert(&log(10, reorged_block_hash, 0));
+
+            // Assert
+            assert!(first);
+            assert!(!duplicate);
+            assert!(next_log);
+            assert!(reorged);
+        }
+
+        #[test]
+        fn should_forget_the_logs_before_the_provided_block() {
+            // Arrange
+            let mut seen_logs = SeenLogs::default();
+            let block_hash = H256::repeat_byte(0x01);
+            let other_block_hash = H256::repeat_byte(0x02);
+
+            seen_logs.insert(&log(10, block_hash, 0));
+            seen_logs.insert(&log(11, other_block_hash, 0));
+
+            // Act
+            seen_logs.prune(11);
+
+            // Assert
+            assert!(seen_logs.insert(&log(10, block_hash, 0)));
+            assert!(!seen_logs.insert(&log(11, other_block_hash, 0)));
+        }
+
+        #[test]
+        fn should_forget_the_removed_logs() {
+            // Arrange
+            let mut seen_logs = SeenLogs::default();
+            let block_hash = H256::repeat_byte(0x01);
+
+            seen_logs.insert(&log(10, block_hash, 0));
+
+            // Act
+            let removed = seen_logs.remove(&log(10, block_hash, 0));
+            let unseen = seen_logs.remove(&log(10, block_hash, 1));
+
+            // Assert
+            assert!(removed);
+            assert!(!unseen);
+            assert!(seen_logs.insert(&log(10, block_hash, 0)));
+        }
+
+        #[test]
+        fn should_always_report_pending_logs() {
+            // Arrange
+            let mut seen_logs = SeenLogs::default();
+            let pending_log = Log::default();
+
+            // Act
+            let first = seen_logs.insert(&pending_log);
+            let second = seen_logs.insert(&pending_log);
+
+            // Assert
+            assert!(first);
+            assert!(second);
+        }
+    }
+
+    mod logs_summary {
+        use ethers::types::{Log, H160, H256};
+
+        use crate::cmd::event::LogsSummary;
+
+        fn log(address: H160, block_number: u64, topic0: H256) -> Log {
+            Log {
+                address,
+                block_number: Some(block_number.into()),
+                topics: vec![topic0],
+                ..Default::default()
+            }
+        }
+
+        #[test]
+        fn should_summarize_the_logs_of_each_contract_across_chunks() {
+            // Arrange
+            let mut summary = LogsSummary::default();
+            let contract = H160::repeat_byte(0x01);
+            let other_contract = H160::repeat_byte(0x02);
+            let topic = H256::repeat_byte(0xaa);
+            let other_topic = H256::repeat_byte(0xbb);
+
+            // Act
+            summary.extend(&[log(contract, 12, topic), log(other_contract, 13, topic)]);
+            summary.extend(&[log(contract, 10, other_topic), log(contract, 20, topic)]);
+
+            // Assert
+            assert_eq!(summary.0.len(), 2);
+
+            let contract_summary = &summary.0[&contract];
+            assert_eq!(contract_summary.log_count, 3);
+            assert_eq!(contract_summary.first_block, 10);
+            assert_eq!(contract_summary.last_block, 20);
+            assert_eq!(contract_summary.distinct_topic0_count, 2);
+
+            let other_contract_summary = &summary.0[&other_contract];
+            assert_eq!(other_contract_summary.log_count, 1);
+            assert_eq!(other_contract_summary.first_block, 13);
+            assert_eq!(other_contract_summary.last_block, 13);
+            assert_eq!(other_contract_summary.distinct_topic0_count, 1);
+        }
+    }
+
+    mod get_logs_bisecting {
+        use std::sync::Mutex;
+
+        use ethers::{
+            providers::{HttpClientError, JsonRpcError, ProviderError},
+            types::{Log, U64},
+        };
+        use serde_json::json;
+
+        use crate::cmd::event::get_logs_bisecting;
+
+        const MAX_RESULTS: u64 = 2;
+
+        fn too_many_results_error() -> anyhow::Error {
+            let err: JsonRpcError = serde_json::from_value(json!({
+                "code": -32005,
+                "message": "query returned more than 10000 results"
+            }))
+            .unwrap();
+
+            ProviderError::JsonRpcClientError(Box::new(HttpClientError::JsonRpcError(err))).into()
+        }
+
+        // Emits one log per block and refuses to return more than MAX_RESULTS logs at once
+        async fn fetch_one_log_per_block(
+            from_block: U64,
+            to_block: U64,
+        ) -> anyhow::Result<Vec<Log>> {
+            if (to_block - from_block).as_u64() + 1 > MAX_RESULTS {
+                return Err(too_many_results_error());
+            }
+
+            Ok((from_block.as_u64()..=to_block.as_u64())
+                .map(|block_number| Log {
+                    block_number: Some(block_number.into()),
+                    ..Default::default()
+                })
+                .collect())
+        }
+
+        #[tokio::test]
+        async fn should_bisect_the_ranges_with_too_many_results() -> anyhow::Result<()> {
+            // Arrange
+            let queried_ranges = Mutex::new(Vec::new());
+
+            // Act
+            let res = get_logs_bisecting(10, 16, |from_block, to_block| {
+                queried_ranges
+                    .lock()
+                    .unwrap()
+                    .push((from_block.as_u64(), to_block.as_u64()));
+
+                fetch_one_log_per_block(from_block, to_block)
+            })
+            .await;
+
+            // Assert
+            assert!(res.is_ok());
+
+            let block_numbers: Vec<u64> = res
+                .unwrap()
+                .iter()
+                .map(|log| log.block_number.unwrap().as_u64())
+                .collect();
+            assert_eq!(block_numbers, (10..=16).collect::<Vec<u64>>());
+
+            assert_eq!(
+                queried_ranges.into_inner().unwrap(),
+                vec![
+                    (10, 16),
+                    (10, 13),
+                    (10, 11),
+                    (12, 13),
+                    (14, 16),
+                    (14, 15),
+                    (16, 16)
+                ]
+            );
+
+            Ok(())
+        }
+
+        #[tokio::test]
+        async fn should_not_bisect_a_single_block() {
+            // Act
+            let res =
+                get_logs_bisecting(10, 10, |_, _| async { Err(too_many_results_error()) }).await;
+
+            // Assert
+            assert!(res.is_err());
+        }
+
+        #[tokio::test]
+        async fn should_not_bisect_on_other_errors() {
+            // Arrange
+            let queries = Mutex::new(0);
+
+            // Act
+            let res = get_logs_bisecting(10, 20, |_, _| {
+                *queries.lock().unwrap() += 1;
+
+                async { Err(anyhow::anyhow!("query returned more than 10000 results")) }
+            })
+            .await;
+
+            // Assert
+            assert!(res.is_err());
+            assert_eq!(queries.into_inner().unwrap(), 1);
+        }
+    }
+
+    mod get_logs {
+        use std::time::Duration;
+
+        use ethers::{
+            providers::{FilterKind, Middleware},
+            types::{Block, BlockNumber, Bytes, Filter, TransactionRequest, H160, H256},
+            utils::Anvil,
+        };
+
+        use crate::{
+            cmd::{
+                checkpoint::CheckpointStore,
+                event::{
+                    follow_logs, get_filter_logs, get_logs, get_logs_at_block_hash,
+                    get_logs_since_checkpoint, get_logs_summary,
+                },
+                helpers::test::{
+                    deploy_contract_helper, mock_node_provider, setup_test,
+                    spawn_mock_rpc_error_server, spawn_mock_rpc_server,
+                    spawn_mock_rpc_server_with_response,
+                },
+            },
+            config::{get_config, ConfigOverrides},
+            context::NodeProvider,
+        };
+        use serde_json::json;
+
+        const MAX_BLOCK_RANGE: u64 = 10_000;
+
+        #[tokio::test]
+        async fn should_not_get_the_logs_of_a_tag_bounded_range_above_the_limit(
+        ) -> anyhow::Result<()> {
+            // Arrange
+            let (url, _, _) = spawn_mock_rpc_server_with_response(
+                |request| {
+                    let result = match request["method"].as_str() {
+                        Some("eth_getBlockByNumber") => {
+                            let number = match request["params"][0].as_str() {
+                                Some("earliest") => 0,
+                                _ => MAX_BLOCK_RANGE * 2,
+                            };
+
+                            json!(Block::<H256> {
+                                number: Some(number.into()),
+                                ..Default::default()
+                            })
+                        }
+                        _ => json!([]),
+                    };
+
+                    json!({ "jsonrpc": "2.0", "id": request["id"], "result": result })
+                },
+                Duration::ZERO,
+            );
+            let node_provider = mock_node_provider(url).await?;
+
+            let test_cases = [
+                Filter::new().from_block(0),
+                Filter::new()
+                    .from_block(BlockNumber::Earliest)
+                    .to_block(BlockNumber::Latest),
+            ];
+
+            for filter in test_cases {
+                // Act
+                let res = get_logs(&node_provider, filter, MAX_BLOCK_RANGE, None).await;
+
+                // Assert
+                assert!(res
+                    .unwrap_err()
+                    .to_string()
+                    .contains("exceeds the maximum of 10000 blocks"));
+            }
+
+            Ok(())
+        }
+
+        #[tokio::test]
+        async fn should_not_find_the_logs_of_an_unknown_block_hash() -> anyhow::Result<()> {
+            // Arrange
+            let url = spawn_mock_rpc_error_server(
+                serde_json::json!({ "code": -32000, "message": "unknown block" }),
+            );
+            let node_provider = mock_node_provider(url).await?;
+
+            let filter = Filter::new().at_block_hash(H256::repeat_byte(0xaa));
+
+            // Act
+            let res = get_logs_at_block_hash(&node_provider, filter).await;
+
+            // Assert
+            assert!(res?.is_not_found());
+
+            Ok(())
+        }
+
+        #[tokio::test]
+        async fn should_find_an_empty_log_set_at_a_known_block_hash() -> anyhow::Result<()> {
+            // Arrange
+            let (url, _) = spawn_mock_rpc_server(serde_json::json!([]), Duration::ZERO);
+            let node_provider = mock_node_provider(url).await?;
+
+            let filter = Filter::new().at_block_hash(H256::repeat_byte(0xaa));
+
+            // Act
+            let res = get_logs_at_block_hash(&node_provider, filter).await;
+
+            // Assert
+            assert_eq!(res?.found(), Some(vec![]));
+
+            Ok(())
+        }
+
+        fn event_topic() -> H256 {
+            H256::repeat_byte(0xee)
+        }
+
+        // Fixture contract that emits an anonymous event with a single topic on every call:
+        // constructor: PUSH1 0x27, DUP1, PUSH1 0x0b, PUSH1 0, CODECOPY, PUSH1 0, RETURN
+        // runtime: PUSH32 topic, PUSH1 0, PUSH1 0, LOG1, STOP
+        fn emitter_init_code() -> Bytes {
+            let mut init_code = vec![
+                0x60, 0x27, 0x80, 0x60, 0x0b, 0x60, 0x00, 0x39, 0x60, 0x00, 0xf3,
+            ];
+            init_code.push(0x7f);
+            init_code.extend_from_slice(event_topic().as_bytes());
+            init_code.extend_from_slice(&[0x60, 0x00, 0x60, 0x00, 0xa1, 0x00]);
+
+            init_code.into()
+        }
+
+        async fn emit_event_helper(
+            node_provider: &NodeProvider,
+            sender: H160,
+        ) -> anyhow::Result<H160> {
+            let emitter =
+                deploy_contract_helper(node_provider, sender, emitter_init_code()).await?;
+
+            let tx = TransactionRequest::new().from(sender).to(emitter);
+            node_provider.send_transaction(tx, None).await?.await?;
+
+            Ok(emitter)
+        }
+
+        #[tokio::test]
+        async fn should_get_the_logs_emitted_by_a_contract() -> anyhow::Result<()> {
+            // Arrange
+            let (node_provider, anvil) = setup_test().await?;
+
+            let sender = *anvil.addresses().first().unwrap();
+            let emitter = emit_event_helper(&node_provider, sender).await?;
+
+            let filter = Filter::new()
+                .from_block(BlockNumber::Earliest)
+                .to_block(BlockNumber::Latest)
+                .address(emitter)
+                .topic0(event_topic());
+
+            // Act
+            let res = get_logs(&node_provider, filter, MAX_BLOCK_RANGE, None).await;
+
+            // Assert
+            assert!(res.is_ok());
+
+            let logs = res.unwrap();
+            assert_eq!(logs.len(), 1);
+            assert_eq!(logs[0].address, emitter);
+            assert_eq!(logs[0].topics, vec![event_topic()]);
+
+            Ok(())
+        }
+
+        #[tokio::test]
+        async fn should_resume_the_scan_after_the_checkpointed_block() -> anyhow::Result<()> {
+            // Arrange
+            let (node_provider, anvil) = setup_test().await?;
+
+            let sender = *anvil.addresses().first().unwrap();
+            let emitter = emit_event_helper(&node_provider, sender).await?;
+
+            let path =
+                std::env::temp_dir().join(format!("yaeth-checkpoint-scan-{}", std::process::id()));
+            let checkpoint_store = CheckpointStore::new(&path);
+            let filter = Filter::new().address(emitter);
+
+            let first_run = get_logs_since_checkpoint(
+                &node_provider,
+                filter.clone(),
+                &checkpoint_store,
+                MAX_BLOCK_RANGE,
+                None,
+            )
+            .await?;
+            let recorded_block = checkpoint_store.load()?.unwrap().block_number;
+
+            let tx = TransactionRequest::new().from(sender).to(emitter);
+            node_provider.send_transaction(tx, None).await?.await?;
+
+            // Act
+            let res = get_logs_since_checkpoint(
+                &node_provider,
+                filter,
+                &checkpoint_store,
+                MAX_BLOCK_RANGE,
+                None,
+            )
+            .await;
+            let checkpoint = checkpoint_store.load();
+            std::fs::remove_file(path)?;
+
+            // Assert
+            assert_eq!(first_run.len(), 1);
+
+            let logs = res?;
+            assert_eq!(logs.len(), 1);
+            assert!(logs[0].block_number.unwrap().as_u64() > recorded_block);
+            assert_eq!(
+                checkpoint?.unwrap().block_number,
+                logs[0].block_number.unwrap().as_u64()
+            );
+
+            Ok(())
+        }
+
+        #[tokio::test]
+        async fn should_get_the_logs_emitted_by_any_of_the_contracts() -> anyhow::Result<()> {
+            // Arrange
+            let (node_provider, anvil) = setup_test().await?;
+
+            let sender = *anvil.addresses().first().unwrap();
+            let emitter = emit_event_helper(&node_provider, sender).await?;
+            let other_emitter = emit_event_helper(&node_provider, sender).await?;
+
+            let filter = Filter::new()
+                .from_block(BlockNumber::Earliest)
+                .to_block(BlockNumber::Latest)
+                .address(vec![emitter, other_emitter]);
+
+            // Act
+            let res = get_logs(&node_provider, filter, MAX_BLOCK_RANGE, None).await;
+
+            // Assert
+            assert!(res.is_ok());
+
+            let emitters: Vec<H160> = res.unwrap().iter().map(|log| log.address).collect();
+            assert_eq!(emitters, vec![emitter, other_emitter]);
+
+            Ok(())
+        }
+
+        #[tokio::test]
+        async fn should_get_the_logs_in_chunks() -> anyhow::Result<()> {
+            // Arrange
+            let (node_provider, anvil) = setup_test().await?;
+
+            let sender = *anvil.addresses().first().unwrap();
+            let emitter = emit_event_helper(&node_provider, sender).await?;
+            let other_emitter = emit_event_helper(&node_provider, sender).await?;
+
+            let filter = Filter::new()
+                .from_block(BlockNumber::Earliest)
+                .to_block(BlockNumber::Latest)
+                .topic0(event_topic());
+
+            // Act
+            let res = get_logs(&node_provider, filter, MAX_BLOCK_RANGE, Some(1)).await;
+
+            // Assert
+            assert!(res.is_ok());
+
+            let emitters: Vec<H160> = res.unwrap().iter().map(|log| log.address).collect();
+            assert_eq!(emitters, vec![emitter, other_emitter]);
+
+            Ok(())
+        }
+
+        #[tokio::test]
+        async fn should_summarize_the_logs_in_chunks() -> anyhow::Result<()> {
+            // Arrange
+            let (node_provider, anvil) = setup_test().await?;
+
+            let sender = *anvil.addresses().first().unwrap();
+            let emitter = emit_event_helper(&node_provider, sender).await?;
+            let other_emitter = emit_event_helper(&node_provider, sender).await?;
+
+            let tx = TransactionRequest::new().from(sender).to(emitter);
+            node_provider.send_transaction(tx, None).await?.await?;
+
+            let filter = Filter::new()
+                .from_block(BlockNumber::Earliest)
+                .to_block(BlockNumber::Latest)
+                .topic0(event_topic());
+
+            // Act
+            let res = get_logs_summary(&node_provider, filter, MAX_BLOCK_RANGE, Some(1)).await;
+
+            // Assert
+            assert!(res.is_ok());
+
+            let summary = res.unwrap();
+            assert_eq!(summary.0.len(), 2);
+            assert_eq!(summary.0[&emitter].log_count, 2);
+            assert_eq!(summary.0[&emitter].distinct_topic0_count, 1);
+            assert!(summary.0[&emitter].first_block < summary.0[&emitter].last_block);
+            assert_eq!(summary.0[&other_emitter].log_count, 1);
+
+            Ok(())
+        }
+
+        #[tokio::test]
+        async fn should_follow_the_new_logs_once() -> anyhow::Result<()> {
+            // Arrange
+            let (node_provider, anvil) = setup_test().await?;
+
+            let sender = *anvil.addresses().first().unwrap();
+            let emitter = emit_event_helper(&node_provider, sender).await?;
+
+            let filter = Filter::new()
+                .from_block(BlockNumber::Earliest)
+                .topic0(event_topic());
+
+            let mut logs = Vec::new();
+
+            // Act
+            let (res, other_emitter) = tokio::join!(
+                tokio::time::timeout(
+                    Duration::from_secs(2),
+                    follow_logs(&node_provider, filter, Duration::from_millis(100), |log| {
+                        logs.push(log);
+
+                        Ok(())
+                    }),
+                ),
+                async {
+                    tokio::time::sleep(Duration::from_millis(500)).await;
+
+                    emit_event_helper(&node_provider, sender).await
+                }
+            );
+
+            // Assert
+            assert!(res.is_err(), "Should keep following until interrupted");
+
+            let emitters: Vec<H160> = logs.iter().map(|log| log.address).collect();
+            assert_eq!(emitters, vec![emitter, other_emitter?]);
+
+            Ok(())
+        }
+
+        #[tokio::test]
+        async fn should_follow_the_new_logs_through_a_subscription() -> anyhow::Result<()> {
+            // Arrange
+            let anvil = Anvil::new().spawn();
+            let config = get_config(ConfigOverrides::new(None, Some(anvil.ws_endpoint()), None))?;
+            let node_provider = NodeProvider::new(&config).await?;
+
+            let sender = *anvil.addresses().first().unwrap();
+            let emitter = emit_event_helper(&node_provider, sender).await?;
+
+            let filter = Filter::new()
+                .from_block(BlockNumber::Earliest)
+                .topic0(event_topic());
+
+            let mut logs = Vec::new();
+
+            // Act
+            let (res, other_emitter) = tokio::join!(
+                tokio::time::timeout(
+                    Duration::from_secs(2),
+                    // The interval is long enough that the new log can only come from the subscription
+                    follow_logs(&node_provider, filter, Duration::from_secs(60), |log| {
+                        logs.push(log);
+
+                        Ok(())
+                    }),
+                ),
+                async {
+                    tokio::time::sleep(Duration::from_millis(500)).await;
+
+                    emit_event_helper(&node_provider, sender).await
+                }
+            );
+
+            // Assert
+            assert!(res.is_err(), "Should keep following until interrupted");
+
+            let emitters: Vec<H160> = logs.iter().map(|log| log.address).collect();
+            assert_eq!(emitters, vec![emitter, other_emitter?]);
+
+            Ok(())
+        }
+
+        #[tokio::test]
+        async fn should_not_get_logs_not_matching_the_filter() -> anyhow::Result<()> {
+            // Arrange
+            let (node_provider, anvil) = setup_test().await?;
+
+            let sender = *anvil.addresses().first().unwrap();
+            let emitter = emit_event_helper(&node_provider, sender).await?;
+
+            let filter = Filter::new()
+                .from_block(BlockNumber::Earliest)
+                .to_block(BlockNumber::Latest)
+                .address(emitter)
+                .topic0(H256::zero());
+
+            // Act
+            let res = get_logs(&node_provider, filter, MAX_BLOCK_RANGE, None).await;
+
+            // Assert
+            assert!(res.is_ok());
+            assert!(res.unwrap().is_empty());
+
+            Ok(())
+        }
+
+        #[tokio::test]
+        async fn should_get_the_logs_of_an_installed_filter() -> anyhow::Result<()> {
+            // Arrange
+            let (node_provider, anvil) = setup_test().await?;
+
+            let sender = *anvil.addresses().first().unwrap();
+
+            let filter = Filter::new()
+                .from_block(BlockNumber::Earliest)
+                .topic0(event_topic());
+            let filter_id = node_provider.new_filter(FilterKind::Logs(&filter)).await?;
+
+            let emitter = emit_event_helper(&node_provider, sender).await?;
+
+            // Act
+            let res = get_filter_logs(&node_provider, filter_id).await;
+
+            // Assert
+            assert!(res.is_ok());
+
+            let logs = res.unwrap();
+            assert_eq!(logs.len(), 1);
+            assert_eq!(logs[0].address, emitter);
+
+            Ok(())
+        }
+
+        #[tokio::test]
+        async fn should_not_get_the_logs_of_an_unknown_filter() -> anyhow::Result<()> {
+            // Arrange
+            let (node_provider, _anvil) = setup_test().await?;
+
+            // Act
+            let res = get_filter_logs(&node_provider, 0xdead.into()).await;
+
+            // Assert
+            assert!(res.is_err());
+
+            Ok(())
+        }
+
+        #[tokio::test]
+        async fn should_not_get_logs_for_a_range_above_the_limit() -> anyhow::Result<()> {
+            // Arrange
+            let (node_provider, _anvil) = setup_test().await?;
+
+            let filter = Filter::new().from_block(0).to_block(MAX_BLOCK_RANGE);
+
+            // Act
+            let res = get_logs(&node_provider, filter, MAX_BLOCK_RANGE, None).await;
+
+            // Assert
+            assert!(res.is_err());
+
+            Ok(())
+        }
+    }
+
+    mod export_logs {
+        use std::{path::PathBuf, sync::atomic::Ordering};
+
+        use ethers::types::Filter;
+        use serde_json::json;
+
+        use crate::cmd::{
+            event::{export_logs, load_logs_export_progress, LogsExportProgress, LogsExportTotals},
+            helpers::test::{mock_node_provider, spawn_counting_mock_rpc_server},
+        };
+
+        const MAX_BLOCK_RANGE: u64 = 10_000;
+
+        fn export_path() -> PathBuf {
+            std::env::temp_dir().join(format!(
+                "yaeth-export-{}-{}.jsonl",
+                std::process::id(),
+                rand::random::<u64>()
+            ))
+        }
+
+        fn log_line(block: u64) -> String {
+            format!(
+                r#"{{"address":"0x0000000000000000000000000000000000000001","topics":[],"data":"0x","blockNumber":"{block:#x}"}}"#
+            )
+        }
+
+        #[tokio::test]
+        async fn should_append_each_chunk_and_the_totals() -> anyhow::Result<()> {
+            // Arrange
+            let (url, requests) = spawn_counting_mock_rpc_server(json!([{
+                "address": "0x0000000000000000000000000000000000000001",
+                "topics": [],
+                "data": "0x",
+                "blockNumber": "0x5"
+            }]));
+            let node_provider = mock_node_provider(url).await?;
+            let path = export_path();
+
+            let filter = Filter::new().from_block(1).to_block(10);
+
+            // Act
+            let res = export_logs(
+                &node_provider,
+                filter,
+                None,
+                MAX_BLOCK_RANGE,
+                5,
+                &path,
+                false,
+            )
+            .await;
+            let content = std::fs::read_to_string(&path)?;
+            std::fs::remove_file(&path)?;
+
+            // Assert
+            assert_eq!(
+                res?,
+                LogsExportTotals {
+                    from_block: 1,
+                    to_block: 10,
+                    logs: 2,
+                }
+            );
+            assert_eq!(requests.load(Ordering::SeqCst), 2);
+
+            let lines: Vec<serde_json::Value> = content
+                .lines()
+                .map(serde_json::from_str)
+                .collect::<Result<_, _>>()?;
+            assert_eq!(lines.len(), 3);
+            assert_eq!(lines[0]["blockNumber"], json!("0x5"));
+            assert_eq!(
+                lines[2],
+                json!({ "totals": { "fromBlock": 1, "toBlock": 10, "logs": 2 } })
+            );
+
+            Ok(())
+        }
+
+        #[tokio::test]
+        async fn should_continue_an_interrupted_export() -> anyhow::Result<()> {
+            // Arrange
+            let (url, requests) = spawn_counting_mock_rpc_server(json!([]));
+            let node_provider = mock_node_provider(url).await?;
+            let path = export_path();
+            std::fs::write(&path, format!("{}\n{}\n", log_line(2), log_line(7)))?;
+
+            let filter = Filter::new().from_block(1).to_block(10);
+
+            // Act
+            let res = export_logs(
+                &node_provider,
+                filter,
+                None,
+                MAX_BLOCK_RANGE,
+                5,
+                &path,
+                true,
+            )
+            .await;
+            let content = std::fs::read_to_string(&path)?;
+            std::fs::remove_file(&path)?;
+
+            // Assert
+            assert_eq!(
+                res?,
+                LogsExportTotals {
+                    from_block: 1,
+                    to_block: 10,
+                    logs: 1,
+                }
+            );
+            // The logs of block 7 are fetched again: blocks 7 to 10 fit in a single chunk
+            assert_eq!(requests.load(Ordering::SeqCst), 1);
+            assert_eq!(
+                content,
+                format!(
+                    "{}\n{}\n",
+                    log_line(2),
+                    r#"{"totals":{"fromBlock":1,"toBlock":10,"logs":1}}"#
+                )
+            );
+
+            Ok(())
+        }
+
+        #[test]
+        fn should_start_an_export_without_file() -> anyhow::Result<()> {
+            // Act
+            let res = load_logs_export_progress(&export_path(), true)?;
+
+            // Assert
+            assert_eq!(res, LogsExportProgress::Started);
+
+            Ok(())
+        }
+
+        #[test]
+        fn should_not_overwrite_an_existing_export_without_resume() -> anyhow::Result<()> {
+            // Arrange
+            let path = export_path();
+            std::fs::write(&path, format!("{}\n", log_line(1)))?;
+
+            // Act
+            let res = load_logs_export_progress(&path, false);
+            std::fs::remove_file(&path)?;
+
+            // Assert
+            assert!(res.is_err());
+
+            Ok(())
+        }
+
+        #[test]
+        fn should_resume_from_the_last_recorded_block() -> anyhow::Result<()> {
+            // Arrange
+            let path = export_path();
+            let kept = format!("{}\n{}\n", log_line(1), log_line(1));
+            let partial_line = &log_line(3)[..20];
+            std::fs::write(
+                &path,
+                format!("{kept}{}\n{}\n{partial_line}", log_line(2), log_line(2)),
+            )?;
+
+            // Act
+            let res = load_logs_export_progress(&path, true);
+            let content = std::fs::read_to_string(&path)?;
+            std::fs::remove_file(&path)?;
+
+            // Assert
+            assert_eq!(res?, LogsExportProgress::Interrupted { block: 2, logs: 2 });
+            assert_eq!(content, kept);
+
+            Ok(())
+        }
+
+        #[test]
+        fn should_not_resume_a_completed_export() -> anyhow::Result<()> {
+            // Arrange
+            let path = export_path();
+            std::fs::write(
+                &path,
+                format!(
+                    "{}\n{}\n",
+                    log_line(1),
+                    json!({ "totals": { "fromBlock": 1, "toBlock": 10, "logs": 1 } })
+                ),
+            )?;
+
+            // Act
+            let res = load_logs_export_progress(&path, true);
+            std::fs::remove_file(&path)?;
+
+            // Assert
+            assert_eq!(
+                res?,
+                LogsExportProgress::Completed(LogsExportTotals {
+                    from_block: 1,
+                    to_block: 10,
+                    logs: 1,
+                })
+            );
+
+            Ok(())
+        }
+    }
+
+    mod load_abi {
+        use crate::cmd::event::load_abi;
+
+        const TRANSFER_ABI: &str = r#"[{"type":"event","name":"Transfer","anonymous":false,"inputs":[{"name":"from","type":"address","indexed":true},{"name":"to","type":"address","indexed":true},{"name":"value","type":"uint256","indexed":false}]}]"#;
+
+        fn write_abi_file(name: &str, content: &str) -> std::path::PathBuf {
+            let path =
+                std::env::temp_dir().join(format!("yaeth-abi-{name}-{}.json", std::process::id()));
+            std::fs::write(&path, content).unwrap();
+
+            path
+        }
+
+        #[test]
+        fn should_load_a_plain_abi() {
+            // Arrange
+            let path = write_abi_file("plain", TRANSFER_ABI);
+
+            // Act
+            let res = load_abi(&path);
+
+            // Assert
+            std::fs::remove_file(&path).unwrap();
+            assert!(res.is_ok());
+            assert!(res.unwrap().event("Transfer").is_ok());
+        }
+
+        #[test]
+        fn should_load_the_abi_of_an_artifact() {
+            // Arrange
+            let path = write_abi_file(
+                "artifact",
+                &format!(r#"{{"contractName":"Token","abi":{TRANSFER_ABI},"bytecode":"0x"}}"#),
+            );
+
+            // Act
+            let res = load_abi(&path);
+
+            // Assert
+            std::fs::remove_file(&path).unwrap();
+            assert!(res.is_ok());
+            assert!(res.unwrap().event("Transfer").is_ok());
+        }
+
+        #[test]
+        fn should_not_load_a_missing_file() {
+            // Arrange
+            let path = std::env::temp_dir().join("yaeth-abi-missing.json");
+
+            // Act
+            let res = load_abi(&path);
+
+            // Assert
+            assert!(res.is_err());
+        }
+    }
+
+    mod parse_event_signature {
+        use ethers::{abi::ParamType, types::H256, utils::keccak256};
+
+        use crate::cmd::event::{parse_event_signature, parse_param_type, EventSignatureParam};
+
+        #[test]
+        fn should_parse_the_elementary_types() {
+            // Setup
+            let test_cases = [
+                ("address", ParamType::Address),
+                ("bool", ParamType::Bool),
+                ("string", ParamType::String),
+                ("bytes", ParamType::Bytes),
+                ("bytes1", ParamType::FixedBytes(1)),
+                ("bytes32", ParamType::FixedBytes(32)),
+                ("uint", ParamType::Uint(256)),
+                ("uint8", ParamType::Uint(8)),
+                ("int128", ParamType::Int(128)),
+            ];
+
+            for (kind, expected) in test_cases {
+                // Act
+                let res = parse_param_type(kind);
+
+                // Assert
+                assert_eq!(res.ok(), Some(expected), "{kind}");
+            }
+        }
+
+        #[test]
+        fn should_parse_arrays_and_tuples() {
+            // Setup
+            let test_cases = [
+                (
+                    "bytes32[]",
+                    ParamType::Array(Box::new(ParamType::FixedBytes(32))),
+                ),
+                (
+                    "uint256[2][]",
+                    ParamType::Array(Box::new(ParamType::FixedArray(
+                        Box::new(ParamType::Uint(256)),
+                        2,
+                    ))),
+                ),
+                (
+                    "(address,bytes)",
+                    ParamType::Tuple(vec![ParamType::Address, ParamType::Bytes]),
+                ),
+                (
+                    "tuple(uint256 amount, (bool,string)[] flags)[3]",
+                    ParamType::FixedArray(
+                        Box::new(ParamType::Tuple(vec![
+                            ParamType::Uint(256),
+                            ParamType::Array(Box::new(ParamType::Tuple(vec![
+                                ParamType::Bool,
+                                ParamType::String,
+                            ]))),
+                        ])),
+                        3,
+                    ),
+                ),
+            ];
+
+            for (kind, expected) in test_cases {
+                // Act
+                let res = parse_param_type(kind);
+
+                // Assert
+                assert_eq!(res.ok(), Some(expected), "{kind}");
+            }
+        }
+
+        #[test]
+        fn should_reject_invalid_types() {
+            // Setup
+            let test_cases = [
+                "bytes0",
+                "bytes33",
+                "uint7",
+                "uint264",
+                "int0",
+                "uint256[x]",
+                "(uint256",
+                "foo",
+            ];
+
+            for kind in test_cases {
+                // Act
+                let res = parse_param_type(kind);
+
+                // Assert
+                assert!(res.is_err(), "{kind}");
+            }
+        }
+
+        #[test]
+        fn should_parse_the_indexed_markers_and_names() -> anyhow::Result<()> {
+            // Act
+            let res = parse_event_signature(
+                "event Swap(address indexed sender, (uint256, bytes) data, bytes32 indexed, bool)",
+            )?;
+
+            // Assert
+            assert_eq!(res.name, "Swap");
+            assert_eq!(
+                res.params,
+                vec![
+                    EventSignatureParam {
+                        name: String::from("sender"),
+                        kind: ParamType::Address,
+                        indexed: true,
+                    },
+                    EventSignatureParam {
+                        name: String::from("data"),
+                        kind: ParamType::Tuple(vec![ParamType::Uint(256), ParamType::Bytes]),
+                        indexed: false,
+                    },
+                    EventSignatureParam {
+                        name: String::new(),
+                        kind: ParamType::FixedBytes(32),
+                        indexed: true,
+                    },
+                    EventSignatureParam {
+                        name: String::new(),
+                        kind: ParamType::Bool,
+                        indexed: false,
+                    },
+                ]
+            );
+
+            Ok(())
+        }
+
+        #[test]
+        fn should_reject_invalid_signatures() {
+            // Setup
+            let test_cases = [
+                "Transfer",
+                "Transfer(address,address",
+                "(address)",
+                "Transfer(address from to)",
+                "Transfer(address,(uint256)))",
+            ];
+
+            for signature in test_cases {
+                // Act
+                let res = parse_event_signature(signature);
+
+                // Assert
+                assert!(res.is_err(), "{signature}");
+            }
+        }
+
+        #[test]
+        fn should_hash_the_canonical_signature() -> anyhow::Result<()> {
+            // Setup
+            let test_cases = [
+                (
+                    "Transfer(address indexed from, address indexed to, uint value)",
+                    "Transfer(address,address,uint256)",
+                ),
+                (
+                    "Swap(address,(uint256 amount, bytes data)[],bool)",
+                    "Swap(address,(uint256,bytes)[],bool)",
+                ),
+                ("Paused()", "Paused()"),
+            ];
+
+            for (signature, canonical) in test_cases {
+                // Act
+                let res = parse_event_signature(signature)?.topic0();
+
+                // Assert
+                assert_eq!(res, H256(keccak256(canonical)), "{signature}");
+            }
+
+            Ok(())
+        }
+    }
+
+    mod decode_log {
+        use ethers::{
+            abi::{self, parse_abi, Abi, Token},
+            providers::Middleware,
+            types::{BlockNumber, Filter, Log, TransactionRequest, H160, H256, U256},
+        };
+
+        use serde_json::json;
+
+        use crate::cmd::{
+            event::{
+                decode_log, get_logs, load_topics_db, parse_event_signature, DecodedLog,
+                LogDecoder, TopicsDb,
+            },
+            helpers::test::{
+                deploy_contract_helper, setup_test, transfer_emitter_init_code, transfer_receiver,
+                transfer_topic,
+            },
+        };
+
+        fn transfer_abi() -> Abi {
+            parse_abi(&["event Transfer(address indexed from, address indexed to, uint256 value)"])
+                .unwrap()
+        }
+
+        fn transfer_log(from: H160, to: H160, value: U256) -> Log {
+            let mut data = [0u8; 32];
+            value.to_big_endian(&mut data);
+
+            Log {
+                topics: vec![transfer_topic(), from.into(), to.into()],
+                data: data.to_vec().into(),
+                ..Default::default()
+            }
+        }
+
+        #[test]
+        fn should_decode_a_log_matching_an_event() {
+            // Arrange
+            let from = H160::repeat_byte(0x01);
+            let log = transfer_log(from, transfer_receiver(), U256::from(1000));
+
+            // Act
+            let res = decode_log(&transfer_abi().into(), log.clone());
+
+            // Assert
+            assert_eq!(res.log, log);
+            assert_eq!(
+                res.decoded,
+                Some(DecodedLog {
+                    event_name: String::from("Transfer"),
+                    params: vec![
+                        (String::from("from"), format!("{from:?}")),
+                        (String::from("to"), format!("{:?}", transfer_receiver())),
+                        (String::from("value"), String::from("1000")),
+                    ],
+                })
+            );
+        }
+
+        #[test]
+        fn should_serialize_the_params_as_an_ordered_map() -> anyhow::Result<()> {
+            // Arrange
+            let from = H160::repeat_byte(0x01);
+            let log = transfer_log(from, transfer_receiver(), U256::from(1000));
+            let decoded = decode_log(&transfer_abi().into(), log).decoded;
+
+            // Act
+            let res = serde_json::to_string(&decoded)?;
+
+            // Assert
+            assert_eq!(
+                res,
+                format!(
+                    r#"{{"eventName":"Transfer","params":{{"from":"{from:?}","to":"{:?}","value":"1000"}}}}"#,
+                    transfer_receiver()
+                )
+            );
+
+            Ok(())
+        }
+
+        #[test]
+        fn should_decode_a_log_with_the_filtered_event() -> anyhow::Result<()> {
+            // Arrange
+            let from = H160::repeat_byte(0x01);
+            let log = transfer_log(from, transfer_receiver(), U256::from(1000));
+            let event = parse_event_signature("Transfer(address from, address to, uint256 value)")?;
+
+            // Act
+            let decoder = LogDecoder::load(None, None, Some(event))?
+                .ok_or(anyhow::anyhow!("Expected a decoder"))?;
+            let res = decode_log(&decoder, log);
+
+            // Assert
+            assert_eq!(
+                res.decoded,
+                Some(DecodedLog {
+                    event_name: String::from("Transfer"),
+                    params: vec![
+                        (String::from("from"), format!("{from:?}")),
+                        (String::from("to"), format!("{:?}", transfer_receiver())),
+                        (String::from("value"), String::from("1000")),
+                    ],
+                })
+            );
+
+            Ok(())
+        }
+
+        #[test]
+        fn should_get_the_abi_event_with_its_indexed_params() -> anyhow::Result<()> {
+            // Arrange
+            let decoder = LogDecoder::from(transfer_abi());
+
+            // Act
+            let res = decoder.abi_event(transfer_topic());
+
+            // Assert
+            assert_eq!(
+                res,
+                Some(parse_event_signature(
+                    "Transfer(address indexed from, address indexed to, uint256 value)"
+                )?)
+            );
+            assert_eq!(decoder.abi_event(H256::repeat_byte(0xee)), None);
+
+            Ok(())
+        }
+
+        #[test]
+        fn should_not_load_a_decoder_without_sources() -> anyhow::Result<()> {
+            // Act
+            let res = LogDecoder::load(None, None, None)?;
+
+            // Assert
+            assert!(res.is_none());
+
+            Ok(())
+        }
+
+        #[test]
+        fn should_not_decode_a_log_not_matching_any_event() {
+            // Arrange
+            let log = Log {
+                topics: vec![H256::repeat_byte(0xee)],
+                ..Default::default()
+            };
+
+            // Act
+            let res = decode_log(&transfer_abi().into(), log);
+
+            // Assert
+            assert_eq!(res.decoded, None);
+        }
+
+        #[test]
+        fn should_not_decode_a_log_without_topics() {
+            // Arrange
+            let log = Log::default();
+
+            // Act
+            let res = decode_log(&transfer_abi().into(), log);
+
+            // Assert
+            assert_eq!(res.decoded, None);
+        }
+
+        fn topics_db(entries: serde_json::Value) -> anyhow::Result<TopicsDb> {
+            let path = std::env::temp_dir().join(format!(
+                "yaeth-topics-{}-{}.json",
+                std::process::id(),
+                rand::random::<u64>()
+            ));
+            std::fs::write(&path, entries.to_string())?;
+
+            let topics_db = load_topics_db(&path);
+            std::fs::remove_file(path)?;
+
+            topics_db
+        }
+
+        #[test]
+        fn should_decode_a_log_with_the_topics_db() -> anyhow::Result<()> {
+            // Arrange
+            let from = H160::repeat_byte(0x01);
+            let log = transfer_log(from, transfer_receiver(), U256::from(1000));
+            let decoder = LogDecoder::from(topics_db(json!({
+                format!("{:?}", transfer_topic()): "Transfer(address,address,uint256)"
+            }))?);
+
+            // Act
+            let res = decode_log(&decoder, log);
+
+            // Assert
+            assert_eq!(
+                res.decoded,
+                Some(DecodedLog {
+                    event_name: String::from("Transfer"),
+                    params: vec![
+                        (String::from("arg0"), format!("{from:?}")),
+                        (String::from("arg1"), format!("{:?}", transfer_receiver())),
+                        (String::from("arg2"), String::from("1000")),
+                    ],
+                })
+            );
+
+            Ok(())
+        }
+
+        #[test]
+        fn should_keep_the_hash_of_an_indexed_dynamic_value() -> anyhow::Result<()> {
+            // Arrange
+            let topic0 = H256::repeat_byte(0xaa);
+            let name_hash = H256::repeat_byte(0xbb);
+            let log = Log {
+                topics: vec![topic0, name_hash],
+                data: abi::encode(&[Token::Bytes(vec![0x12, 0x34])]).into(),
+                ..Default::default()
+            };
+            let decoder = LogDecoder::from(topics_db(json!({
+                format!("{topic0:?}"): "Registered(string indexed name, bytes payload)"
+            }))?);
+
+            // Act
+            let res = decode_log(&decoder, log);
+
+            // Assert
+            assert_eq!(
+                res.decoded,
+                Some(DecodedLog {
+                    event_name: String::from("Registered"),
+                    params: vec![
+                        (String::from("name"), format!("{name_hash:?}")),
+                        (String::from("payload"), String::from("0x1234")),
+                    ],
+                })
+            );
+
+            Ok(())
+        }
+
+        #[test]
+        fn should_not_decode_a_log_whose_topics_dont_match_the_signature() -> anyhow::Result<()> {
+            // Arrange
+            let from = H160::repeat_byte(0x01);
+            let log = transfer_log(from, transfer_receiver(), U256::from(1000));
+            let decoder = LogDecoder::from(topics_db(json!({
+                format!("{:?}", transfer_topic()):
+                    "Transfer(address indexed from, address to, uint256 value)",
+                format!("{:?}", H256::repeat_byte(0xee)): "Unrelated(uint256)"
+            }))?);
+
+            // Act
+            let res = decode_log(&decoder, log);
+
+            // Assert
+            assert_eq!(res.decoded, None);
+
+            Ok(())
+        }
+
+        #[test]
+        fn should_prefer_the_abi_over_the_topics_db() -> anyhow::Result<()> {
+            // Arrange
+            let from = H160::repeat_byte(0x01);
+            let log = transfer_log(from, transfer_receiver(), U256::from(1000));
+            let decoder = LogDecoder {
+                abi: Some(transfer_abi()),
+                topics_db: Some(topics_db(json!({
+                    format!("{:?}", transfer_topic()): "Moved(address,address,uint256)"
+                }))?),
+            };
+
+            // Act
+            let res = decode_log(&decoder, log);
+
+            // Assert
+            assert_eq!(
+                res.decoded.map(|decoded| decoded.event_name),
+                Some(String::from("Transfer"))
+            );
+
+            Ok(())
+        }
+
+        #[test]
+        fn should_not_load_a_topics_db_with_an_invalid_signature() {
+            // Act
+            let res = topics_db(json!({
+                format!("{:?}", transfer_topic()): "Transfer(address,address,uint255)"
+            }));
+
+            // Assert
+            assert!(res.is_err());
+        }
+
+        #[tokio::test]
+        async fn should_decode_the_logs_emitted_by_a_contract() -> anyhow::Result<()> {
+            // Arrange
+            let (node_provider, anvil) = setup_test().await?;
+
+            let sender = *anvil.addresses().first().unwrap();
+            let emitter =
+                deploy_contract_helper(&node_provider, sender, transfer_emitter_init_code())
+                    .await?;
+
+            let tx = TransactionRequest::new().from(sender).to(emitter);
+            node_provider.send_transaction(tx, None).await?.await?;
+
+            let filter = Filter::new()
+                .from_block(BlockNumber::Earliest)
+                .to_block(BlockNumber::Latest)
+                .address(emitter);
+            let logs = get_logs(&node_provider, filter, 10_000, None).await?;
+
+            // Act
+            let res = decode_log(&transfer_abi().into(), logs[0].clone());
+
+            // Assert
+            assert_eq!(
+                res.decoded,
+                Some(DecodedLog {
+                    event_name: String::from("Transfer"),
+                    params: vec![
+                        (String::from("from"), format!("{sender:?}")),
+                        (String::from("to"), format!("{:?}", transfer_receiver())),
+                        (String::from("value"), String::from("42")),
+                    ],
+                })
+            );
+
+            Ok(())
+        }
+    }
+
+    mod get_erc20_transfers {
+        use std::time::Duration;
+
+        use ethers::{
+            providers::Middleware,
+            types::{Block, BlockNumber, Filter, Log, TransactionRequest, H160, H256, U256},
+        };
+        use serde_json::json;
+
+        use crate::cmd::{
+            event::{decode_erc20_transfer, get_erc20_transfers, Erc20TransfersFilter},
+            helpers::test::{
+                deploy_contract_helper, mock_node_provider, setup_test,
+                spawn_mock_rpc_server_with_response, transfer_emitter_init_code, transfer_receiver,
+                transfer_topic,
+            },
+        };
+
+        fn transfer_log(topics: Vec<H256>, value: U256) -> Log {
+            let mut data = [0u8; 32];
+            value.to_big_endian(&mut data);
+
+            Log {
+                topics,
+                data: data.to_vec().into(),
+                ..Default::default()
+            }
+        }
+
+        #[tokio::test]
+        async fn should_not_get_the_transfers_of_a_tag_bounded_range_above_the_limit(
+        ) -> anyhow::Result<()> {
+            // Arrange
+            let max_block_range = 10_000;
+            let (url, _, _) = spawn_mock_rpc_server_with_response(
+                move |request| {
+                    let result = match request["method"].as_str() {
+                        Some("eth_getBlockByNumber") => {
+                            let number = match request["params"][0].as_str() {
+                                Some("earliest") => 0,
+                                _ => max_block_range * 2,
+                            };
+
+                            json!(Block::<H256> {
+                                number: Some(number.into()),
+                                ..Default::default()
+                            })
+                        }
+                        Some("eth_call") => json!(H256::from_low_u64_be(18)),
+                        _ => json!([]),
+                    };
+
+                    json!({ "jsonrpc": "2.0", "id": request["id"], "result": result })
+                },
+                Duration::ZERO,
+            );
+            let node_provider = mock_node_provider(url).await?;
+
+            let filter = Erc20TransfersFilter {
+                token: H160::repeat_byte(0xaa),
+                from: Some(H160::repeat_byte(0x01)),
+                to: None,
+                from_block: BlockNumber::Earliest,
+                to_block: BlockNumber::Latest,
+            };
+
+            // Act
+            let res = get_erc20_transfers(&node_provider, filter, max_block_range).await;
+
+            // Assert
+            assert!(res
+                .unwrap_err()
+                .to_string()
+                .contains("exceeds the maximum of 10000 blocks"));
+
+            Ok(())
+        }
+
+        #[test]
+        fn should_filter_the_transfers_by_sender_and_recipient() {
+            // Arrange
+            let token = H160::repeat_byte(0xaa);
+            let sender = H160::repeat_byte(0x01);
+
+            let transfers_filter = Erc20TransfersFilter {
+                token,
+                from: Some(sender),
+                to: None,
+                from_block: BlockNumber::Earliest,
+                to_block: BlockNumber::Latest,
+            };
+
+            // Act
+            let res = Filter::from(&transfers_filter);
+
+            // Assert
+            assert_eq!(
+                res,
+                Filter::new()
+                    .address(token)
+                    .topic0(transfer_topic())
+                    .topic1(H256::from(sender))
+                    .from_block(BlockNumber::Earliest)
+                    .to_block(BlockNumber::Latest)
+            );
+        }
+
+        #[test]
+        fn should_decode_an_erc20_transfer() {
+            // Arrange
+            let sender = H160::repeat_byte(0x01);
+            let log = transfer_log(
+                vec![transfer_topic(), sender.into(), transfer_receiver().into()],
+                U256::from(1_500_000),
+            );
+
+            // Act
+            let res = decode_erc20_transfer(&log, Some(6));
+
+            // Assert
+            assert!(res.is_some());
+
+            let transfer = res.unwrap();
+            assert_eq!(transfer.from, sender);
+            assert_eq!(transfer.to, transfer_receiver());
+            assert_eq!(transfer.value, U256::from(1_500_000));
+            assert_eq!(transfer.formatted_value, Some(String::from("1.500000")));
+        }
+
+        #[test]
+        fn should_skip_the_erc721_transfers() {
+            // Arrange
+            let log = transfer_log(
+                vec![
+                    transfer_topic(),
+                    H160::repeat_byte(0x01).into(),
+                    transfer_receiver().into(),
+                    H256::from_low_u64_be(7),
+                ],
+                U256::zero(),
+            );
+
+            // Act
+            let res = decode_erc20_transfer(&log, Some(0));
+
+            // Assert
+            assert!(res.is_none());
+        }
+
+        #[tokio::test]
+        async fn should_get_the_transfers_of_the_sender() -> anyhow::Result<()> {
+            // Arrange
+            let (node_provider, anvil) = setup_test().await?;
+
+            let sender = *anvil.addresses().first().unwrap();
+            let other_sender = *anvil.addresses().get(1).unwrap();
+            let token =
+                deploy_contract_helper(&node_provider, sender, transfer_emitter_init_code())
+                    .await?;
+
+            for from in [sender, other_sender] {
+                let tx = TransactionRequest::new().from(from).to(token);
+                node_provider.send_transaction(tx, None).await?.await?;
+            }
+
+            let transfers_filter = Erc20TransfersFilter {
+                token,
+                from: Some(sender),
+                to: None,
+                from_block: BlockNumber::Earliest,
+                to_block: BlockNumber::Latest,
+            };
+
+            // Act
+            let res = get_erc20_transfers(&node_provider, transfers_filter, 10_000).await;
+
+            // Assert
+            assert!(res.is_ok());
+
+            let transfers = res.unwrap();
+            assert_eq!(transfers.len(), 1);
+            assert_eq!(transfers[0].from, sender);
+            assert_eq!(transfers[0].to, transfer_receiver());
+            assert_eq!(transfers[0].value, U256::from(42));
+            assert_eq!(transfers[0].formatted_value, Some(String::from("0.000042")));
+
+            Ok(())
+        }
+    }
+
+    mod pending_transactions {
+        use std::time::Duration;
+
+        use ethers::{providers::Middleware, types::TransactionRequest, utils::parse_ether};
+        use serde_json::json;
+
+        use crate::cmd::{
+            event::{follow_pending_transactions, get_pending_transactions, PendingTx},
+            helpers::test::{mock_node_provider, setup_test, spawn_mock_rpc_error_server},
+        };
+
+        #[tokio::test]
+        async fn should_follow_the_transactions_entering_the_mempool() -> anyhow::Result<()> {
+            // Arrange
+            let (node_provider, anvil) = setup_test().await?;
+
+            let sender = *anvil.addresses().first().unwrap();
+            let receiver = *anvil.addresses().last().unwrap();
+
+            let mut pending_txs = Vec::new();
+
+            // Act
+            let (res, tx_hash) = tokio::join!(
+                follow_pending_transactions(
+                    &node_provider,
+                    Duration::from_millis(100),
+                    true,
+                    async {
+                        tokio::time::sleep(Duration::from_secs(1)).await;
+                        Ok(())
+                    },
+                    |tx| {
+                        pending_txs.push(tx);
+                        Ok(())
+                    },
+                ),
+                async {
+                    tokio::time::sleep(Duration::from_millis(200)).await;
+
+                    let tx = TransactionRequest::new()
+                        .from(sender)
+                        .to(receiver)
+                        .value(parse_ether(1)?);
+
+                    anyhow::Ok(*node_provider.send_transaction(tx, None).await?)
+                }
+            );
+
+            // Assert
+            assert!(res.is_ok());
+
+            let tx_hash = tx_hash?;
+            assert!(pending_txs
+                .iter()
+                .any(|tx| matches!(tx, PendingTx::Full(tx) if tx.hash == tx_hash)));
+
+            Ok(())
+        }
+
+        #[tokio::test]
+        async fn should_explain_that_the_node_does_not_support_pending_transaction_filters(
+        ) -> anyhow::Result<()> {
+            // Arrange
+            let url = spawn_mock_rpc_error_server(
+                json!({ "code": -32601, "message": "the method eth_newPendingTransactionFilter does not exist/is not available" }),
+            );
+            let node_provider = mock_node_provider(url).await?;
+
+            // Act
+            let res = get_pending_transactions(&node_provider, Duration::ZERO, false).await;
+
+            // Assert
+            assert!(res.is_err());
+            assert!(res
+                .unwrap_err()
+                .to_string()
+                .contains("does not support pending transaction filters"));
+
+            Ok(())
+        }
+    }
+
+    mod new_blocks {
+        use std::time::Duration;
+
+        use ethers::utils::{Anvil, AnvilInstance};
+        use serde_json::json;
+
+        use crate::{
+            cmd::{
+                event::{follow_new_blocks, get_new_blocks, NewBlock},
+                helpers::test::{mock_node_provider, setup_test, spawn_mock_rpc_error_server},
+            },
+            config::{get_config, ConfigOverrides},
+            context::NodeProvider,
+        };
+
+        async fn setup_block_time_test() -> anyhow::Result<(NodeProvider, AnvilInstance)> {
+            let anvil = Anvil::new().block_time(1u64).spawn();
+
+            let config = get_config(ConfigOverrides::new(None, Some(anvil.endpoint()), None))?;
+            let node_provider = NodeProvider::new(&config).await?;
+
+            Ok((node_provider, anvil))
+        }
+
+        #[tokio::test]
+        async fn should_wait_for_a_new_block() -> anyhow::Result<()> {
+            // Arrange
+            let (node_provider, _anvil) = setup_block_time_test().await?;
+
+            // Act
+            let res = get_new_blocks(
+                &node_provider,
+                Duration::from_millis(200),
+                Duration::from_secs(5),
+                true,
+            )
+            .await;
+
+            // Assert
+            let blocks = res?;
+            assert!(!blocks.is_empty());
+            assert!(matches!(
+                blocks[0],
+                NewBlock::Summary {
+                    number: Some(_),
+                    ..
+                }
+            ));
+
+            Ok(())
+        }
+
+        #[tokio::test]
+        async fn should_not_get_any_block_if_none_is_mined_before_the_timeout() -> anyhow::Result<()>
+        {
+            // Arrange
+            let (node_provider, _anvil) = setup_test().await?;
+
+            // Act
+            let res = get_new_blocks(
+                &node_provider,
+                Duration::from_millis(200),
+                Duration::from_secs(1),
+                false,
+            )
+            .await;
+
+            // Assert
+            assert!(res?.is_empty());
+
+            Ok(())
+        }
+
+        #[tokio::test]
+        async fn should_follow_the_new_heads_through_a_subscription() -> anyhow::Result<()> {
+            // Arrange
+            let anvil = Anvil::new().block_time(1u64).spawn();
+            let config = get_config(ConfigOverrides::new(None, Some(anvil.ws_endpoint()), None))?;
+            let node_provider = NodeProvider::new(&config).await?;
+
+            let mut blocks = Vec::new();
+
+            // Act
+            let res = follow_new_blocks(
+                &node_provider,
+                Duration::from_secs(60),
+                true,
+                async {
+                    tokio::time::sleep(Duration::from_millis(2500)).await;
+                    Ok(())
+                },
+                |block| {
+                    blocks.push(block);
+                    Ok(())
+                },
+            )
+            .await;
+
+            // Assert
+            assert!(res.is_ok());
+            assert!(blocks.len() >= 2);
+            assert!(blocks
+                .iter()
+                .all(|block| matches!(block, NewBlock::Summary { .. })));
+
+            Ok(())
+        }
+
+        #[tokio::test]
+        async fn should_follow_the_new_blocks() -> anyhow::Result<()> {
+            // Arrange
+            let (node_provider, _anvil) = setup_block_time_test().await?;
+
+            let mut blocks = Vec::new();
+
+            // Act
+            let res = follow_new_blocks(
+                &node_provider,
+                Duration::from_millis(200),
+                false,
+                async {
+                    tokio::time::sleep(Duration::from_millis(2500)).await;
+                    Ok(())
+                },
+                |block| {
+                    blocks.push(block);
+                    Ok(())
+                },
+            )
+            .await;
+
+            // Assert
+            assert!(res.is_ok());
+            assert!(blocks.len() >= 2);
+            assert!(blocks
+                .iter()
+                .all(|block| matches!(block, NewBlock::Hash(_))));
+
+            Ok(())
+        }
+
+        #[tokio::test]
+        async fn should_explain_that_the_node_does_not_support_new_block_filters(
+        ) -> anyhow::Result<()> {
+            // Arrange
+            let url = spawn_mock_rpc_error_server(
+                json!({ "code": -32601, "message": "Method not found" }),
+            );
+            let node_provider = mock_node_provider(url).await?;
+
+            // Act
+            let res = get_new_blocks(
+                &node_provider,
+                Duration::ZERO,
+                Duration::from_secs(1),
+                false,
+            )
+            .await;
+
+            // Assert
+            assert!(res.is_err());
+            assert!(res
+                .unwrap_err()
+                .to_string()
+                .contains("does not support new block filters (eth_newBlockFilter)"));
+
+            Ok(())
+        }
+    }
+}
@@ -1,39 +1,349 @@
+use std::sync::Mutex;
+
 use ethers::{
     providers::Middleware,
-    types::{BlockId, FeeHistory, TransactionRequest, U256},
+    types::{transaction::eip2718::TypedTransaction, BlockId, BlockNumber, FeeHistory, U256, U64},
 };
+use futures::TryStreamExt;
+use serde::Serialize;
 
 use crate::context::NodeProvider;
 
-use super::helpers::get_block_number_by_block_id;
+use super::{
+    helpers::{
+        buffered_fetch, ensure_block_range_within_limit, get_block_number_by_block_id,
+        get_block_number_value,
+    },
+    lookup::{Lookup, Missing},
+};
 
 // eth_estimateGas
 pub async fn estimate_gas(
     node_provider: &NodeProvider,
-    tx: TransactionRequest,
+    tx: TypedTransaction,
     block_id: Option<BlockId>,
 ) -> anyhow::Result<U256> {
-    let estimated_gas = node_provider.estimate_gas(&tx.into(), block_id).await?;
+    let estimated_gas = node_provider.estimate_gas(&tx, block_id).await?;
 
     Ok(estimated_gas)
 }
 
+const TRANSACTION_BASE_GAS: u64 = 21_000;
+const CONTRACT_CREATION_BASE_GAS: u64 = 53_000;
+const ZERO_BYTE_GAS: u64 = 4;
+const NON_ZERO_BYTE_GAS: u64 = 16;
+
+#[derive(Debug, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct CalldataCost {
+    pub zero_bytes: usize,
+    pub non_zero_bytes: usize,
+    pub gas: U256,
+}
+
+/// Computes the gas charged for the calldata of a transaction, 4 gas per zero byte and 16 gas per
+/// non zero byte. This is the part of the cost that L2s mostly pay to post the data on L1.
+pub fn calldata_cost(data: &[u8]) -> CalldataCost {
+    let zero_bytes = data.iter().filter(|byte| **byte == 0).count();
+    let non_zero_bytes = data.len() - zero_bytes;
+
+    CalldataCost {
+        zero_bytes,
+        non_zero_bytes,
+        gas: U256::from(
+            zero_bytes as u64 * ZERO_BYTE_GAS + non_zero_bytes as u64 * NON_ZERO_BYTE_GAS,
+        ),
+    }
+}
+
+#[derive(Debug, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct GasEstimateBreakdown {
+    pub total: U256,
+    pub base: U256,
+    pub calldata: CalldataCost,
+    pub execution: U256,
+}
+
+// eth_estimateGas
+/// Splits the gas estimate in the base cost of the transaction, the cost of its calldata and the
+/// gas left for its execution.
+pub async fn estimate_gas_breakdown(
+    node_provider: &NodeProvider,
+    tx: TypedTransaction,
+    block_id: Option<BlockId>,
+) -> anyhow::Result<GasEstimateBreakdown> {
+    let total = estimate_gas(node_provider, tx.clone(), block_id).await?;
+
+    let base = U256::from(match tx.to() {
+        Some(_) => TRANSACTION_BASE_GAS,
+        None => CONTRACT_CREATION_BASE_GAS,
+    });
+    let calldata = calldata_cost(tx.data().map(|data| data.as_ref()).unwrap_or_default());
+    let execution = total.saturating_sub(base).saturating_sub(calldata.gas);
+
+    Ok(GasEstimateBreakdown {
+        total,
+        base,
+        calldata,
+        execution,
+    })
+}
+
+pub const MAX_GAS_VARIANCE_BLOCK_RANGE: u64 = 20;
+const GAS_VARIANCE_CONCURRENCY: usize = 5;
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GasEstimateVariance {
+    min: U256,
+    max: U256,
+    std_dev: f64,
+    by_block: Vec<(U64, U256)>,
+}
+
+impl GasEstimateVariance {
+    fn new(by_block: Vec<(U64, U256)>) -> Self {
+        let estimates: Vec<U256> = by_block.iter().map(|(_, gas)| *gas).collect();
+
+        let min = estimates.iter().min().copied().unwrap_or_default();
+        let max = estimates.iter().max().copied().unwrap_or_default();
+
+        let values: Vec<f64> = estimates.iter().map(|gas| gas.as_u128() as f64).collect();
+        let count = values.len().max(1) as f64;
+        let mean = values.iter().sum::<f64>() / count;
+        let variance = values.iter().map(|v| (v - mean).powi(2)).sum::<f64>() / count;
+
+        Self {
+            min,
+            max,
+            std_dev: variance.sqrt(),
+            by_block,
+        }
+    }
+}
+
+// eth_estimateGas for each block in the [from, to] range
+pub async fn estimate_gas_variance(
+    node_provider: &NodeProvider,
+    tx: TypedTransaction,
+    from: BlockId,
+    to: BlockId,
+    max_block_range: u64,
+) -> anyhow::Result<GasEstimateVariance> {
+    let from = get_block_number_value(node_provider, from)
+        .await?
+        .ok_or(anyhow::anyhow!(
+            "Block not found for the provided starting block identifier"
+        ))?;
+    let to = get_block_number_value(node_provider, to)
+        .await?
+        .ok_or(anyhow::anyhow!(
+            "Block not found for the provided ending block identifier"
+        ))?;
+
+    if from > to {
+        anyhow::bail!(
+            "Invalid block range. The starting block {from} is after the ending block {to}"
+        );
+    }
+
+    let range_len = (to - from).as_u64() + 1;
+    ensure_block_range_within_limit(range_len, max_block_range)?;
+    if range_len > MAX_GAS_VARIANCE_BLOCK_RANGE {
+        anyhow::bail!(
+            "Block range too large. At most {MAX_GAS_VARIANCE_BLOCK_RANGE} blocks can be estimated but {range_len} were requested"
+        );
+    }
+
+    let by_block = buffered_fetch(
+        from.as_u64()..=to.as_u64(),
+        GAS_VARIANCE_CONCURRENCY,
+        |block_number| {
+            let tx = tx.clone();
+
+            async move {
+                let block_number = U64::from(block_number);
+
+                let estimated_gas = estimate_gas(
+                    node_provider,
+                    tx,
+                    Some(BlockId::Number(BlockNumber::Number(block_number))),
+                )
+                .await?;
+
+                Ok((block_number, estimated_gas))
+            }
+        },
+        |_, _| {},
+    )
+    .try_collect()
+    .await?;
+
+    Ok(GasEstimateVariance::new(by_block))
+}
+
+pub const MAX_FEE_HISTORY_BLOCK_COUNT: u64 = 1024;
+
+/// Block count, last block and reward percentiles of a fee history request.
+type FeeHistoryKey = (u64, BlockNumber, Vec<f64>);
+
+/// Last fee history returned by the node, reused by the following requests with the same
+/// parameters for the lifetime of the process.
+#[derive(Default)]
+pub struct FeeHistoryCache(Mutex<Option<(FeeHistoryKey, FeeHistory)>>);
+
+impl FeeHistoryCache {
+    fn get(&self, key: &FeeHistoryKey) -> Option<FeeHistory> {
+        let cached = self.0.lock().ok()?;
+
+        cached
+            .as_ref()
+            .filter(|(cached_key, _)| cached_key == key)
+            .map(|(_, fee_history)| fee_history.clone())
+    }
+
+    fn set(&self, key: FeeHistoryKey, fee_history: FeeHistory) {
+        if let Ok(mut cached) = self.0.lock() {
+            *cached = Some((key, fee_history));
+        }
+    }
+}
+
+// eth_feeHistory
+/// Gets the fee history from the cache, if provided and holding the same request, or the node.
+async fn fee_history(
+    node_provider: &NodeProvider,
+    cache: Option<&FeeHistoryCache>,
+    block_count: u64,
+    last_block: BlockNumber,
+    reward_percentiles: &[f64],
+) -> anyhow::Result<FeeHistory> {
+    let key = (block_count, last_block, reward_percentiles.to_vec());
+
+    if let Some(fee_history) = cache.and_then(|cache| cache.get(&key)) {
+        return Ok(fee_history);
+    }
+
+    let fee_history = node_provider
+        .fee_history(block_count, last_block, reward_percentiles)
+        .await?;
+
+    if let Some(cache) = cache {
+        cache.set(key, fee_history.clone());
+    }
+
+    Ok(fee_history)
+}
+
 // eth_feeHistory
 pub async fn get_fee_history(
     node_provider: &NodeProvider,
-    block_count: U256,
+    block_count: u64,
     last_block_id: BlockId,
     reward_percentiles: Vec<f64>,
-) -> anyhow::Result<Option<FeeHistory>> {
+    max_block_range: u64,
+    cache: Option<&FeeHistoryCache>,
+) -> anyhow::Result<Lookup<FeeHistory>> {
+    ensure_block_range_within_limit(block_count, max_block_range)?;
+
+    if block_count > MAX_FEE_HISTORY_BLOCK_COUNT {
+        let fee_history = get_chunked_fee_history(
+            node_provider,
+            block_count,
+            last_block_id,
+            reward_percentiles,
+            cache,
+        )
+        .await?;
+
+        return Ok(Lookup::from_option(fee_history, Missing::Block));
+    }
+
     if let Some(block_number) = get_block_number_by_block_id(node_provider, last_block_id).await? {
-        let fee_history = node_provider
-            .fee_history(block_count, block_number, &reward_percentiles)
-            .await?;
+        let fee_history = fee_history(
+            node_provider,
+            cache,
+            block_count,
+            block_number,
+            &reward_percentiles,
+        )
+        .await?;
+
+        return Ok(Lookup::Found(fee_history));
+    }
+
+    Ok(Lookup::NotFound {
+        what: Missing::Block,
+    })
+}
+
+async fn get_chunked_fee_history(
+    node_provider: &NodeProvider,
+    block_count: u64,
+    last_block_id: BlockId,
+    reward_percentiles: Vec<f64>,
+    cache: Option<&FeeHistoryCache>,
+) -> anyhow::Result<Option<FeeHistory>> {
+    let Some(last_block) = get_block_number_value(node_provider, last_block_id).await? else {
+        return Ok(None);
+    };
+
+    let mut chunks = Vec::new();
+
+    for (chunk_block_count, chunk_last_block) in
+        plan_fee_history_chunks(block_count, last_block.as_u64())
+    {
+        let fee_history = fee_history(
+            node_provider,
+            cache,
+            chunk_block_count,
+            BlockNumber::Number(chunk_last_block.into()),
+            &reward_percentiles,
+        )
+        .await?;
+
+        chunks.push(fee_history);
+    }
+
+    Ok(stitch_fee_histories(chunks))
+}
+
+/// Splits the requested range in (block count, last block) chunks of at most
+/// [`MAX_FEE_HISTORY_BLOCK_COUNT`] blocks, ordered from the oldest to the newest.
+fn plan_fee_history_chunks(block_count: u64, last_block: u64) -> Vec<(u64, u64)> {
+    let mut chunks = Vec::new();
+    let mut remaining_blocks = block_count.min(last_block + 1);
+    let mut chunk_last_block = last_block;
+
+    while remaining_blocks > 0 {
+        let chunk_block_count = remaining_blocks.min(MAX_FEE_HISTORY_BLOCK_COUNT);
+
+        chunks.push((chunk_block_count, chunk_last_block));
+
+        remaining_blocks -= chunk_block_count;
+        chunk_last_block = chunk_last_block.saturating_sub(chunk_block_count);
+    }
+
+    chunks.reverse();
+
+    chunks
+}
 
-        return Ok(Some(fee_history));
+/// Merges consecutive fee histories ordered from the oldest to the newest into one.
+fn stitch_fee_histories(chunks: Vec<FeeHistory>) -> Option<FeeHistory> {
+    let mut chunks = chunks.into_iter();
+    let mut fee_history = chunks.next()?;
+
+    for chunk in chunks {
+        // The last base fee of a chunk is the next block base fee, which is the first base fee of the following chunk
+        fee_history.base_fee_per_gas.pop();
+        fee_history.base_fee_per_gas.extend(chunk.base_fee_per_gas);
+        fee_history.gas_used_ratio.extend(chunk.gas_used_ratio);
+        fee_history.reward.extend(chunk.reward);
     }
 
-    Ok(None)
+    Some(fee_history)
 }
 
 // eth_gasPrice
@@ -50,9 +360,485 @@ pub async fn get_max_priority_fee(node_provider: &NodeProvider) -> anyhow::Resul
     Ok(current_max_priority_fee)
 }
 
+pub const FEE_SUGGESTION_BLOCK_COUNT: u64 = 20;
+pub const FEE_SUGGESTION_PERCENTILES: [f64; 9] =
+    [10.0, 20.0, 30.0, 40.0, 50.0, 60.0, 70.0, 80.0, 90.0];
+
+#[derive(Debug, Serialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct FeeSuggestion {
+    pub within: u64,
+    pub reward_percentile: f64,
+    pub base_fee_per_gas: U256,
+    pub max_priority_fee_per_gas: U256,
+    pub max_fee_per_gas: U256,
+}
+
+// eth_feeHistory
+/// Suggests EIP-1559 fees aimed at getting a transaction included within the provided number of
+/// blocks, based on the priority fees paid in the recent blocks.
+pub async fn suggest_fees(
+    node_provider: &NodeProvider,
+    within: u64,
+    cache: Option<&FeeHistoryCache>,
+) -> anyhow::Result<FeeSuggestion> {
+    let fee_history = fee_history(
+        node_provider,
+        cache,
+        FEE_SUGGESTION_BLOCK_COUNT,
+        BlockNumber::Latest,
+        &FEE_SUGGESTION_PERCENTILES,
+    )
+    .await?;
+
+    Ok(suggest_fees_from_history(&fee_history, within))
+}
+
+/// Each additional block of tolerated delay lowers the reward percentile by one step, from the
+/// 90th percentile for the next block down to the 10th percentile.
+pub fn suggest_fees_from_history(fee_history: &FeeHistory, within: u64) -> FeeSuggestion {
+    let steps = usize::try_from(within.saturating_sub(1)).unwrap_or(usize::MAX);
+    let percentile_idx =
+        FEE_SUGGESTION_PERCENTILES.len() - 1 - steps.min(FEE_SUGGESTION_PERCENTILES.len() - 1);
+
+    let max_priority_fee_per_gas = median_reward(fee_history, percentile_idx).unwrap_or_default();
+    let base_fee_per_gas = next_base_fee(fee_history);
+
+    FeeSuggestion {
+        within,
+        reward_percentile: FEE_SUGGESTION_PERCENTILES[percentile_idx],
+        base_fee_per_gas,
+        max_priority_fee_per_gas,
+        max_fee_per_gas: max_fee_per_gas(base_fee_per_gas, max_priority_fee_per_gas),
+    }
+}
+
+/// Index of the median reward in [`FEE_SUGGESTION_PERCENTILES`].
+const MEDIAN_REWARD_PERCENTILE_IDX: usize = 4;
+
+// eth_feeHistory
+/// Averages the gas price paid over the last `block_count` blocks, as the base fee of each block
+/// plus the median priority fee paid in it. The fee history is requested with the same
+/// percentiles as [`suggest_fees`], so that both share the cached history of the same blocks.
+pub async fn average_price(
+    node_provider: &NodeProvider,
+    block_count: u64,
+    cache: Option<&FeeHistoryCache>,
+) -> anyhow::Result<U256> {
+    let fee_history = fee_history(
+        node_provider,
+        cache,
+        block_count,
+        BlockNumber::Latest,
+        &FEE_SUGGESTION_PERCENTILES,
+    )
+    .await?;
+
+    Ok(average_price_from_history(&fee_history))
+}
+
+pub fn average_price_from_history(fee_history: &FeeHistory) -> U256 {
+    // The base fees hold one more entry than the rewards, the base fee of the next block
+    let prices: Vec<U256> = fee_history
+        .reward
+        .iter()
+        .zip(&fee_history.base_fee_per_gas)
+        .map(|(block_rewards, base_fee)| {
+            *base_fee
+                + block_rewards
+                    .get(MEDIAN_REWARD_PERCENTILE_IDX)
+                    .copied()
+                    .unwrap_or_default()
+        })
+        .collect();
+
+    if prices.is_empty() {
+        return U256::zero();
+    }
+
+    prices.iter().fold(U256::zero(), |sum, price| sum + price) / prices.len()
+}
+
+/// Median across the blocks of the history of the reward at the provided percentile index.
+fn median_reward(fee_history: &FeeHistory, percentile_idx: usize) -> Option<U256> {
+    // Empty blocks report a 0 reward, which says nothing about the fees needed to get included
+    let mut rewards: Vec<U256> = fee_history
+        .reward
+        .iter()
+        .filter_map(|block_rewards| block_rewards.get(percentile_idx).copied())
+        .filter(|reward| !reward.is_zero())
+        .collect();
+    rewards.sort();
+
+    rewards.get(rewards.len() / 2).copied()
+}
+
+fn next_base_fee(fee_history: &FeeHistory) -> U256 {
+    // The last entry is the base fee of the block after the newest one in the history
+    fee_history
+        .base_fee_per_gas
+        .last()
+        .copied()
+        .unwrap_or_default()
+}
+
+fn max_fee_per_gas(base_fee_per_gas: U256, max_priority_fee_per_gas: U256) -> U256 {
+    // Leaves room for the base fee to double before the transaction becomes unincludable
+    base_fee_per_gas * 2 + max_priority_fee_per_gas
+}
+
+/// Reward percentiles of the slow, standard and fast oracle tiers.
+pub const GAS_ORACLE_PERCENTILES: [f64; 3] = [25.0, 50.0, 75.0];
+
+#[derive(Debug, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct FeeTier {
+    pub max_fee_per_gas: U256,
+    pub max_priority_fee_per_gas: U256,
+}
+
+#[derive(Debug, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct GasOracle {
+    pub base_fee: U256,
+    pub gas_price: U256,
+    pub slow: FeeTier,
+    pub standard: FeeTier,
+    pub fast: FeeTier,
+}
+
+// eth_gasPrice || eth_maxPriorityFeePerGas || eth_feeHistory
+/// Recommends slow, standard and fast EIP-1559 fees from the priority fees paid over the last
+/// `block_count` blocks, along with the legacy gas price.
+pub async fn gas_oracle(
+    node_provider: &NodeProvider,
+    block_count: u64,
+    cache: Option<&FeeHistoryCache>,
+) -> anyhow::Result<GasOracle> {
+    let (gas_price, max_priority_fee, fee_history) = futures::try_join!(
+        gas_price(node_provider),
+        get_max_priority_fee(node_provider),
+        fee_history(
+            node_provider,
+            cache,
+            block_count,
+            BlockNumber::Latest,
+            &GAS_ORACLE_PERCENTILES,
+        ),
+    )?;
+
+    Ok(gas_oracle_from_history(
+        &fee_history,
+        gas_price,
+        max_priority_fee,
+    ))
+}
+
+/// Each tier uses the median of the rewards paid at its percentile. Tiers without any reward in
+/// the history (e.g. a run of empty blocks) fall back to the priority fee suggested by the node.
+pub fn gas_oracle_from_history(
+    fee_history: &FeeHistory,
+    gas_price: U256,
+    max_priority_fee: U256,
+) -> GasOracle {
+    let base_fee = next_base_fee(fee_history);
+
+    let [slow, standard, fast] = [0, 1, 2].map(|percentile_idx| {
+        let max_priority_fee_per_gas =
+            median_reward(fee_history, percentile_idx).unwrap_or(max_priority_fee);
+
+        FeeTier {
+            max_fee_per_gas: max_fee_per_gas(base_fee, max_priority_fee_per_gas),
+            max_priority_fee_per_gas,
+        }
+    });
+
+    GasOracle {
+        base_fee,
+        gas_price,
+        slow,
+        standard,
+        fast,
+    }
+}
+
 #[cfg(test)]
 mod tests {
 
+    mod suggest_fees {
+        use std::sync::atomic::Ordering;
+
+        use ethers::types::{BlockNumber, FeeHistory, U256};
+
+        use crate::cmd::{
+            gas::{
+                average_price, get_fee_history, suggest_fees, suggest_fees_from_history,
+                FeeHistoryCache, FEE_SUGGESTION_BLOCK_COUNT, FEE_SUGGESTION_PERCENTILES,
+            },
+            helpers::test::{mock_node_provider, setup_test, spawn_counting_mock_rpc_server},
+        };
+
+        // Every block pays a reward of percentile * (block index + 1) gwei at each percentile
+        fn fee_history(block_count: usize) -> FeeHistory {
+            FeeHistory {
+                base_fee_per_gas: vec![U256::exp10(9); block_count + 1],
+                gas_used_ratio: vec![0.5; block_count],
+                oldest_block: U256::one(),
+                reward: (1..=block_count)
+                    .map(|block| {
+                        FEE_SUGGESTION_PERCENTILES
+                            .iter()
+                            .map(|percentile| U256::from(*percentile as u64 * block as u64))
+                            .collect()
+                    })
+                    .collect(),
+            }
+        }
+
+        #[test]
+        fn should_suggest_a_higher_tip_for_a_faster_inclusion() {
+            // Arrange
+            let fee_history = fee_history(5);
+
+            // Act
+            let next_block = suggest_fees_from_history(&fee_history, 1);
+            let within_ten_blocks = suggest_fees_from_history(&fee_history, 10);
+
+            // Assert
+            assert!(
+                next_block.max_priority_fee_per_gas > within_ten_blocks.max_priority_fee_per_gas
+            );
+            assert_eq!(next_block.reward_percentile, 90.0);
+            assert_eq!(within_ten_blocks.reward_percentile, 10.0);
+        }
+
+        #[test]
+        fn should_suggest_the_median_reward_of_the_non_empty_blocks() {
+            // Arrange
+            let mut fee_history = fee_history(3);
+            fee_history
+                .reward
+                .push(vec![U256::zero(); FEE_SUGGESTION_PERCENTILES.len()]);
+
+            // Act
+            let res = suggest_fees_from_history(&fee_history, 5);
+
+            // Assert
+            assert_eq!(res.reward_percentile, 50.0);
+            assert_eq!(res.max_priority_fee_per_gas, U256::from(100));
+            assert_eq!(res.base_fee_per_gas, U256::exp10(9));
+            assert_eq!(res.max_fee_per_gas, U256::exp10(9) * 2 + 100);
+        }
+
+        #[tokio::test]
+        async fn should_suggest_fees() -> anyhow::Result<()> {
+            // Arrange
+            let (node_provider, _anvil) = setup_test().await?;
+
+            // Act
+            let res = suggest_fees(&node_provider, 3, None).await;
+
+            // Assert
+            assert!(res.is_ok());
+
+            let suggestion = res.unwrap();
+            assert_eq!(suggestion.within, 3);
+            assert!(suggestion.max_fee_per_gas >= suggestion.base_fee_per_gas);
+
+            Ok(())
+        }
+
+        #[tokio::test]
+        async fn should_reuse_the_cached_fee_history_of_the_same_blocks() -> anyhow::Result<()> {
+            // Arrange
+            let (url, requests) = spawn_counting_mock_rpc_server(serde_json::to_value(
+                fee_history(FEE_SUGGESTION_BLOCK_COUNT as usize),
+            )?);
+            let node_provider = mock_node_provider(url).await?;
+
+            let cache = FeeHistoryCache::default();
+
+            // Act
+            let history = get_fee_history(
+                &node_provider,
+                FEE_SUGGESTION_BLOCK_COUNT,
+                BlockNumber::Latest.into(),
+                FEE_SUGGESTION_PERCENTILES.to_vec(),
+                10_000,
+                Some(&cache),
+            )
+            .await;
+            let suggestion = suggest_fees(&node_provider, 3, Some(&cache)).await;
+            let price =
+                average_price(&node_provider, FEE_SUGGESTION_BLOCK_COUNT, Some(&cache)).await;
+
+            // Assert
+            assert!(history?.is_found());
+            assert!(suggestion.is_ok());
+            assert!(price.is_ok());
+            assert_eq!(requests.load(Ordering::SeqCst), 1);
+
+            Ok(())
+        }
+
+        #[tokio::test]
+        async fn should_request_the_fee_history_each_time_without_a_cache() -> anyhow::Result<()> {
+            // Arrange
+            let (url, requests) = spawn_counting_mock_rpc_server(serde_json::to_value(
+                fee_history(FEE_SUGGESTION_BLOCK_COUNT as usize),
+            )?);
+            let node_provider = mock_node_provider(url).await?;
+
+            // Act
+            let suggestion = suggest_fees(&node_provider, 3, None).await;
+            let price = average_price(&node_provider, FEE_SUGGESTION_BLOCK_COUNT, None).await;
+
+            // Assert
+            assert!(suggestion.is_ok());
+            assert!(price.is_ok());
+            assert_eq!(requests.load(Ordering::SeqCst), 2);
+
+            Ok(())
+        }
+    }
+
+    mod average_price {
+        use ethers::types::{FeeHistory, U256};
+
+        use crate::cmd::gas::{average_price_from_history, FEE_SUGGESTION_PERCENTILES};
+
+        #[test]
+        fn should_average_the_base_fee_plus_the_median_reward() {
+            // Arrange
+            let fee_history = FeeHistory {
+                // The last base fee belongs to the next block and is not averaged
+                base_fee_per_gas: vec![100.into(), 200.into(), 10_000.into()],
+                gas_used_ratio: vec![0.5; 2],
+                oldest_block: U256::one(),
+                reward: [10, 30]
+                    .map(|median| {
+                        FEE_SUGGESTION_PERCENTILES
+                            .iter()
+                            .map(|percentile| U256::from(median * (*percentile as u64) / 50))
+                            .collect()
+                    })
+                    .to_vec(),
+            };
+
+            // Act
+            let res = average_price_from_history(&fee_history);
+
+            // Assert
+            assert_eq!(res, U256::from(170));
+        }
+
+        #[test]
+        fn should_average_an_empty_history_to_zero() {
+            // Act
+            let res = average_price_from_history(&FeeHistory {
+                base_fee_per_gas: vec![100.into()],
+                gas_used_ratio: vec![],
+                oldest_block: U256::one(),
+                reward: vec![],
+            });
+
+            // Assert
+            assert_eq!(res, U256::zero());
+        }
+    }
+
+    mod gas_oracle {
+        use ethers::types::{FeeHistory, U256};
+
+        use crate::cmd::{
+            gas::{gas_oracle, gas_oracle_from_history, FeeTier, GasOracle},
+            helpers::test::setup_test,
+        };
+
+        fn gwei(value: u64) -> U256 {
+            U256::from(value) * U256::exp10(9)
+        }
+
+        fn fee_history(rewards: Vec<Vec<U256>>) -> FeeHistory {
+            FeeHistory {
+                base_fee_per_gas: vec![gwei(10); rewards.len() + 1],
+                gas_used_ratio: vec![0.5; rewards.len()],
+                oldest_block: U256::one(),
+                reward: rewards,
+            }
+        }
+
+        #[test]
+        fn should_recommend_the_median_reward_of_each_tier() {
+            // Arrange
+            let fee_history = fee_history(
+                (1..=3)
+                    .map(|block| vec![gwei(block), gwei(2 * block), gwei(3 * block)])
+                    .collect(),
+            );
+
+            // Act
+            let res = gas_oracle_from_history(&fee_history, gwei(12), gwei(1));
+
+            // Assert
+            assert_eq!(
+                res,
+                GasOracle {
+                    base_fee: gwei(10),
+                    gas_price: gwei(12),
+                    slow: FeeTier {
+                        max_fee_per_gas: gwei(22),
+                        max_priority_fee_per_gas: gwei(2),
+                    },
+                    standard: FeeTier {
+                        max_fee_per_gas: gwei(24),
+                        max_priority_fee_per_gas: gwei(4),
+                    },
+                    fast: FeeTier {
+                        max_fee_per_gas: gwei(26),
+                        max_priority_fee_per_gas: gwei(6),
+                    },
+                }
+            );
+        }
+
+        #[test]
+        fn should_fall_back_to_the_node_priority_fee_without_rewards() {
+            // Arrange
+            let fee_history = fee_history(vec![vec![U256::zero(); 3]; 2]);
+
+            // Act
+            let res = gas_oracle_from_history(&fee_history, gwei(12), gwei(1));
+
+            // Assert
+            for tier in [res.slow, res.standard, res.fast] {
+                assert_eq!(
+                    tier,
+                    FeeTier {
+                        max_fee_per_gas: gwei(21),
+                        max_priority_fee_per_gas: gwei(1),
+                    }
+                );
+            }
+        }
+
+        #[tokio::test]
+        async fn should_recommend_fees() -> anyhow::Result<()> {
+            // Arrange
+            let (node_provider, _anvil) = setup_test().await?;
+
+            // Act
+            let res = gas_oracle(&node_provider, 5, None).await?;
+
+            // Assert
+            assert!(res.slow.max_priority_fee_per_gas <= res.standard.max_priority_fee_per_gas);
+            assert!(res.standard.max_priority_fee_per_gas <= res.fast.max_priority_fee_per_gas);
+            assert!(res.fast.max_fee_per_gas >= res.base_fee);
+
+            Ok(())
+        }
+    }
+
     mod estimate_gas {
         use ethers::types::TransactionRequest;
 
@@ -63,10 +849,10 @@ mod tests {
             // Arrange
             let (node_provider, anvil) = setup_test().await?;
 
-            let sender = *anvil.addresses().get(0).unwrap();
+            let sender = *anvil.addresses().first().unwrap();
             let receiver = *anvil.addresses().get(1).unwrap();
 
-            let typed_tx = TransactionRequest::new().from(sender).to(receiver);
+            let typed_tx = TransactionRequest::new().from(sender).to(receiver).into();
 
             let expected_gas = 21_000;
 
@@ -83,11 +869,196 @@ mod tests {
         }
     }
 
+    mod calldata_cost {
+        use ethers::types::U256;
+
+        use crate::cmd::gas::{calldata_cost, CalldataCost};
+
+        #[test]
+        fn should_charge_4_gas_per_zero_byte_and_16_gas_per_non_zero_byte() {
+            // Setup
+            let test_cases: [(&[u8], CalldataCost); 3] = [
+                (
+                    &[],
+                    CalldataCost {
+                        zero_bytes: 0,
+                        non_zero_bytes: 0,
+                        gas: U256::zero(),
+                    },
+                ),
+                (
+                    &[0x00, 0x00, 0x12, 0x34, 0x00],
+                    CalldataCost {
+                        zero_bytes: 3,
+                        non_zero_bytes: 2,
+                        gas: U256::from(44),
+                    },
+                ),
+                (
+                    &[0xa9, 0x05, 0x9c, 0xbb],
+                    CalldataCost {
+                        zero_bytes: 0,
+                        non_zero_bytes: 4,
+                        gas: U256::from(64),
+                    },
+                ),
+            ];
+
+            for (data, expected_res) in test_cases {
+                // Act
+                let res = calldata_cost(data);
+
+                // Assert
+                assert_eq!(res, expected_res, "{data:?}");
+            }
+        }
+    }
+
+    mod estimate_gas_breakdown {
+        use ethers::types::{Bytes, TransactionRequest, U256};
+
+        use crate::cmd::{gas::estimate_gas_breakdown, helpers::test::setup_test};
+
+        #[tokio::test]
+        async fn should_split_the_estimate_in_base_calldata_and_execution_gas() -> anyhow::Result<()>
+        {
+            // Arrange
+            let (node_provider, anvil) = setup_test().await?;
+
+            let sender = *anvil.addresses().first().unwrap();
+            let receiver = *anvil.addresses().get(1).unwrap();
+
+            let typed_tx = TransactionRequest::new()
+                .from(sender)
+                .to(receiver)
+                .data(Bytes::from_static(&[0x00, 0x12]))
+                .into();
+
+            // Act
+            let res = estimate_gas_breakdown(&node_provider, typed_tx, None).await?;
+
+            // Assert
+            assert_eq!(res.base, U256::from(21_000));
+            assert_eq!(res.calldata.gas, U256::from(20));
+            assert_eq!(res.total, res.base + res.calldata.gas + res.execution);
+
+            Ok(())
+        }
+    }
+
+    mod estimate_gas_variance {
+        use ethers::{
+            types::{BlockId, BlockNumber, TransactionRequest, U256},
+            utils::parse_ether,
+        };
+
+        use crate::cmd::{
+            gas::{estimate_gas_variance, MAX_GAS_VARIANCE_BLOCK_RANGE},
+            helpers::test::{send_tx_helper, setup_test},
+        };
+
+        const MAX_BLOCK_RANGE: u64 = 10_000;
+
+        #[tokio::test]
+        async fn should_estimate_the_gas_for_each_block_in_the_range() -> anyhow::Result<()> {
+            // Arrange
+            let (node_provider, anvil) = setup_test().await?;
+
+            let sender = *anvil.addresses().first().unwrap();
+            let receiver = *anvil.addresses().get(1).unwrap();
+
+            send_tx_helper(&node_provider, sender, receiver, parse_ether(1)?).await?;
+
+            let typed_tx = TransactionRequest::new().from(sender).to(receiver).into();
+
+            let expected_gas: U256 = 21_000.into();
+
+            // Act
+            let res = estimate_gas_variance(
+                &node_provider,
+                typed_tx,
+                BlockId::Number(BlockNumber::Earliest),
+                BlockId::Number(BlockNumber::Latest),
+                MAX_BLOCK_RANGE,
+            )
+            .await;
+
+            // Assert
+            assert!(res.is_ok());
+            let res = res.unwrap();
+
+            assert_eq!(res.by_block.len(), 2);
+            assert_eq!(res.min, expected_gas);
+            assert_eq!(res.max, expected_gas);
+            assert_eq!(res.std_dev, 0.0);
+
+            Ok(())
+        }
+
+        #[tokio::test]
+        async fn should_not_estimate_the_gas_for_a_range_above_the_cap() -> anyhow::Result<()> {
+            // Arrange
+            let (node_provider, anvil) = setup_test().await?;
+
+            let sender = *anvil.addresses().first().unwrap();
+            let receiver = *anvil.addresses().get(1).unwrap();
+
+            for _ in 0..MAX_GAS_VARIANCE_BLOCK_RANGE {
+                send_tx_helper(&node_provider, sender, receiver, parse_ether(1)?).await?;
+            }
+
+            let typed_tx = TransactionRequest::new().from(sender).to(receiver).into();
+
+            // Act
+            let res = estimate_gas_variance(
+                &node_provider,
+                typed_tx,
+                BlockId::Number(BlockNumber::Earliest),
+                BlockId::Number(BlockNumber::Latest),
+                MAX_BLOCK_RANGE,
+            )
+            .await;
+
+            // Assert
+            assert!(res.is_err());
+            assert!(res
+                .unwrap_err()
+                .to_string()
+                .contains("Block range too large"));
+
+            Ok(())
+        }
+    }
+
     mod get_fee_history {
         use ethers::types::{BlockNumber, H256};
 
         use crate::cmd::{gas::get_fee_history, helpers::test::setup_test};
 
+        const MAX_BLOCK_RANGE: u64 = 10_000;
+
+        #[tokio::test]
+        async fn should_not_get_the_fee_history_of_a_range_above_the_limit() -> anyhow::Result<()> {
+            // Arrange
+            let (node_provider, _anvil) = setup_test().await?;
+
+            // Act
+            let res = get_fee_history(
+                &node_provider,
+                10,
+                BlockNumber::Latest.into(),
+                [90.0].into(),
+                5,
+                None,
+            )
+            .await;
+
+            // Assert
+            assert!(res.is_err());
+
+            Ok(())
+        }
+
         #[tokio::test]
         async fn should_get_the_fee_history() -> anyhow::Result<()> {
             // Arrange
@@ -96,9 +1067,11 @@ mod tests {
             // Act
             let res = get_fee_history(
                 &node_provider,
-                10.into(),
+                10,
                 BlockNumber::Finalized.into(),
                 [90.0, 97.7].into(),
+                MAX_BLOCK_RANGE,
+                None,
             )
             .await;
 
@@ -106,7 +1079,7 @@ mod tests {
             assert!(res.is_ok());
             let res = res.unwrap();
 
-            assert!(res.is_some());
+            assert!(res.is_found());
 
             Ok(())
         }
@@ -119,11 +1092,13 @@ mod tests {
             // Act
             let res = get_fee_history(
                 &node_provider,
-                10.into(),
+                10,
                 "0xef94b6d16908712a41ea538a21944826404d72be407bef0b050b7bab41300ec8"
                     .parse::<H256>()?
                     .into(),
                 [90.0, 97.7].into(),
+                MAX_BLOCK_RANGE,
+                None,
             )
             .await;
 
@@ -131,12 +1106,88 @@ mod tests {
             assert!(res.is_ok());
             let res = res.unwrap();
 
-            assert!(res.is_none());
+            assert!(res.is_not_found());
 
             Ok(())
         }
     }
 
+    mod plan_fee_history_chunks {
+        use crate::cmd::gas::{plan_fee_history_chunks, MAX_FEE_HISTORY_BLOCK_COUNT};
+
+        #[test]
+        fn should_split_the_range_in_chunks_ordered_from_the_oldest() {
+            // Setup
+            let test_cases = [
+                (10, 100, vec![(10, 100)]),
+                (
+                    MAX_FEE_HISTORY_BLOCK_COUNT,
+                    5000,
+                    vec![(MAX_FEE_HISTORY_BLOCK_COUNT, 5000)],
+                ),
+                (2500, 5000, vec![(452, 2952), (1024, 3976), (1024, 5000)]),
+                (3000, 1999, vec![(976, 975), (1024, 1999)]),
+            ];
+
+            for (block_count, last_block, expected_res) in test_cases {
+                // Act
+                let res = plan_fee_history_chunks(block_count, last_block);
+
+                // Assert
+                assert_eq!(res, expected_res);
+            }
+        }
+    }
+
+    mod stitch_fee_histories {
+        use ethers::types::{FeeHistory, U256};
+
+        use crate::cmd::gas::stitch_fee_histories;
+
+        fn fee_history(oldest_block: u64, base_fees: &[u64], rewards: &[u64]) -> FeeHistory {
+            FeeHistory {
+                base_fee_per_gas: base_fees.iter().map(|fee| U256::from(*fee)).collect(),
+                gas_used_ratio: rewards.iter().map(|reward| *reward as f64 / 10.0).collect(),
+                oldest_block: oldest_block.into(),
+                reward: rewards
+                    .iter()
+                    .map(|reward| vec![U256::from(*reward)])
+                    .collect(),
+            }
+        }
+
+        #[test]
+        fn should_stitch_consecutive_fee_histories() {
+            // Arrange
+            let chunks = vec![
+                fee_history(10, &[100, 101, 102], &[1, 2]),
+                fee_history(12, &[102, 103, 104], &[3, 4]),
+                fee_history(14, &[104, 105], &[5]),
+            ];
+
+            // Act
+            let res = stitch_fee_histories(chunks);
+
+            // Assert
+            let res = res.unwrap();
+            let expected_res = fee_history(10, &[100, 101, 102, 103, 104, 105], &[1, 2, 3, 4, 5]);
+
+            assert_eq!(res.oldest_block, expected_res.oldest_block);
+            assert_eq!(res.base_fee_per_gas, expected_res.base_fee_per_gas);
+            assert_eq!(res.gas_used_ratio, expected_res.gas_used_ratio);
+            assert_eq!(res.reward, expected_res.reward);
+        }
+
+        #[test]
+        fn should_not_stitch_an_empty_list_of_fee_histories() {
+            // Act
+            let res = stitch_fee_histories(vec![]);
+
+            // Assert
+            assert!(res.is_none());
+        }
+    }
+
     mod gas_price {
         use crate::cmd::{gas::gas_price, helpers::test::setup_test};
 
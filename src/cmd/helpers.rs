@@ -1,9 +1,14 @@
+use std::{future::Future, ops::RangeInclusive, sync::Arc, time::Duration};
+
 use ethers::{
-    providers::Middleware,
-    types::{Block, BlockId, BlockNumber, H256},
+    providers::{JsonRpcError, Middleware, MiddlewareError, ProviderError},
+    types::{Block, BlockId, BlockNumber, H256, U64},
 };
+use futures::{stream, Stream, StreamExt};
+
+use crate::context::{NodeProvider, NodeProviderError};
 
-use crate::context::NodeProvider;
+use super::block::{get_block, BlockKind};
 
 pub async fn get_raw_block(
     node_provider: &NodeProvider,
@@ -35,15 +40,232 @@ pub async fn get_block_number_by_block_id(
     Ok(Some(block_number))
 }
 
+pub async fn get_block_number_value(
+    node_provider: &NodeProvider,
+    block_id: BlockId,
+) -> anyhow::Result<Option<U64>> {
+    let block_number = get_raw_block(node_provider, block_id)
+        .await?
+        .and_then(|block| block.number);
+
+    Ok(block_number)
+}
+
+/// Fails if the operation doesn't complete within `timeout`, so that a node that stops answering
+/// doesn't hang the command.
+pub async fn with_timeout<T>(
+    timeout: Duration,
+    operation: &str,
+    f: impl Future<Output = anyhow::Result<T>>,
+) -> anyhow::Result<T> {
+    tokio::time::timeout(timeout, f).await.map_err(|_| {
+        anyhow::anyhow!(
+            "The {operation} timed out after {}s. Raise the limit with --command-timeout",
+            timeout.as_secs_f64()
+        )
+    })?
+}
+
+/// Refuses to scan ranges of more than `max_block_range` blocks to prevent accidental full chain scans.
+pub fn ensure_block_range_within_limit(range_len: u64, max_block_range: u64) -> anyhow::Result<()> {
+    if range_len > max_block_range {
+        anyhow::bail!(
+            "Block range of {range_len} blocks exceeds the maximum of {max_block_range} blocks. Split the scan in smaller chunks or raise the limit with --max-block-range"
+        );
+    }
+
+    Ok(())
+}
+
+const METHOD_NOT_FOUND_CODE: i64 = -32601;
+
+// Some hosted providers reject an unknown method with a non standard error code
+const METHOD_NOT_FOUND_MESSAGE: &str = "method not found";
+
+/// Extracts the json rpc error returned by the node, if the error is one.
+pub fn as_json_rpc_error(err: &anyhow::Error) -> Option<&JsonRpcError> {
+    if let Some(err) = err.downcast_ref::<ProviderError>() {
+        err.as_error_response()
+    } else if let Some(err) = err.downcast_ref::<NodeProviderError>() {
+        err.as_error_response()
+    } else if let Some(err) = err.downcast_ref::<JsonRpcError>() {
+        Some(err)
+    } else {
+        None
+    }
+}
+
+/// Tells if the error is the node rejecting a json rpc method that it does not implement.
+pub fn is_method_not_found(err: &anyhow::Error) -> bool {
+    as_json_rpc_error(err).is_some_and(is_method_not_found_response)
+}
+
+fn is_method_not_found_response(err: &JsonRpcError) -> bool {
+    let message = err.message.to_lowercase();
+
+    err.code == METHOD_NOT_FOUND_CODE || message.contains(METHOD_NOT_FOUND_MESSAGE)
+}
+
+// Nodes report a block hash they don't know with a generic error
+const UNKNOWN_BLOCK_MESSAGES: [&str; 4] = [
+    "unknown block",
+    "block not found",
+    "header not found",
+    "could not find block",
+];
+
+/// Tells if the node rejected the request because the queried block does not exist.
+pub fn is_unknown_block(err: &anyhow::Error) -> bool {
+    as_json_rpc_error(err).is_some_and(|err| {
+        let message = err.message.to_lowercase();
+
+        UNKNOWN_BLOCK_MESSAGES
+            .iter()
+            .any(|pattern| message.contains(pattern))
+    })
+}
+
+// Json rpc error codes used by nodes and hosted providers to report a rate limited request
+const RATE_LIMITED_CODES: [i64; 2] = [429, -32005];
+
+const RATE_LIMITED_MESSAGES: [&str; 2] = ["rate limit", "too many requests"];
+
+/// Tells if the request failed for a reason that may go away when retried: the node being
+/// unreachable, the request timing out or the node rate limiting it. Errors returned by the node
+/// for the request itself, like invalid params or a revert, are final.
+pub fn is_transient_error(err: &anyhow::Error) -> bool {
+    if let Some(err) = as_json_rpc_error(err) {
+        return is_rate_limited_response(err);
+    }
+
+    let provider_error = err.downcast_ref::<ProviderError>().or_else(|| {
+        err.downcast_ref::<NodeProviderError>()
+            .and_then(MiddlewareError::as_inner)
+    });
+
+    match provider_error {
+        Some(ProviderError::HTTPError(_)) => true,
+        Some(ProviderError::JsonRpcClientError(err)) => err.as_serde_error().is_none(),
+        _ => err.downcast_ref::<reqwest::Error>().is_some(),
+    }
+}
+
+fn is_rate_limited_response(err: &JsonRpcError) -> bool {
+    let message = err.message.to_lowercase();
+
+    RATE_LIMITED_CODES.contains(&err.code)
+        || RATE_LIMITED_MESSAGES
+            .iter()
+            .any(|pattern| message.contains(pattern))
+}
+
+pub const MAX_FETCH_ATTEMPTS: usize = 3;
+
+/// Backoff before the first retry of a failed fetch, doubled on each following one.
+pub const INITIAL_FETCH_BACKOFF: Duration = Duration::from_millis(100);
+
+/// Fetches every item in the range with at most `concurrency` requests in flight, yielding the
+/// results in range order. Fetches failing with a transient error are retried up to
+/// [`MAX_FETCH_ATTEMPTS`] times with an exponential backoff and `on_progress` is called with the
+/// number of fetched items and the range length.
+pub fn buffered_fetch<'a, T, F, Fut>(
+    range: RangeInclusive<u64>,
+    concurrency: usize,
+    fetch: F,
+    on_progress: impl Fn(u64, u64) + 'a,
+) -> impl Stream<Item = anyhow::Result<T>> + 'a
+where
+    T: 'a,
+    F: Fn(u64) -> Fut + 'a,
+    Fut: Future<Output = anyhow::Result<T>> + 'a,
+{
+    let total = range.end().saturating_sub(*range.start()) + 1;
+    let fetch = Arc::new(fetch);
+    let mut fetched = 0;
+
+    stream::iter(range)
+        .map(move |idx| {
+            let fetch = fetch.clone();
+
+            async move {
+                let mut attempt = 1;
+                let mut backoff = INITIAL_FETCH_BACKOFF;
+
+                loop {
+                    match fetch(idx).await {
+                        Err(err) if attempt < MAX_FETCH_ATTEMPTS && is_transient_error(&err) => {
+                            tokio::time::sleep(backoff).await;
+
+                            attempt += 1;
+                            backoff *= 2;
+                        }
+                        res => return res,
+                    }
+                }
+            }
+        })
+        .buffered(concurrency.max(1))
+        .map(move |res| {
+            fetched += 1;
+            on_progress(fetched, total);
+
+            res
+        })
+}
+
+/// Default number of blocks requested concurrently by the block range fetches.
+pub const BLOCK_FETCH_CONCURRENCY: usize = 8;
+
+/// Fetches the blocks in the provided range, yielding them in block number order. The requests go
+/// through the node provider, so rate limited ones are retried with its backoff.
+pub fn fetch_blocks_stream<'a>(
+    node_provider: &'a NodeProvider,
+    range: RangeInclusive<u64>,
+    include_tx: bool,
+    concurrency: usize,
+    on_progress: impl Fn(u64, u64) + 'a,
+) -> impl Stream<Item = anyhow::Result<BlockKind>> + 'a {
+    buffered_fetch(
+        range,
+        concurrency,
+        move |block_number| async move {
+            get_block(
+                node_provider,
+                BlockId::Number(BlockNumber::Number(block_number.into())),
+                include_tx,
+                None,
+            )
+            .await?
+            .found()
+            .ok_or(anyhow::anyhow!("Block {block_number} not found"))
+        },
+        on_progress,
+    )
+}
+
 #[cfg(test)]
 pub mod test {
 
+    use std::{
+        io::{BufRead, BufReader, Read, Write},
+        net::TcpListener,
+        sync::{
+            atomic::{AtomicUsize, Ordering},
+            Arc,
+        },
+        thread,
+        time::Duration,
+    };
+
     use ethers::{
+        abi::{self, Token},
         providers::Middleware,
-        types::{TransactionReceipt, TransactionRequest, H160, H256, U256},
-        utils::{Anvil, AnvilInstance},
+        types::{Bytes, TransactionReceipt, TransactionRequest, H160, H256, U256},
+        utils::{keccak256, Anvil, AnvilInstance},
     };
+    use native_tls::{Identity, TlsAcceptor};
     use rand::Rng;
+    use serde_json::{json, Value};
 
     use crate::{
         config::{get_config, ConfigOverrides},
@@ -78,6 +300,86 @@ pub mod test {
         Ok(tx.unwrap())
     }
 
+    /// Deploys the provided contract creation code and returns the contract address.
+    pub async fn deploy_contract_helper(
+        node_provider: &NodeProvider,
+        deployer: H160,
+        init_code: Bytes,
+    ) -> anyhow::Result<H160> {
+        let tx = TransactionRequest::new().from(deployer).data(init_code);
+
+        let receipt = node_provider
+            .send_transaction(tx, None)
+            .await?
+            .await?
+            .unwrap();
+
+        Ok(receipt.contract_address.unwrap())
+    }
+
+    /// Creates a node provider connected to the mock rpc server at the provided url.
+    pub async fn mock_node_provider(url: String) -> anyhow::Result<NodeProvider> {
+        let config = get_config(ConfigOverrides::new(None, Some(url), None))?;
+
+        Ok(NodeProvider::new(&config).await?)
+    }
+
+    /// Reason of the revert of every call to the [`reverting_contract_init_code`] fixture.
+    pub const REVERT_REASON: &str = "nope";
+
+    /// Encodes the revert data of a `require` failing with the provided reason.
+    pub fn error_string_revert_data(reason: &str) -> Vec<u8> {
+        let mut revert_data = vec![0x08, 0xc3, 0x79, 0xa0];
+        revert_data.extend(abi::encode(&[Token::String(reason.to_string())]));
+
+        revert_data
+    }
+
+    // Fixture contract that reverts every call with Error("nope"):
+    // constructor: PUSH1 0x70, DUP1, PUSH1 0x0b, PUSH1 0, CODECOPY, PUSH1 0, RETURN
+    // runtime: PUSH1 0x64, PUSH1 0x0c, PUSH1 0, CODECOPY, PUSH1 0x64, PUSH1 0, REVERT, <revert data>
+    pub fn reverting_contract_init_code() -> Bytes {
+        let mut init_code = vec![
+            0x60, 0x70, 0x80, 0x60, 0x0b, 0x60, 0x00, 0x39, 0x60, 0x00, 0xf3,
+        ];
+        init_code.extend_from_slice(&[
+            0x60, 0x64, 0x60, 0x0c, 0x60, 0x00, 0x39, 0x60, 0x64, 0x60, 0x00, 0xfd,
+        ]);
+        init_code.extend(error_string_revert_data(REVERT_REASON));
+
+        init_code.into()
+    }
+
+    pub fn transfer_topic() -> H256 {
+        keccak256("Transfer(address,address,uint256)").into()
+    }
+
+    /// Receiver of the transfers emitted by the [`transfer_emitter_init_code`] fixture.
+    pub fn transfer_receiver() -> H160 {
+        H160::repeat_byte(0x11)
+    }
+
+    // Fixture token that emits Transfer(msg.sender, receiver, 42) and returns 6 on every call,
+    // so that it also answers decimals():
+    // constructor: PUSH1 0x4b, DUP1, PUSH1 0x0b, PUSH1 0, CODECOPY, PUSH1 0, RETURN
+    // runtime: PUSH1 42, PUSH1 0, MSTORE, PUSH20 receiver, CALLER, PUSH32 topic,
+    // PUSH1 32, PUSH1 0, LOG3, PUSH1 6, PUSH1 0, MSTORE, PUSH1 32, PUSH1 0, RETURN
+    pub fn transfer_emitter_init_code() -> Bytes {
+        let mut init_code = vec![
+            0x60, 0x4b, 0x80, 0x60, 0x0b, 0x60, 0x00, 0x39, 0x60, 0x00, 0xf3,
+        ];
+        init_code.extend_from_slice(&[0x60, 0x2a, 0x60, 0x00, 0x52, 0x73]);
+        init_code.extend_from_slice(transfer_receiver().as_bytes());
+        init_code.extend_from_slice(&[0x33, 0x7f]);
+        init_code.extend_from_slice(transfer_topic().as_bytes());
+        init_code.extend_from_slice(&[
+            0x60, 0x20, 0x60, 0x00, 0xa3, 0x60, 0x06, 0x60, 0x00, 0x52, 0x60, 0x20, 0x60, 0x00,
+            0xf3,
+        ]);
+
+        init_code.into()
+    }
+
     pub fn generate_random_h256() -> H256 {
         let mut data = [0u8; 32];
 
@@ -85,4 +387,565 @@ pub mod test {
 
         data.into()
     }
+
+    /// Spawns a keep-alive json rpc server that answers every request with the provided result
+    /// after the provided latency. Returns the server url and the number of accepted connections.
+    pub fn spawn_mock_rpc_server(result: Value, latency: Duration) -> (String, Arc<AtomicUsize>) {
+        let (url, connections, _) =
+            spawn_mock_rpc_server_with_response(rpc_response("result", result), latency);
+
+        (url, connections)
+    }
+
+    /// Spawns a keep-alive json rpc server that answers every request with the provided result.
+    /// Returns the server url and the number of answered requests.
+    pub fn spawn_counting_mock_rpc_server(result: Value) -> (String, Arc<AtomicUsize>) {
+        let (url, _, requests) =
+            spawn_mock_rpc_server_with_response(rpc_response("result", result), Duration::ZERO);
+
+        (url, requests)
+    }
+
+    /// Spawns a keep-alive json rpc server that answers every request with the provided error.
+    pub fn spawn_mock_rpc_error_server(error: Value) -> String {
+        let (url, _, _) =
+            spawn_mock_rpc_server_with_response(rpc_response("error", error), Duration::ZERO);
+
+        url
+    }
+
+    /// Spawns a keep-alive json rpc server that answers each method with the response registered
+    /// for it, an object holding either a `result` or an `error` field. Unregistered methods are
+    /// answered with a method not found error.
+    pub fn spawn_mock_rpc_method_server(responses: Value) -> String {
+        let respond = move |request: &Value| {
+            let response_fields = request["method"]
+                .as_str()
+                .and_then(|method| responses.get(method))
+                .cloned()
+                .unwrap_or_else(
+                    || json!({ "error": { "code": -32601, "message": "Method not found" } }),
+                );
+
+            let mut response = json!({ "jsonrpc": "2.0", "id": request["id"] });
+            response
+                .as_object_mut()
+                .unwrap()
+                .extend(response_fields.as_object().cloned().unwrap_or_default());
+
+            response
+        };
+
+        let (url, _, _) = spawn_mock_rpc_server_with_response(respond, Duration::ZERO);
+
+        url
+    }
+
+    /// Spawns a keep-alive http server that answers every request with the provided json body.
+    /// Returns the server url and the number of answered requests.
+    pub fn spawn_mock_http_server(body: Value) -> (String, Arc<AtomicUsize>) {
+        let (url, _, requests) =
+            spawn_mock_rpc_server_with_response(move |_: &Value| body.clone(), Duration::ZERO);
+
+        (url, requests)
+    }
+
+    /// Answers every json rpc request with the provided value under the provided response field.
+    fn rpc_response(field: &'static str, value: Value) -> impl Fn(&Value) -> Value {
+        move |request| json!({ "jsonrpc": "2.0", "id": request["id"], field: value })
+    }
+
+    /// Spawns a keep-alive json rpc server behind TLS with a self-signed certificate, answering
+    /// every request with the provided result. Returns the https server url and the number of
+    /// accepted connections.
+    pub fn spawn_mock_tls_rpc_server(result: Value) -> (String, Arc<AtomicUsize>) {
+        let identity = Identity::from_pkcs8(
+            include_bytes!("../../tests/tls/cert.pem"),
+            include_bytes!("../../tests/tls/key.pem"),
+        )
+        .unwrap();
+        let acceptor = Arc::new(TlsAcceptor::new(identity).unwrap());
+        let respond = Arc::new(rpc_response("result", result));
+
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("https://{}", listener.local_addr().unwrap());
+
+        let connections = Arc::new(AtomicUsize::new(0));
+        let accepted_connections = connections.clone();
+
+        thread::spawn(move || {
+            for stream in listener.incoming().flatten() {
+                accepted_connections.fetch_add(1, Ordering::SeqCst);
+
+                let acceptor = acceptor.clone();
+                let respond = respond.clone();
+                thread::spawn(move || {
+                    if let Ok(stream) = acceptor.accept(stream) {
+                        serve_mock_rpc_connection(
+                            stream,
+                            &*respond,
+                            Duration::ZERO,
+                            &AtomicUsize::new(0),
+                        )
+                    }
+                });
+            }
+        });
+
+        (url, connections)
+    }
+
+    /// Answers every request with the body built by the provided function from the request.
+    pub fn spawn_mock_rpc_server_with_response(
+        respond: impl Fn(&Value) -> Value + Send + Sync + 'static,
+        latency: Duration,
+    ) -> (String, Arc<AtomicUsize>, Arc<AtomicUsize>) {
+        let respond = Arc::new(respond);
+
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+
+        let connections = Arc::new(AtomicUsize::new(0));
+        let accepted_connections = connections.clone();
+        let requests = Arc::new(AtomicUsize::new(0));
+        let answered_requests = requests.clone();
+
+        thread::spawn(move || {
+            for stream in listener.incoming().flatten() {
+                accepted_connections.fetch_add(1, Ordering::SeqCst);
+
+                let respond = respond.clone();
+                let answered_requests = answered_requests.clone();
+                thread::spawn(move || {
+                    serve_mock_rpc_connection(stream, &*respond, latency, &answered_requests)
+                });
+            }
+        });
+
+        (url, connections, requests)
+    }
+
+    fn serve_mock_rpc_connection(
+        stream: impl Read + Write,
+        respond: &dyn Fn(&Value) -> Value,
+        latency: Duration,
+        answered_requests: &AtomicUsize,
+    ) {
+        let mut reader = BufReader::new(stream);
+
+        loop {
+            let mut content_length = 0;
+
+            loop {
+                let mut line = String::new();
+                if reader.read_line(&mut line).unwrap_or(0) == 0 {
+                    return;
+                }
+
+                let line = line.trim_end();
+                if line.is_empty() {
+                    break;
+                }
+
+                if let Some((name, value)) = line.split_once(':') {
+                    if name.eq_ignore_ascii_case("content-length") {
+                        content_length = value.trim().parse().unwrap_or_default();
+                    }
+                }
+            }
+
+            let mut body = vec![0; content_length];
+            if reader.read_exact(&mut body).is_err() {
+                return;
+            }
+
+            let request: Value = serde_json::from_slice(&body).unwrap_or_default();
+
+            thread::sleep(latency);
+            answered_requests.fetch_add(1, Ordering::SeqCst);
+
+            let response = respond(&request).to_string();
+
+            let response = format!(
+                "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\n\r\n{}",
+                response.len(),
+                response
+            );
+
+            if reader.get_mut().write_all(response.as_bytes()).is_err() {
+                return;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    mod ensure_block_range_within_limit {
+        use crate::cmd::helpers::ensure_block_range_within_limit;
+
+        #[test]
+        fn should_accept_a_range_within_the_limit() {
+            // Setup
+            let test_cases = [(1, 1), (10, 100), (100, 100)];
+
+            for (range_len, max_block_range) in test_cases {
+                // Act
+                let res = ensure_block_range_within_limit(range_len, max_block_range);
+
+                // Assert
+                assert!(res.is_ok());
+            }
+        }
+
+        #[test]
+        fn should_reject_a_range_above_the_limit() {
+            // Act
+            let res = ensure_block_range_within_limit(101, 100);
+
+            // Assert
+            assert!(res.is_err());
+            assert!(res.unwrap_err().to_string().contains("--max-block-range"));
+        }
+    }
+
+    mod buffered_fetch {
+        use std::{
+            cell::RefCell,
+            collections::HashMap,
+            time::{Duration, Instant},
+        };
+
+        use ethers::providers::JsonRpcError;
+        use futures::StreamExt;
+
+        use crate::cmd::helpers::{buffered_fetch, INITIAL_FETCH_BACKOFF, MAX_FETCH_ATTEMPTS};
+
+        const MOCK_LATENCY: Duration = Duration::from_millis(50);
+
+        async fn slow_fetch(idx: u64) -> anyhow::Result<u64> {
+            // Later items resolve first to make sure the output order is preserved
+            tokio::time::sleep(MOCK_LATENCY * (10 - idx as u32) / 10 + MOCK_LATENCY).await;
+
+            Ok(idx)
+        }
+
+        #[tokio::test]
+        async fn should_yield_the_items_in_range_order() -> anyhow::Result<()> {
+            // Act
+            let res: Vec<u64> = buffered_fetch(0..=9, 5, slow_fetch, |_, _| {})
+                .map(Result::unwrap)
+                .collect()
+                .await;
+
+            // Assert
+            assert_eq!(res, (0..=9).collect::<Vec<u64>>());
+
+            Ok(())
+        }
+
+        #[tokio::test]
+        async fn should_fetch_the_items_concurrently() -> anyhow::Result<()> {
+            // Arrange
+            let start = Instant::now();
+
+            // Act
+            let res: Vec<anyhow::Result<u64>> = buffered_fetch(0..=9, 10, slow_fetch, |_, _| {})
+                .collect()
+                .await;
+
+            // Assert
+            assert_eq!(res.len(), 10);
+            assert!(start.elapsed() < MOCK_LATENCY * 5);
+
+            Ok(())
+        }
+
+        fn rate_limited_error() -> anyhow::Error {
+            JsonRpcError {
+                code: 429,
+                message: "Too Many Requests".to_string(),
+                data: None,
+            }
+            .into()
+        }
+
+        #[tokio::test]
+        async fn should_retry_the_fetches_failing_with_a_transient_error() -> anyhow::Result<()> {
+            // Arrange
+            let attempts: RefCell<HashMap<u64, usize>> = RefCell::default();
+            let start = Instant::now();
+
+            let flaky_fetch = |idx: u64| {
+                let attempt = {
+                    let mut attempts = attempts.borrow_mut();
+                    let attempt = attempts.entry(idx).or_default();
+                    *attempt += 1;
+                    *attempt
+                };
+
+                async move {
+                    if idx.is_multiple_of(2) && attempt < MAX_FETCH_ATTEMPTS {
+                        return Err(rate_limited_error());
+                    }
+
+                    Ok(idx)
+                }
+            };
+
+            // Act
+            let res: Vec<anyhow::Result<u64>> = buffered_fetch(0..=3, 2, flaky_fetch, |_, _| {})
+                .collect()
+                .await;
+
+            // Assert
+            assert!(res.iter().all(Result::is_ok));
+            assert_eq!(attempts.borrow()[&0], MAX_FETCH_ATTEMPTS);
+            assert_eq!(attempts.borrow()[&1], 1);
+            assert!(start.elapsed() >= INITIAL_FETCH_BACKOFF * 3);
+
+            Ok(())
+        }
+
+        #[tokio::test]
+        async fn should_not_retry_the_fetches_failing_with_a_final_error() -> anyhow::Result<()> {
+            // Arrange
+            let attempts: RefCell<HashMap<u64, usize>> = RefCell::default();
+
+            let failing_fetch = |idx: u64| {
+                *attempts.borrow_mut().entry(idx).or_default() += 1;
+
+                async move {
+                    let err: anyhow::Error = match idx {
+                        0 => JsonRpcError {
+                            code: -32602,
+                            message: "invalid argument 0: hex string without 0x prefix".to_string(),
+                            data: None,
+                        }
+                        .into(),
+                        1 => JsonRpcError {
+                            code: 3,
+                            message: "execution reverted".to_string(),
+                            data: None,
+                        }
+                        .into(),
+                        _ => anyhow::anyhow!("Block {idx} not found"),
+                    };
+
+                    Err::<u64, _>(err)
+                }
+            };
+
+            // Act
+            let res: Vec<anyhow::Result<u64>> = buffered_fetch(0..=2, 3, failing_fetch, |_, _| {})
+                .collect()
+                .await;
+
+            // Assert
+            assert!(res.iter().all(Result::is_err));
+            assert!(attempts.borrow().values().all(|attempts| *attempts == 1));
+
+            Ok(())
+        }
+
+        #[tokio::test]
+        async fn should_report_the_progress() -> anyhow::Result<()> {
+            // Arrange
+            let progress: RefCell<Vec<(u64, u64)>> = RefCell::default();
+
+            // Act
+            let _: Vec<anyhow::Result<u64>> = buffered_fetch(
+                0..=2,
+                2,
+                |idx| async move { Ok(idx) },
+                |fetched, total| progress.borrow_mut().push((fetched, total)),
+            )
+            .collect()
+            .await;
+
+            // Assert
+            assert_eq!(progress.into_inner(), [(1, 3), (2, 3), (3, 3)]);
+
+            Ok(())
+        }
+    }
+
+    mod fetch_blocks_stream {
+        use ethers::utils::parse_ether;
+        use futures::StreamExt;
+
+        use crate::cmd::{
+            block::BlockKind,
+            helpers::{
+                fetch_blocks_stream,
+                test::{send_tx_helper, setup_test},
+            },
+        };
+
+        #[tokio::test]
+        async fn should_fetch_the_blocks_in_order() -> anyhow::Result<()> {
+            // Arrange
+            let (node_provider, anvil) = setup_test().await?;
+
+            let sender = *anvil.addresses().first().unwrap();
+            let receiver = *anvil.addresses().get(1).unwrap();
+
+            for _ in 0..5 {
+                send_tx_helper(&node_provider, sender, receiver, parse_ether(1)?).await?;
+            }
+
+            // Act
+            let res: Vec<anyhow::Result<BlockKind>> =
+                fetch_blocks_stream(&node_provider, 0..=5, false, 3, |_, _| {})
+                    .collect()
+                    .await;
+
+            // Assert
+            let block_numbers: Vec<u64> = res
+                .into_iter()
+                .map(|block| match block.unwrap() {
+                    BlockKind::RawBlock(block) => block.number.unwrap().as_u64(),
+                    BlockKind::BlockWithTransaction(block) => block.number.unwrap().as_u64(),
+                })
+                .collect();
+
+            assert_eq!(block_numbers, (0..=5).collect::<Vec<u64>>());
+
+            Ok(())
+        }
+    }
+
+    mod is_transient_error {
+        use ethers::providers::{JsonRpcError, ProviderError};
+        use serde_json::json;
+
+        use crate::{cmd::helpers::is_transient_error, context::RpcTransportError};
+
+        fn error_response(payload: serde_json::Value) -> anyhow::Error {
+            serde_json::from_value::<JsonRpcError>(payload)
+                .unwrap()
+                .into()
+        }
+
+        #[test]
+        fn should_detect_the_transport_and_rate_limit_errors() {
+            // Arrange
+            let test_cases = [
+                error_response(json!({ "code": 429, "message": "Too Many Requests" })),
+                error_response(
+                    json!({ "code": -32005, "message": "daily request count exceeded" }),
+                ),
+                error_response(
+                    json!({ "code": -32000, "message": "rate limit reached, retry later" }),
+                ),
+                ProviderError::from(RpcTransportError::RateLimited(2)).into(),
+                ProviderError::from(RpcTransportError::BatchRequest(
+                    "connection reset by peer".to_string(),
+                ))
+                .into(),
+            ];
+
+            for test_case in test_cases {
+                // Act
+                let res = is_transient_error(&test_case);
+
+                // Assert
+                assert!(res, "{test_case} should be a transient error");
+            }
+        }
+
+        #[test]
+        fn should_not_detect_the_errors_of_the_request_itself() {
+            // Arrange
+            let test_cases = [
+                error_response(json!({ "code": -32602, "message": "invalid argument 0" })),
+                error_response(json!({ "code": 3, "message": "execution reverted", "data": "0x" })),
+                error_response(json!({ "code": -32000, "message": "header not found" })),
+                ProviderError::CustomError("invalid response".to_string()).into(),
+                anyhow::anyhow!("Block 10 not found"),
+            ];
+
+            for test_case in test_cases {
+                // Act
+                let res = is_transient_error(&test_case);
+
+                // Assert
+                assert!(!res, "{test_case} should not be a transient error");
+            }
+        }
+    }
+
+    mod is_method_not_found {
+        use ethers::providers::{JsonRpcError, ProviderError};
+        use serde_json::json;
+
+        use crate::cmd::helpers::{is_method_not_found, is_method_not_found_response};
+
+        fn error_response(payload: serde_json::Value) -> JsonRpcError {
+            serde_json::from_value(payload).unwrap()
+        }
+
+        #[test]
+        fn should_detect_the_method_not_found_error_of_known_clients() {
+            // Arrange
+            let test_cases = [
+                // geth
+                json!({ "code": -32601, "message": "the method eth_protocolVersion does not exist/is not available" }),
+                // erigon
+                json!({ "code": -32601, "message": "the method eth_protocolVersion does not exist/is not available" }),
+                // nethermind
+                json!({ "code": -32601, "message": "Method not found" }),
+                // infura
+                json!({ "code": -32601, "message": "The method eth_protocolVersion does not exist/is not available" }),
+                // hosted providers with non standard codes
+                json!({ "code": -32000, "message": "Method not found: eth_protocolVersion" }),
+            ];
+
+            for test_case in test_cases {
+                // Act
+                let res = is_method_not_found_response(&error_response(test_case.clone()));
+
+                // Assert
+                assert!(res, "{test_case} should be a method not found error");
+            }
+        }
+
+        #[test]
+        fn should_not_detect_other_errors() {
+            // Arrange
+            let test_cases = [
+                json!({ "code": 3, "message": "execution reverted", "data": "0x" }),
+                json!({ "code": -32000, "message": "nonce too low" }),
+                json!({ "code": -32602, "message": "invalid argument 0: hex string without 0x prefix" }),
+                json!({ "code": -32000, "message": "transaction type not supported" }),
+                json!({ "code": -32000, "message": "pending block is not supported" }),
+                json!({ "code": -32000, "message": "eip-1559 fee market not implemented" }),
+            ];
+
+            for test_case in test_cases {
+                // Act
+                let res = is_method_not_found_response(&error_response(test_case.clone()));
+
+                // Assert
+                assert!(!res, "{test_case} should not be a method not found error");
+            }
+        }
+
+        #[test]
+        fn should_only_detect_json_rpc_errors() {
+            // Arrange
+            let test_cases = [
+                anyhow::anyhow!("method not found"),
+                ProviderError::CustomError("method not found".to_string()).into(),
+            ];
+
+            for test_case in test_cases {
+                // Act
+                let res = is_method_not_found(&test_case);
+
+                // Assert
+                assert!(!res);
+            }
+        }
+    }
 }
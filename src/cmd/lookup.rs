@@ -0,0 +1,106 @@
+use super::helpers::is_unknown_block;
+
+/// What a query did not find, reported by the namespaces as a `null` result under its name.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Missing {
+    Block,
+    Transaction,
+    Receipt,
+}
+
+/// Result of a query that can legitimately miss, e.g. a block or transaction that does not exist.
+#[derive(Debug, PartialEq, Eq)]
+pub enum Lookup<T> {
+    Found(T),
+    NotFound { what: Missing },
+}
+
+impl<T> Lookup<T> {
+    pub fn from_option(value: Option<T>, what: Missing) -> Self {
+        match value {
+            Some(value) => Lookup::Found(value),
+            None => Lookup::NotFound { what },
+        }
+    }
+
+    pub fn map<U>(self, f: impl FnOnce(T) -> U) -> Lookup<U> {
+        match self {
+            Lookup::Found(value) => Lookup::Found(f(value)),
+            Lookup::NotFound { what } => Lookup::NotFound { what },
+        }
+    }
+
+    pub fn found(self) -> Option<T> {
+        match self {
+            Lookup::Found(value) => Some(value),
+            Lookup::NotFound { .. } => None,
+        }
+    }
+
+    /// Reports the node rejecting a query at a block it does not know as the block not being found.
+    pub fn from_block_query(res: anyhow::Result<T>) -> anyhow::Result<Self> {
+        match res {
+            Ok(value) => Ok(Lookup::Found(value)),
+            Err(err) if is_unknown_block(&err) => Ok(Lookup::NotFound {
+                what: Missing::Block,
+            }),
+            Err(err) => Err(err),
+        }
+    }
+
+    #[cfg(test)]
+    pub fn is_found(&self) -> bool {
+        matches!(self, Lookup::Found(_))
+    }
+
+    #[cfg(test)]
+    pub fn is_not_found(&self) -> bool {
+        !self.is_found()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Lookup, Missing};
+
+    #[test]
+    fn should_build_the_lookup_from_an_option() {
+        // Setup
+        let test_cases = [
+            (Some(1), Lookup::Found(1)),
+            (
+                None,
+                Lookup::NotFound {
+                    what: Missing::Block,
+                },
+            ),
+        ];
+
+        for (value, expected_res) in test_cases {
+            // Act
+            let res = Lookup::from_option(value, Missing::Block);
+
+            // Assert
+            assert_eq!(res, expected_res);
+        }
+    }
+
+    #[test]
+    fn should_map_the_found_value_and_keep_what_was_not_found() {
+        // Act
+        let found = Lookup::Found(1).map(|value| value + 1);
+        let not_found = Lookup::<u64>::NotFound {
+            what: Missing::Receipt,
+        }
+        .map(|value| value + 1);
+
+        // Assert
+        assert_eq!(found, Lookup::Found(2));
+        assert_eq!(
+            not_found,
+            Lookup::NotFound {
+                what: Missing::Receipt
+            }
+        );
+    }
+}
@@ -1,6 +1,11 @@
 pub mod account;
 pub mod block;
+pub mod checkpoint;
+pub mod event;
 pub mod gas;
-mod helpers;
+pub(crate) mod helpers;
+pub mod lookup;
+pub mod signatures;
+pub mod trace;
 pub mod transaction;
 pub mod utils;
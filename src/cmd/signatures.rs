@@ -0,0 +1,543 @@
+use ethers::{types::Selector, utils::id};
+
+/// Function signatures of the most common token, proxy, access control, multicall, dex, lending,
+/// staking, governance, multisig, bridge and account abstraction contracts, used to identify
+/// selectors without querying a signature database.
+pub const KNOWN_FUNCTION_SIGNATURES: &[&str] = &[
+    // ERC20
+    "name()",
+    "symbol()",
+    "decimals()",
+    "totalSupply()",
+    "balanceOf(address)",
+    "transfer(address,uint256)",
+    "transferFrom(address,address,uint256)",
+    "approve(address,uint256)",
+    "allowance(address,address)",
+    "increaseAllowance(address,uint256)",
+    "decreaseAllowance(address,uint256)",
+    "mint(address,uint256)",
+    "burn(uint256)",
+    "burn(address,uint256)",
+    "burnFrom(address,uint256)",
+    "increaseApproval(address,uint256)",
+    "decreaseApproval(address,uint256)",
+    "cap()",
+    "approveAndCall(address,uint256,bytes)",
+    "transfer(address,uint256,bytes)",
+    // Stablecoins (USDC, USDT)
+    "transferWithAuthorization(address,address,uint256,uint256,uint256,bytes32,uint8,bytes32,bytes32)",
+    "receiveWithAuthorization(address,address,uint256,uint256,uint256,bytes32,uint8,bytes32,bytes32)",
+    "cancelAuthorization(address,bytes32,uint8,bytes32,bytes32)",
+    "authorizationState(address,bytes32)",
+    "isBlacklisted(address)",
+    "blacklist(address)",
+    "unBlacklist(address)",
+    "configureMinter(address,uint256)",
+    "removeMinter(address)",
+    "masterMinter()",
+    "isMinter(address)",
+    "minterAllowance(address)",
+    "addBlackList(address)",
+    "removeBlackList(address)",
+    "getBlackListStatus(address)",
+    "issue(uint256)",
+    // ERC2612 and EIP-712
+    "permit(address,address,uint256,uint256,uint8,bytes32,bytes32)",
+    "nonces(address)",
+    "DOMAIN_SEPARATOR()",
+    "eip712Domain()",
+    // WETH
+    "deposit()",
+    "withdraw(uint256)",
+    // ERC721
+    "ownerOf(uint256)",
+    "safeTransferFrom(address,address,uint256)",
+    "safeTransferFrom(address,address,uint256,bytes)",
+    "setApprovalForAll(address,bool)",
+    "getApproved(uint256)",
+    "isApprovedForAll(address,address)",
+    "tokenURI(uint256)",
+    "baseURI()",
+    "tokenByIndex(uint256)",
+    "tokenOfOwnerByIndex(address,uint256)",
+    "safeMint(address,uint256)",
+    "mint(address)",
+    "safeMint(address)",
+    "setBaseURI(string)",
+    "setTokenURI(uint256,string)",
+    "contractURI()",
+    // ERC1155
+    "balanceOfBatch(address[],uint256[])",
+    "safeTransferFrom(address,address,uint256,uint256,bytes)",
+    "safeBatchTransferFrom(address,address,uint256[],uint256[],bytes)",
+    "uri(uint256)",
+    "setURI(string)",
+    "mint(address,uint256,uint256,bytes)",
+    "mintBatch(address,uint256[],uint256[],bytes)",
+    "burn(address,uint256,uint256)",
+    "burnBatch(address,uint256[],uint256[])",
+    "totalSupply(uint256)",
+    "exists(uint256)",
+    // Token receivers, ERC165, ERC1271 and ERC2981
+    "onERC721Received(address,address,uint256,bytes)",
+    "onERC1155Received(address,address,uint256,uint256,bytes)",
+    "onERC1155BatchReceived(address,address,uint256[],uint256[],bytes)",
+    "supportsInterface(bytes4)",
+    "isValidSignature(bytes32,bytes)",
+    "royaltyInfo(uint256,uint256)",
+    // ERC4626
+    "asset()",
+    "totalAssets()",
+    "convertToShares(uint256)",
+    "convertToAssets(uint256)",
+    "maxDeposit(address)",
+    "previewDeposit(uint256)",
+    "deposit(uint256,address)",
+    "maxMint(address)",
+    "previewMint(uint256)",
+    "mint(uint256,address)",
+    "maxWithdraw(address)",
+    "previewWithdraw(uint256)",
+    "withdraw(uint256,address,address)",
+    "maxRedeem(address)",
+    "previewRedeem(uint256)",
+    "redeem(uint256,address,address)",
+    // Voting tokens
+    "delegate(address)",
+    "delegates(address)",
+    "getVotes(address)",
+    "getPastVotes(address,uint256)",
+    "getPastTotalSupply(uint256)",
+    "delegateBySig(address,uint256,uint256,uint8,bytes32,bytes32)",
+    "checkpoints(address,uint32)",
+    "numCheckpoints(address)",
+    "clock()",
+    "CLOCK_MODE()",
+    // Ownable and access control
+    "owner()",
+    "transferOwnership(address)",
+    "renounceOwnership()",
+    "pendingOwner()",
+    "acceptOwnership()",
+    "hasRole(bytes32,address)",
+    "getRoleAdmin(bytes32)",
+    "grantRole(bytes32,address)",
+    "revokeRole(bytes32,address)",
+    "renounceRole(bytes32,address)",
+    "DEFAULT_ADMIN_ROLE()",
+    "getRoleMember(bytes32,uint256)",
+    "getRoleMemberCount(bytes32)",
+    "MINTER_ROLE()",
+    "PAUSER_ROLE()",
+    "UPGRADER_ROLE()",
+    "paused()",
+    "pause()",
+    "unpause()",
+    // Proxies
+    "implementation()",
+    "admin()",
+    "beacon()",
+    "upgradeTo(address)",
+    "upgradeToAndCall(address,bytes)",
+    "changeAdmin(address)",
+    "proxiableUUID()",
+    "getProxyImplementation(address)",
+    "getProxyAdmin(address)",
+    "changeProxyAdmin(address,address)",
+    "upgrade(address,address)",
+    "upgradeAndCall(address,address,bytes)",
+    "initialize()",
+    "initialize(address)",
+    "masterCopy()",
+    "setImplementation(address)",
+    // Multicall
+    "multicall(bytes[])",
+    "multicall(uint256,bytes[])",
+    "aggregate((address,bytes)[])",
+    "tryAggregate(bool,(address,bytes)[])",
+    "aggregate3((address,bool,bytes)[])",
+    "aggregate3Value((address,bool,uint256,bytes)[])",
+    "getEthBalance(address)",
+    "getBlockNumber()",
+    "getCurrentBlockTimestamp()",
+    "blockAndAggregate((address,bytes)[])",
+    "tryBlockAndAggregate(bool,(address,bytes)[])",
+    "getBlockHash(uint256)",
+    "getLastBlockHash()",
+    "getCurrentBlockCoinbase()",
+    "getCurrentBlockDifficulty()",
+    "getCurrentBlockGasLimit()",
+    "getBasefee()",
+    "getChainId()",
+    // Uniswap V2
+    "factory()",
+    "WETH()",
+    "swapExactTokensForTokens(uint256,uint256,address[],address,uint256)",
+    "swapTokensForExactTokens(uint256,uint256,address[],address,uint256)",
+    "swapExactETHForTokens(uint256,address[],address,uint256)",
+    "swapTokensForExactETH(uint256,uint256,address[],address,uint256)",
+    "swapExactTokensForETH(uint256,uint256,address[],address,uint256)",
+    "swapETHForExactTokens(uint256,address[],address,uint256)",
+    "swapExactTokensForTokensSupportingFeeOnTransferTokens(uint256,uint256,address[],address,uint256)",
+    "swapExactETHForTokensSupportingFeeOnTransferTokens(uint256,address[],address,uint256)",
+    "swapExactTokensForETHSupportingFeeOnTransferTokens(uint256,uint256,address[],address,uint256)",
+    "addLiquidity(address,address,uint256,uint256,uint256,uint256,address,uint256)",
+    "addLiquidityETH(address,uint256,uint256,uint256,address,uint256)",
+    "removeLiquidity(address,address,uint256,uint256,uint256,address,uint256)",
+    "removeLiquidityETH(address,uint256,uint256,uint256,address,uint256)",
+    "getAmountsOut(uint256,address[])",
+    "getAmountsIn(uint256,address[])",
+    "getReserves()",
+    "token0()",
+    "token1()",
+    "swap(uint256,uint256,address,bytes)",
+    "sync()",
+    "skim(address)",
+    "getPair(address,address)",
+    "createPair(address,address)",
+    "allPairs(uint256)",
+    "allPairsLength()",
+    "quote(uint256,uint256,uint256)",
+    "getAmountOut(uint256,uint256,uint256)",
+    "getAmountIn(uint256,uint256,uint256)",
+    "removeLiquidityWithPermit(address,address,uint256,uint256,uint256,address,uint256,bool,uint8,bytes32,bytes32)",
+    "removeLiquidityETHWithPermit(address,uint256,uint256,uint256,address,uint256,bool,uint8,bytes32,bytes32)",
+    "removeLiquidityETHSupportingFeeOnTransferTokens(address,uint256,uint256,uint256,address,uint256)",
+    "price0CumulativeLast()",
+    "price1CumulativeLast()",
+    "kLast()",
+    "burn(address)",
+    "feeTo()",
+    "feeToSetter()",
+    "setFeeTo(address)",
+    "MINIMUM_LIQUIDITY()",
+    // Uniswap V3
+    "exactInputSingle((address,address,uint24,address,uint256,uint256,uint256,uint160))",
+    "exactInput((bytes,address,uint256,uint256,uint256))",
+    "exactOutputSingle((address,address,uint24,address,uint256,uint256,uint256,uint160))",
+    "exactOutput((bytes,address,uint256,uint256,uint256))",
+    "slot0()",
+    "fee()",
+    "liquidity()",
+    "getPool(address,address,uint24)",
+    "createPool(address,address,uint24)",
+    "refundETH()",
+    "unwrapWETH9(uint256,address)",
+    "sweepToken(address,uint256,address)",
+    "execute(bytes,bytes[])",
+    "execute(bytes,bytes[],uint256)",
+    "observe(uint32[])",
+    "positions(bytes32)",
+    "ticks(int24)",
+    "tickSpacing()",
+    "maxLiquidityPerTick()",
+    "feeGrowthGlobal0X128()",
+    "feeGrowthGlobal1X128()",
+    "swap(address,bool,int256,uint160,bytes)",
+    "mint(address,int24,int24,uint128,bytes)",
+    "collect(address,int24,int24,uint128,uint128)",
+    "burn(int24,int24,uint128)",
+    "flash(address,uint256,uint256,bytes)",
+    "increaseObservationCardinalityNext(uint16)",
+    "initialize(uint160)",
+    "uniswapV3SwapCallback(int256,int256,bytes)",
+    "uniswapV3MintCallback(uint256,uint256,bytes)",
+    "mint((address,address,uint24,int24,int24,uint256,uint256,uint256,uint256,address,uint256))",
+    "increaseLiquidity((uint256,uint256,uint256,uint256,uint256,uint256))",
+    "decreaseLiquidity((uint256,uint128,uint256,uint256,uint256))",
+    "collect((uint256,address,uint128,uint128))",
+    "positions(uint256)",
+    "createAndInitializePoolIfNecessary(address,address,uint24,uint160)",
+    "exactInputSingle((address,address,uint24,address,uint256,uint256,uint160))",
+    "multicall(bytes32,bytes[])",
+    "quoteExactInputSingle(address,address,uint24,uint256,uint160)",
+    "quoteExactInput(bytes,uint256)",
+    "quoteExactOutputSingle(address,address,uint24,uint256,uint160)",
+    "quoteExactOutput(bytes,uint256)",
+    // Permit2
+    "transferFrom(address,address,uint160,address)",
+    "approve(address,address,uint160,uint48)",
+    "allowance(address,address,address)",
+    "invalidateNonces(address,address,uint48)",
+    "lockdown((address,address)[])",
+    // Curve
+    "exchange(int128,int128,uint256,uint256)",
+    "exchange_underlying(int128,int128,uint256,uint256)",
+    "exchange(uint256,uint256,uint256,uint256)",
+    "get_dy(int128,int128,uint256)",
+    "get_dy(uint256,uint256,uint256)",
+    "get_dy_underlying(int128,int128,uint256)",
+    "get_virtual_price()",
+    "coins(uint256)",
+    "balances(uint256)",
+    "add_liquidity(uint256[2],uint256)",
+    "add_liquidity(uint256[3],uint256)",
+    "remove_liquidity(uint256,uint256[2])",
+    "remove_liquidity(uint256,uint256[3])",
+    "remove_liquidity_one_coin(uint256,int128,uint256)",
+    "calc_withdraw_one_coin(uint256,int128)",
+    "calc_token_amount(uint256[2],bool)",
+    "calc_token_amount(uint256[3],bool)",
+    "A()",
+    // Balancer
+    "swap((bytes32,uint8,address,address,uint256,bytes),(address,bool,address,bool),uint256,uint256)",
+    "batchSwap(uint8,(bytes32,uint256,uint256,uint256,bytes)[],address[],(address,bool,address,bool),int256[],uint256)",
+    "joinPool(bytes32,address,address,(address[],uint256[],bytes,bool))",
+    "exitPool(bytes32,address,address,(address[],uint256[],bytes,bool))",
+    "getPoolTokens(bytes32)",
+    "flashLoan(address,address[],uint256[],bytes)",
+    "getPoolId()",
+    // Aave
+    "deposit(address,uint256,address,uint16)",
+    "supply(address,uint256,address,uint16)",
+    "withdraw(address,uint256,address)",
+    "borrow(address,uint256,uint256,uint16,address)",
+    "repay(address,uint256,uint256,address)",
+    "repayWithATokens(address,uint256,uint256)",
+    "liquidationCall(address,address,address,uint256,bool)",
+    "flashLoan(address,address[],uint256[],uint256[],address,bytes,uint16)",
+    "flashLoanSimple(address,address,uint256,bytes,uint16)",
+    "getUserAccountData(address)",
+    "getReserveData(address)",
+    "setUserUseReserveAsCollateral(address,bool)",
+    "executeOperation(address[],uint256[],uint256[],address,bytes)",
+    "executeOperation(address,uint256,uint256,address,bytes)",
+    "UNDERLYING_ASSET_ADDRESS()",
+    "scaledBalanceOf(address)",
+    // Compound
+    "mint(uint256)",
+    "redeem(uint256)",
+    "redeemUnderlying(uint256)",
+    "borrow(uint256)",
+    "repayBorrow(uint256)",
+    "repayBorrowBehalf(address,uint256)",
+    "liquidateBorrow(address,uint256,address)",
+    "exchangeRateCurrent()",
+    "exchangeRateStored()",
+    "balanceOfUnderlying(address)",
+    "borrowBalanceCurrent(address)",
+    "supplyRatePerBlock()",
+    "borrowRatePerBlock()",
+    "underlying()",
+    "enterMarkets(address[])",
+    "exitMarket(address)",
+    "claimComp(address)",
+    "getAccountLiquidity(address)",
+    "getAllMarkets()",
+    "supply(address,uint256)",
+    "withdraw(address,uint256)",
+    "baseToken()",
+    // Liquid staking (Lido)
+    "submit(address)",
+    "getPooledEthByShares(uint256)",
+    "getSharesByPooledEth(uint256)",
+    "sharesOf(address)",
+    "getTotalShares()",
+    "wrap(uint256)",
+    "unwrap(uint256)",
+    "stEthPerToken()",
+    "tokensPerStEth()",
+    "getStETHByWstETH(uint256)",
+    "getWstETHByStETH(uint256)",
+    // Safe
+    "execTransaction(address,uint256,bytes,uint8,uint256,uint256,uint256,address,address,bytes)",
+    "setup(address[],uint256,address,bytes,address,address,uint256,address)",
+    "getOwners()",
+    "getThreshold()",
+    "isOwner(address)",
+    "nonce()",
+    "addOwnerWithThreshold(address,uint256)",
+    "removeOwner(address,address,uint256)",
+    "swapOwner(address,address,address)",
+    "changeThreshold(uint256)",
+    "enableModule(address)",
+    "disableModule(address,address)",
+    "getTransactionHash(address,uint256,bytes,uint8,uint256,uint256,uint256,address,address,uint256)",
+    "approveHash(bytes32)",
+    "approvedHashes(address,bytes32)",
+    "execTransactionFromModule(address,uint256,bytes,uint8)",
+    "isModuleEnabled(address)",
+    "getModulesPaginated(address,uint256)",
+    "setGuard(address)",
+    "setFallbackHandler(address)",
+    "checkSignatures(bytes32,bytes,bytes)",
+    "domainSeparator()",
+    "VERSION()",
+    "createProxyWithNonce(address,bytes,uint256)",
+    "multiSend(bytes)",
+    // Governance and timelocks
+    "propose(address[],uint256[],bytes[],string)",
+    "castVote(uint256,uint8)",
+    "castVoteWithReason(uint256,uint8,string)",
+    "queue(address[],uint256[],bytes[],bytes32)",
+    "execute(address[],uint256[],bytes[],bytes32)",
+    "state(uint256)",
+    "schedule(address,uint256,bytes,bytes32,bytes32,uint256)",
+    "execute(address,uint256,bytes,bytes32,bytes32)",
+    "getMinDelay()",
+    "hashProposal(address[],uint256[],bytes[],bytes32)",
+    "proposalSnapshot(uint256)",
+    "proposalDeadline(uint256)",
+    "proposalThreshold()",
+    "quorum(uint256)",
+    "votingDelay()",
+    "votingPeriod()",
+    "hasVoted(uint256,address)",
+    "castVoteBySig(uint256,uint8,uint8,bytes32,bytes32)",
+    "cancel(address[],uint256[],bytes[],bytes32)",
+    "scheduleBatch(address[],uint256[],bytes[],bytes32,bytes32,uint256)",
+    "executeBatch(address[],uint256[],bytes[],bytes32,bytes32)",
+    "cancel(bytes32)",
+    "isOperationReady(bytes32)",
+    "isOperationDone(bytes32)",
+    "getTimestamp(bytes32)",
+    "propose(address[],uint256[],string[],bytes[],string)",
+    "queue(uint256)",
+    "execute(uint256)",
+    "cancel(uint256)",
+    "queueTransaction(address,uint256,string,bytes,uint256)",
+    "executeTransaction(address,uint256,string,bytes,uint256)",
+    "delay()",
+    // Oracles
+    "latestRoundData()",
+    "latestAnswer()",
+    "getRoundData(uint80)",
+    "description()",
+    "version()",
+    "latestTimestamp()",
+    "latestRound()",
+    "getAnswer(uint256)",
+    "getTimestamp(uint256)",
+    "aggregator()",
+    "proposedAggregator()",
+    "phaseId()",
+    // Staking rewards
+    "stake(uint256)",
+    "getReward()",
+    "exit()",
+    "earned(address)",
+    "rewardPerToken()",
+    "claim()",
+    "unstake(uint256)",
+    "notifyRewardAmount(uint256)",
+    "rewardRate()",
+    "periodFinish()",
+    "lastTimeRewardApplicable()",
+    "rewardsToken()",
+    "stakingToken()",
+    "claim(uint256,address,uint256,bytes32[])",
+    "isClaimed(uint256)",
+    "merkleRoot()",
+    // ENS
+    "resolver(bytes32)",
+    "addr(bytes32)",
+    "name(bytes32)",
+    "setName(string)",
+    "owner(bytes32)",
+    "setResolver(bytes32,address)",
+    "setOwner(bytes32,address)",
+    "setSubnodeOwner(bytes32,bytes32,address)",
+    "setAddr(bytes32,address)",
+    "text(bytes32,string)",
+    "setText(bytes32,string,string)",
+    "contenthash(bytes32)",
+    "register(string,address,uint256,bytes32)",
+    "renew(string,uint256)",
+    "rentPrice(string,uint256)",
+    "available(string)",
+    "commit(bytes32)",
+    "makeCommitment(string,address,bytes32)",
+    // Rollup bridges
+    "depositETH(uint32,bytes)",
+    "depositERC20(address,address,uint256,uint32,bytes)",
+    "depositTransaction(address,uint256,uint64,bool,bytes)",
+    "sendMessage(address,bytes,uint32)",
+    "relayMessage(address,address,bytes,uint256)",
+    "outboundTransfer(address,address,uint256,bytes)",
+    "depositEth()",
+    "createRetryableTicket(address,uint256,uint256,address,address,uint256,uint256,bytes)",
+    // ERC4337 account abstraction
+    "handleOps((address,uint256,bytes,bytes,uint256,uint256,uint256,uint256,uint256,bytes,bytes)[],address)",
+    "validateUserOp((address,uint256,bytes,bytes,uint256,uint256,uint256,uint256,uint256,bytes,bytes),bytes32,uint256)",
+    "getNonce(address,uint192)",
+    "depositTo(address)",
+    "getUserOpHash((address,uint256,bytes,bytes,uint256,uint256,uint256,uint256,uint256,bytes,bytes))",
+    "execute(address,uint256,bytes)",
+    "executeBatch(address[],bytes[])",
+    "entryPoint()",
+];
+
+/// Gets the signatures of the built in table matching the function selector.
+pub fn find_known_signatures(selector: Selector) -> Vec<String> {
+    KNOWN_FUNCTION_SIGNATURES
+        .iter()
+        .filter(|signature| id(signature) == selector)
+        .map(|signature| signature.to_string())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    mod find_known_signatures {
+        use std::collections::BTreeSet;
+
+        use ethers::abi::param_type::Reader;
+
+        use crate::cmd::signatures::{find_known_signatures, KNOWN_FUNCTION_SIGNATURES};
+
+        #[test]
+        fn should_find_the_signatures_of_known_selectors() {
+            // Setup
+            let test_cases = [
+                ([0xa9, 0x05, 0x9c, 0xbb], "transfer(address,uint256)"),
+                ([0xa2, 0x2c, 0xb4, 0x65], "setApprovalForAll(address,bool)"),
+                ([0x70, 0xa0, 0x82, 0x31], "balanceOf(address)"),
+                (
+                    [0x61, 0x7b, 0xa0, 0x37],
+                    "supply(address,uint256,address,uint16)",
+                ),
+                ([0xa1, 0x90, 0x3e, 0xab], "submit(address)"),
+                (
+                    [0x1f, 0xad, 0x94, 0x8c],
+                    "handleOps((address,uint256,bytes,bytes,uint256,uint256,uint256,uint256,uint256,bytes,bytes)[],address)",
+                ),
+            ];
+
+            for (selector, expected_res) in test_cases {
+                // Act
+                let res = find_known_signatures(selector);
+
+                // Assert
+                assert_eq!(res, vec![expected_res.to_string()]);
+            }
+        }
+
+        #[test]
+        fn should_not_find_signatures_for_an_unknown_selector() {
+            // Act
+            let res = find_known_signatures([0xde, 0xad, 0xbe, 0xef]);
+
+            // Assert
+            assert!(res.is_empty());
+        }
+
+        #[test]
+        fn should_only_hold_unique_canonical_signatures() -> anyhow::Result<()> {
+            // Setup
+            let mut seen = BTreeSet::new();
+
+            for signature in KNOWN_FUNCTION_SIGNATURES {
+                // Act
+                let (name, inputs) = signature.split_at(signature.find('(').unwrap());
+                let canonical = format!("{name}{}", Reader::read(inputs)?);
+
+                // Assert
+                assert_eq!(&canonical, signature);
+                assert!(seen.insert(signature), "{signature} is duplicated");
+            }
+
+            Ok(())
+        }
+    }
+}
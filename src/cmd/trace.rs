@@ -0,0 +1,180 @@
+use ethers::types::{
+    CallFrame, DefaultFrame, GethDebugBuiltInTracerType, GethDebugTracerType,
+    GethDebugTracingOptions, H256,
+};
+use serde::Serialize;
+
+use crate::{cmd::helpers::is_method_not_found, context::NodeProvider};
+
+/// Tracer run by the node while re-executing the transaction.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Tracer {
+    #[default]
+    StructLogger,
+    CallTracer,
+}
+
+/// Serialized as the plain trace returned by the node for the selected tracer.
+#[derive(Debug, Serialize)]
+#[serde(untagged)]
+pub enum TransactionTrace {
+    StructLogs(DefaultFrame),
+    CallFrame(CallFrame),
+}
+
+// debug_traceTransaction
+pub async fn trace_transaction(
+    node_provider: &NodeProvider,
+    hash: H256,
+    tracer: Tracer,
+) -> anyhow::Result<TransactionTrace> {
+    let res = match tracer {
+        Tracer::StructLogger => node_provider
+            .get_transaction_trace(hash, GethDebugTracingOptions::default())
+            .await
+            .map(TransactionTrace::StructLogs),
+        Tracer::CallTracer => node_provider
+            .get_transaction_trace(
+                hash,
+                GethDebugTracingOptions {
+                    tracer: Some(GethDebugTracerType::BuiltInTracer(
+                        GethDebugBuiltInTracerType::CallTracer,
+                    )),
+                    ..Default::default()
+                },
+            )
+            .await
+            .map(TransactionTrace::CallFrame),
+    };
+
+    res.map_err(|err| {
+        if is_method_not_found(&err) {
+            anyhow::anyhow!(
+                "The node does not support transaction tracing (debug_traceTransaction)"
+            )
+        } else {
+            err
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+
+    mod trace_transaction {
+        use ethers::{
+            types::{NameOrAddress, H256},
+            utils::parse_ether,
+        };
+        use serde_json::json;
+        use std::time::Duration;
+
+        use crate::cmd::{
+            helpers::test::{
+                mock_node_provider, send_tx_helper, setup_test, spawn_mock_rpc_error_server,
+                spawn_mock_rpc_server,
+            },
+            trace::{trace_transaction, Tracer, TransactionTrace},
+        };
+
+        #[tokio::test]
+        async fn should_trace_the_transaction_opcodes() -> anyhow::Result<()> {
+            // Arrange
+            let (node_provider, anvil) = setup_test().await?;
+
+            let sender = *anvil.addresses().first().unwrap();
+            let receiver = *anvil.addresses().last().unwrap();
+            let receipt = send_tx_helper(&node_provider, sender, receiver, parse_ether(1)?).await?;
+
+            // Act
+            let res = trace_transaction(
+                &node_provider,
+                receipt.transaction_hash,
+                Tracer::StructLogger,
+            )
+            .await;
+
+            // Assert
+            assert!(res.is_ok());
+
+            let TransactionTrace::StructLogs(trace) = res.unwrap() else {
+                panic!("Should be the struct logs trace!");
+            };
+            assert!(!trace.failed);
+            // A plain transfer doesn't execute any code
+            assert!(trace.struct_logs.is_empty());
+
+            Ok(())
+        }
+
+        #[tokio::test]
+        async fn should_return_the_call_frames() -> anyhow::Result<()> {
+            // Arrange
+            let (url, _) = spawn_mock_rpc_server(
+                json!({
+                    "type": "CALL",
+                    "from": "0x000000000000000000000000000000000000dead",
+                    "to": "0x000000000000000000000000000000000000beef",
+                    "value": "0x1",
+                    "gas": "0x5208",
+                    "gasUsed": "0x5208",
+                    "input": "0x",
+                    "calls": [{
+                        "type": "STATICCALL",
+                        "from": "0x000000000000000000000000000000000000beef",
+                        "to": "0x000000000000000000000000000000000000cafe",
+                        "gas": "0x100",
+                        "gasUsed": "0x10",
+                        "input": "0x01",
+                        "output": "0x02"
+                    }]
+                }),
+                Duration::ZERO,
+            );
+            let node_provider = mock_node_provider(url).await?;
+
+            // Act
+            let res = trace_transaction(&node_provider, H256::zero(), Tracer::CallTracer).await;
+
+            // Assert
+            assert!(res.is_ok());
+
+            let TransactionTrace::CallFrame(frame) = res.unwrap() else {
+                panic!("Should be the call frame trace!");
+            };
+            assert_eq!(frame.typ, "CALL");
+
+            let calls = frame.calls.unwrap();
+            assert_eq!(calls.len(), 1);
+            assert_eq!(
+                calls[0].to,
+                Some(NameOrAddress::Address(
+                    "0x000000000000000000000000000000000000cafe".parse()?
+                ))
+            );
+
+            Ok(())
+        }
+
+        #[tokio::test]
+        async fn should_report_that_the_node_does_not_support_tracing() -> anyhow::Result<()> {
+            // Arrange
+            let url = spawn_mock_rpc_error_server(
+                json!({ "code": -32601, "message": "the method debug_traceTransaction does not exist/is not available" }),
+            );
+            let node_provider = mock_node_provider(url).await?;
+
+            // Act
+            let res = trace_transaction(&node_provider, H256::zero(), Tracer::CallTracer).await;
+
+            // Assert
+            assert!(res.is_err());
+            assert!(res
+                .unwrap_err()
+                .to_string()
+                .contains("does not support transaction tracing"));
+
+            Ok(())
+        }
+    }
+}
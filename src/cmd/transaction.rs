@@ -1,11 +1,27 @@
+use std::{ops::Deref, path::Path, time::Duration};
+
 use anyhow::Ok;
 use ethers::{
-    providers::{Http, Middleware, PendingTransaction},
-    types::{BlockId, Bytes, Transaction, TransactionReceipt, TransactionRequest, H256},
+    providers::{Middleware, PendingTransaction},
+    types::{
+        transaction::eip2718::TypedTransaction, Address, BlockId, BlockNumber, Bytes, CallFrame,
+        NameOrAddress, Signature, Transaction, TransactionReceipt, TransactionRequest, H256, U256,
+        U64,
+    },
+    utils::{keccak256, rlp::Rlp},
 };
+use futures::future::join_all;
 use serde::Serialize;
 
-use crate::context::NodeProvider;
+use crate::context::{NodeProvider, NodeProviderError, RpcTransport};
+
+use super::{
+    account::get_code,
+    helpers::with_timeout,
+    lookup::{Lookup, Missing},
+    trace::{trace_transaction, Tracer, TransactionTrace},
+    utils::{decode_revert_reason, get_account_at},
+};
 
 pub enum GetTransaction {
     TransactionHash(H256),
@@ -15,13 +31,17 @@ pub enum GetTransaction {
 pub async fn get_transaction(
     node_provider: &NodeProvider,
     get_by: GetTransaction,
-) -> anyhow::Result<Option<Transaction>> {
-    match get_by {
-        GetTransaction::TransactionHash(hash) => get_transaction_by_hash(node_provider, hash).await,
+) -> anyhow::Result<Lookup<Transaction>> {
+    let tx = match get_by {
+        GetTransaction::TransactionHash(hash) => {
+            get_transaction_by_hash(node_provider, hash).await?
+        }
         GetTransaction::BlockIdAndIdx(block_id, idx) => {
-            get_transaction_block_id_and_idx(node_provider, block_id, idx).await
+            get_transaction_block_id_and_idx(node_provider, block_id, idx).await?
         }
-    }
+    };
+
+    Ok(Lookup::from_option(tx, Missing::Transaction))
 }
 
 // eth_getTransactionByHash
@@ -39,36 +59,318 @@ async fn get_transaction_block_id_and_idx(
     node_provider: &NodeProvider,
     block_id: BlockId,
     idx: usize,
+) -> anyhow::Result<Option<Transaction>> {
+    // Many nodes return null when querying the pending block by index, so the pending block is fetched instead
+    if block_id != BlockId::Number(BlockNumber::Pending) {
+        let tx = node_provider
+            .get_transaction_by_block_and_index(block_id, idx.into())
+            .await;
+
+        if let std::result::Result::Ok(Some(tx)) = tx {
+            return Ok(Some(tx));
+        }
+    }
+
+    // Fallback for nodes that do not support the by block and index methods and to tell a missing
+    // block apart from an out of range index
+    get_transaction_from_block(node_provider, block_id, idx).await
+}
+
+async fn get_transaction_from_block(
+    node_provider: &NodeProvider,
+    block_id: BlockId,
+    idx: usize,
 ) -> anyhow::Result<Option<Transaction>> {
     let block = node_provider.get_block_with_txs(block_id).await?;
 
     if let Some(block) = block {
-        let tx = block.transactions.get(idx).cloned();
+        let tx_count = block.transactions.len();
+
+        let tx = block.transactions.into_iter().nth(idx).ok_or_else(|| {
+            anyhow::anyhow!(
+                "Transaction index {idx} is out of range for block {} containing {tx_count} transactions",
+                format_block_id(block_id)
+            )
+        })?;
 
-        return Ok(tx);
+        return Ok(Some(tx));
     }
 
     Ok(None)
 }
 
+fn format_block_id(block_id: BlockId) -> String {
+    match block_id {
+        BlockId::Hash(hash) => format!("{hash:?}"),
+        BlockId::Number(number) => number.to_string(),
+    }
+}
+
+/// Transaction receipt with its status interpreted and, for failed transactions, the revert reason.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EnrichedReceipt {
+    #[serde(flatten)]
+    pub receipt: TransactionReceipt,
+    pub status_text: &'static str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub revert_reason: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub internal_calls: Option<Vec<InternalCall>>,
+}
+
+/// Call made by a contract while executing the transaction, moving `value` wei from `from` to `to`.
+#[derive(Debug, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct InternalCall {
+    pub call_type: String,
+    pub from: Address,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub to: Option<NameOrAddress>,
+    pub value: U256,
+    /// Nesting level of the call, 1 for the calls made by the transaction target
+    pub depth: usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+impl Deref for EnrichedReceipt {
+    type Target = TransactionReceipt;
+
+    fn deref(&self) -> &Self::Target {
+        &self.receipt
+    }
+}
+
+fn get_status_text(receipt: &TransactionReceipt) -> &'static str {
+    match receipt.status.map(|status| status.as_u64()) {
+        Some(1) => "success",
+        Some(0) => "failed",
+        _ => "unknown",
+    }
+}
+
+pub async fn enrich_receipt(
+    node_provider: &NodeProvider,
+    receipt: TransactionReceipt,
+    explain_failure: bool,
+) -> anyhow::Result<EnrichedReceipt> {
+    let status_text = get_status_text(&receipt);
+
+    let revert_reason = if explain_failure && status_text == "failed" {
+        get_revert_reason(node_provider, receipt.transaction_hash).await?
+    } else {
+        None
+    };
+
+    Ok(EnrichedReceipt {
+        receipt,
+        status_text,
+        revert_reason,
+        internal_calls: None,
+    })
+}
+
+// debug_traceTransaction
+/// Traces the transaction with the call tracer and lists the calls made by contracts during its
+/// execution, in execution order.
+pub async fn get_internal_calls(
+    node_provider: &NodeProvider,
+    hash: H256,
+) -> anyhow::Result<Vec<InternalCall>> {
+    let TransactionTrace::CallFrame(trace) =
+        trace_transaction(node_provider, hash, Tracer::CallTracer).await?
+    else {
+        anyhow::bail!("The node did not return a call trace for transaction {hash:?}");
+    };
+
+    let mut internal_calls = Vec::new();
+    flatten_call_frames(trace.calls.unwrap_or_default(), 1, &mut internal_calls);
+
+    Ok(internal_calls)
+}
+
+fn flatten_call_frames(frames: Vec<CallFrame>, depth: usize, out: &mut Vec<InternalCall>) {
+    for frame in frames {
+        out.push(InternalCall {
+            call_type: frame.typ,
+            from: frame.from,
+            to: frame.to,
+            value: frame.value.unwrap_or_default(),
+            depth,
+            error: frame.error,
+        });
+
+        flatten_call_frames(frame.calls.unwrap_or_default(), depth + 1, out);
+    }
+}
+
+// eth_call
+/// Re-simulates the transaction with the hash provided on top of the state of its parent block
+/// and decodes the revert data. Returns None if the transaction does not revert when simulated or
+/// if the node does not return the revert data.
+async fn get_revert_reason(
+    node_provider: &NodeProvider,
+    hash: H256,
+) -> anyhow::Result<Option<String>> {
+    let tx = get_transaction_by_hash(node_provider, hash)
+        .await?
+        .ok_or(anyhow::anyhow!("Transaction {hash:?} not found"))?;
+
+    let block_number = tx
+        .block_number
+        .ok_or(anyhow::anyhow!("Transaction {hash:?} is not mined"))?;
+
+    let mut call = TransactionRequest::new()
+        .from(tx.from)
+        .value(tx.value)
+        .gas(tx.gas)
+        .data(tx.input);
+
+    if let Some(to) = tx.to {
+        call = call.to(to);
+    }
+
+    let res = node_provider
+        .call(
+            &call.into(),
+            Some(BlockId::Number(BlockNumber::Number(
+                block_number.saturating_sub(1.into()),
+            ))),
+        )
+        .await;
+
+    let err = match res {
+        std::result::Result::Ok(_) => return Ok(None),
+        Err(err) => err,
+    };
+
+    Ok(err
+        .revert_data()
+        .map(|revert_data| decode_revert_reason(&revert_data)))
+}
+
 // eth_getTransactionReceipt
 pub async fn get_transaction_receipt(
     node_provider: &NodeProvider,
     hash: H256,
-) -> anyhow::Result<Option<TransactionReceipt>> {
-    let receipt = node_provider.get_transaction_receipt(hash).await?;
+    explain_failure: bool,
+    follow_internal: bool,
+) -> anyhow::Result<Lookup<EnrichedReceipt>> {
+    let receipt = match node_provider.get_transaction_receipt(hash).await? {
+        Some(receipt) => {
+            let mut receipt = enrich_receipt(node_provider, receipt, explain_failure).await?;
+            if follow_internal {
+                receipt.internal_calls = Some(get_internal_calls(node_provider, hash).await?);
+            }
 
-    Ok(receipt)
+            Some(receipt)
+        }
+        None => None,
+    };
+
+    Ok(Lookup::from_option(receipt, Missing::Receipt))
+}
+
+/// Default time limit of waiting for the confirmations of a transaction.
+pub const CONFIRMATIONS_TIMEOUT: Duration = Duration::from_secs(300);
+
+/// Interval between two checks of the confirmations of a transaction.
+pub const CONFIRMATIONS_POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase", tag = "status")]
+pub enum TransactionStatus {
+    Pending,
+    Mined {
+        confirmations: u64,
+        receipt: TransactionReceipt,
+    },
+    NotFound,
+}
+
+// eth_getTransactionReceipt, eth_getTransactionByHash and eth_blockNumber
+async fn get_transaction_status(
+    node_provider: &NodeProvider,
+    hash: H256,
+) -> anyhow::Result<TransactionStatus> {
+    let Some(receipt) = node_provider.get_transaction_receipt(hash).await? else {
+        let status = match node_provider.get_transaction(hash).await? {
+            Some(_) => TransactionStatus::Pending,
+            None => TransactionStatus::NotFound,
+        };
+
+        return Ok(status);
+    };
+
+    // A receipt without block number belongs to a pending block
+    let Some(block_number) = receipt.block_number else {
+        return Ok(TransactionStatus::Pending);
+    };
+
+    let latest_block = node_provider.get_block_number().await?;
+    let confirmations = (latest_block.as_u64() + 1).saturating_sub(block_number.as_u64());
+
+    Ok(TransactionStatus::Mined {
+        confirmations,
+        receipt,
+    })
+}
+
+/// Reports whether the transaction is pending, mined or unknown to the node. If `confirmations`
+/// is provided, the status is polled every `interval` until the transaction is mined with at least
+/// that many confirmations. Unknown transactions keep being polled as they might not have reached
+/// the node yet.
+pub async fn wait_for_confirmations(
+    node_provider: &NodeProvider,
+    hash: H256,
+    confirmations: Option<u64>,
+    interval: Duration,
+    timeout: Option<Duration>,
+) -> anyhow::Result<TransactionStatus> {
+    let Some(target_confirmations) = confirmations else {
+        return get_transaction_status(node_provider, hash).await;
+    };
+
+    with_timeout(
+        timeout.unwrap_or(CONFIRMATIONS_TIMEOUT),
+        "wait for the transaction confirmations",
+        async move {
+            loop {
+                let status = get_transaction_status(node_provider, hash).await?;
+
+                if let TransactionStatus::Mined { confirmations, .. } = status {
+                    if confirmations >= target_confirmations {
+                        return Ok(status);
+                    }
+                }
+
+                tokio::time::sleep(interval).await;
+            }
+        },
+    )
+    .await
 }
 
 pub enum TransactionKind {
     RawTransaction(Bytes),
-    TypedTransaction(TransactionRequest),
+    TypedTransaction(TypedTransaction),
 }
 
+/// Default time limit of sending a transaction, including waiting for it to be mined.
+pub const SEND_TIMEOUT: Duration = Duration::from_secs(300);
+
+/// Default time limit of simulating a call.
+pub const CALL_TIMEOUT: Duration = Duration::from_secs(30);
+
 pub struct SendTransactionOptions {
     tx_data: TransactionKind,
     wait: bool,
+    explorer_base: Option<String>,
+    explain_failure: bool,
+    only_if_contract: bool,
+    from_index: Option<usize>,
+    timeout: Option<Duration>,
 }
 
 impl SendTransactionOptions {
@@ -76,43 +378,162 @@ impl SendTransactionOptions {
         Self {
             tx_data: data,
             wait: wait.unwrap_or(false),
+            explorer_base: None,
+            explain_failure: false,
+            only_if_contract: false,
+            from_index: None,
+            timeout: None,
         }
     }
+
+    /// Overrides the block explorer used to build the transaction url of mined transactions
+    pub fn with_explorer_base(mut self, explorer_base: Option<String>) -> Self {
+        self.explorer_base = explorer_base;
+
+        self
+    }
+
+    /// Includes the revert reason of failed mined transactions
+    pub fn with_explain_failure(mut self, explain_failure: bool) -> Self {
+        self.explain_failure = explain_failure;
+
+        self
+    }
+
+    /// Refuses to send typed transactions whose target has no code
+    pub fn with_only_if_contract(mut self, only_if_contract: bool) -> Self {
+        self.only_if_contract = only_if_contract;
+
+        self
+    }
+
+    /// Sends typed transactions from the unlocked node account at the given index
+    pub fn with_from_index(mut self, from_index: Option<usize>) -> Self {
+        self.from_index = from_index;
+
+        self
+    }
+
+    /// Overrides the default time limit of the command
+    pub fn with_timeout(mut self, timeout: Option<Duration>) -> Self {
+        self.timeout = timeout;
+
+        self
+    }
 }
 
 #[derive(Debug, Serialize)]
 pub enum SendTxResult {
     PendingTransaction(H256),
-    Receipt(Option<TransactionReceipt>),
+    Receipt(MinedTransaction),
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MinedTransaction {
+    #[serde(flatten)]
+    pub receipt: Option<EnrichedReceipt>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub explorer_url: Option<String>,
+}
+
+/// Returns the block explorer base url of well known chains.
+fn get_explorer_base(chain_id: u64) -> Option<&'static str> {
+    let explorer_base = match chain_id {
+        1 => "https://etherscan.io",
+        5 => "https://goerli.etherscan.io",
+        10 => "https://optimistic.etherscan.io",
+        56 => "https://bscscan.com",
+        100 => "https://gnosisscan.io",
+        137 => "https://polygonscan.com",
+        8453 => "https://basescan.org",
+        42161 => "https://arbiscan.io",
+        11155111 => "https://sepolia.etherscan.io",
+        _ => return None,
+    };
+
+    Some(explorer_base)
+}
+
+fn get_explorer_tx_url(explorer_base: &str, tx_hash: H256) -> String {
+    format!("{}/tx/{tx_hash:?}", explorer_base.trim_end_matches('/'))
 }
 
 pub async fn send_transaction(
     node_provider: &NodeProvider,
     tx_data: SendTransactionOptions,
 ) -> anyhow::Result<SendTxResult> {
-    let SendTransactionOptions { tx_data, wait } = tx_data;
-
-    let pending_tx = match tx_data {
-        TransactionKind::RawTransaction(raw_tx) => {
-            send_raw_transaction(node_provider, raw_tx).await?
-        }
-        TransactionKind::TypedTransaction(tx) => send_typed_transaction(node_provider, tx).await?,
-    };
-
-    let res = if wait {
-        SendTxResult::Receipt(pending_tx.await?)
-    } else {
-        SendTxResult::PendingTransaction(pending_tx.tx_hash())
-    };
+    let SendTransactionOptions {
+        tx_data,
+        wait,
+        explorer_base,
+        explain_failure,
+        only_if_contract,
+        from_index,
+        timeout,
+    } = tx_data;
+
+    with_timeout(
+        timeout.unwrap_or(SEND_TIMEOUT),
+        "transaction submission",
+        async move {
+            let pending_tx = match tx_data {
+                TransactionKind::RawTransaction(raw_tx) => {
+                    send_raw_transaction(node_provider, raw_tx).await?
+                }
+                TransactionKind::TypedTransaction(mut tx) => {
+                    if let Some(index) = from_index {
+                        tx.set_from(get_account_at(node_provider, index).await?);
+                    }
+
+                    if only_if_contract {
+                        ensure_target_is_contract(node_provider, tx.to(), None).await?;
+                    }
+
+                    send_typed_transaction(node_provider, tx).await?
+                }
+            };
+
+            if !wait {
+                return Ok(SendTxResult::PendingTransaction(pending_tx.tx_hash()));
+            }
 
-    Ok(res)
+            let receipt = match pending_tx.await? {
+                Some(receipt) => {
+                    Some(enrich_receipt(node_provider, receipt, explain_failure).await?)
+                }
+                None => None,
+            };
+
+            let explorer_url = match (&receipt, explorer_base) {
+                (None, _) => None,
+                (Some(receipt), Some(explorer_base)) => Some(get_explorer_tx_url(
+                    &explorer_base,
+                    receipt.transaction_hash,
+                )),
+                (Some(receipt), None) => {
+                    let chain_id = node_provider.get_chainid().await?;
+
+                    get_explorer_base(chain_id.as_u64()).map(|explorer_base| {
+                        get_explorer_tx_url(explorer_base, receipt.transaction_hash)
+                    })
+                }
+            };
+
+            Ok(SendTxResult::Receipt(MinedTransaction {
+                receipt,
+                explorer_url,
+            }))
+        },
+    )
+    .await
 }
 
 // eth_sendRawTransaction
 async fn send_raw_transaction(
     node_provider: &NodeProvider,
     encoded_tx: Bytes,
-) -> anyhow::Result<PendingTransaction<Http>> {
+) -> anyhow::Result<PendingTransaction<'_, RpcTransport>> {
     let receipt = node_provider.send_raw_transaction(encoded_tx).await?;
 
     Ok(receipt)
@@ -120,28 +541,306 @@ async fn send_raw_transaction(
 
 async fn send_typed_transaction(
     node_provider: &NodeProvider,
-    tx: TransactionRequest,
-) -> anyhow::Result<PendingTransaction<Http>> {
+    tx: TypedTransaction,
+) -> anyhow::Result<PendingTransaction<'_, RpcTransport>> {
     let receipt = node_provider.send_transaction(tx, None).await?;
 
     Ok(receipt)
 }
 
-pub struct SimulateTransactionOptions(TransactionRequest, Option<BlockId>);
+/// Transfer read from a row of a batch file.
+#[derive(Debug, PartialEq, Eq)]
+pub struct BatchTransfer {
+    pub line: usize,
+    pub to: Address,
+    pub value: U256,
+    pub data: Option<Bytes>,
+}
+
+/// Reads the `to,value[,data]` rows of a csv batch file, the value being expressed in wei. A
+/// leading header row and blank lines are skipped.
+pub fn load_batch_transfers(path: &Path) -> anyhow::Result<Vec<BatchTransfer>> {
+    let content = std::fs::read_to_string(path).map_err(|err| {
+        anyhow::anyhow!("Failed to read the batch file {}: {err}", path.display())
+    })?;
+
+    content
+        .lines()
+        .enumerate()
+        .map(|(idx, row)| (idx + 1, row.trim()))
+        .filter(|(line, row)| {
+            let is_header = *line == 1 && row.starts_with("to,");
+
+            !row.is_empty() && !is_header
+        })
+        .map(|(line, row)| {
+            parse_batch_transfer(line, row)
+                .map_err(|err| anyhow::anyhow!("Invalid row at line {line}: {err}"))
+        })
+        .collect()
+}
+
+fn parse_batch_transfer(line: usize, row: &str) -> anyhow::Result<BatchTransfer> {
+    let fields: Vec<&str> = row.split(',').map(str::trim).collect();
+
+    let (to, value, data) = match fields.as_slice() {
+        [to, value] => (to, value, None),
+        [to, value, data] => (to, value, Some(data).filter(|data| !data.is_empty())),
+        _ => anyhow::bail!("expected to,value[,data] but found {} fields", fields.len()),
+    };
+
+    let value = match value.strip_prefix("0x") {
+        Some(hex_value) => U256::from_str_radix(hex_value, 16).map_err(|err| err.to_string()),
+        None => U256::from_dec_str(value).map_err(|err| err.to_string()),
+    }
+    .map_err(|err| anyhow::anyhow!("invalid value {value}: {err}"))?;
+
+    Ok(BatchTransfer {
+        line,
+        to: to
+            .parse()
+            .map_err(|err| anyhow::anyhow!("invalid address {to}: {err}"))?,
+        value,
+        data: data
+            .map(|data| data.parse())
+            .transpose()
+            .map_err(|err| anyhow::anyhow!("invalid data: {err}"))?,
+    })
+}
+
+/// Outcome of sending a single row of a batch.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BatchTxResult {
+    pub line: usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub hash: Option<H256>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+// eth_getTransactionCount || eth_sendTransaction
+/// Sends the transfers one after the other from the provided account (or the configured signer),
+/// assigning consecutive nonces starting from the pending one. A transfer that can't be sent is
+/// reported without stopping the batch, and doesn't use up a nonce.
+pub async fn send_batch(
+    node_provider: &NodeProvider,
+    from: Option<Address>,
+    transfers: Vec<BatchTransfer>,
+) -> anyhow::Result<Vec<BatchTxResult>> {
+    let from = from
+        .or(node_provider.default_sender())
+        .ok_or(anyhow::anyhow!(
+            "Missing sender. Provide --from or configure a private key"
+        ))?;
+
+    let mut nonce = node_provider
+        .get_transaction_count(from, Some(BlockNumber::Pending.into()))
+        .await?;
+
+    let mut results = Vec::with_capacity(transfers.len());
+
+    for BatchTransfer {
+        line,
+        to,
+        value,
+        data,
+    } in transfers
+    {
+        let mut tx = TransactionRequest::new()
+            .from(from)
+            .to(to)
+            .value(value)
+            .nonce(nonce);
+
+        if let Some(data) = data {
+            tx = tx.data(data);
+        }
+
+        let res = send_typed_transaction(node_provider, tx.into())
+            .await
+            .map(|pending_tx| pending_tx.tx_hash());
+
+        if res.is_ok() {
+            nonce += U256::one();
+        }
+
+        results.push(BatchTxResult {
+            line,
+            hash: res.as_ref().ok().copied(),
+            error: res.err().map(|err| err.to_string()),
+        });
+    }
+
+    Ok(results)
+}
+
+pub struct SimulateTransactionOptions(TypedTransaction, Option<BlockId>, bool, Option<Duration>);
 
 impl SimulateTransactionOptions {
-    pub fn new(tx: TransactionRequest, block_id: Option<BlockId>) -> Self {
-        Self(tx, block_id)
+    pub fn new(tx: TypedTransaction, block_id: Option<BlockId>) -> Self {
+        Self(tx, block_id, false, None)
+    }
+
+    /// Builds a call that only includes the target account and calldata
+    pub fn raw(to: NameOrAddress, data: Bytes, block_id: Option<BlockId>) -> Self {
+        Self(
+            TransactionRequest::new().to(to).data(data).into(),
+            block_id,
+            false,
+            None,
+        )
+    }
+
+    /// Refuses to simulate the call if its target has no code
+    pub fn with_only_if_contract(mut self, only_if_contract: bool) -> Self {
+        self.2 = only_if_contract;
+
+        self
+    }
+
+    /// Overrides the default time limit of the command
+    pub fn with_timeout(mut self, timeout: Option<Duration>) -> Self {
+        self.3 = timeout;
+
+        self
+    }
+}
+
+/// Fails if the target account has no code, as a call to an externally owned account would
+/// silently succeed as a plain transfer.
+pub async fn ensure_target_is_contract(
+    node_provider: &NodeProvider,
+    to: Option<&NameOrAddress>,
+    block_id: Option<BlockId>,
+) -> anyhow::Result<()> {
+    let to = to.ok_or(anyhow::anyhow!(
+        "Missing target account. A contract address or ens must be provided"
+    ))?;
+
+    let code = get_code(node_provider, to.clone(), block_id).await?;
+
+    if code.is_empty() {
+        let to = match to {
+            NameOrAddress::Name(ens) => ens.clone(),
+            NameOrAddress::Address(address) => format!("{address:?}"),
+        };
+
+        anyhow::bail!("The target account {to} is not a contract");
     }
+
+    Ok(())
 }
 
 pub async fn call(
     node_provider: &NodeProvider,
     options: SimulateTransactionOptions,
 ) -> anyhow::Result<Bytes> {
-    let res = node_provider.call(&options.0.into(), options.1).await?;
+    let SimulateTransactionOptions(tx, block_id, only_if_contract, timeout) = options;
+
+    with_timeout(timeout.unwrap_or(CALL_TIMEOUT), "call", async {
+        if only_if_contract {
+            ensure_target_is_contract(node_provider, tx.to(), block_id).await?;
+        }
+
+        let res = node_provider
+            .call(&tx, block_id)
+            .await
+            .map_err(explain_revert)?;
+
+        Ok(res)
+    })
+    .await
+}
+
+/// Output of a call at one of the compared blocks.
+#[derive(Debug, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct BlockCallResult {
+    pub block: BlockId,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub result: Option<Bytes>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+// eth_call
+/// Runs the same call concurrently at each of the provided blocks, returning the outputs in the
+/// same order. A call failing at a block (e.g. one mined before the contract was deployed) is
+/// reported without failing the others.
+pub async fn call_multi_block(
+    node_provider: &NodeProvider,
+    options: SimulateTransactionOptions,
+    block_ids: Vec<BlockId>,
+) -> anyhow::Result<Vec<BlockCallResult>> {
+    let SimulateTransactionOptions(tx, _, only_if_contract, timeout) = options;
+
+    let results = join_all(block_ids.iter().map(|block_id| {
+        call(
+            node_provider,
+            SimulateTransactionOptions(tx.clone(), Some(*block_id), only_if_contract, timeout),
+        )
+    }))
+    .await;
+
+    let results = block_ids
+        .into_iter()
+        .zip(results)
+        .map(|(block, res)| match res {
+            std::result::Result::Ok(result) => BlockCallResult {
+                block,
+                result: Some(result),
+                error: None,
+            },
+            Err(err) => BlockCallResult {
+                block,
+                result: None,
+                error: Some(err.to_string()),
+            },
+        })
+        .collect();
+
+    Ok(results)
+}
 
-    Ok(res)
+/// Replaces the json rpc error of a reverted execution with its decoded revert reason.
+fn explain_revert(err: NodeProviderError) -> anyhow::Error {
+    match err.revert_data() {
+        Some(revert_data) => {
+            anyhow::anyhow!("execution reverted: {}", decode_revert_reason(&revert_data))
+        }
+        None => err.into(),
+    }
+}
+
+/// Fields of a signed raw transaction along with its hash and recovered sender.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DecodedTransaction {
+    pub hash: H256,
+    #[serde(flatten)]
+    pub transaction: TypedTransaction,
+    // Not serialized by the ethers transaction types
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub chain_id: Option<U64>,
+    pub signature: Signature,
+}
+
+/// Decodes a signed rlp encoded transaction, either legacy or EIP-2718 typed, recovering its
+/// sender from the signature.
+pub fn decode_raw_transaction(raw: Bytes) -> anyhow::Result<DecodedTransaction> {
+    let (mut transaction, signature) = TypedTransaction::decode_signed(&Rlp::new(&raw))
+        .map_err(|err| anyhow::anyhow!("Invalid raw transaction: {err}"))?;
+
+    let from = signature.recover(transaction.sighash())?;
+    transaction.set_from(from);
+
+    Ok(DecodedTransaction {
+        hash: H256::from(keccak256(&raw)),
+        chain_id: transaction.chain_id(),
+        transaction,
+        signature,
+    })
 }
 
 #[cfg(test)]
@@ -171,7 +870,7 @@ mod tests {
 
             // Assert
             assert!(res.is_ok());
-            assert!(res.unwrap().is_none());
+            assert!(res.unwrap().is_not_found());
 
             Ok(())
         }
@@ -181,7 +880,7 @@ mod tests {
             // Arrange
             let (node_provider, anvil) = setup_test().await?;
 
-            let sender = *anvil.addresses().get(0).unwrap();
+            let sender = *anvil.addresses().first().unwrap();
             let receiver = *anvil.addresses().get(1).unwrap();
 
             let value = parse_ether(1)?;
@@ -211,9 +910,9 @@ mod tests {
                 assert!(res.is_ok());
 
                 let maybe_tx = res.unwrap();
-                assert!(maybe_tx.is_some());
+                assert!(maybe_tx.is_found());
 
-                let tx = maybe_tx.unwrap();
+                let tx = maybe_tx.found().unwrap();
                 assert_eq!(tx.hash, tx_hash);
                 assert_eq!(tx.from, sender);
                 assert_eq!(tx.to.unwrap(), receiver);
@@ -223,24 +922,91 @@ mod tests {
         }
     }
 
-    mod get_transaction_receipt {
-
-        use ethers::utils::parse_ether;
+    mod get_transaction_block_id_and_idx {
+        use ethers::{
+            providers::Middleware,
+            types::{BlockId, BlockNumber, TransactionRequest},
+            utils::parse_ether,
+        };
 
         use crate::cmd::{
             helpers::test::{generate_random_h256, send_tx_helper, setup_test},
-            transaction::get_transaction_receipt,
+            transaction::{get_transaction_block_id_and_idx, get_transaction_from_block},
         };
 
         #[tokio::test]
-        async fn should_not_find_a_transaction_receipt() -> anyhow::Result<()> {
+        async fn should_return_the_same_transaction_as_the_block_based_lookup() -> anyhow::Result<()>
+        {
             // Arrange
-            let (node_provider, _anvil) = setup_test().await?;
+            let (node_provider, anvil) = setup_test().await?;
 
-            let tx_hash = generate_random_h256();
+            let sender = *anvil.addresses().first().unwrap();
+            let receiver = *anvil.addresses().get(1).unwrap();
+
+            let tx_receipt =
+                send_tx_helper(&node_provider, sender, receiver, parse_ether(1)?).await?;
+
+            let test_cases = [
+                BlockId::Hash(tx_receipt.block_hash.unwrap()),
+                BlockId::Number(BlockNumber::Number(tx_receipt.block_number.unwrap())),
+            ];
+
+            for block_id in test_cases {
+                // Act
+                let res = get_transaction_block_id_and_idx(&node_provider, block_id, 0).await;
+
+                // Assert
+                assert!(res.is_ok());
+
+                let expected_tx = get_transaction_from_block(&node_provider, block_id, 0).await?;
+                assert!(expected_tx.is_some());
+                assert_eq!(res.unwrap(), expected_tx);
+            }
+
+            Ok(())
+        }
+
+        #[tokio::test]
+        async fn should_fail_with_an_out_of_range_index() -> anyhow::Result<()> {
+            // Arrange
+            let (node_provider, anvil) = setup_test().await?;
+
+            let sender = *anvil.addresses().first().unwrap();
+            let receiver = *anvil.addresses().get(1).unwrap();
+
+            let tx_receipt =
+                send_tx_helper(&node_provider, sender, receiver, parse_ether(1)?).await?;
+
+            // Act
+            let res = get_transaction_block_id_and_idx(
+                &node_provider,
+                BlockId::Hash(tx_receipt.block_hash.unwrap()),
+                1,
+            )
+            .await;
+
+            // Assert
+            assert!(res.is_err());
+            assert!(res
+                .unwrap_err()
+                .to_string()
+                .contains("Transaction index 1 is out of range"));
+
+            Ok(())
+        }
+
+        #[tokio::test]
+        async fn should_not_find_a_transaction_in_a_non_existing_block() -> anyhow::Result<()> {
+            // Arrange
+            let (node_provider, _anvil) = setup_test().await?;
 
             // Act
-            let res = get_transaction_receipt(&node_provider, tx_hash).await;
+            let res = get_transaction_block_id_and_idx(
+                &node_provider,
+                BlockId::Hash(generate_random_h256()),
+                0,
+            )
+            .await;
 
             // Assert
             assert!(res.is_ok());
@@ -250,47 +1016,503 @@ mod tests {
         }
 
         #[tokio::test]
-        async fn should_find_a_transaction_receipt() -> anyhow::Result<()> {
+        async fn should_find_a_transaction_in_the_pending_block() -> anyhow::Result<()> {
             // Arrange
             let (node_provider, anvil) = setup_test().await?;
 
-            let sender = *anvil.addresses().get(0).unwrap();
+            let sender = *anvil.addresses().first().unwrap();
             let receiver = *anvil.addresses().get(1).unwrap();
 
-            let value = parse_ether(1)?;
+            node_provider
+                .provider()
+                .request::<_, ()>("evm_setAutomine", [false])
+                .await?;
 
-            let tx_hash = send_tx_helper(&node_provider, sender, receiver, value)
-                .await?
-                .transaction_hash;
+            let tx = TransactionRequest::new()
+                .value(parse_ether(1)?)
+                .from(sender)
+                .to(receiver);
+            let tx_hash = node_provider.send_transaction(tx, None).await?.tx_hash();
 
             // Act
-            let res = get_transaction_receipt(&node_provider, tx_hash).await;
+            let res = get_transaction_block_id_and_idx(
+                &node_provider,
+                BlockId::Number(BlockNumber::Pending),
+                0,
+            )
+            .await;
 
             // Assert
             assert!(res.is_ok());
 
-            let maybe_tx_receipt = res.unwrap();
-            assert!(maybe_tx_receipt.is_some());
-
-            let tx_receipt = maybe_tx_receipt.unwrap();
-            assert_eq!(tx_receipt.transaction_hash, tx_hash);
-            assert_eq!(tx_receipt.from, sender);
-            assert_eq!(tx_receipt.to.unwrap(), receiver);
+            let maybe_tx = res.unwrap();
+            assert!(maybe_tx.is_some());
+            assert_eq!(maybe_tx.unwrap().hash, tx_hash);
 
             Ok(())
         }
     }
 
-    mod send_transaction {
-        use ethers::{
-            signers::{LocalWallet, Signer},
-            types::{
-                transaction::eip2718::TypedTransaction, Bytes, TransactionRequest, H160, U256,
-            },
-            utils::Anvil,
+    mod wait_for_confirmations {
+        use std::time::Duration;
+
+        use ethers::{providers::Middleware, types::TransactionRequest, utils::parse_ether};
+
+        use crate::cmd::{
+            helpers::test::{generate_random_h256, send_tx_helper, setup_test},
+            transaction::{wait_for_confirmations, TransactionStatus},
         };
 
-        use crate::{
+        const INTERVAL: Duration = Duration::from_millis(50);
+
+        #[tokio::test]
+        async fn should_report_the_confirmations_of_a_mined_transaction() -> anyhow::Result<()> {
+            // Arrange
+            let (node_provider, anvil) = setup_test().await?;
+
+            let sender = *anvil.addresses().first().unwrap();
+            let receiver = *anvil.addresses().get(1).unwrap();
+
+            let receipt = send_tx_helper(&node_provider, sender, receiver, parse_ether(1)?).await?;
+            for _ in 0..2 {
+                node_provider
+                    .provider()
+                    .request::<_, ()>("evm_mine", ())
+                    .await?;
+            }
+
+            // Act
+            let res = wait_for_confirmations(
+                &node_provider,
+                receipt.transaction_hash,
+                Some(3),
+                INTERVAL,
+                None,
+            )
+            .await?;
+
+            // Assert
+            let TransactionStatus::Mined {
+                confirmations,
+                receipt: status_receipt,
+            } = res
+            else {
+                panic!("Should be mined!");
+            };
+            assert_eq!(confirmations, 3);
+            assert_eq!(status_receipt.transaction_hash, receipt.transaction_hash);
+
+            Ok(())
+        }
+
+        #[tokio::test]
+        async fn should_report_a_pending_transaction() -> anyhow::Result<()> {
+            // Arrange
+            let (node_provider, anvil) = setup_test().await?;
+
+            let sender = *anvil.addresses().first().unwrap();
+            let receiver = *anvil.addresses().get(1).unwrap();
+
+            node_provider
+                .provider()
+                .request::<_, ()>("evm_setAutomine", [false])
+                .await?;
+            let pending_tx = node_provider
+                .send_transaction(TransactionRequest::new().from(sender).to(receiver), None)
+                .await?;
+
+            // Act
+            let res =
+                wait_for_confirmations(&node_provider, pending_tx.tx_hash(), None, INTERVAL, None)
+                    .await?;
+
+            // Assert
+            assert!(matches!(res, TransactionStatus::Pending));
+
+            Ok(())
+        }
+
+        #[tokio::test]
+        async fn should_report_an_unknown_transaction_as_not_found() -> anyhow::Result<()> {
+            // Arrange
+            let (node_provider, _anvil) = setup_test().await?;
+
+            // Act
+            let res = wait_for_confirmations(
+                &node_provider,
+                generate_random_h256(),
+                None,
+                INTERVAL,
+                None,
+            )
+            .await?;
+
+            // Assert
+            assert!(matches!(res, TransactionStatus::NotFound));
+
+            Ok(())
+        }
+
+        #[tokio::test]
+        async fn should_stop_waiting_after_the_timeout() -> anyhow::Result<()> {
+            // Arrange
+            let (node_provider, _anvil) = setup_test().await?;
+
+            // Act
+            let res = wait_for_confirmations(
+                &node_provider,
+                generate_random_h256(),
+                Some(1),
+                INTERVAL,
+                Some(Duration::from_millis(200)),
+            )
+            .await;
+
+            // Assert
+            assert!(res.is_err());
+
+            Ok(())
+        }
+    }
+
+    mod get_transaction_receipt {
+
+        use ethers::{
+            providers::Middleware,
+            types::{Address, Bytes, TransactionReceipt, TransactionRequest, H256, U64},
+            utils::parse_ether,
+        };
+        use serde_json::json;
+
+        use crate::cmd::{
+            helpers::test::{
+                deploy_contract_helper, generate_random_h256, mock_node_provider,
+                reverting_contract_init_code, send_tx_helper, setup_test,
+                spawn_mock_rpc_method_server, REVERT_REASON,
+            },
+            transaction::get_transaction_receipt,
+        };
+
+        // Fixture contract that forwards the received ETH to `receiver`:
+        // constructor: PUSH1 0x21, DUP1, PUSH1 0x0b, PUSH1 0, CODECOPY, PUSH1 0, RETURN
+        // runtime: PUSH1 0 (x4), CALLVALUE, PUSH20 <receiver>, GAS, CALL, STOP
+        fn forwarder_contract_init_code(receiver: Address) -> Bytes {
+            let mut init_code = vec![
+                0x60, 0x21, 0x80, 0x60, 0x0b, 0x60, 0x00, 0x39, 0x60, 0x00, 0xf3,
+            ];
+            init_code
+                .extend_from_slice(&[0x60, 0x00, 0x60, 0x00, 0x60, 0x00, 0x60, 0x00, 0x34, 0x73]);
+            init_code.extend_from_slice(receiver.as_bytes());
+            init_code.extend_from_slice(&[0x5a, 0xf1, 0x00]);
+
+            init_code.into()
+        }
+
+        #[tokio::test]
+        async fn should_not_find_a_transaction_receipt() -> anyhow::Result<()> {
+            // Arrange
+            let (node_provider, _anvil) = setup_test().await?;
+
+            let tx_hash = generate_random_h256();
+
+            // Act
+            let res = get_transaction_receipt(&node_provider, tx_hash, false, false).await;
+
+            // Assert
+            assert!(res.is_ok());
+            assert!(res.unwrap().is_not_found());
+
+            Ok(())
+        }
+
+        #[tokio::test]
+        async fn should_find_a_transaction_receipt() -> anyhow::Result<()> {
+            // Arrange
+            let (node_provider, anvil) = setup_test().await?;
+
+            let sender = *anvil.addresses().first().unwrap();
+            let receiver = *anvil.addresses().get(1).unwrap();
+
+            let value = parse_ether(1)?;
+
+            let tx_hash = send_tx_helper(&node_provider, sender, receiver, value)
+                .await?
+                .transaction_hash;
+
+            // Act
+            let res = get_transaction_receipt(&node_provider, tx_hash, false, false).await;
+
+            // Assert
+            assert!(res.is_ok());
+
+            let maybe_tx_receipt = res.unwrap();
+            assert!(maybe_tx_receipt.is_found());
+
+            let tx_receipt = maybe_tx_receipt.found().unwrap();
+            assert_eq!(tx_receipt.transaction_hash, tx_hash);
+            assert_eq!(tx_receipt.from, sender);
+            assert_eq!(tx_receipt.to.unwrap(), receiver);
+            assert_eq!(tx_receipt.status_text, "success");
+            assert_eq!(tx_receipt.revert_reason, None);
+
+            Ok(())
+        }
+
+        #[tokio::test]
+        async fn should_explain_a_failed_transaction() -> anyhow::Result<()> {
+            // Arrange
+            let (node_provider, anvil) = setup_test().await?;
+
+            let sender = *anvil.addresses().first().unwrap();
+
+            let contract =
+                deploy_contract_helper(&node_provider, sender, reverting_contract_init_code())
+                    .await?;
+
+            let tx = TransactionRequest::new()
+                .from(sender)
+                .to(contract)
+                .gas(100_000);
+
+            let tx_hash = node_provider
+                .send_transaction(tx, None)
+                .await?
+                .await?
+                .unwrap()
+                .transaction_hash;
+
+            // Act
+            let res = get_transaction_receipt(&node_provider, tx_hash, true, false).await;
+
+            // Assert
+            assert!(res.is_ok());
+
+            let tx_receipt = res.unwrap().found().unwrap();
+            assert_eq!(tx_receipt.status_text, "failed");
+            assert_eq!(tx_receipt.revert_reason, Some(REVERT_REASON.to_string()));
+
+            Ok(())
+        }
+
+        #[tokio::test]
+        async fn should_not_explain_a_failed_transaction_unless_requested() -> anyhow::Result<()> {
+            // Arrange
+            let (node_provider, anvil) = setup_test().await?;
+
+            let sender = *anvil.addresses().first().unwrap();
+
+            let contract =
+                deploy_contract_helper(&node_provider, sender, reverting_contract_init_code())
+                    .await?;
+
+            let tx = TransactionRequest::new()
+                .from(sender)
+                .to(contract)
+                .gas(100_000);
+
+            let tx_hash = node_provider
+                .send_transaction(tx, None)
+                .await?
+                .await?
+                .unwrap()
+                .transaction_hash;
+
+            // Act
+            let res = get_transaction_receipt(&node_provider, tx_hash, false, false).await;
+
+            // Assert
+            assert!(res.is_ok());
+
+            let tx_receipt = res.unwrap().found().unwrap();
+            assert_eq!(tx_receipt.status_text, "failed");
+            assert_eq!(tx_receipt.revert_reason, None);
+
+            Ok(())
+        }
+
+        #[tokio::test]
+        async fn should_include_the_internal_eth_transfers() -> anyhow::Result<()> {
+            // Arrange
+            let (node_provider, anvil) = setup_test().await?;
+
+            let sender = *anvil.addresses().first().unwrap();
+            let receiver = *anvil.addresses().last().unwrap();
+            let value = parse_ether(1)?;
+
+            let forwarder = deploy_contract_helper(
+                &node_provider,
+                sender,
+                forwarder_contract_init_code(receiver),
+            )
+            .await?;
+
+            let tx_hash = send_tx_helper(&node_provider, sender, forwarder, value)
+                .await?
+                .transaction_hash;
+
+            // Act
+            let res = get_transaction_receipt(&node_provider, tx_hash, false, true).await;
+
+            // Assert
+            assert!(res.is_ok());
+
+            let internal_calls = res.unwrap().found().unwrap().internal_calls.unwrap();
+            assert_eq!(internal_calls.len(), 1);
+            assert_eq!(internal_calls[0].call_type, "CALL");
+            assert_eq!(internal_calls[0].from, forwarder);
+            assert_eq!(internal_calls[0].to, Some(receiver.into()));
+            assert_eq!(internal_calls[0].value, value);
+            assert_eq!(internal_calls[0].depth, 1);
+
+            Ok(())
+        }
+
+        #[tokio::test]
+        async fn should_not_trace_the_transaction_unless_requested() -> anyhow::Result<()> {
+            // Arrange
+            let (node_provider, anvil) = setup_test().await?;
+
+            let sender = *anvil.addresses().first().unwrap();
+            let receiver = *anvil.addresses().last().unwrap();
+
+            let tx_hash = send_tx_helper(&node_provider, sender, receiver, parse_ether(1)?)
+                .await?
+                .transaction_hash;
+
+            // Act
+            let res = get_transaction_receipt(&node_provider, tx_hash, false, false).await;
+
+            // Assert
+            assert!(res.is_ok());
+            assert_eq!(res.unwrap().found().unwrap().internal_calls, None);
+
+            Ok(())
+        }
+
+        #[tokio::test]
+        async fn should_report_a_node_that_does_not_support_tracing() -> anyhow::Result<()> {
+            // Arrange
+            let receipt = TransactionReceipt {
+                status: Some(U64::one()),
+                ..Default::default()
+            };
+            // debug_traceTransaction is answered with a method not found error
+            let url = spawn_mock_rpc_method_server(json!({
+                "eth_getTransactionReceipt": { "result": receipt },
+            }));
+            let node_provider = mock_node_provider(url).await?;
+
+            // Act
+            let res = get_transaction_receipt(&node_provider, H256::zero(), false, true).await;
+
+            // Assert
+            assert_eq!(
+                res.unwrap_err().to_string(),
+                "The node does not support transaction tracing (debug_traceTransaction)"
+            );
+
+            Ok(())
+        }
+    }
+
+    mod enriched_receipt {
+        use ethers::types::{Transaction, TransactionReceipt, U64};
+        use serde_json::json;
+
+        use crate::cmd::{
+            helpers::test::{mock_node_provider, spawn_mock_rpc_method_server},
+            transaction::{enrich_receipt, get_status_text, EnrichedReceipt},
+        };
+
+        #[test]
+        fn should_interpret_the_receipt_status() {
+            // Arrange
+            let test_cases = [
+                (Some(U64::one()), "success"),
+                (Some(U64::zero()), "failed"),
+                (None, "unknown"),
+            ];
+
+            for (status, expected) in test_cases {
+                let receipt = TransactionReceipt {
+                    status,
+                    ..Default::default()
+                };
+
+                // Act
+                let res = get_status_text(&receipt);
+
+                // Assert
+                assert_eq!(res, expected);
+            }
+        }
+
+        #[test]
+        fn should_add_the_interpreted_fields_next_to_the_receipt_fields() -> anyhow::Result<()> {
+            // Arrange
+            let receipt = EnrichedReceipt {
+                receipt: TransactionReceipt {
+                    status: Some(U64::zero()),
+                    ..Default::default()
+                },
+                status_text: "failed",
+                revert_reason: Some("nope".to_string()),
+                internal_calls: None,
+            };
+
+            // Act
+            let res = serde_json::to_value(receipt)?;
+
+            // Assert
+            assert_eq!(res["status"], json!("0x0"));
+            assert_eq!(res["statusText"], json!("failed"));
+            assert_eq!(res["revertReason"], json!("nope"));
+
+            Ok(())
+        }
+
+        #[tokio::test]
+        async fn should_not_fail_if_the_node_does_not_return_the_revert_data() -> anyhow::Result<()>
+        {
+            // Arrange
+            let tx = Transaction {
+                block_number: Some(U64::one()),
+                ..Default::default()
+            };
+            let url = spawn_mock_rpc_method_server(json!({
+                "eth_getTransactionByHash": { "result": tx },
+                "eth_call": { "error": { "code": -32000, "message": "missing trie node" } },
+            }));
+            let node_provider = mock_node_provider(url).await?;
+
+            let receipt = TransactionReceipt {
+                status: Some(U64::zero()),
+                ..Default::default()
+            };
+
+            // Act
+            let res = enrich_receipt(&node_provider, receipt, true).await?;
+
+            // Assert
+            assert_eq!(res.status_text, "failed");
+            assert_eq!(res.revert_reason, None);
+
+            Ok(())
+        }
+    }
+
+    mod send_transaction {
+        use ethers::{
+            providers::Middleware,
+            signers::{LocalWallet, Signer},
+            types::{
+                transaction::eip2718::TypedTransaction, Bytes, Eip1559TransactionRequest,
+                TransactionRequest, H160, U256,
+            },
+            utils::Anvil,
+        };
+
+        use crate::{
             cmd::{
                 helpers::test::setup_test,
                 transaction::{
@@ -301,185 +1523,909 @@ mod tests {
             context::CommandExecutionContext,
         };
 
-        fn get_raw_transaction(
-            signer: &LocalWallet,
-            receiver: H160,
-            chain_id: u64,
-            value: Option<U256>,
-        ) -> Bytes {
-            let mut tx: TypedTransaction = TransactionRequest::new()
-                .to(receiver)
-                .gas(30000)
-                .gas_price(14_000_000_000_u128)
-                .chain_id(chain_id)
-                .into();
+        fn get_raw_transaction(
+            signer: &LocalWallet,
+            receiver: H160,
+            chain_id: u64,
+            value: Option<U256>,
+        ) -> Bytes {
+            let mut tx: TypedTransaction = TransactionRequest::new()
+                .to(receiver)
+                .gas(30000)
+                .gas_price(14_000_000_000_u128)
+                .chain_id(chain_id)
+                .into();
+
+            if let Some(value) = value {
+                tx.set_value(value);
+            }
+
+            let sig = signer.sign_transaction_sync(&tx);
+
+            tx.rlp_signed(&sig)
+        }
+
+        #[tokio::test]
+        async fn should_send_the_raw_transaction() -> anyhow::Result<()> {
+            // Arrange
+            let (node_provider, anvil) = setup_test().await?;
+
+            let receiver = *anvil.addresses().get(1).unwrap();
+            let signer: LocalWallet = anvil.keys().first().unwrap().clone().into();
+
+            let raw_tx = get_raw_transaction(&signer, receiver, anvil.chain_id(), None);
+
+            // Act
+            let res = send_transaction(
+                &node_provider,
+                SendTransactionOptions::new(TransactionKind::RawTransaction(raw_tx), None),
+            )
+            .await;
+
+            // Assert
+            assert!(res.is_ok());
+
+            Ok(())
+        }
+
+        #[tokio::test]
+        async fn should_send_the_typed_transaction() -> anyhow::Result<()> {
+            // Arrange
+            let (node_provider, anvil) = setup_test().await?;
+
+            let sender = *anvil.addresses().first().unwrap();
+            let receiver = *anvil.addresses().get(1).unwrap();
+
+            let typed_tx = TransactionRequest::new().from(sender).to(receiver).into();
+
+            // Act
+            let res = send_transaction(
+                &node_provider,
+                SendTransactionOptions::new(TransactionKind::TypedTransaction(typed_tx), None),
+            )
+            .await;
+
+            // Assert
+            assert!(res.is_ok());
+
+            Ok(())
+        }
+
+        #[tokio::test]
+        async fn should_send_from_the_unlocked_account_at_the_given_index() -> anyhow::Result<()> {
+            // Arrange
+            let (node_provider, anvil) = setup_test().await?;
+
+            let sender = *anvil.addresses().get(1).unwrap();
+            let receiver = *anvil.addresses().get(2).unwrap();
+
+            let typed_tx = TransactionRequest::new().to(receiver).into();
+
+            // Act
+            let res = send_transaction(
+                &node_provider,
+                SendTransactionOptions::new(
+                    TransactionKind::TypedTransaction(typed_tx),
+                    Some(true),
+                )
+                .with_from_index(Some(1)),
+            )
+            .await?;
+
+            // Assert
+            let SendTxResult::Receipt(mined_tx) = res else {
+                panic!("Should have waited for the receipt!");
+            };
+
+            assert_eq!(mined_tx.receipt.unwrap().receipt.from, sender);
+
+            Ok(())
+        }
+
+        #[tokio::test]
+        async fn should_fail_if_the_from_index_is_out_of_range() -> anyhow::Result<()> {
+            // Arrange
+            let (node_provider, anvil) = setup_test().await?;
+
+            let receiver = *anvil.addresses().first().unwrap();
+            let accounts_count = anvil.addresses().len();
+
+            let typed_tx = TransactionRequest::new().to(receiver).into();
+
+            // Act
+            let res = send_transaction(
+                &node_provider,
+                SendTransactionOptions::new(TransactionKind::TypedTransaction(typed_tx), None)
+                    .with_from_index(Some(accounts_count)),
+            )
+            .await;
+
+            // Assert
+            assert!(res.is_err());
+
+            Ok(())
+        }
+
+        #[tokio::test]
+        async fn should_not_send_to_an_account_without_code_if_only_if_contract_is_set(
+        ) -> anyhow::Result<()> {
+            // Arrange
+            let (node_provider, anvil) = setup_test().await?;
+
+            let sender = *anvil.addresses().first().unwrap();
+            let receiver = *anvil.addresses().get(1).unwrap();
+
+            let typed_tx = TransactionRequest::new()
+                .from(sender)
+                .to(receiver)
+                .data(Bytes::from_static(&[0x12, 0x34]))
+                .into();
+
+            // Act
+            let res = send_transaction(
+                &node_provider,
+                SendTransactionOptions::new(TransactionKind::TypedTransaction(typed_tx), None)
+                    .with_only_if_contract(true),
+            )
+            .await;
+
+            // Assert
+            assert!(res.is_err());
+            assert_eq!(
+                node_provider.get_transaction_count(sender, None).await?,
+                0.into()
+            );
+
+            Ok(())
+        }
+
+        #[tokio::test]
+        async fn should_send_an_eip1559_transaction() -> anyhow::Result<()> {
+            // Arrange
+            let (node_provider, anvil) = setup_test().await?;
+
+            let sender = *anvil.addresses().first().unwrap();
+            let receiver = *anvil.addresses().get(1).unwrap();
+
+            let typed_tx = Eip1559TransactionRequest::new()
+                .from(sender)
+                .to(receiver)
+                .into();
+
+            // Act
+            let res = send_transaction(
+                &node_provider,
+                SendTransactionOptions::new(
+                    TransactionKind::TypedTransaction(typed_tx),
+                    Some(true),
+                ),
+            )
+            .await?;
+
+            // Assert
+            let SendTxResult::Receipt(mined_tx) = res else {
+                panic!("Should have waited for the receipt!");
+            };
+
+            assert_eq!(mined_tx.receipt.unwrap().transaction_type, Some(2.into()));
+
+            Ok(())
+        }
+
+        #[tokio::test]
+        async fn should_return_the_transaction_hash_if_wait_is_false() -> anyhow::Result<()> {
+            // Arrange
+            let (node_provider, anvil) = setup_test().await?;
+
+            let receiver = *anvil.addresses().get(1).unwrap();
+            let signer: LocalWallet = anvil.keys().first().unwrap().clone().into();
+
+            let raw_tx = get_raw_transaction(&signer, receiver, anvil.chain_id(), None);
+
+            // Act
+            let res = send_transaction(
+                &node_provider,
+                SendTransactionOptions::new(TransactionKind::RawTransaction(raw_tx), Some(false)),
+            )
+            .await?;
+
+            // Assert
+            assert!(matches!(res, SendTxResult::PendingTransaction(_)));
+
+            Ok(())
+        }
+
+        #[tokio::test]
+        async fn should_return_the_transaction_receipt_if_wait_is_true() -> anyhow::Result<()> {
+            // Arrange
+            let (node_provider, anvil) = setup_test().await?;
+
+            let receiver = *anvil.addresses().get(1).unwrap();
+            let signer: LocalWallet = anvil.keys().first().unwrap().clone().into();
+
+            let raw_tx = get_raw_transaction(&signer, receiver, anvil.chain_id(), None);
+
+            // Act
+            let res = send_transaction(
+                &node_provider,
+                SendTransactionOptions::new(TransactionKind::RawTransaction(raw_tx), Some(true)),
+            )
+            .await?;
+
+            // Assert
+            assert!(matches!(res, SendTxResult::Receipt(_)));
+
+            Ok(())
+        }
+
+        #[tokio::test]
+        async fn should_include_the_explorer_url_of_the_provided_explorer_base(
+        ) -> anyhow::Result<()> {
+            // Arrange
+            let (node_provider, anvil) = setup_test().await?;
+
+            let receiver = *anvil.addresses().get(1).unwrap();
+            let signer: LocalWallet = anvil.keys().first().unwrap().clone().into();
+
+            let raw_tx = get_raw_transaction(&signer, receiver, anvil.chain_id(), None);
+
+            // Act
+            let res = send_transaction(
+                &node_provider,
+                SendTransactionOptions::new(TransactionKind::RawTransaction(raw_tx), Some(true))
+                    .with_explorer_base(Some("https://explorer.local/".into())),
+            )
+            .await?;
+
+            // Assert
+            let SendTxResult::Receipt(mined_tx) = res else {
+                panic!("Should be a receipt!");
+            };
+
+            let tx_hash = mined_tx.receipt.unwrap().transaction_hash;
+            assert_eq!(
+                mined_tx.explorer_url,
+                Some(format!("https://explorer.local/tx/{tx_hash:?}"))
+            );
+
+            Ok(())
+        }
+
+        #[tokio::test]
+        async fn should_omit_the_explorer_url_for_unknown_chains() -> anyhow::Result<()> {
+            // Arrange
+            let (node_provider, anvil) = setup_test().await?;
+
+            let receiver = *anvil.addresses().get(1).unwrap();
+            let signer: LocalWallet = anvil.keys().first().unwrap().clone().into();
+
+            let raw_tx = get_raw_transaction(&signer, receiver, anvil.chain_id(), None);
+
+            // Act
+            let res = send_transaction(
+                &node_provider,
+                SendTransactionOptions::new(TransactionKind::RawTransaction(raw_tx), Some(true)),
+            )
+            .await?;
+
+            // Assert
+            let SendTxResult::Receipt(mined_tx) = res else {
+                panic!("Should be a receipt!");
+            };
+
+            assert!(mined_tx.explorer_url.is_none());
+
+            Ok(())
+        }
+
+        #[test]
+        fn should_send_the_transaction_from_the_private_key_address() -> anyhow::Result<()> {
+            // Arrange
+            let anvil = Anvil::new().spawn();
+
+            let receiver = *anvil.addresses().get(1).unwrap();
+            let priv_key = hex::encode(anvil.keys().first().unwrap().to_be_bytes());
+            let signer: LocalWallet = priv_key.parse()?;
+
+            let overrides = ConfigOverrides::new(Some(priv_key), Some(anvil.endpoint()), None);
+
+            let config = get_config(overrides)?;
+
+            let execution_context = CommandExecutionContext::new(config)?;
+
+            let typed_tx = TransactionRequest::new().to(receiver).into();
+
+            // Act
+            let res = execution_context.execute(send_transaction(
+                execution_context.node_provider(),
+                SendTransactionOptions::new(
+                    TransactionKind::TypedTransaction(typed_tx),
+                    Some(true),
+                ),
+            ))?;
+
+            // Assert
+            match res {
+                SendTxResult::PendingTransaction(_) => panic!("Should be a receipt!"),
+                SendTxResult::Receipt(r) => assert_eq!(r.receipt.unwrap().from, signer.address()),
+            }
+
+            Ok(())
+        }
+    }
+
+    mod send_batch {
+        use ethers::{
+            providers::Middleware,
+            types::{Bytes, H160, U256},
+        };
+
+        use crate::cmd::{
+            helpers::test::setup_test,
+            transaction::{load_batch_transfers, send_batch, BatchTransfer},
+        };
 
-            if let Some(value) = value {
-                tx.set_value(value);
+        fn write_batch_file(name: &str, content: &str) -> anyhow::Result<std::path::PathBuf> {
+            let path =
+                std::env::temp_dir().join(format!("yaeth-batch-{name}-{}", std::process::id()));
+            std::fs::write(&path, content)?;
+
+            Ok(path)
+        }
+
+        #[test]
+        fn should_load_the_transfers_skipping_the_header() -> anyhow::Result<()> {
+            // Arrange
+            let receiver = H160::repeat_byte(0x01);
+            let path = write_batch_file(
+                "header",
+                &format!("to,value,data\n{receiver:?},1000\n\n{receiver:?},0x10,0x1234\n"),
+            )?;
+
+            // Act
+            let res = load_batch_transfers(&path);
+            std::fs::remove_file(path)?;
+
+            // Assert
+            assert_eq!(
+                res?,
+                vec![
+                    BatchTransfer {
+                        line: 2,
+                        to: receiver,
+                        value: 1000.into(),
+                        data: None,
+                    },
+                    BatchTransfer {
+                        line: 4,
+                        to: receiver,
+                        value: 16.into(),
+                        data: Some(Bytes::from_static(&[0x12, 0x34])),
+                    },
+                ]
+            );
+
+            Ok(())
+        }
+
+        #[test]
+        fn should_report_the_line_of_an_invalid_row() -> anyhow::Result<()> {
+            // Arrange
+            let path = write_batch_file("invalid", "0x01,1000\n")?;
+
+            // Act
+            let res = load_batch_transfers(&path);
+            std::fs::remove_file(path)?;
+
+            // Assert
+            assert!(res
+                .unwrap_err()
+                .to_string()
+                .starts_with("Invalid row at line 1: invalid address 0x01"));
+
+            Ok(())
+        }
+
+        #[tokio::test]
+        async fn should_send_every_transfer_of_the_batch() -> anyhow::Result<()> {
+            // Arrange
+            let (node_provider, anvil) = setup_test().await?;
+
+            let sender = anvil.addresses()[0];
+            let receivers = [anvil.addresses()[1], anvil.addresses()[2]];
+            let balances_before = [
+                node_provider.get_balance(receivers[0], None).await?,
+                node_provider.get_balance(receivers[1], None).await?,
+            ];
+
+            let transfers = receivers
+                .iter()
+                .enumerate()
+                .map(|(idx, receiver)| BatchTransfer {
+                    line: idx + 1,
+                    to: *receiver,
+                    value: U256::from(1000 * (idx + 1)),
+                    data: None,
+                })
+                .collect();
+
+            // Act
+            let res = send_batch(&node_provider, Some(sender), transfers).await;
+
+            // Assert
+            let results = res?;
+            assert_eq!(results.len(), 2);
+
+            for (idx, result) in results.iter().enumerate() {
+                assert!(result.error.is_none());
+
+                let receipt = node_provider
+                    .get_transaction_receipt(result.hash.unwrap())
+                    .await?
+                    .unwrap();
+                assert_eq!(receipt.status, Some(1.into()));
+
+                let balance = node_provider.get_balance(receivers[idx], None).await?;
+                assert_eq!(balance, balances_before[idx] + U256::from(1000 * (idx + 1)));
             }
 
-            let sig = signer.sign_transaction_sync(&tx);
+            Ok(())
+        }
+    }
 
-            tx.rlp_signed(&sig)
+    mod get_explorer_base {
+        use ethers::types::H256;
+
+        use crate::cmd::transaction::{get_explorer_base, get_explorer_tx_url};
+
+        #[test]
+        fn should_build_the_explorer_url_for_mainnet() {
+            // Arrange
+            let tx_hash = H256::repeat_byte(0xab);
+
+            // Act
+            let res = get_explorer_base(1).map(|base| get_explorer_tx_url(base, tx_hash));
+
+            // Assert
+            assert_eq!(res, Some(format!("https://etherscan.io/tx/{tx_hash:?}")));
+        }
+
+        #[test]
+        fn should_not_find_an_explorer_for_unknown_chains() {
+            // Act
+            let res = get_explorer_base(31337);
+
+            // Assert
+            assert!(res.is_none());
         }
+    }
+
+    mod call {
+        use ethers::{
+            abi::{self, Token},
+            types::{transaction::eip2718::TypedTransaction, Bytes, TransactionRequest, H160},
+        };
+        use serde_json::json;
+        use std::time::Duration;
+
+        use crate::{
+            cmd::{
+                helpers::test::{
+                    deploy_contract_helper, error_string_revert_data, mock_node_provider,
+                    reverting_contract_init_code, setup_test, spawn_mock_rpc_error_server,
+                    spawn_mock_rpc_server,
+                },
+                transaction::{call, SimulateTransactionOptions},
+            },
+            context::NodeProvider,
+        };
+
+        // Fixture contract returning 42 on every call:
+        // constructor: PUSH1 0x0a, DUP1, PUSH1 0x0b, PUSH1 0, CODECOPY, PUSH1 0, RETURN
+        // runtime: PUSH1 0x2a, PUSH1 0, MSTORE, PUSH1 0x20, PUSH1 0, RETURN
+        const CONTRACT_INIT_CODE: &str = "0x600a80600b6000396000f3602a60005260206000f3";
 
         #[tokio::test]
-        async fn should_send_the_raw_transaction() -> anyhow::Result<()> {
+        async fn should_not_call_an_account_without_code_if_only_if_contract_is_set(
+        ) -> anyhow::Result<()> {
             // Arrange
             let (node_provider, anvil) = setup_test().await?;
 
             let receiver = *anvil.addresses().get(1).unwrap();
-            let signer: LocalWallet = anvil.keys().get(0).unwrap().clone().into();
 
-            let raw_tx = get_raw_transaction(&signer, receiver, anvil.chain_id(), None);
+            let typed_tx = TransactionRequest::new().to(receiver).into();
 
             // Act
-            let res = send_transaction(
+            let res = call(
                 &node_provider,
-                SendTransactionOptions::new(TransactionKind::RawTransaction(raw_tx), None),
+                SimulateTransactionOptions::new(typed_tx, None).with_only_if_contract(true),
             )
             .await;
 
             // Assert
-            assert!(res.is_ok());
+            assert!(res.is_err());
 
             Ok(())
         }
 
         #[tokio::test]
-        async fn should_send_the_typed_transaction() -> anyhow::Result<()> {
+        async fn should_call_a_contract_if_only_if_contract_is_set() -> anyhow::Result<()> {
             // Arrange
             let (node_provider, anvil) = setup_test().await?;
 
-            let sender = *anvil.addresses().get(0).unwrap();
-            let receiver = *anvil.addresses().get(1).unwrap();
+            let deployer = *anvil.addresses().first().unwrap();
+            let contract =
+                deploy_contract_helper(&node_provider, deployer, CONTRACT_INIT_CODE.parse()?)
+                    .await?;
 
-            let typed_tx = TransactionRequest::new().from(sender).to(receiver);
+            let typed_tx = TransactionRequest::new().to(contract).into();
 
             // Act
-            let res = send_transaction(
+            let res = call(
                 &node_provider,
-                SendTransactionOptions::new(TransactionKind::TypedTransaction(typed_tx), None),
+                SimulateTransactionOptions::new(typed_tx, None).with_only_if_contract(true),
             )
             .await;
 
             // Assert
             assert!(res.is_ok());
+            assert_eq!(res.unwrap().last(), Some(&0x2a));
 
             Ok(())
         }
 
+        async fn reverting_node_provider(revert_data: Vec<u8>) -> anyhow::Result<NodeProvider> {
+            let url = spawn_mock_rpc_error_server(json!({
+                "code": 3,
+                "message": "execution reverted",
+                "data": Bytes::from(revert_data),
+            }));
+            mock_node_provider(url).await
+        }
+
         #[tokio::test]
-        async fn should_return_the_transaction_hash_if_wait_is_false() -> anyhow::Result<()> {
+        async fn should_decode_the_revert_reason_of_a_reverted_call() -> anyhow::Result<()> {
+            // Arrange
+            let mut panic_data = vec![0x4e, 0x48, 0x7b, 0x71];
+            panic_data.extend(abi::encode(&[Token::Uint(0x11.into())]));
+
+            let test_cases = [
+                (
+                    error_string_revert_data("ERC20: insufficient allowance"),
+                    "execution reverted: ERC20: insufficient allowance",
+                ),
+                (panic_data, "execution reverted: Panic(0x11)"),
+                (
+                    vec![0xde, 0xad, 0xbe, 0xef],
+                    "execution reverted: Custom error 0xdeadbeef",
+                ),
+            ];
+
+            for (revert_data, expected) in test_cases {
+                let node_provider = reverting_node_provider(revert_data).await?;
+
+                let typed_tx = TransactionRequest::new().to(H160::random()).into();
+
+                // Act
+                let res = call(
+                    &node_provider,
+                    SimulateTransactionOptions::new(typed_tx, None),
+                )
+                .await;
+
+                // Assert
+                assert_eq!(res.unwrap_err().to_string(), expected);
+            }
+
+            Ok(())
+        }
+
+        #[tokio::test]
+        async fn should_keep_the_node_error_if_the_call_did_not_revert() -> anyhow::Result<()> {
+            // Arrange
+            let url = spawn_mock_rpc_error_server(json!({ "code": -32000, "message": "boom" }));
+            let node_provider = mock_node_provider(url).await?;
+
+            let typed_tx = TransactionRequest::new().to(H160::random()).into();
+
+            // Act
+            let res = call(
+                &node_provider,
+                SimulateTransactionOptions::new(typed_tx, None),
+            )
+            .await;
+
+            // Assert
+            assert!(res.unwrap_err().to_string().contains("boom"));
+
+            Ok(())
+        }
+
+        #[tokio::test]
+        async fn should_decode_the_revert_reason_of_a_reverting_contract() -> anyhow::Result<()> {
             // Arrange
             let (node_provider, anvil) = setup_test().await?;
 
-            let receiver = *anvil.addresses().get(1).unwrap();
-            let signer: LocalWallet = anvil.keys().get(0).unwrap().clone().into();
+            let deployer = *anvil.addresses().first().unwrap();
+            let contract =
+                deploy_contract_helper(&node_provider, deployer, reverting_contract_init_code())
+                    .await?;
 
-            let raw_tx = get_raw_transaction(&signer, receiver, anvil.chain_id(), None);
+            let typed_tx = TransactionRequest::new().to(contract).into();
 
             // Act
-            let res = send_transaction(
+            let res = call(
                 &node_provider,
-                SendTransactionOptions::new(TransactionKind::RawTransaction(raw_tx), Some(false)),
+                SimulateTransactionOptions::new(typed_tx, None),
             )
-            .await?;
+            .await;
 
             // Assert
-            assert!(matches!(res, SendTxResult::PendingTransaction(_)));
+            assert_eq!(res.unwrap_err().to_string(), "execution reverted: nope");
 
             Ok(())
         }
 
         #[tokio::test]
-        async fn should_return_the_transaction_receipt_if_wait_is_true() -> anyhow::Result<()> {
+        async fn should_simulate_the_transaction() -> anyhow::Result<()> {
             // Arrange
             let (node_provider, anvil) = setup_test().await?;
 
+            let sender = *anvil.addresses().first().unwrap();
             let receiver = *anvil.addresses().get(1).unwrap();
-            let signer: LocalWallet = anvil.keys().get(0).unwrap().clone().into();
 
-            let raw_tx = get_raw_transaction(&signer, receiver, anvil.chain_id(), None);
+            let typed_tx = TransactionRequest::new().from(sender).to(receiver).into();
 
             // Act
-            let res = send_transaction(
+            let res = call(
                 &node_provider,
-                SendTransactionOptions::new(TransactionKind::RawTransaction(raw_tx), Some(true)),
+                SimulateTransactionOptions::new(typed_tx, None),
             )
-            .await?;
+            .await;
 
             // Assert
-            assert!(matches!(res, SendTxResult::Receipt(_)));
+            assert!(res.is_ok());
 
             Ok(())
         }
 
-        #[test]
-        fn should_send_the_transaction_from_the_private_key_address() -> anyhow::Result<()> {
+        #[tokio::test]
+        async fn should_fail_if_the_call_does_not_complete_within_the_timeout() -> anyhow::Result<()>
+        {
             // Arrange
-            let anvil = Anvil::new().spawn();
+            let (url, _) = spawn_mock_rpc_server(json!("0x2a"), Duration::from_secs(2));
+            let node_provider = mock_node_provider(url).await?;
 
-            let receiver = *anvil.addresses().get(1).unwrap();
-            let priv_key = hex::encode(anvil.keys().get(0).unwrap().to_be_bytes());
-            let signer: LocalWallet = priv_key.parse()?;
+            let typed_tx = TransactionRequest::new().to(H160::random()).into();
 
-            let overrides = ConfigOverrides::new(Some(priv_key), Some(anvil.endpoint()), None);
+            // Act
+            let res = call(
+                &node_provider,
+                SimulateTransactionOptions::new(typed_tx, None)
+                    .with_timeout(Some(Duration::from_millis(100))),
+            )
+            .await;
 
-            let config = get_config(overrides)?;
+            // Assert
+            assert_eq!(
+                res.unwrap_err().to_string(),
+                "The call timed out after 0.1s. Raise the limit with --command-timeout"
+            );
 
-            let execution_context = CommandExecutionContext::new(config)?;
+            Ok(())
+        }
 
-            let typed_tx = TransactionRequest::new().to(receiver);
+        #[test]
+        fn should_only_send_the_target_and_data_for_a_raw_call() -> anyhow::Result<()> {
+            // Arrange
+            let receiver = "0xf39fd6e51aad88f6f4ce6ab8827279cfffb92266".parse::<H160>()?;
+            let data = Bytes::from_static(&[0x12, 0x34]);
 
             // Act
-            let res = execution_context.execute(send_transaction(
-                execution_context.node_provider(),
-                SendTransactionOptions::new(
-                    TransactionKind::TypedTransaction(typed_tx),
-                    Some(true),
-                ),
-            ))?;
+            let options = SimulateTransactionOptions::raw(receiver.into(), data, None);
 
             // Assert
-            match res {
-                SendTxResult::PendingTransaction(_) => panic!("Should be a receipt!"),
-                SendTxResult::Receipt(r) => assert_eq!(r.unwrap().from, signer.address()),
-            }
+            let TypedTransaction::Legacy(request) = options.0 else {
+                panic!("Should be a legacy call!");
+            };
+
+            let request = serde_json::to_value(request)?;
+            let fields: Vec<&String> = request.as_object().unwrap().keys().collect();
+
+            assert_eq!(fields, ["data", "to"]);
+            assert_eq!(request["to"], format!("{receiver:?}"));
+            assert_eq!(request["data"], "0x1234");
 
             Ok(())
         }
     }
 
-    mod call {
-        use ethers::types::TransactionRequest;
+    mod call_multi_block {
+        use ethers::{
+            providers::Middleware,
+            types::{BlockId, BlockNumber, TransactionRequest, U256},
+        };
 
-        use crate::cmd::{
-            helpers::test::setup_test,
-            transaction::{call, SimulateTransactionOptions},
+        use crate::{
+            cmd::{
+                helpers::test::{deploy_contract_helper, send_tx_helper, setup_test},
+                transaction::{call_multi_block, BlockCallResult, SimulateTransactionOptions},
+            },
+            context::NodeProvider,
         };
 
+        // Fixture contract returning its own balance on every call:
+        // constructor: PUSH1 0x09, DUP1, PUSH1 0x0b, PUSH1 0, CODECOPY, PUSH1 0, RETURN
+        // runtime: SELFBALANCE, PUSH1 0, MSTORE, PUSH1 0x20, PUSH1 0, RETURN
+        const CONTRACT_INIT_CODE: &str = "0x600980600b6000396000f34760005260206000f3";
+
+        async fn block_id(node_provider: &NodeProvider) -> anyhow::Result<BlockId> {
+            let block_number = node_provider.get_block_number().await?;
+
+            Ok(BlockId::Number(BlockNumber::Number(block_number)))
+        }
+
         #[tokio::test]
-        async fn should_simulate_the_transaction() -> anyhow::Result<()> {
+        async fn should_return_the_call_output_at_each_block() -> anyhow::Result<()> {
             // Arrange
             let (node_provider, anvil) = setup_test().await?;
 
-            let sender = *anvil.addresses().get(0).unwrap();
-            let receiver = *anvil.addresses().get(1).unwrap();
+            let deployer = *anvil.addresses().first().unwrap();
+            let contract =
+                deploy_contract_helper(&node_provider, deployer, CONTRACT_INIT_CODE.parse()?)
+                    .await?;
+            let before_transfer = block_id(&node_provider).await?;
+
+            send_tx_helper(&node_provider, deployer, contract, U256::from(1000)).await?;
+            let after_transfer = block_id(&node_provider).await?;
 
-            let typed_tx = TransactionRequest::new().from(sender).to(receiver);
+            let typed_tx = TransactionRequest::new().to(contract).into();
 
             // Act
-            let res = call(
+            let res = call_multi_block(
                 &node_provider,
                 SimulateTransactionOptions::new(typed_tx, None),
+                vec![before_transfer, after_transfer],
             )
-            .await;
+            .await?;
 
             // Assert
-            assert!(res.is_ok());
+            let balance = |value: u64| {
+                let mut output = [0u8; 32];
+                U256::from(value).to_big_endian(&mut output);
+
+                Some(output.to_vec().into())
+            };
+            assert_eq!(
+                res,
+                vec![
+                    BlockCallResult {
+                        block: before_transfer,
+                        result: balance(0),
+                        error: None,
+                    },
+                    BlockCallResult {
+                        block: after_transfer,
+                        result: balance(1000),
+                        error: None,
+                    },
+                ]
+            );
+
+            Ok(())
+        }
+
+        #[tokio::test]
+        async fn should_report_the_failed_calls_without_failing_the_others() -> anyhow::Result<()> {
+            // Arrange
+            let (node_provider, anvil) = setup_test().await?;
+
+            let deployer = *anvil.addresses().first().unwrap();
+            let before_deployment = block_id(&node_provider).await?;
+            let contract =
+                deploy_contract_helper(&node_provider, deployer, CONTRACT_INIT_CODE.parse()?)
+                    .await?;
+
+            let typed_tx = TransactionRequest::new().to(contract).into();
+
+            // Act
+            let res = call_multi_block(
+                &node_provider,
+                SimulateTransactionOptions::new(typed_tx, None).with_only_if_contract(true),
+                vec![before_deployment, BlockId::Number(BlockNumber::Latest)],
+            )
+            .await?;
+
+            // Assert
+            assert_eq!(res.len(), 2);
+            assert!(res[0].result.is_none());
+            assert!(res[0].error.is_some());
+            assert!(res[1].result.is_some());
+            assert!(res[1].error.is_none());
+
+            Ok(())
+        }
+    }
+
+    mod decode_raw_transaction {
+        use ethers::{
+            signers::{LocalWallet, Signer},
+            types::{
+                transaction::eip2718::TypedTransaction, Bytes, Eip1559TransactionRequest,
+                NameOrAddress, TransactionRequest, H160, H256,
+            },
+            utils::{keccak256, parse_ether},
+        };
+
+        use crate::cmd::transaction::decode_raw_transaction;
+
+        #[test]
+        fn should_decode_the_signed_transaction_and_recover_its_sender() -> anyhow::Result<()> {
+            // Setup
+            let wallet = LocalWallet::new(&mut rand::thread_rng());
+            let receiver = H160::random();
+
+            let test_cases: [TypedTransaction; 2] = [
+                TransactionRequest::new()
+                    .to(receiver)
+                    .value(parse_ether(1)?)
+                    .gas(21_000)
+                    .gas_price(1_000_000_000)
+                    .nonce(7)
+                    .chain_id(1)
+                    .into(),
+                Eip1559TransactionRequest::new()
+                    .to(receiver)
+                    .value(parse_ether(1)?)
+                    .gas(21_000)
+                    .max_fee_per_gas(2_000_000_000)
+                    .max_priority_fee_per_gas(1_000_000_000)
+                    .nonce(7)
+                    .chain_id(1)
+                    .into(),
+            ];
+
+            for tx in test_cases {
+                // Arrange
+                let signature = wallet.sign_transaction_sync(&tx);
+                let raw = tx.rlp_signed(&signature);
+
+                // Act
+                let res = decode_raw_transaction(raw.clone());
+
+                // Assert
+                let decoded = res?;
+                assert_eq!(decoded.hash, H256::from(keccak256(&raw)));
+                assert_eq!(decoded.signature.r, signature.r);
+                assert_eq!(decoded.signature.s, signature.s);
+                assert_eq!(decoded.transaction.from(), Some(&wallet.address()));
+                assert_eq!(
+                    decoded.transaction.to(),
+                    Some(&NameOrAddress::Address(receiver))
+                );
+                assert_eq!(decoded.transaction.value(), Some(&parse_ether(1)?));
+                assert_eq!(decoded.transaction.gas(), Some(&21_000.into()));
+                assert_eq!(decoded.transaction.nonce(), Some(&7.into()));
+                assert_eq!(decoded.chain_id, Some(1.into()));
+            }
 
             Ok(())
         }
+
+        #[test]
+        fn should_not_decode_invalid_raw_transactions() {
+            // Arrange
+            let raw = Bytes::from_static(&[0x02, 0x01, 0x02]);
+
+            // Act
+            let res = decode_raw_transaction(raw);
+
+            // Assert
+            assert!(res.is_err());
+            assert!(res
+                .unwrap_err()
+                .to_string()
+                .contains("Invalid raw transaction"));
+        }
     }
 }
@@ -1,12 +1,40 @@
-use crate::context::NodeProvider;
+use std::{
+    collections::BTreeMap,
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+use crate::{
+    cmd::{
+        account::get_code,
+        event::{format_token, load_abi, param_name},
+        helpers::is_method_not_found,
+        signatures::find_known_signatures,
+    },
+    context::{NodeProvider, NodeProviderError},
+};
 use anyhow::Result;
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
 use ethers::{
-    providers::Middleware,
+    abi::{
+        self,
+        token::{LenientTokenizer, Tokenizer},
+        AbiParser, ParamType, Token,
+    },
+    core::k256::{elliptic_curve::sec1::ToEncodedPoint, PublicKey},
+    providers::{Middleware, MiddlewareError, ProviderError},
     types::{
-        transaction::eip2718::TypedTransaction, Address, BlockId, Bytes, EIP1186ProofResponse,
-        NameOrAddress, Signature, SyncingStatus, TransactionRequest, H160, H256, U256,
+        transaction::{
+            eip2718::TypedTransaction,
+            eip712::{EIP712Domain, TypedData},
+        },
+        Address, BlockId, Bytes, EIP1186ProofResponse, NameOrAddress, Selector, Signature,
+        SyncingStatus, TransactionRequest, H160, H256, U256,
     },
+    utils::{format_units, keccak256, parse_units, to_checksum, ParseUnits, Units},
 };
+use serde::{Deserialize, Serialize};
+use serde_json::json;
 
 // eth_accounts
 pub async fn get_accounts(node_provider: &NodeProvider) -> Result<Vec<H160>> {
@@ -15,6 +43,18 @@ pub async fn get_accounts(node_provider: &NodeProvider) -> Result<Vec<H160>> {
     Ok(accounts)
 }
 
+/// Returns the unlocked account of the node at the given position of eth_accounts.
+pub async fn get_account_at(node_provider: &NodeProvider, index: usize) -> Result<H160> {
+    let accounts = get_accounts(node_provider).await?;
+
+    accounts.get(index).copied().ok_or_else(|| {
+        anyhow::anyhow!(
+            "Account index {index} is out of range, the node has {} unlocked accounts",
+            accounts.len()
+        )
+    })
+}
+
 // eth_chainId
 pub async fn get_chain_id(node_provider: &NodeProvider) -> Result<U256> {
     let chain_id = node_provider.get_chainid().await?;
@@ -22,6 +62,65 @@ pub async fn get_chain_id(node_provider: &NodeProvider) -> Result<U256> {
     Ok(chain_id)
 }
 
+#[derive(Debug, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct ChainInfo {
+    pub chain_id: U256,
+    pub name: &'static str,
+    pub native_currency: Option<NativeCurrency>,
+}
+
+#[derive(Debug, Serialize, PartialEq, Eq)]
+pub struct NativeCurrency {
+    pub symbol: &'static str,
+    pub decimals: u8,
+}
+
+// eth_chainId
+/// Gets the chain id from the node along with the name and native currency of well known chains.
+pub async fn get_chain_info(node_provider: &NodeProvider) -> Result<ChainInfo> {
+    let chain_id = get_chain_id(node_provider).await?;
+
+    Ok(chain_info(chain_id))
+}
+
+/// Looks up the chain in the built-in registry of well known chains.
+pub fn chain_info(chain_id: U256) -> ChainInfo {
+    let known_chain = u64::try_from(chain_id).ok().and_then(get_known_chain);
+
+    match known_chain {
+        Some((name, symbol, decimals)) => ChainInfo {
+            chain_id,
+            name,
+            native_currency: Some(NativeCurrency { symbol, decimals }),
+        },
+        None => ChainInfo {
+            chain_id,
+            name: "unknown",
+            native_currency: None,
+        },
+    }
+}
+
+/// Returns the name, native currency symbol and decimals of well known chains.
+fn get_known_chain(chain_id: u64) -> Option<(&'static str, &'static str, u8)> {
+    let known_chain = match chain_id {
+        1 => ("mainnet", "ETH", 18),
+        5 => ("goerli", "ETH", 18),
+        10 => ("optimism", "ETH", 18),
+        56 => ("bsc", "BNB", 18),
+        100 => ("gnosis", "xDAI", 18),
+        137 => ("polygon", "MATIC", 18),
+        8453 => ("base", "ETH", 18),
+        42161 => ("arbitrum", "ETH", 18),
+        11155111 => ("sepolia", "ETH", 18),
+        1337 | 31337 => ("local", "ETH", 18),
+        _ => return None,
+    };
+
+    Some(known_chain)
+}
+
 // eth_getProof
 pub async fn get_proof(
     node_provider: &NodeProvider,
@@ -36,15 +135,185 @@ pub async fn get_proof(
     Ok(account_proof)
 }
 
-pub async fn get_protocol_version(node_provider: &NodeProvider) -> Result<U256> {
-    let protocol_version = node_provider.get_protocol_version().await?;
+#[derive(Debug, Serialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ProtocolVersion {
+    pub protocol_version: Option<U256>,
+    pub supported: bool,
+}
+
+// eth_protocolVersion
+/// Nodes that removed the method report it as not supported unless `strict` is set.
+pub async fn get_protocol_version(
+    node_provider: &NodeProvider,
+    strict: bool,
+) -> Result<ProtocolVersion> {
+    match node_provider.get_protocol_version().await {
+        Ok(protocol_version) => Ok(ProtocolVersion {
+            protocol_version: Some(protocol_version),
+            supported: true,
+        }),
+        Err(err) if !strict && is_method_not_found(&err) => Ok(ProtocolVersion {
+            protocol_version: None,
+            supported: false,
+        }),
+        Err(err) => Err(err),
+    }
+}
+
+// eth_call
+/// Resolves the ENS name to the address it points to.
+pub async fn resolve_ens_name(node_provider: &NodeProvider, name: String) -> Result<Address> {
+    match node_provider.resolve_name(&name).await {
+        Ok(address) if !address.is_zero() => Ok(address),
+        Ok(_) => anyhow::bail!("ENS name {name} not found"),
+        Err(err) if is_ens_name_not_found(&err) => anyhow::bail!("ENS name {name} not found"),
+        Err(err) => Err(err.into()),
+    }
+}
+
+// eth_call
+/// Gets the primary ENS name of the address through its reverse record.
+pub async fn lookup_ens_name(node_provider: &NodeProvider, address: Address) -> Result<String> {
+    match node_provider.lookup_address(address).await {
+        Ok(name) if !name.is_empty() => Ok(name),
+        Ok(_) => anyhow::bail!("ENS name not found for {address:?}"),
+        Err(err) if is_ens_name_not_found(&err) => {
+            anyhow::bail!("ENS name not found for {address:?}")
+        }
+        Err(err) => match err.as_inner() {
+            Some(ProviderError::EnsNotOwned(name)) => {
+                anyhow::bail!("The ENS name {name} of {address:?} does not resolve back to it")
+            }
+            _ => Err(err.into()),
+        },
+    }
+}
 
-    Ok(protocol_version)
+/// Tells if the resolution failed because the name has no resolver, which the provider reports
+/// as a generic ens error.
+fn is_ens_name_not_found(err: &NodeProviderError) -> bool {
+    matches!(err.as_inner(), Some(ProviderError::EnsError(_)))
 }
 
 pub enum SignTransactionData {
     Raw(Bytes),
-    Transaction(TransactionRequest),
+    Transaction(TypedTransaction),
+    TypedData(TypedData),
+}
+
+pub const DEFAULT_SIGNATURE_DATABASE_URL: &str = "https://www.4byte.directory";
+
+/// Json file holding the signatures found for each selector between runs.
+pub struct SelectorCache {
+    path: PathBuf,
+}
+
+impl SelectorCache {
+    pub fn new(path: impl AsRef<Path>) -> Self {
+        Self {
+            path: path.as_ref().to_path_buf(),
+        }
+    }
+
+    pub fn default_path() -> PathBuf {
+        std::env::temp_dir().join("yaeth-selectors.json")
+    }
+
+    /// Reads the cached signatures, a missing or unreadable cache is treated as empty.
+    fn load(&self) -> BTreeMap<String, Vec<String>> {
+        std::fs::read_to_string(&self.path)
+            .ok()
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .unwrap_or_default()
+    }
+
+    fn save(&self, signatures: &BTreeMap<String, Vec<String>>) -> Result<()> {
+        std::fs::write(&self.path, serde_json::to_string(signatures)?)?;
+
+        Ok(())
+    }
+}
+
+#[derive(Deserialize)]
+struct SignatureDatabasePage {
+    results: Vec<SignatureDatabaseEntry>,
+}
+
+#[derive(Deserialize)]
+struct SignatureDatabaseEntry {
+    id: u64,
+    text_signature: String,
+}
+
+/// Gets the signatures matching the function selector from a 4byte-directory compatible database.
+/// The oldest submissions come first as the later ones are more likely to be crafted collisions.
+/// Only found signatures are cached so that a selector registered later is picked up.
+pub async fn lookup_selector(
+    api_url: &str,
+    cache: &SelectorCache,
+    selector: Selector,
+) -> Result<Vec<String>> {
+    let hex_selector = format!("0x{}", hex::encode(selector));
+
+    let mut cached_signatures = cache.load();
+    if let Some(signatures) = cached_signatures.get(&hex_selector) {
+        return Ok(signatures.clone());
+    }
+
+    let url = format!(
+        "{}/api/v1/signatures/?hex_signature={hex_selector}",
+        api_url.trim_end_matches('/')
+    );
+    let response = reqwest::get(&url).await?.error_for_status()?.text().await?;
+
+    let mut page: SignatureDatabasePage = serde_json::from_str(&response)
+        .map_err(|err| anyhow::anyhow!("Invalid response from the signature database: {err}"))?;
+    page.results.sort_by_key(|entry| entry.id);
+
+    let signatures: Vec<String> = page
+        .results
+        .into_iter()
+        .map(|entry| entry.text_signature)
+        .collect();
+
+    if !signatures.is_empty() {
+        cached_signatures.insert(hex_selector, signatures.clone());
+
+        // Failing to update the cache must not hide the signatures
+        let _ = cache.save(&cached_signatures);
+    }
+
+    Ok(signatures)
+}
+
+const REMOTE_SELECTOR_LOOKUP_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Gets the signatures matching the function selector from the built in table followed by the ones
+/// found in the signature database. Only the built in signatures are returned if the database
+/// can't be reached in time.
+pub async fn lookup_selector_with_known_signatures(
+    api_url: &str,
+    cache: &SelectorCache,
+    selector: Selector,
+) -> Result<Vec<String>> {
+    let mut signatures = find_known_signatures(selector);
+
+    let remote_lookup = tokio::time::timeout(
+        REMOTE_SELECTOR_LOOKUP_TIMEOUT,
+        lookup_selector(api_url, cache, selector),
+    )
+    .await;
+
+    if let Ok(Ok(remote_signatures)) = remote_lookup {
+        for signature in remote_signatures {
+            if !signatures.contains(&signature) {
+                signatures.push(signature);
+            }
+        }
+    }
+
+    Ok(signatures)
 }
 
 pub async fn sign(
@@ -59,12 +328,29 @@ pub async fn sign(
 
     match data {
         SignTransactionData::Raw(data) => sign_raw_data(node_provider, from, data).await,
-        SignTransactionData::Transaction(tx) => {
-            sign_transaction(node_provider, from, tx.into()).await
+        SignTransactionData::Transaction(tx) => sign_transaction(node_provider, from, tx).await,
+        SignTransactionData::TypedData(typed_data) => {
+            node_provider.sign_typed_data(&typed_data, from).await
         }
     }
 }
 
+/// Reads the EIP-712 domain, types and message to sign from a json file.
+pub fn load_typed_data(path: &Path) -> Result<TypedData> {
+    let content = std::fs::read_to_string(path).map_err(|err| {
+        anyhow::anyhow!(
+            "Failed to read the typed data file {}: {err}",
+            path.display()
+        )
+    })?;
+
+    let typed_data = serde_json::from_str(&content).map_err(|err| {
+        anyhow::anyhow!("Invalid EIP-712 typed data in {}: {err}", path.display())
+    })?;
+
+    Ok(typed_data)
+}
+
 async fn sign_raw_data(
     node_provider: &NodeProvider,
     from: Address,
@@ -85,232 +371,3037 @@ async fn sign_transaction(
     Ok(signature)
 }
 
-pub async fn get_sync_status(node_provider: &NodeProvider) -> Result<SyncingStatus> {
-    let sync_status = node_provider.syncing().await?;
-
-    Ok(sync_status)
+// name()
+const NAME_SELECTOR: [u8; 4] = [0x06, 0xfd, 0xde, 0x03];
+// version()
+const VERSION_SELECTOR: [u8; 4] = [0x54, 0xfd, 0x4d, 0x50];
+// nonces(address)
+const NONCES_SELECTOR: [u8; 4] = [0x7e, 0xce, 0xbe, 0x00];
+// DOMAIN_SEPARATOR()
+const DOMAIN_SEPARATOR_SELECTOR: [u8; 4] = [0x36, 0x44, 0xe5, 0x15];
+// eip712Domain()
+const EIP712_DOMAIN_SELECTOR: [u8; 4] = [0x84, 0xb0, 0x19, 0x6e];
+
+// Most EIP-2612 tokens, e.g. the OpenZeppelin ones, use this version when they do not expose it
+const DEFAULT_PERMIT_VERSION: &str = "1";
+
+pub struct PermitOptions {
+    pub token: Address,
+    pub owner: Address,
+    pub spender: Address,
+    pub value: U256,
+    pub deadline: U256,
 }
 
-#[cfg(test)]
-mod tests {
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PermitSignature {
+    pub nonce: U256,
+    pub deadline: U256,
+    pub v: u64,
+    pub r: H256,
+    pub s: H256,
+}
 
-    mod get_accounts {
+/// Signs an EIP-2612 permit for the token, reading the owner nonce and the token signing domain
+/// from the chain.
+pub async fn sign_permit(
+    node_provider: &NodeProvider,
+    options: PermitOptions,
+) -> Result<PermitSignature> {
+    let PermitOptions {
+        token,
+        owner,
+        spender,
+        value,
+        deadline,
+    } = options;
+
+    let domain = get_permit_domain(node_provider, token).await?;
+
+    let nonce = call_token(
+        node_provider,
+        token,
+        &NONCES_SELECTOR,
+        &[Token::Address(owner)],
+    )
+    .await?
+    .ok_or(anyhow::anyhow!(
+        "Token {token:?} does not implement nonces(address)"
+    ))?;
+    let nonce = decode_single(ParamType::Uint(256), &nonce)?
+        .into_uint()
+        .unwrap_or_default();
+
+    let typed_data = permit_typed_data(domain, owner, spender, value, nonce, deadline)?;
+
+    let signature = node_provider.sign_typed_data(&typed_data, owner).await?;
+
+    Ok(PermitSignature {
+        nonce,
+        deadline,
+        v: signature.v,
+        r: u256_to_h256(signature.r),
+        s: u256_to_h256(signature.s),
+    })
+}
 
-        use ethers::types::H160;
+pub fn permit_typed_data(
+    domain: EIP712Domain,
+    owner: Address,
+    spender: Address,
+    value: U256,
+    nonce: U256,
+    deadline: U256,
+) -> Result<TypedData> {
+    let mut domain_type = vec![];
+
+    if domain.name.is_some() {
+        domain_type.push(json!({ "name": "name", "type": "string" }));
+    }
+    if domain.version.is_some() {
+        domain_type.push(json!({ "name": "version", "type": "string" }));
+    }
+    if domain.chain_id.is_some() {
+        domain_type.push(json!({ "name": "chainId", "type": "uint256" }));
+    }
+    if domain.verifying_contract.is_some() {
+        domain_type.push(json!({ "name": "verifyingContract", "type": "address" }));
+    }
+    if domain.salt.is_some() {
+        domain_type.push(json!({ "name": "salt", "type": "bytes32" }));
+    }
 
-        use crate::cmd::{helpers::test::setup_test, utils::get_accounts};
+    let typed_data = serde_json::from_value(json!({
+        "types": {
+            "EIP712Domain": domain_type,
+            "Permit": [
+                { "name": "owner", "type": "address" },
+                { "name": "spender", "type": "address" },
+                { "name": "value", "type": "uint256" },
+                { "name": "nonce", "type": "uint256" },
+                { "name": "deadline", "type": "uint256" },
+            ],
+        },
+        "primaryType": "Permit",
+        "domain": domain,
+        "message": {
+            "owner": owner,
+            "spender": spender,
+            "value": value,
+            "nonce": nonce,
+            "deadline": deadline,
+        },
+    }))?;
+
+    Ok(typed_data)
+}
 
-        #[tokio::test]
-        async fn should_get_the_accounts_known_by_the_node() -> anyhow::Result<()> {
-            // Arrange
-            let (node_provider, _anvil) = setup_test().await?;
+/// Reads the token signing domain from `eip712Domain()` (EIP-5267) when available, otherwise
+/// rebuilds it from `name()` and `version()` and checks it against `DOMAIN_SEPARATOR()`.
+async fn get_permit_domain(node_provider: &NodeProvider, token: Address) -> Result<EIP712Domain> {
+    let domain = match call_token(node_provider, token, &EIP712_DOMAIN_SELECTOR, &[]).await? {
+        Some(data) => decode_eip712_domain(&data)?,
+        None => {
+            let name = call_token(node_provider, token, &NAME_SELECTOR, &[])
+                .await?
+                .ok_or(anyhow::anyhow!("Token {token:?} does not implement name()"))?;
+
+            let version = match call_token(node_provider, token, &VERSION_SELECTOR, &[]).await? {
+                Some(version) => decode_single(ParamType::String, &version)?
+                    .into_string()
+                    .unwrap_or_default(),
+                None => DEFAULT_PERMIT_VERSION.to_string(),
+            };
+
+            EIP712Domain {
+                name: decode_single(ParamType::String, &name)?.into_string(),
+                version: Some(version),
+                chain_id: Some(node_provider.get_chainid().await?),
+                verifying_contract: Some(token),
+                salt: None,
+            }
+        }
+    };
 
-            let expected_res: [H160; 10] = [
-                "0xf39fd6e51aad88f6f4ce6ab8827279cfffb92266".parse()?,
-                "0x70997970c51812dc3a010c7d01b50e0d17dc79c8".parse()?,
-                "0x3c44cdddb6a900fa2b585dd299e03d12fa4293bc".parse()?,
-                "0x90f79bf6eb2c4f870365e785982e1f101e93b906".parse()?,
-                "0x15d34aaf54267db7d7c367839aaf71a00a2c6a65".parse()?,
-                "0x9965507d1a55bcc2695c58ba16fb37d819b0a4dc".parse()?,
-                "0x976ea74026e726554db657fa54763abd0c3a0aa9".parse()?,
-                "0x14dc79964da2c08b23698b3d3cc7ca32193d9955".parse()?,
-                "0x23618e81e3f5cdf7f54c3d65f7fbc0abf5b21e8f".parse()?,
-                "0xa0ee7a142d267c1f36714e4a8f75612f20a79720".parse()?,
-            ];
+    if let Some(domain_separator) =
+        call_token(node_provider, token, &DOMAIN_SEPARATOR_SELECTOR, &[]).await?
+    {
+        let domain_separator = H256::from_slice(
+            &decode_single(ParamType::FixedBytes(32), &domain_separator)?
+                .into_fixed_bytes()
+                .unwrap_or_default(),
+        );
+
+        if domain_separator != H256::from(domain.separator()) {
+            anyhow::bail!(
+                "Could not reproduce the signing domain of token {token:?}: DOMAIN_SEPARATOR() returned {domain_separator:?}"
+            );
+        }
+    }
 
-            // Act
-            let res = get_accounts(&node_provider).await;
+    Ok(domain)
+}
 
-            // Assert
-            assert!(res.is_ok());
+fn decode_eip712_domain(data: &[u8]) -> Result<EIP712Domain> {
+    let mut tokens = abi::decode(
+        &[
+            ParamType::FixedBytes(1),
+            ParamType::String,
+            ParamType::String,
+            ParamType::Uint(256),
+            ParamType::Address,
+            ParamType::FixedBytes(32),
+            ParamType::Array(Box::new(ParamType::Uint(256))),
+        ],
+        data,
+    )?
+    .into_iter();
+
+    let mut next = || tokens.next().unwrap_or(Token::Bool(false));
+
+    let fields = next().into_fixed_bytes().unwrap_or_default();
+    let fields = fields.first().copied().unwrap_or_default();
+    let is_set = |bit: u8| fields & (1 << bit) != 0;
+
+    let name = next().into_string();
+    let version = next().into_string();
+    let chain_id = next().into_uint();
+    let verifying_contract = next().into_address();
+    let salt = next()
+        .into_fixed_bytes()
+        .and_then(|salt| <[u8; 32]>::try_from(salt).ok());
+
+    if !next().into_array().unwrap_or_default().is_empty() {
+        anyhow::bail!("EIP-712 domain extensions are not supported");
+    }
 
-            let maybe_accounts = res.unwrap();
-            assert_eq!(maybe_accounts.len(), 10);
-            assert_eq!(maybe_accounts, expected_res);
+    Ok(EIP712Domain {
+        name: name.filter(|_| is_set(0)),
+        version: version.filter(|_| is_set(1)),
+        chain_id: chain_id.filter(|_| is_set(2)),
+        verifying_contract: verifying_contract.filter(|_| is_set(3)),
+        salt: salt.filter(|_| is_set(4)),
+    })
+}
 
-            Ok(())
-        }
+// eth_call
+/// Calls the token function with the provided selector. Returns None if the token reverts,
+/// which is how missing optional functions are reported.
+pub async fn call_token(
+    node_provider: &NodeProvider,
+    token: Address,
+    selector: &[u8; 4],
+    args: &[Token],
+) -> Result<Option<Bytes>> {
+    let mut data = selector.to_vec();
+    data.extend(abi::encode(args));
+
+    let tx = TransactionRequest::new().to(token).data(data);
+
+    match node_provider.call(&tx.into(), None).await {
+        Ok(res) if res.is_empty() => Ok(None),
+        Ok(res) => Ok(Some(res)),
+        Err(err) if err.as_error_response().is_some() => Ok(None),
+        Err(err) => Err(err.into()),
     }
+}
 
-    mod get_chain_id {
+pub fn decode_single(param_type: ParamType, data: &[u8]) -> Result<Token> {
+    let token = abi::decode(&[param_type], data)?
+        .pop()
+        .ok_or(anyhow::anyhow!("Missing return value"))?;
 
-        use ethers::types::U256;
+    Ok(token)
+}
 
-        use crate::cmd::{helpers::test::setup_test, utils::get_chain_id};
+fn u256_to_h256(value: U256) -> H256 {
+    let mut bytes = [0u8; 32];
+    value.to_big_endian(&mut bytes);
 
-        #[tokio::test]
-        async fn should_get_the_chain_id() -> anyhow::Result<()> {
-            // Arrange
-            let (node_provider, _anvil) = setup_test().await?;
+    H256(bytes)
+}
 
-            let expected_res: U256 = 31337.into();
+/// ABI encodes a call to the function with the provided human readable signature, e.g.
+/// `transfer(address,uint256)`, parsing each argument according to its parameter type.
+pub fn encode_function_call(signature: &str, args: &[String]) -> Result<Bytes> {
+    let function = AbiParser::default().parse_function(signature)?;
+
+    if function.inputs.len() != args.len() {
+        anyhow::bail!(
+            "{} expects {} arguments, got {}",
+            function.signature(),
+            function.inputs.len(),
+            args.len()
+        );
+    }
 
-            // Act
-            let res = get_chain_id(&node_provider).await;
+    let tokens = function
+        .inputs
+        .iter()
+        .zip(args)
+        .enumerate()
+        .map(|(idx, (param, arg))| {
+            let kind = &param.kind;
+
+            match ArgTokenizer::tokenize(kind, arg) {
+                Ok(token) if token_fits(kind, &token) => Ok(token),
+                Ok(_) => anyhow::bail!("Argument {idx} of type {kind} is out of range: {arg}"),
+                Err(err) => anyhow::bail!("Invalid argument {idx} of type {kind}: {arg} ({err})"),
+            }
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    Ok(function.encode_input(&tokens)?.into())
+}
 
-            // Assert
-            assert!(res.is_ok());
+/// Function parameter decoded from calldata.
+#[derive(Debug, Serialize, PartialEq)]
+pub struct DecodedParam {
+    pub name: String,
+    #[serde(rename = "type")]
+    pub kind: String,
+    pub value: String,
+}
 
-            let maybe_chain_id = res.unwrap();
-            assert_eq!(maybe_chain_id, expected_res);
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DecodedCall {
+    pub function: String,
+    pub selector: Bytes,
+    /// Set if the calldata selector doesn't match the one of the provided signature
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub warning: Option<String>,
+    pub params: Vec<DecodedParam>,
+}
 
-            Ok(())
+/// Function used to decode calldata, either a human readable signature or an ABI whose
+/// functions are matched against the calldata selector.
+#[derive(Debug, Clone)]
+pub enum CalldataFunction {
+    Signature(String),
+    Abi(PathBuf),
+}
+
+/// Decodes the arguments of the function call. The arguments are decoded with the provided
+/// signature even if its selector doesn't match the calldata one, which is reported as a warning.
+pub fn decode_function_call(function: CalldataFunction, data: &[u8]) -> Result<DecodedCall> {
+    let Some((selector, args)) = data.split_first_chunk::<4>() else {
+        anyhow::bail!(
+            "Calldata is {} bytes long, expected at least the 4 bytes of the selector",
+            data.len()
+        );
+    };
+
+    let (function, warning) = match function {
+        CalldataFunction::Signature(signature) => {
+            let function = AbiParser::default().parse_function(&signature)?;
+            let warning = (function.short_signature() != *selector).then(|| {
+                format!(
+                    "The calldata selector 0x{} does not match the selector 0x{} of {}",
+                    hex::encode(selector),
+                    hex::encode(function.short_signature()),
+                    function.signature()
+                )
+            });
+
+            (function, warning)
         }
-    }
+        CalldataFunction::Abi(path) => {
+            let abi = load_abi(&path)?;
+            let function = abi
+                .functions()
+                .find(|function| function.short_signature() == *selector)
+                .cloned()
+                .ok_or_else(|| {
+                    anyhow::anyhow!(
+                        "No function of the ABI matches the selector 0x{}",
+                        hex::encode(selector)
+                    )
+                })?;
+
+            (function, None)
+        }
+    };
 
-    mod get_proof {
+    let kinds: Vec<_> = function
+        .inputs
+        .iter()
+        .map(|param| param.kind.clone())
+        .collect();
+    let tokens = abi::decode(&kinds, args).map_err(|err| {
+        anyhow::anyhow!(
+            "Failed to decode the arguments of {}, the calldata is truncated or holds invalid offsets ({err})",
+            function.signature()
+        )
+    })?;
+
+    let params = function
+        .inputs
+        .iter()
+        .zip(tokens)
+        .enumerate()
+        .map(|(idx, (param, token))| DecodedParam {
+            name: param_name(&param.name, idx),
+            kind: param.kind.to_string(),
+            value: format_token(&token),
+        })
+        .collect();
+
+    Ok(DecodedCall {
+        function: function.signature(),
+        selector: selector.to_vec().into(),
+        warning,
+        params,
+    })
+}
 
-        use ethers::utils::parse_ether;
+/// Lenient tokenizer that also accepts 0x prefixed hex unsigned integers.
+struct ArgTokenizer;
 
-        use crate::cmd::{helpers::test::setup_test, utils::get_proof};
+impl Tokenizer for ArgTokenizer {
+    fn tokenize_address(value: &str) -> std::result::Result<[u8; 20], abi::Error> {
+        LenientTokenizer::tokenize_address(value)
+    }
 
-        #[tokio::test]
-        async fn should_get_the_account_merkle_proof() -> anyhow::Result<()> {
-            // Arrange
-            let (node_provider, anvil) = setup_test().await?;
+    fn tokenize_string(value: &str) -> std::result::Result<String, abi::Error> {
+        LenientTokenizer::tokenize_string(value)
+    }
 
-            let account = *anvil.addresses().get(0).unwrap();
-            let expected_account_balance = parse_ether(10000)?;
+    fn tokenize_bool(value: &str) -> std::result::Result<bool, abi::Error> {
+        LenientTokenizer::tokenize_bool(value)
+    }
 
-            // Act
-            let res = get_proof(&node_provider, account.into(), [].into(), None).await;
+    fn tokenize_bytes(value: &str) -> std::result::Result<Vec<u8>, abi::Error> {
+        LenientTokenizer::tokenize_bytes(value)
+    }
 
-            // Assert
-            assert!(res.is_ok());
+    fn tokenize_fixed_bytes(value: &str, len: usize) -> std::result::Result<Vec<u8>, abi::Error> {
+        LenientTokenizer::tokenize_fixed_bytes(value, len)
+    }
 
-            let maybe_account_proof = res.unwrap();
-            assert_eq!(maybe_account_proof.address, account);
-            assert_eq!(maybe_account_proof.balance, expected_account_balance);
-            assert_eq!(maybe_account_proof.nonce, 0.into());
+    fn tokenize_uint(value: &str) -> std::result::Result<[u8; 32], abi::Error> {
+        // The lenient tokenizer only accepts hex values padded to 32 bytes
+        match value.strip_prefix("0x") {
+            Some(hex_value) => U256::from_str_radix(hex_value, 16)
+                .map(Into::into)
+                .map_err(|_| abi::Error::InvalidData),
+            None => LenientTokenizer::tokenize_uint(value),
+        }
+    }
 
-            Ok(())
+    fn tokenize_int(value: &str) -> std::result::Result<[u8; 32], abi::Error> {
+        LenientTokenizer::tokenize_int(value)
+    }
+}
+
+/// Tells if the integers of the token fit in the bits of their parameter type, as the tokenizer
+/// parses all of them as 256 bits integers.
+fn token_fits(kind: &ParamType, token: &Token) -> bool {
+    match (kind, token) {
+        (ParamType::Uint(size), Token::Uint(value)) => value.bits() <= *size,
+        (ParamType::Int(size), Token::Int(value)) => {
+            // The magnitude of a negative two's complement value is its complement plus one
+            let magnitude = if value.bit(255) { !*value } else { *value };
+
+            magnitude.bits() < *size
+        }
+        (ParamType::Array(kind), Token::Array(tokens))
+        | (ParamType::FixedArray(kind, _), Token::FixedArray(tokens)) => {
+            tokens.iter().all(|token| token_fits(kind, token))
         }
+        (ParamType::Tuple(kinds), Token::Tuple(tokens)) => kinds
+            .iter()
+            .zip(tokens)
+            .all(|(kind, token)| token_fits(kind, token)),
+        _ => true,
     }
+}
 
-    mod sign {
-        use ethers::{
-            types::{Bytes, RecoveryMessage, TransactionRequest, H160},
-            utils::Anvil,
-        };
+pub async fn get_sync_status(node_provider: &NodeProvider) -> Result<SyncingStatus> {
+    let sync_status = node_provider.syncing().await?;
 
-        use crate::{
-            cmd::{
-                helpers::test::setup_test,
-                utils::{sign, SignTransactionData},
-            },
-            config::{get_config, ConfigOverrides},
-            context::NodeProvider,
-        };
+    Ok(sync_status)
+}
 
-        #[tokio::test]
-        async fn should_not_sign_the_data_if_with_an_unkwnon_signer() -> anyhow::Result<()> {
-            // Arrange
-            let (node_provider, _anvil) = setup_test().await?;
+pub fn base58_encode(data: Bytes) -> String {
+    bs58::encode(data).into_string()
+}
 
-            let bytes = SignTransactionData::Raw(Bytes::from_static(b"somerandomdata"));
-            let from = "0xf39fd6e51aad88f6f4ce6ab8827279cfffb92267".parse::<H160>()?;
+pub fn base58_decode(data: &str) -> Result<Bytes> {
+    let decoded = bs58::decode(data).into_vec()?;
 
-            // Act
-            let res = sign(&node_provider, from.into(), bytes).await;
+    Ok(decoded.into())
+}
 
-            // Assert
-            assert!(res.is_err());
+pub fn base64_encode(data: Bytes) -> String {
+    BASE64.encode(data)
+}
 
-            let err = res.unwrap_err();
-            assert!(err.to_string().contains("No Signer available"));
+pub fn base64_decode(data: &str) -> Result<Bytes> {
+    let decoded = BASE64.decode(data)?;
 
-            Ok(())
+    Ok(decoded.into())
+}
+
+/// Converts an amount expressed in `unit`, e.g. `1.5` ether, to wei.
+pub fn to_wei(amount: &str, unit: Units) -> Result<U256> {
+    // parse_units silently truncates the digits that don't fit in wei
+    if let Some((_, fraction)) = amount.split_once('.') {
+        if fraction.trim_end_matches('0').len() > unit.as_num() as usize {
+            anyhow::bail!("Amount {amount} has more than {} decimals", unit.as_num());
         }
+    }
 
-        #[tokio::test]
-        async fn should_sign_the_data() -> anyhow::Result<()> {
-            // Arrange
-            let (node_provider, anvil) = setup_test().await?;
+    if amount.starts_with('-') {
+        anyhow::bail!("Negative amounts can't be converted");
+    }
 
-            let bytes = Bytes::from_static(b"somerandomdata");
-            let data = SignTransactionData::Raw(bytes.clone());
-            let from = *anvil.addresses().get(0).unwrap();
+    // parse_units panics instead of failing if the amount doesn't fit in a uint256
+    let (integer, fraction) = amount.split_once('.').unwrap_or((amount, ""));
+    let fraction = fraction.trim_end_matches('0');
+    let overflows = U256::from_dec_str(&format!("{integer}{fraction}"))
+        .ok()
+        .map(|digits| {
+            digits
+                .checked_mul(U256::exp10(unit.as_num() as usize - fraction.len()))
+                .is_none()
+        })
+        .unwrap_or_default();
+    if overflows {
+        anyhow::bail!("Amount {amount} exceeds the maximum uint256 value once converted to wei");
+    }
 
-            // Act
-            let res = sign(&node_provider, from.into(), data).await;
+    match parse_units(amount, unit.as_num())? {
+        ParseUnits::U256(wei) => Ok(wei),
+        ParseUnits::I256(_) => anyhow::bail!("Negative amounts can't be converted"),
+    }
+}
 
-            // Assert
-            assert!(res.is_ok());
+/// Converts an amount expressed in `unit` to gwei.
+pub fn to_gwei(amount: &str, unit: Units) -> Result<String> {
+    format_amount(to_wei(amount, unit)?, Units::Gwei)
+}
 
-            let sig = res.unwrap();
-            assert!(sig
-                .verify(RecoveryMessage::Data(bytes.into_iter().collect()), from)
-                .is_ok());
+/// Converts a decimal amount of wei to `unit`.
+pub fn from_wei(wei: &str, unit: Units) -> Result<String> {
+    let wei = U256::from_dec_str(wei)
+        .map_err(|err| anyhow::anyhow!("Invalid wei amount {wei}: {err}"))?;
 
-            Ok(())
-        }
+    format_amount(wei, unit)
+}
 
-        #[tokio::test]
-        async fn should_not_sign_the_tx_data_if_no_private_key_is_configured() -> anyhow::Result<()>
-        {
-            // Arrange
-            let (node_provider, anvil) = setup_test().await?;
+#[derive(Debug, Serialize, PartialEq, Eq)]
+pub struct ConvertedAmount {
+    pub value: String,
+    pub unit: String,
+}
 
-            let tx = TransactionRequest::new();
-            let data = SignTransactionData::Transaction(tx);
-            let from = *anvil.addresses().get(0).unwrap();
+/// Returns the name of the unit, or its number of decimals if it has no common name.
+fn unit_name(unit: Units) -> String {
+    match unit.as_num() {
+        0 => "wei".to_string(),
+        3 => "kwei".to_string(),
+        6 => "mwei".to_string(),
+        9 => "gwei".to_string(),
+        12 => "szabo".to_string(),
+        15 => "finney".to_string(),
+        18 => "ether".to_string(),
+        decimals => decimals.to_string(),
+    }
+}
 
-            // Act
-            let res = sign(&node_provider, from.into(), data).await;
+/// Converts an amount expressed in `from` to `to` going through its exact wei value, so amounts
+/// that are not a whole number of wei are rejected instead of rounded.
+pub fn convert_units(amount: &str, from: Units, to: Units) -> Result<ConvertedAmount> {
+    let wei = to_wei(amount, from)?;
 
-            // Assert
-            assert!(res.is_err());
+    Ok(ConvertedAmount {
+        value: format_amount(wei, to)?,
+        unit: unit_name(to),
+    })
+}
 
-            Ok(())
-        }
+/// Formats the wei amount in `unit` without the trailing zeros of the fractional part.
+fn format_amount(wei: U256, unit: Units) -> Result<String> {
+    let formatted = format_units(wei, unit.as_num())?;
 
-        #[tokio::test]
-        async fn should_sign_the_tx_data() -> anyhow::Result<()> {
+    match formatted.split_once('.') {
+        Some((integer, fraction)) if fraction.trim_end_matches('0').is_empty() => {
+            Ok(integer.to_string())
+        }
+        Some(_) => Ok(formatted.trim_end_matches('0').to_string()),
+        None => Ok(formatted),
+    }
+}
+
+/// Arithmetic operation applied to two uint256 values.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MathOperation {
+    Add,
+    Sub,
+    Mul,
+    Div,
+    Mod,
+}
+
+/// Applies the operation to the provided values, failing instead of wrapping around when the
+/// result doesn't fit in a uint256 and on division by zero.
+pub fn wei_math(operation: MathOperation, lhs: U256, rhs: U256) -> Result<U256> {
+    let res = match operation {
+        MathOperation::Add => lhs
+            .checked_add(rhs)
+            .ok_or(anyhow::anyhow!("{lhs} + {rhs} overflows uint256")),
+        MathOperation::Sub => lhs
+            .checked_sub(rhs)
+            .ok_or(anyhow::anyhow!("{lhs} - {rhs} underflows uint256")),
+        MathOperation::Mul => lhs
+            .checked_mul(rhs)
+            .ok_or(anyhow::anyhow!("{lhs} * {rhs} overflows uint256")),
+        MathOperation::Div => lhs
+            .checked_div(rhs)
+            .ok_or(anyhow::anyhow!("Division by zero")),
+        MathOperation::Mod => lhs
+            .checked_rem(rhs)
+            .ok_or(anyhow::anyhow!("Division by zero")),
+    }?;
+
+    Ok(res)
+}
+
+const SIGNATURE_LEN: usize = 65;
+
+#[derive(Debug, Serialize, PartialEq, Eq)]
+pub struct SignatureComponents {
+    pub r: H256,
+    pub s: H256,
+    pub v: u64,
+}
+
+/// Splits a 65 bytes `r || s || v` signature in its components, normalizing `v` to 27/28.
+pub fn split_signature(signature: Bytes) -> Result<SignatureComponents> {
+    if signature.len() != SIGNATURE_LEN {
+        anyhow::bail!(
+            "Invalid signature length. Expected {SIGNATURE_LEN} bytes but got {}",
+            signature.len()
+        );
+    }
+
+    Ok(SignatureComponents {
+        r: H256::from_slice(&signature[..32]),
+        s: H256::from_slice(&signature[32..64]),
+        v: normalize_recovery_id(signature[64].into())?,
+    })
+}
+
+/// Joins the signature components in the 65 bytes `r || s || v` form, normalizing `v` to 27/28.
+pub fn join_signature(r: H256, s: H256, v: u64) -> Result<Bytes> {
+    let mut signature = Vec::with_capacity(SIGNATURE_LEN);
+
+    signature.extend_from_slice(r.as_bytes());
+    signature.extend_from_slice(s.as_bytes());
+    signature.push(normalize_recovery_id(v)? as u8);
+
+    Ok(signature.into())
+}
+
+// Signers either encode the recovery id as 0/1 or as 27/28 like ecrecover expects
+fn normalize_recovery_id(v: u64) -> Result<u64> {
+    match v {
+        0 | 1 => Ok(v + 27),
+        27 | 28 => Ok(v),
+        _ => anyhow::bail!("Invalid signature v value {v}. Expected 0, 1, 27 or 28"),
+    }
+}
+
+/// Derives the address of a secp256k1 public key, either SEC1 encoded (compressed or uncompressed)
+/// or as the raw 64 bytes of its coordinates.
+pub fn address_from_public_key(public_key: Bytes) -> Result<Address> {
+    let sec1_bytes = match public_key.len() {
+        64 => [&[0x04], public_key.as_ref()].concat(),
+        _ => public_key.to_vec(),
+    };
+
+    let public_key = PublicKey::from_sec1_bytes(&sec1_bytes)
+        .map_err(|_| anyhow::anyhow!("Invalid secp256k1 public key {public_key}"))?;
+
+    // The address is the last 20 bytes of the hash of the uncompressed coordinates
+    let hash = keccak256(&public_key.to_encoded_point(false).as_bytes()[1..]);
+
+    Ok(Address::from_slice(&hash[12..]))
+}
+
+/// Normalizes an address, with or without the 0x prefix and in any case, or a 32 bytes word left
+/// padded with zeros to its EIP-55 checksummed form. Mixed case addresses must already be
+/// correctly checksummed as they are likely mistyped otherwise.
+pub fn normalize_address(value: &str) -> Result<String> {
+    let hex_value = value.strip_prefix("0x").unwrap_or(value);
+
+    let bytes = hex::decode(hex_value)
+        .map_err(|_| anyhow::anyhow!("Invalid address {value}, expected hex encoded bytes"))?;
+
+    let address = match bytes.len() {
+        20 => Address::from_slice(&bytes),
+        32 if bytes[..12].iter().all(|byte| *byte == 0) => Address::from_slice(&bytes[12..]),
+        32 => anyhow::bail!("The word {value} has non zero high bytes and is not a padded address"),
+        len => anyhow::bail!(
+            "Invalid address {value}, expected 20 bytes or a 32 bytes padded word but got {len} bytes"
+        ),
+    };
+
+    let checksummed = to_checksum(&address, None);
+
+    if bytes.len() == 20 && is_mixed_case(hex_value) && checksummed[2..] != *hex_value {
+        anyhow::bail!("Invalid EIP-55 checksum for the address {value}, expected {checksummed}");
+    }
+
+    Ok(checksummed)
+}
+
+/// Tells if the hex value carries an EIP-55 checksum, all lower or upper case values don't.
+fn is_mixed_case(hex_value: &str) -> bool {
+    hex_value.chars().any(|c| c.is_ascii_uppercase())
+        && hex_value.chars().any(|c| c.is_ascii_lowercase())
+}
+
+/// Gets the EIP-55 checksummed form of the 40 hex chars address. In verify mode a mixed case
+/// address must already be correctly checksummed.
+pub fn checksum_address(hex_address: &str, verify: bool) -> Result<String> {
+    let address: Address = hex_address
+        .parse()
+        .map_err(|_| anyhow::anyhow!("Invalid address {hex_address}, expected 40 hex chars"))?;
+
+    let checksummed = to_checksum(&address, None);
+
+    if verify && is_mixed_case(hex_address) && checksummed[2..] != *hex_address {
+        anyhow::bail!(
+            "The address 0x{hex_address} fails the EIP-55 checksum validation, the checksummed form is {checksummed}"
+        );
+    }
+
+    Ok(checksummed)
+}
+
+#[derive(Debug, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct BytecodeVerification {
+    pub matches: bool,
+    /// Offset of the first differing byte, if any
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub first_mismatch_offset: Option<usize>,
+    pub deployed_length: usize,
+    pub expected_length: usize,
+}
+
+/// Strips the trailing cbor encoded metadata that solc appends to the runtime bytecode, whose
+/// length is stored in the last 2 bytes. The bytecode is returned unchanged if its tail doesn't
+/// decode as a solc metadata map, so that bytecode compiled without metadata is compared whole.
+fn strip_bytecode_metadata(bytecode: &[u8]) -> &[u8] {
+    let Some(length_idx) = bytecode.len().checked_sub(2) else {
+        return bytecode;
+    };
+
+    let metadata_length =
+        u16::from_be_bytes([bytecode[length_idx], bytecode[length_idx + 1]]) as usize;
+
+    match length_idx.checked_sub(metadata_length) {
+        Some(code_length) if is_solc_metadata(&bytecode[code_length..length_idx]) => {
+            &bytecode[..code_length]
+        }
+        _ => bytecode,
+    }
+}
+
+// Keys of the metadata map written by solc, bzzr0 and bzzr1 by the versions predating ipfs
+const SOLC_METADATA_KEYS: [&str; 4] = ["ipfs", "bzzr0", "bzzr1", "solc"];
+
+/// Tells if the bytes are a cbor map whose keys are all text strings, including at least one of
+/// the keys written by solc, and whose values are byte strings, text strings or booleans.
+fn is_solc_metadata(metadata: &[u8]) -> bool {
+    let Some((&header, mut rest)) = metadata.split_first() else {
+        return false;
+    };
+
+    // Major type 5 (map) with the number of entries in the header
+    if header >> 5 != 5 || header & 0x1f > 23 {
+        return false;
+    }
+
+    let mut has_solc_key = false;
+
+    for _ in 0..header & 0x1f {
+        let Some((key, after_key)) = read_cbor_string(rest, 3) else {
+            return false;
+        };
+
+        has_solc_key |= std::str::from_utf8(key).is_ok_and(|key| SOLC_METADATA_KEYS.contains(&key));
+
+        rest = match after_key.split_first() {
+            // false, true
+            Some((0xf4 | 0xf5, after_value)) => after_value,
+            // Byte or text string
+            Some((value_header, _)) if matches!(value_header >> 5, 2 | 3) => {
+                match read_cbor_string(after_key, value_header >> 5) {
+                    Some((_, after_value)) => after_value,
+                    None => return false,
+                }
+            }
+            _ => return false,
+        };
+    }
+
+    has_solc_key && rest.is_empty()
+}
+
+/// Reads a cbor byte (major type 2) or text (major type 3) string of up to 255 bytes, returning
+/// its content and the bytes following it.
+fn read_cbor_string(bytes: &[u8], major_type: u8) -> Option<(&[u8], &[u8])> {
+    let (&header, rest) = bytes.split_first()?;
+
+    if header >> 5 != major_type {
+        return None;
+    }
+
+    let (length, rest) = match header & 0x1f {
+        length @ 0..=23 => (length as usize, rest),
+        24 => {
+            let (&length, rest) = rest.split_first()?;
+            (length as usize, rest)
+        }
+        _ => return None,
+    };
+
+    (rest.len() >= length).then(|| rest.split_at(length))
+}
+
+pub fn compare_bytecode(
+    deployed: &[u8],
+    expected: &[u8],
+    ignore_metadata: bool,
+) -> BytecodeVerification {
+    let (deployed, expected) = if ignore_metadata {
+        (
+            strip_bytecode_metadata(deployed),
+            strip_bytecode_metadata(expected),
+        )
+    } else {
+        (deployed, expected)
+    };
+
+    let first_mismatch_offset = deployed
+        .iter()
+        .zip(expected)
+        .position(|(deployed_byte, expected_byte)| deployed_byte != expected_byte)
+        .or_else(|| (deployed.len() != expected.len()).then(|| deployed.len().min(expected.len())));
+
+    BytecodeVerification {
+        matches: first_mismatch_offset.is_none(),
+        first_mismatch_offset,
+        deployed_length: deployed.len(),
+        expected_length: expected.len(),
+    }
+}
+
+// eth_getCode
+pub async fn verify_bytecode(
+    node_provider: &NodeProvider,
+    account_id: NameOrAddress,
+    expected: Bytes,
+    ignore_metadata: bool,
+    block_id: Option<BlockId>,
+) -> Result<BytecodeVerification> {
+    let deployed = get_code(node_provider, account_id, block_id).await?;
+
+    Ok(compare_bytecode(&deployed, &expected, ignore_metadata))
+}
+
+const ERROR_STRING_SELECTOR: [u8; 4] = [0x08, 0xc3, 0x79, 0xa0];
+const PANIC_SELECTOR: [u8; 4] = [0x4e, 0x48, 0x7b, 0x71];
+
+/// Decodes the data returned by a reverted call into a human readable reason, handling the
+/// `Error(string)` and `Panic(uint256)` solidity errors and falling back to the raw custom error data.
+pub fn decode_revert_reason(data: &[u8]) -> String {
+    if data.is_empty() {
+        return "Reverted without a reason".into();
+    }
+
+    let (selector, args) = data.split_at(data.len().min(4));
+
+    if selector == ERROR_STRING_SELECTOR {
+        if let Ok(Some(Token::String(reason))) =
+            abi::decode(&[ParamType::String], args).map(|tokens| tokens.into_iter().next())
+        {
+            return reason;
+        }
+    }
+
+    if selector == PANIC_SELECTOR {
+        if let Ok(Some(Token::Uint(code))) =
+            abi::decode(&[ParamType::Uint(256)], args).map(|tokens| tokens.into_iter().next())
+        {
+            return format!("Panic({code:#x})");
+        }
+    }
+
+    format!("Custom error {}", Bytes::from(data.to_vec()))
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MappingKey {
+    Address(Address),
+    Uint(U256),
+    Bytes32(H256),
+}
+
+impl MappingKey {
+    fn to_word(&self) -> [u8; 32] {
+        match self {
+            MappingKey::Address(address) => H256::from(*address).to_fixed_bytes(),
+            MappingKey::Uint(value) => {
+                let mut word = [0u8; 32];
+                value.to_big_endian(&mut word);
+
+                word
+            }
+            MappingKey::Bytes32(value) => value.to_fixed_bytes(),
+        }
+    }
+}
+
+/// Computes the storage slot of a (possibly nested) mapping entry following the solidity storage layout,
+/// where each key derives the slot `keccak256(pad(key) ++ slot)` starting from the mapping base slot.
+pub fn derive_mapping_slot(base_slot: H256, keys: &[MappingKey]) -> H256 {
+    keys.iter().fold(base_slot, |slot, key| {
+        let mut data = [0u8; 64];
+        data[..32].copy_from_slice(&key.to_word());
+        data[32..].copy_from_slice(slot.as_bytes());
+
+        keccak256(data).into()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+
+    mod get_accounts {
+
+        use ethers::types::H160;
+
+        use crate::cmd::{helpers::test::setup_test, utils::get_accounts};
+
+        #[tokio::test]
+        async fn should_get_the_accounts_known_by_the_node() -> anyhow::Result<()> {
             // Arrange
-            let anvil = Anvil::new().spawn();
-            let priv_key = hex::encode(anvil.keys().get(0).unwrap().to_be_bytes());
+            let (node_provider, _anvil) = setup_test().await?;
 
-            let overrides = ConfigOverrides::new(Some(priv_key), Some(anvil.endpoint()), None);
-            let config = get_config(overrides)?;
+            let expected_res: [H160; 10] = [
+                "0xf39fd6e51aad88f6f4ce6ab8827279cfffb92266".parse()?,
+                "0x70997970c51812dc3a010c7d01b50e0d17dc79c8".parse()?,
+                "0x3c44cdddb6a900fa2b585dd299e03d12fa4293bc".parse()?,
+                "0x90f79bf6eb2c4f870365e785982e1f101e93b906".parse()?,
+                "0x15d34aaf54267db7d7c367839aaf71a00a2c6a65".parse()?,
+                "0x9965507d1a55bcc2695c58ba16fb37d819b0a4dc".parse()?,
+                "0x976ea74026e726554db657fa54763abd0c3a0aa9".parse()?,
+                "0x14dc79964da2c08b23698b3d3cc7ca32193d9955".parse()?,
+                "0x23618e81e3f5cdf7f54c3d65f7fbc0abf5b21e8f".parse()?,
+                "0xa0ee7a142d267c1f36714e4a8f75612f20a79720".parse()?,
+            ];
 
-            let node_provider = NodeProvider::new(&config).await?;
+            // Act
+            let res = get_accounts(&node_provider).await;
 
-            let tx = TransactionRequest::new();
-            let data = SignTransactionData::Transaction(tx.clone());
-            let from = *anvil.addresses().get(0).unwrap();
+            // Assert
+            assert!(res.is_ok());
+
+            let maybe_accounts = res.unwrap();
+            assert_eq!(maybe_accounts.len(), 10);
+            assert_eq!(maybe_accounts, expected_res);
+
+            Ok(())
+        }
+    }
+
+    mod get_chain_id {
+
+        use ethers::types::U256;
+
+        use crate::cmd::{helpers::test::setup_test, utils::get_chain_id};
+
+        #[tokio::test]
+        async fn should_get_the_chain_id() -> anyhow::Result<()> {
+            // Arrange
+            let (node_provider, _anvil) = setup_test().await?;
+
+            let expected_res: U256 = 31337.into();
 
             // Act
-            let res = sign(&node_provider, from.into(), data).await;
+            let res = get_chain_id(&node_provider).await;
 
             // Assert
             assert!(res.is_ok());
 
-            let tx_with_chain_id = tx.chain_id(anvil.chain_id());
-            let sig = res.unwrap();
-            assert!(sig
-                .verify(RecoveryMessage::Hash(tx_with_chain_id.sighash()), from)
-                .is_ok());
+            let maybe_chain_id = res.unwrap();
+            assert_eq!(maybe_chain_id, expected_res);
 
             Ok(())
         }
     }
 
-    mod get_sync_status {
+    mod chain_info {
+        use ethers::types::U256;
 
-        use crate::cmd::{helpers::test::setup_test, utils::get_sync_status};
+        use crate::cmd::{
+            helpers::test::setup_test,
+            utils::{chain_info, get_chain_info, NativeCurrency},
+        };
 
         #[tokio::test]
-        async fn should_get_the_node_sync_status() -> anyhow::Result<()> {
+        async fn should_get_the_local_chain_info() -> anyhow::Result<()> {
             // Arrange
             let (node_provider, _anvil) = setup_test().await?;
 
             // Act
-            let res = get_sync_status(&node_provider).await;
+            let res = get_chain_info(&node_provider).await;
 
             // Assert
             assert!(res.is_ok());
 
+            let info = res.unwrap();
+            assert_eq!(info.chain_id, 31337.into());
+            assert_eq!(info.name, "local");
+
+            Ok(())
+        }
+
+        #[test]
+        fn should_map_the_chain_id_to_a_known_chain() {
+            // Setup
+            let test_cases = [
+                (U256::from(1), "mainnet", Some(("ETH", 18))),
+                (U256::from(137), "polygon", Some(("MATIC", 18))),
+                (U256::from(123456789), "unknown", None),
+                (U256::MAX, "unknown", None),
+            ];
+
+            for (chain_id, expected_name, expected_currency) in test_cases {
+                // Act
+                let res = chain_info(chain_id);
+
+                // Assert
+                assert_eq!(res.chain_id, chain_id);
+                assert_eq!(res.name, expected_name);
+                assert_eq!(
+                    res.native_currency,
+                    expected_currency.map(|(symbol, decimals)| NativeCurrency { symbol, decimals })
+                );
+            }
+        }
+    }
+
+    mod ens {
+        use std::time::Duration;
+
+        use ethers::types::H160;
+        use serde_json::json;
+
+        use crate::{
+            cmd::{
+                helpers::test::{mock_node_provider, spawn_mock_rpc_server},
+                utils::{lookup_ens_name, resolve_ens_name},
+            },
+            context::NodeProvider,
+        };
+
+        async fn node_provider_answering(result: &str) -> anyhow::Result<NodeProvider> {
+            let (url, _) = spawn_mock_rpc_server(json!(result), Duration::ZERO);
+            mock_node_provider(url).await
+        }
+
+        #[tokio::test]
+        async fn should_resolve_the_address_of_the_name() -> anyhow::Result<()> {
+            // Arrange
+            // Every call returns the same word, read as the resolver address, the resolver
+            // interface support flag and the resolved address
+            let node_provider = node_provider_answering(&format!("0x{:0>64}", "1")).await?;
+
+            // Act
+            let res = resolve_ens_name(&node_provider, "vitalik.eth".into()).await;
+
+            // Assert
+            assert_eq!(res?, H160::from_low_u64_be(1));
+
+            Ok(())
+        }
+
+        #[tokio::test]
+        async fn should_report_a_name_without_resolver_as_not_found() -> anyhow::Result<()> {
+            // Arrange
+            let node_provider = node_provider_answering("0x").await?;
+
+            // Act
+            let res = resolve_ens_name(&node_provider, "missing.eth".into()).await;
+
+            // Assert
+            assert_eq!(
+                res.unwrap_err().to_string(),
+                "ENS name missing.eth not found"
+            );
+
+            Ok(())
+        }
+
+        #[tokio::test]
+        async fn should_report_an_address_without_reverse_record_as_not_found() -> anyhow::Result<()>
+        {
+            // Arrange
+            let node_provider = node_provider_answering("0x").await?;
+            let address = H160::repeat_byte(0x01);
+
+            // Act
+            let res = lookup_ens_name(&node_provider, address).await;
+
+            // Assert
+            assert_eq!(
+                res.unwrap_err().to_string(),
+                format!("ENS name not found for {address:?}")
+            );
+
             Ok(())
         }
     }
+
+    mod lookup_selector {
+        use std::sync::atomic::Ordering;
+
+        use serde_json::json;
+
+        use crate::cmd::{
+            helpers::test::spawn_mock_http_server,
+            utils::{lookup_selector, SelectorCache},
+        };
+
+        const TRANSFER_SELECTOR: [u8; 4] = [0xa9, 0x05, 0x9c, 0xbb];
+
+        fn cache(name: &str) -> (SelectorCache, std::path::PathBuf) {
+            let path = std::env::temp_dir().join(format!(
+                "yaeth-selectors-{name}-{}.json",
+                std::process::id()
+            ));
+            let _ = std::fs::remove_file(&path);
+
+            (SelectorCache::new(&path), path)
+        }
+
+        #[tokio::test]
+        async fn should_find_the_signatures_of_the_selector() -> anyhow::Result<()> {
+            // Arrange
+            let (url, requests) = spawn_mock_http_server(json!({
+                "count": 2,
+                "next": null,
+                "previous": null,
+                "results": [
+                    { "id": 31781, "text_signature": "many_msg_babbage(bytes1)", "hex_signature": "0xa9059cbb" },
+                    { "id": 145, "text_signature": "transfer(address,uint256)", "hex_signature": "0xa9059cbb" }
+                ]
+            }));
+            let (cache, path) = cache("found");
+
+            // Act
+            let res = lookup_selector(&url, &cache, TRANSFER_SELECTOR).await;
+            let cached_res = lookup_selector(&url, &cache, TRANSFER_SELECTOR).await;
+            std::fs::remove_file(path)?;
+
+            // Assert
+            let expected = vec![
+                "transfer(address,uint256)".to_string(),
+                "many_msg_babbage(bytes1)".to_string(),
+            ];
+            assert_eq!(res?, expected);
+            assert_eq!(cached_res?, expected);
+            assert_eq!(requests.load(Ordering::SeqCst), 1);
+
+            Ok(())
+        }
+
+        #[tokio::test]
+        async fn should_not_find_signatures_for_an_unknown_selector() -> anyhow::Result<()> {
+            // Arrange
+            let (url, requests) = spawn_mock_http_server(json!({
+                "count": 0,
+                "next": null,
+                "previous": null,
+                "results": []
+            }));
+            let (cache, path) = cache("not-found");
+
+            // Act
+            let res = lookup_selector(&url, &cache, [0xde, 0xad, 0xbe, 0xef]).await;
+            let retried_res = lookup_selector(&url, &cache, [0xde, 0xad, 0xbe, 0xef]).await;
+
+            // Assert
+            assert!(res?.is_empty());
+            assert!(retried_res?.is_empty());
+            assert_eq!(requests.load(Ordering::SeqCst), 2);
+            assert!(!path.exists());
+
+            Ok(())
+        }
+    }
+
+    mod lookup_selector_with_known_signatures {
+        use serde_json::json;
+
+        use crate::cmd::{
+            helpers::test::spawn_mock_http_server,
+            utils::{lookup_selector_with_known_signatures, SelectorCache},
+        };
+
+        const TRANSFER_SELECTOR: [u8; 4] = [0xa9, 0x05, 0x9c, 0xbb];
+
+        fn cache(name: &str) -> (SelectorCache, std::path::PathBuf) {
+            let path = std::env::temp_dir().join(format!(
+                "yaeth-known-selectors-{name}-{}.json",
+                std::process::id()
+            ));
+            let _ = std::fs::remove_file(&path);
+
+            (SelectorCache::new(&path), path)
+        }
+
+        #[tokio::test]
+        async fn should_merge_the_known_and_remote_signatures() -> anyhow::Result<()> {
+            // Arrange
+            let (url, _) = spawn_mock_http_server(json!({
+                "count": 2,
+                "next": null,
+                "previous": null,
+                "results": [
+                    { "id": 31781, "text_signature": "many_msg_babbage(bytes1)", "hex_signature": "0xa9059cbb" },
+                    { "id": 145, "text_signature": "transfer(address,uint256)", "hex_signature": "0xa9059cbb" }
+                ]
+            }));
+            let (cache, path) = cache("merged");
+
+            // Act
+            let res = lookup_selector_with_known_signatures(&url, &cache, TRANSFER_SELECTOR).await;
+            let _ = std::fs::remove_file(path);
+
+            // Assert
+            assert_eq!(
+                res?,
+                vec![
+                    "transfer(address,uint256)".to_string(),
+                    "many_msg_babbage(bytes1)".to_string(),
+                ]
+            );
+
+            Ok(())
+        }
+
+        #[tokio::test]
+        async fn should_only_return_the_known_signatures_if_the_database_is_unreachable(
+        ) -> anyhow::Result<()> {
+            // Arrange
+            let (cache, _) = cache("unreachable");
+
+            // Act
+            let res = lookup_selector_with_known_signatures(
+                "http://127.0.0.1:1",
+                &cache,
+                TRANSFER_SELECTOR,
+            )
+            .await;
+
+            // Assert
+            assert_eq!(res?, vec!["transfer(address,uint256)".to_string()]);
+
+            Ok(())
+        }
+    }
+
+    mod get_proof {
+
+        use ethers::utils::parse_ether;
+
+        use crate::cmd::{helpers::test::setup_test, utils::get_proof};
+
+        #[tokio::test]
+        async fn should_get_the_account_merkle_proof() -> anyhow::Result<()> {
+            // Arrange
+            let (node_provider, anvil) = setup_test().await?;
+
+            let account = *anvil.addresses().first().unwrap();
+            let expected_account_balance = parse_ether(10000)?;
+
+            // Act
+            let res = get_proof(&node_provider, account.into(), [].into(), None).await;
+
+            // Assert
+            assert!(res.is_ok());
+
+            let maybe_account_proof = res.unwrap();
+            assert_eq!(maybe_account_proof.address, account);
+            assert_eq!(maybe_account_proof.balance, expected_account_balance);
+            assert_eq!(maybe_account_proof.nonce, 0.into());
+
+            Ok(())
+        }
+    }
+
+    mod permit {
+        use ethers::{
+            abi::{self, Token},
+            providers::Middleware,
+            types::{
+                transaction::eip712::{EIP712Domain, Eip712},
+                Bytes, TransactionRequest, H160, H256, U256, U64,
+            },
+            utils::{id, keccak256},
+        };
+
+        use crate::cmd::{
+            helpers::test::{deploy_contract_helper, setup_test},
+            utils::{
+                call_token, decode_eip712_domain, permit_typed_data, sign_permit, PermitOptions,
+            },
+        };
+
+        const TOKEN_NAME: &str = "Permit Token";
+
+        fn permit_digest(
+            domain: &EIP712Domain,
+            owner: H160,
+            spender: H160,
+            value: U256,
+            nonce: U256,
+            deadline: U256,
+        ) -> [u8; 32] {
+            let permit_typehash = keccak256(
+                "Permit(address owner,address spender,uint256 value,uint256 nonce,uint256 deadline)",
+            );
+
+            let struct_hash = keccak256(abi::encode(&[
+                Token::FixedBytes(permit_typehash.to_vec()),
+                Token::Address(owner),
+                Token::Address(spender),
+                Token::Uint(value),
+                Token::Uint(nonce),
+                Token::Uint(deadline),
+            ]));
+
+            let mut digest_input = vec![0x19, 0x01];
+            digest_input.extend(domain.separator());
+            digest_input.extend(struct_hash);
+
+            keccak256(digest_input)
+        }
+
+        // Fixture ERC-2612 token with version "1" implementing name(), nonces(address),
+        // DOMAIN_SEPARATOR(), allowance(address,address) and permit(address,address,uint256,
+        // uint256,uint8,bytes32,bytes32). Failed permit checks revert:
+        // constructor: PUSH2 0x022b, DUP1, PUSH1 0x0c, PUSH1 0, CODECOPY, PUSH1 0, RETURN
+        fn permit_token_init_code() -> Bytes {
+            let mut init_code = vec![
+                0x61, 0x02, 0x2b, 0x80, 0x60, 0x0c, 0x60, 0x00, 0x39, 0x60, 0x00, 0xf3,
+            ];
+            // dispatch the selector, unknown selectors revert
+            init_code.extend_from_slice(&[
+                0x60, 0x00, 0x35, 0x60, 0xe0, 0x1c, 0x80, 0x63, 0x06, 0xfd, 0xde, 0x03, 0x14, 0x61,
+                0x00, 0x42, 0x57, 0x80, 0x63, 0x7e, 0xce, 0xbe, 0x00, 0x14, 0x61, 0x00, 0x50, 0x57,
+                0x80, 0x63, 0x36, 0x44, 0xe5, 0x15, 0x14, 0x61, 0x00, 0x78, 0x57, 0x80, 0x63, 0xdd,
+                0x62, 0xed, 0x3e, 0x14, 0x61, 0x00, 0x5d, 0x57, 0x80, 0x63, 0xd5, 0x05, 0xac, 0xcf,
+                0x14, 0x61, 0x01, 0x05, 0x57, 0x5b, 0x60, 0x00, 0x80, 0xfd,
+            ]);
+            // name() copies the encoded name appended to the code
+            init_code.extend_from_slice(&[
+                0x5b, 0x60, 0x60, 0x61, 0x01, 0xcb, 0x60, 0x00, 0x39, 0x60, 0x60, 0x60, 0x00, 0xf3,
+            ]);
+            // nonces(owner) reads the owner slot
+            init_code.extend_from_slice(&[
+                0x5b, 0x60, 0x04, 0x35, 0x54, 0x60, 0x00, 0x52, 0x60, 0x20, 0x60, 0x00, 0xf3,
+            ]);
+            // allowance(owner, spender) reads the keccak256(owner, spender) slot
+            init_code.extend_from_slice(&[
+                0x5b, 0x60, 0x04, 0x35, 0x60, 0x00, 0x52, 0x60, 0x24, 0x35, 0x60, 0x20, 0x52, 0x60,
+                0x40, 0x60, 0x00, 0x20, 0x54, 0x60, 0x00, 0x52, 0x60, 0x20, 0x60, 0x00, 0xf3,
+            ]);
+            // DOMAIN_SEPARATOR() returns the result of the domain separator subroutine
+            init_code.extend_from_slice(&[
+                0x5b, 0x61, 0x00, 0x80, 0x61, 0x00, 0x89, 0x56, 0x5b, 0x60, 0x00, 0x52, 0x60, 0x20,
+                0x60, 0x00, 0xf3,
+            ]);
+            // domain separator subroutine: hashes the domain type, name, version "1", chain id and
+            // address
+            init_code.extend_from_slice(&[0x5b, 0x7f]);
+            init_code.extend(keccak256(
+                "EIP712Domain(string name,string version,uint256 chainId,address verifyingContract)",
+            ));
+            init_code.extend_from_slice(&[0x60, 0x00, 0x52, 0x7f]);
+            init_code.extend(keccak256(TOKEN_NAME));
+            init_code.extend_from_slice(&[0x60, 0x20, 0x52, 0x7f]);
+            init_code.extend(keccak256("1"));
+            init_code.extend_from_slice(&[
+                0x60, 0x40, 0x52, 0x46, 0x60, 0x60, 0x52, 0x30, 0x60, 0x80, 0x52, 0x60, 0xa0, 0x60,
+                0x00, 0x20, 0x90, 0x56,
+            ]);
+            // permit(owner, spender, value, deadline, v, r, s) checks the deadline, hashes the permit
+            // with the owner nonce and increments it
+            init_code.extend_from_slice(&[
+                0x5b, 0x60, 0x64, 0x35, 0x42, 0x11, 0x61, 0x00, 0x3d, 0x57, 0x7f,
+            ]);
+            init_code.extend(keccak256(
+                "Permit(address owner,address spender,uint256 value,uint256 nonce,uint256 deadline)",
+            ));
+            init_code.extend_from_slice(&[
+                0x60, 0x00, 0x52, 0x60, 0x04, 0x35, 0x60, 0x20, 0x52, 0x60, 0x24, 0x35, 0x60, 0x40,
+                0x52, 0x60, 0x44, 0x35, 0x60, 0x60, 0x52, 0x60, 0x04, 0x35, 0x54, 0x80, 0x60, 0x80,
+                0x52, 0x60, 0x01, 0x01, 0x60, 0x04, 0x35, 0x55, 0x60, 0x64, 0x35, 0x60, 0xa0, 0x52,
+                0x60, 0xc0, 0x60, 0x00, 0x20, 0x61, 0x01, 0x66, 0x61, 0x00, 0x89, 0x56,
+            ]);
+            // then recovers the digest signer, requires it to be the owner and sets the allowance
+            init_code.extend_from_slice(&[
+                0x5b, 0x60, 0x20, 0x52, 0x60, 0x40, 0x52, 0x61, 0x19, 0x01, 0x60, 0x00, 0x52, 0x60,
+                0x42, 0x60, 0x1e, 0x20, 0x60, 0x00, 0x52, 0x60, 0x84, 0x35, 0x60, 0x20, 0x52, 0x60,
+                0xa4, 0x35, 0x60, 0x40, 0x52, 0x60, 0xc4, 0x35, 0x60, 0x60, 0x52, 0x60, 0x00, 0x60,
+                0x80, 0x52, 0x60, 0x20, 0x60, 0x80, 0x60, 0x80, 0x60, 0x00, 0x60, 0x01, 0x5a, 0xfa,
+                0x15, 0x61, 0x00, 0x3d, 0x57, 0x60, 0x80, 0x51, 0x80, 0x15, 0x61, 0x00, 0x3d, 0x57,
+                0x60, 0x04, 0x35, 0x14, 0x15, 0x61, 0x00, 0x3d, 0x57, 0x60, 0x44, 0x35, 0x60, 0x04,
+                0x35, 0x60, 0x00, 0x52, 0x60, 0x24, 0x35, 0x60, 0x20, 0x52, 0x60, 0x40, 0x60, 0x00,
+                0x20, 0x55, 0x00,
+            ]);
+            init_code.extend(abi::encode(&[Token::String(TOKEN_NAME.to_string())]));
+
+            init_code.into()
+        }
+
+        #[test]
+        fn should_build_the_eip2612_permit_typed_data() -> anyhow::Result<()> {
+            // Arrange
+            let domain = EIP712Domain {
+                name: Some(TOKEN_NAME.to_string()),
+                version: Some("1".to_string()),
+                chain_id: Some(1.into()),
+                verifying_contract: Some(H160::repeat_byte(0x01)),
+                salt: None,
+            };
+            let owner = H160::repeat_byte(0x02);
+            let spender = H160::repeat_byte(0x03);
+            let value = U256::exp10(18);
+            let nonce = U256::from(7);
+            let deadline = U256::from(1_700_000_000);
+
+            let expected_digest = permit_digest(&domain, owner, spender, value, nonce, deadline);
+
+            // Act
+            let res = permit_typed_data(domain, owner, spender, value, nonce, deadline);
+
+            // Assert
+            assert!(res.is_ok());
+            assert_eq!(res.unwrap().encode_eip712()?, expected_digest);
+
+            Ok(())
+        }
+
+        #[test]
+        fn should_decode_the_eip5267_domain_fields() -> anyhow::Result<()> {
+            // Arrange
+            let verifying_contract = H160::repeat_byte(0x01);
+
+            // name, version, chainId and verifyingContract are set, salt is not
+            let data = abi::encode(&[
+                Token::FixedBytes(vec![0x0f]),
+                Token::String(TOKEN_NAME.to_string()),
+                Token::String("2".to_string()),
+                Token::Uint(10.into()),
+                Token::Address(verifying_contract),
+                Token::FixedBytes(H256::repeat_byte(0xff).as_bytes().to_vec()),
+                Token::Array(vec![]),
+            ]);
+
+            // Act
+            let res = decode_eip712_domain(&data);
+
+            // Assert
+            assert!(res.is_ok());
+            assert_eq!(
+                res.unwrap(),
+                EIP712Domain {
+                    name: Some(TOKEN_NAME.to_string()),
+                    version: Some("2".to_string()),
+                    chain_id: Some(10.into()),
+                    verifying_contract: Some(verifying_contract),
+                    salt: None,
+                }
+            );
+
+            Ok(())
+        }
+
+        #[tokio::test]
+        async fn should_sign_a_permit_accepted_by_the_token() -> anyhow::Result<()> {
+            // Arrange
+            let (node_provider, anvil) = setup_test().await?;
+
+            let owner = *anvil.addresses().first().unwrap();
+            let spender = *anvil.addresses().get(1).unwrap();
+            let value = U256::exp10(18);
+            let deadline = U256::from(u64::MAX);
+
+            let token =
+                deploy_contract_helper(&node_provider, owner, permit_token_init_code()).await?;
+
+            // Act
+            let res = sign_permit(
+                &node_provider,
+                PermitOptions {
+                    token,
+                    owner,
+                    spender,
+                    value,
+                    deadline,
+                },
+            )
+            .await;
+
+            // Assert
+            assert!(res.is_ok());
+
+            let permit = res.unwrap();
+            assert_eq!(permit.nonce, U256::zero());
+
+            let mut data =
+                id("permit(address,address,uint256,uint256,uint8,bytes32,bytes32)").to_vec();
+            data.extend(abi::encode(&[
+                Token::Address(owner),
+                Token::Address(spender),
+                Token::Uint(value),
+                Token::Uint(deadline),
+                Token::Uint(permit.v.into()),
+                Token::FixedBytes(permit.r.as_bytes().to_vec()),
+                Token::FixedBytes(permit.s.as_bytes().to_vec()),
+            ]));
+            let permit_tx = TransactionRequest::new().from(spender).to(token).data(data);
+
+            let receipt = node_provider
+                .send_transaction(permit_tx, None)
+                .await?
+                .await?
+                .unwrap();
+            assert_eq!(receipt.status, Some(U64::one()));
+
+            let allowance = call_token(
+                &node_provider,
+                token,
+                &id("allowance(address,address)"),
+                &[Token::Address(owner), Token::Address(spender)],
+            )
+            .await?;
+            assert_eq!(allowance, Some(abi::encode(&[Token::Uint(value)]).into()));
+
+            let nonce = call_token(
+                &node_provider,
+                token,
+                &id("nonces(address)"),
+                &[Token::Address(owner)],
+            )
+            .await?;
+            assert_eq!(nonce, Some(abi::encode(&[Token::Uint(U256::one())]).into()));
+
+            Ok(())
+        }
+    }
+
+    mod get_protocol_version {
+        use std::time::Duration;
+
+        use serde_json::json;
+
+        use crate::cmd::{
+            helpers::test::{
+                mock_node_provider, spawn_mock_rpc_error_server, spawn_mock_rpc_server,
+            },
+            utils::{get_protocol_version, ProtocolVersion},
+        };
+
+        fn method_not_found_server() -> String {
+            spawn_mock_rpc_error_server(json!({
+                "code": -32601,
+                "message": "the method eth_protocolVersion does not exist/is not available"
+            }))
+        }
+
+        #[tokio::test]
+        async fn should_get_the_protocol_version() -> anyhow::Result<()> {
+            // Arrange
+            let (url, _) = spawn_mock_rpc_server(json!("0x41"), Duration::ZERO);
+            let node_provider = mock_node_provider(url).await?;
+
+            // Act
+            let res = get_protocol_version(&node_provider, false).await;
+
+            // Assert
+            assert_eq!(
+                res?,
+                ProtocolVersion {
+                    protocol_version: Some(0x41.into()),
+                    supported: true
+                }
+            );
+
+            Ok(())
+        }
+
+        #[tokio::test]
+        async fn should_report_the_protocol_version_as_not_supported() -> anyhow::Result<()> {
+            // Arrange
+            let node_provider = mock_node_provider(method_not_found_server()).await?;
+
+            // Act
+            let res = get_protocol_version(&node_provider, false).await;
+
+            // Assert
+            assert_eq!(
+                res?,
+                ProtocolVersion {
+                    protocol_version: None,
+                    supported: false
+                }
+            );
+
+            Ok(())
+        }
+
+        #[tokio::test]
+        async fn should_fail_on_unsupported_protocol_version_in_strict_mode() -> anyhow::Result<()>
+        {
+            // Arrange
+            let node_provider = mock_node_provider(method_not_found_server()).await?;
+
+            // Act
+            let res = get_protocol_version(&node_provider, true).await;
+
+            // Assert
+            assert!(res.is_err());
+
+            Ok(())
+        }
+
+        #[tokio::test]
+        async fn should_not_hide_other_errors() -> anyhow::Result<()> {
+            // Arrange
+            let url =
+                spawn_mock_rpc_error_server(json!({ "code": -32000, "message": "internal error" }));
+            let node_provider = mock_node_provider(url).await?;
+
+            // Act
+            let res = get_protocol_version(&node_provider, false).await;
+
+            // Assert
+            assert!(res.is_err());
+
+            Ok(())
+        }
+    }
+
+    mod sign {
+        use ethers::{
+            types::{
+                transaction::eip712::{Eip712, TypedData},
+                Bytes, RecoveryMessage, TransactionRequest, H160, H256,
+            },
+            utils::Anvil,
+        };
+        use serde_json::json;
+
+        use crate::{
+            cmd::{
+                helpers::test::setup_test,
+                utils::{load_typed_data, sign, SignTransactionData},
+            },
+            config::{get_config, ConfigOverrides},
+            context::NodeProvider,
+        };
+
+        #[tokio::test]
+        async fn should_not_sign_the_data_if_with_an_unkwnon_signer() -> anyhow::Result<()> {
+            // Arrange
+            let (node_provider, _anvil) = setup_test().await?;
+
+            let bytes = SignTransactionData::Raw(Bytes::from_static(b"somerandomdata"));
+            let from = "0xf39fd6e51aad88f6f4ce6ab8827279cfffb92267".parse::<H160>()?;
+
+            // Act
+            let res = sign(&node_provider, from.into(), bytes).await;
+
+            // Assert
+            assert!(res.is_err());
+
+            let err = res.unwrap_err();
+            assert!(err.to_string().contains("No Signer available"));
+
+            Ok(())
+        }
+
+        #[tokio::test]
+        async fn should_sign_the_data() -> anyhow::Result<()> {
+            // Arrange
+            let (node_provider, anvil) = setup_test().await?;
+
+            let bytes = Bytes::from_static(b"somerandomdata");
+            let data = SignTransactionData::Raw(bytes.clone());
+            let from = *anvil.addresses().first().unwrap();
+
+            // Act
+            let res = sign(&node_provider, from.into(), data).await;
+
+            // Assert
+            assert!(res.is_ok());
+
+            let sig = res.unwrap();
+            assert!(sig
+                .verify(RecoveryMessage::Data(bytes.into_iter().collect()), from)
+                .is_ok());
+
+            Ok(())
+        }
+
+        #[tokio::test]
+        async fn should_not_sign_the_tx_data_if_no_private_key_is_configured() -> anyhow::Result<()>
+        {
+            // Arrange
+            let (node_provider, anvil) = setup_test().await?;
+
+            let tx = TransactionRequest::new();
+            let data = SignTransactionData::Transaction(tx.into());
+            let from = *anvil.addresses().first().unwrap();
+
+            // Act
+            let res = sign(&node_provider, from.into(), data).await;
+
+            // Assert
+            assert!(res.is_err());
+
+            Ok(())
+        }
+
+        #[tokio::test]
+        async fn should_sign_the_tx_data() -> anyhow::Result<()> {
+            // Arrange
+            let anvil = Anvil::new().spawn();
+            let priv_key = hex::encode(anvil.keys().first().unwrap().to_be_bytes());
+
+            let overrides = ConfigOverrides::new(Some(priv_key), Some(anvil.endpoint()), None);
+            let config = get_config(overrides)?;
+
+            let node_provider = NodeProvider::new(&config).await?;
+
+            let tx = TransactionRequest::new();
+            let data = SignTransactionData::Transaction(tx.clone().into());
+            let from = *anvil.addresses().first().unwrap();
+
+            // Act
+            let res = sign(&node_provider, from.into(), data).await;
+
+            // Assert
+            assert!(res.is_ok());
+
+            let tx_with_chain_id = tx.chain_id(anvil.chain_id());
+            let sig = res.unwrap();
+            assert!(sig
+                .verify(RecoveryMessage::Hash(tx_with_chain_id.sighash()), from)
+                .is_ok());
+
+            Ok(())
+        }
+
+        fn mail_typed_data() -> serde_json::Value {
+            json!({
+                "types": {
+                    "EIP712Domain": [
+                        { "name": "name", "type": "string" },
+                        { "name": "chainId", "type": "uint256" }
+                    ],
+                    "Mail": [
+                        { "name": "to", "type": "address" },
+                        { "name": "contents", "type": "string" }
+                    ],
+                },
+                "primaryType": "Mail",
+                "domain": { "name": "yaeth", "chainId": 31337 },
+                "message": {
+                    "to": "0x70997970c51812dc3a010c7d01b50e0d17dc79c8",
+                    "contents": "hello"
+                },
+            })
+        }
+
+        #[tokio::test]
+        async fn should_sign_the_typed_data() -> anyhow::Result<()> {
+            // Arrange
+            let (node_provider, anvil) = setup_test().await?;
+
+            let typed_data: TypedData = serde_json::from_value(mail_typed_data())?;
+            let digest = H256::from(typed_data.encode_eip712()?);
+            let data = SignTransactionData::TypedData(typed_data);
+            let from = *anvil.addresses().first().unwrap();
+
+            // Act
+            let res = sign(&node_provider, from.into(), data).await;
+
+            // Assert
+            assert!(res.is_ok());
+            assert_eq!(res.unwrap().recover(digest)?, from);
+
+            Ok(())
+        }
+
+        #[test]
+        fn should_load_the_typed_data_file() -> anyhow::Result<()> {
+            // Arrange
+            let path =
+                std::env::temp_dir().join(format!("yaeth-typed-data-{}.json", std::process::id()));
+            std::fs::write(&path, mail_typed_data().to_string())?;
+
+            // Act
+            let res = load_typed_data(&path);
+            std::fs::remove_file(path)?;
+
+            // Assert
+            let typed_data = res?;
+            assert_eq!(typed_data.primary_type, "Mail");
+            assert_eq!(typed_data.domain.name.as_deref(), Some("yaeth"));
+
+            Ok(())
+        }
+
+        #[test]
+        fn should_not_load_an_invalid_typed_data_file() -> anyhow::Result<()> {
+            // Arrange
+            let path = std::env::temp_dir().join(format!(
+                "yaeth-typed-data-invalid-{}.json",
+                std::process::id()
+            ));
+            std::fs::write(&path, json!({ "message": {} }).to_string())?;
+
+            // Act
+            let res = load_typed_data(&path);
+            std::fs::remove_file(path)?;
+
+            // Assert
+            assert!(res.is_err());
+            assert!(res
+                .unwrap_err()
+                .to_string()
+                .contains("Invalid EIP-712 typed data"));
+
+            Ok(())
+        }
+    }
+
+    mod encode_function_call {
+        use ethers::types::Bytes;
+
+        use crate::cmd::utils::encode_function_call;
+
+        #[test]
+        fn should_encode_the_function_call() -> anyhow::Result<()> {
+            // Arrange
+            let args = ["0x000000000000000000000000000000000000dead".to_string()];
+            let expected_res: Bytes =
+                "0x70a08231000000000000000000000000000000000000000000000000000000000000dead"
+                    .parse()?;
+
+            // Act
+            let res = encode_function_call("balanceOf(address)", &args);
+
+            // Assert
+            assert!(res.is_ok());
+            assert_eq!(res.unwrap(), expected_res);
+
+            Ok(())
+        }
+
+        #[test]
+        fn should_not_encode_a_call_with_invalid_arguments() {
+            // Setup
+            let test_cases: [(&str, &[&str]); 3] = [
+                ("balanceOf(address)", &[]),
+                ("balanceOf(address)", &["not an address"]),
+                (
+                    "balanceOf(address",
+                    &["0x000000000000000000000000000000000000dead"],
+                ),
+            ];
+
+            for (signature, args) in test_cases {
+                let args: Vec<String> = args.iter().map(ToString::to_string).collect();
+
+                // Act
+                let res = encode_function_call(signature, &args);
+
+                // Assert
+                assert!(res.is_err());
+            }
+        }
+
+        #[test]
+        fn should_coerce_the_arguments_into_their_parameter_types() -> anyhow::Result<()> {
+            // Setup
+            let test_cases: [(&str, &[&str], &str); 5] = [
+                (
+                    "transfer(address,uint256)",
+                    &["0x000000000000000000000000000000000000dead", "1000000000000000000"],
+                    "0xa9059cbb000000000000000000000000000000000000000000000000000000000000dead0000000000000000000000000000000000000000000000000de0b6b3a7640000",
+                ),
+                (
+                    "transfer(address,uint256)",
+                    &["0x000000000000000000000000000000000000dead", "0xde0b6b3a7640000"],
+                    "0xa9059cbb000000000000000000000000000000000000000000000000000000000000dead0000000000000000000000000000000000000000000000000de0b6b3a7640000",
+                ),
+                (
+                    "setApprovalForAll(address,bool)",
+                    &["0x000000000000000000000000000000000000dead", "true"],
+                    "0xa22cb465000000000000000000000000000000000000000000000000000000000000dead0000000000000000000000000000000000000000000000000000000000000001",
+                ),
+                (
+                    "f(uint8[2],int8)",
+                    &["[1,0x2]", "-1"],
+                    "0x580fed5600000000000000000000000000000000000000000000000000000000000000010000000000000000000000000000000000000000000000000000000000000002ffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff",
+                ),
+                (
+                    "f(string,bytes)",
+                    &["yaeth", "0x0102"],
+                    "0x5d5cfcc2000000000000000000000000000000000000000000000000000000000000004000000000000000000000000000000000000000000000000000000000000000800000000000000000000000000000000000000000000000000000000000000005796165746800000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000020102000000000000000000000000000000000000000000000000000000000000",
+                ),
+            ];
+
+            for (signature, args, expected_res) in test_cases {
+                let args: Vec<String> = args.iter().map(ToString::to_string).collect();
+                let expected_res: Bytes = expected_res.parse()?;
+
+                // Act
+                let res = encode_function_call(signature, &args);
+
+                // Assert
+                assert_eq!(res?, expected_res, "{signature}");
+            }
+
+            Ok(())
+        }
+
+        #[test]
+        fn should_name_the_invalid_argument() {
+            // Setup
+            let test_cases: [(&str, &[&str], &str); 5] = [
+                (
+                    "transfer(address,uint256)",
+                    &["0x000000000000000000000000000000000000dead", "a lot"],
+                    "Invalid argument 1 of type uint256",
+                ),
+                (
+                    "transfer(address,uint256)",
+                    &["dead", "1"],
+                    "Invalid argument 0 of type address",
+                ),
+                (
+                    "f(uint8)",
+                    &["256"],
+                    "Argument 0 of type uint8 is out of range",
+                ),
+                (
+                    "f(int8)",
+                    &["-129"],
+                    "Argument 0 of type int8 is out of range",
+                ),
+                (
+                    "f(uint8[2])",
+                    &["[1]"],
+                    "Invalid argument 0 of type uint8[2]",
+                ),
+            ];
+
+            for (signature, args, expected_err) in test_cases {
+                let args: Vec<String> = args.iter().map(ToString::to_string).collect();
+
+                // Act
+                let res = encode_function_call(signature, &args);
+
+                // Assert
+                let err = res.unwrap_err().to_string();
+                assert!(err.contains(expected_err), "{err}");
+            }
+        }
+    }
+
+    mod decode_function_call {
+        use ethers::{
+            abi::{self, Token},
+            types::Bytes,
+        };
+
+        use crate::cmd::utils::{
+            decode_function_call, encode_function_call, CalldataFunction, DecodedParam,
+        };
+
+        const TRANSFER_CALLDATA: &str = "0xa9059cbb000000000000000000000000000000000000000000000000000000000000dead0000000000000000000000000000000000000000000000000de0b6b3a7640000";
+
+        fn param(name: &str, kind: &str, value: &str) -> DecodedParam {
+            DecodedParam {
+                name: name.into(),
+                kind: kind.into(),
+                value: value.into(),
+            }
+        }
+
+        #[test]
+        fn should_decode_the_call_arguments() -> anyhow::Result<()> {
+            // Arrange
+            let data: Bytes = TRANSFER_CALLDATA.parse()?;
+
+            // Act
+            let res = decode_function_call(
+                CalldataFunction::Signature("transfer(address to, uint256 amount)".into()),
+                &data,
+            );
+
+            // Assert
+            let res = res?;
+            assert_eq!(res.function, "transfer(address,uint256)");
+            assert!(res.warning.is_none());
+            assert_eq!(
+                res.params,
+                [
+                    param(
+                        "to",
+                        "address",
+                        "0x000000000000000000000000000000000000dEaD"
+                    ),
+                    param("amount", "uint256", "1000000000000000000"),
+                ]
+            );
+
+            Ok(())
+        }
+
+        #[test]
+        fn should_decode_dynamic_arguments() -> anyhow::Result<()> {
+            // Arrange
+            let signature = "f(string,bytes[],uint8)";
+            let data = encode_function_call(
+                signature,
+                &["yaeth".into(), "[0x0102,0x]".into(), "7".into()],
+            )?;
+
+            // Act
+            let res = decode_function_call(CalldataFunction::Signature(signature.into()), &data);
+
+            // Assert
+            assert_eq!(
+                res?.params,
+                [
+                    param("arg0", "string", "yaeth"),
+                    param("arg1", "bytes[]", "[0x0102, 0x]"),
+                    param("arg2", "uint8", "7"),
+                ]
+            );
+
+            Ok(())
+        }
+
+        #[test]
+        fn should_warn_about_a_selector_mismatch() -> anyhow::Result<()> {
+            // Arrange
+            let data: Bytes = TRANSFER_CALLDATA.parse()?;
+
+            // Act
+            let res = decode_function_call(
+                CalldataFunction::Signature("approve(address,uint256)".into()),
+                &data,
+            );
+
+            // Assert
+            let res = res?;
+            assert!(res
+                .warning
+                .is_some_and(|warning| warning.contains("does not match")));
+            assert_eq!(res.params.len(), 2);
+
+            Ok(())
+        }
+
+        #[test]
+        fn should_find_the_function_in_the_abi() -> anyhow::Result<()> {
+            // Arrange
+            let path =
+                std::env::temp_dir().join(format!("yaeth-abi-decode-{}.json", std::process::id()));
+            std::fs::write(
+                &path,
+                r#"[
+                    {"type":"function","name":"approve","inputs":[{"name":"spender","type":"address"},{"name":"value","type":"uint256"}],"outputs":[],"stateMutability":"nonpayable"},
+                    {"type":"function","name":"transfer","inputs":[{"name":"to","type":"address"},{"name":"value","type":"uint256"}],"outputs":[],"stateMutability":"nonpayable"}
+                ]"#,
+            )?;
+            let data: Bytes = TRANSFER_CALLDATA.parse()?;
+
+            // Act
+            let res = decode_function_call(CalldataFunction::Abi(path.clone()), &data);
+            let unknown_selector_res = decode_function_call(
+                CalldataFunction::Abi(path.clone()),
+                &[0x12, 0x34, 0x56, 0x78],
+            );
+            std::fs::remove_file(path)?;
+
+            // Assert
+            let res = res?;
+            assert_eq!(res.function, "transfer(address,uint256)");
+            assert_eq!(
+                res.params[1],
+                param("value", "uint256", "1000000000000000000")
+            );
+
+            assert!(unknown_selector_res
+                .unwrap_err()
+                .to_string()
+                .contains("No function of the ABI matches the selector 0x12345678"));
+
+            Ok(())
+        }
+
+        #[test]
+        fn should_not_decode_malformed_calldata() -> anyhow::Result<()> {
+            // Setup
+            let selector = &TRANSFER_CALLDATA.parse::<Bytes>()?[..4];
+            let dynamic_selector = &encode_function_call("f(bytes[])", &["[]".into()])?[..4];
+
+            let truncated = [selector, &[0u8; 40]].concat();
+            // Offset pointing far past the end of the calldata
+            let bad_offset = [
+                dynamic_selector,
+                &abi::encode(&[Token::Uint(0xffff.into())]),
+            ]
+            .concat();
+
+            let test_cases = [
+                ("transfer(address,uint256)", vec![0xa9, 0x05]),
+                ("transfer(address,uint256)", truncated),
+                ("f(bytes[])", bad_offset),
+            ];
+
+            for (signature, data) in test_cases {
+                // Act
+                let res =
+                    decode_function_call(CalldataFunction::Signature(signature.into()), &data);
+
+                // Assert
+                assert!(res.is_err(), "{signature}");
+            }
+
+            Ok(())
+        }
+    }
+
+    mod get_sync_status {
+
+        use crate::cmd::{helpers::test::setup_test, utils::get_sync_status};
+
+        #[tokio::test]
+        async fn should_get_the_node_sync_status() -> anyhow::Result<()> {
+            // Arrange
+            let (node_provider, _anvil) = setup_test().await?;
+
+            // Act
+            let res = get_sync_status(&node_provider).await;
+
+            // Assert
+            assert!(res.is_ok());
+
+            Ok(())
+        }
+    }
+
+    mod base58 {
+        use ethers::types::Bytes;
+
+        use crate::cmd::utils::{base58_decode, base58_encode};
+
+        #[test]
+        fn should_encode_and_decode_known_vectors() -> anyhow::Result<()> {
+            // Setup
+            let test_cases = [
+                (Bytes::from_static(b""), ""),
+                (Bytes::from_static(&[0x00, 0x00, 0x01]), "112"),
+                (Bytes::from_static(b"hello world"), "StV1DL6CwTryKyV"),
+            ];
+
+            for (data, expected_res) in test_cases {
+                // Act
+                let encoded = base58_encode(data.clone());
+                let decoded = base58_decode(&encoded)?;
+
+                // Assert
+                assert_eq!(encoded, expected_res);
+                assert_eq!(decoded, data);
+            }
+
+            Ok(())
+        }
+
+        #[test]
+        fn should_not_decode_invalid_base58_data() {
+            // Act
+            let res = base58_decode("0OIl");
+
+            // Assert
+            assert!(res.is_err());
+        }
+    }
+
+    mod base64 {
+        use ethers::types::Bytes;
+
+        use crate::cmd::utils::{base64_decode, base64_encode};
+
+        #[test]
+        fn should_encode_and_decode_known_vectors() -> anyhow::Result<()> {
+            // Setup
+            let test_cases = [
+                (Bytes::from_static(b""), ""),
+                (Bytes::from_static(b"f"), "Zg=="),
+                (Bytes::from_static(b"foobar"), "Zm9vYmFy"),
+                (Bytes::from_static(&[0xde, 0xad, 0xbe, 0xef]), "3q2+7w=="),
+            ];
+
+            for (data, expected_res) in test_cases {
+                // Act
+                let encoded = base64_encode(data.clone());
+                let decoded = base64_decode(&encoded)?;
+
+                // Assert
+                assert_eq!(encoded, expected_res);
+                assert_eq!(decoded, data);
+            }
+
+            Ok(())
+        }
+
+        #[test]
+        fn should_not_decode_invalid_base64_data() {
+            // Act
+            let res = base64_decode("not base64!");
+
+            // Assert
+            assert!(res.is_err());
+        }
+    }
+
+    mod unit_conversion {
+        use ethers::{types::U256, utils::Units};
+
+        use crate::cmd::utils::{from_wei, to_gwei, to_wei};
+
+        #[test]
+        fn should_convert_amounts_to_wei() -> anyhow::Result<()> {
+            // Setup
+            let test_cases = [
+                ("1", Units::Ether, "1000000000000000000"),
+                ("1.5", Units::Ether, "1500000000000000000"),
+                ("30", Units::Gwei, "30000000000"),
+                ("42", Units::Wei, "42"),
+            ];
+
+            for (amount, unit, expected_res) in test_cases {
+                // Act
+                let res = to_wei(amount, unit)?;
+
+                // Assert
+                assert_eq!(res, U256::from_dec_str(expected_res)?);
+            }
+
+            Ok(())
+        }
+
+        #[test]
+        fn should_convert_amounts_to_gwei() -> anyhow::Result<()> {
+            // Setup
+            let test_cases = [
+                ("1", Units::Ether, "1000000000"),
+                ("0.000000001", Units::Ether, "1"),
+                ("1500000000", Units::Wei, "1.5"),
+            ];
+
+            for (amount, unit, expected_res) in test_cases {
+                // Act
+                let res = to_gwei(amount, unit)?;
+
+                // Assert
+                assert_eq!(res, expected_res);
+            }
+
+            Ok(())
+        }
+
+        #[test]
+        fn should_convert_wei_to_the_provided_unit() -> anyhow::Result<()> {
+            // Setup
+            let test_cases = [
+                ("1000000000000000000", Units::Ether, "1"),
+                ("1230000000000000000", Units::Ether, "1.23"),
+                ("1", Units::Ether, "0.000000000000000001"),
+                ("0", Units::Gwei, "0"),
+                ("42", Units::Wei, "42"),
+            ];
+
+            for (wei, unit, expected_res) in test_cases {
+                // Act
+                let res = from_wei(wei, unit)?;
+
+                // Assert
+                assert_eq!(res, expected_res);
+            }
+
+            Ok(())
+        }
+
+        #[test]
+        fn should_not_convert_invalid_amounts() {
+            // Act
+            let negative_res = to_wei("-1", Units::Ether);
+            let too_precise_res = to_wei("0.1", Units::Wei);
+            let non_decimal_res = from_wei("0x10", Units::Ether);
+
+            // Assert
+            assert!(negative_res.is_err());
+            assert!(too_precise_res.is_err());
+            assert!(non_decimal_res.is_err());
+        }
+    }
+
+    mod convert_units {
+        use ethers::utils::Units;
+
+        use crate::cmd::utils::{convert_units, ConvertedAmount};
+
+        #[test]
+        fn should_convert_between_units() -> anyhow::Result<()> {
+            // Setup
+            let test_cases = [
+                (
+                    "1.5",
+                    Units::Ether,
+                    Units::Wei,
+                    "1500000000000000000",
+                    "wei",
+                ),
+                (
+                    "1",
+                    Units::Wei,
+                    Units::Ether,
+                    "0.000000000000000001",
+                    "ether",
+                ),
+                ("0.000000000000000001", Units::Ether, Units::Wei, "1", "wei"),
+                ("1.50", Units::Ether, Units::Gwei, "1500000000", "gwei"),
+                ("1", Units::Gwei, Units::Ether, "0.000000001", "ether"),
+                ("2", Units::Pwei, Units::Twei, "2000", "szabo"),
+                ("1", Units::Kwei, Units::Mwei, "0.001", "mwei"),
+                ("0", Units::Ether, Units::Wei, "0", "wei"),
+                ("1", Units::Other(5), Units::Other(2), "1000", "2"),
+                (
+                    "1000000000000000000000",
+                    Units::Ether,
+                    Units::Wei,
+                    "1000000000000000000000000000000000000000",
+                    "wei",
+                ),
+                (
+                    "1000000000000000000000000000000000000000",
+                    Units::Wei,
+                    Units::Ether,
+                    "1000000000000000000000",
+                    "ether",
+                ),
+            ];
+
+            for (amount, from, to, expected_value, expected_unit) in test_cases {
+                // Act
+                let res = convert_units(amount, from, to)?;
+
+                // Assert
+                assert_eq!(
+                    res,
+                    ConvertedAmount {
+                        value: expected_value.to_string(),
+                        unit: expected_unit.to_string(),
+                    },
+                    "{amount} {from:?} to {to:?}"
+                );
+            }
+
+            Ok(())
+        }
+
+        #[test]
+        fn should_reject_amounts_that_are_not_a_whole_number_of_wei() {
+            // Setup
+            let test_cases = [
+                ("0.0000000000000000001", Units::Ether),
+                ("0.5", Units::Wei),
+                ("1.0001", Units::Kwei),
+            ];
+
+            for (amount, from) in test_cases {
+                // Act
+                let res = convert_units(amount, from, Units::Wei);
+
+                // Assert
+                assert!(res.is_err(), "{amount} {from:?}");
+            }
+        }
+
+        #[test]
+        fn should_reject_invalid_amounts() {
+            // Setup
+            let test_cases = ["-1", "abc", "1.2.3", &format!("1{}", "0".repeat(60))];
+
+            for amount in test_cases {
+                // Act
+                let res = convert_units(amount, Units::Ether, Units::Wei);
+
+                // Assert
+                assert!(res.is_err(), "{amount}");
+            }
+        }
+    }
+
+    mod wei_math {
+        use ethers::types::U256;
+
+        use crate::cmd::utils::{wei_math, MathOperation};
+
+        #[test]
+        fn should_apply_the_operation() -> anyhow::Result<()> {
+            // Setup
+            let test_cases = [
+                (MathOperation::Add, 7, 3, 10),
+                (MathOperation::Sub, 7, 3, 4),
+                (MathOperation::Mul, 7, 3, 21),
+                (MathOperation::Div, 7, 3, 2),
+                (MathOperation::Mod, 7, 3, 1),
+                (MathOperation::Div, 3, 7, 0),
+                (MathOperation::Mod, 3, 7, 3),
+            ];
+
+            for (operation, lhs, rhs, expected_res) in test_cases {
+                // Act
+                let res = wei_math(operation, U256::from(lhs), U256::from(rhs))?;
+
+                // Assert
+                assert_eq!(res, U256::from(expected_res), "{operation:?}");
+            }
+
+            Ok(())
+        }
+
+        #[test]
+        fn should_divide_amounts_larger_than_64_bits() -> anyhow::Result<()> {
+            // Arrange
+            let wei = U256::from_dec_str("123456789000000000000000000")?;
+            let ether = U256::exp10(18);
+
+            // Act
+            let quotient = wei_math(MathOperation::Div, wei, ether)?;
+            let remainder = wei_math(MathOperation::Mod, wei, ether)?;
+
+            // Assert
+            assert_eq!(quotient, U256::from(123456789));
+            assert_eq!(remainder, U256::zero());
+
+            Ok(())
+        }
+
+        #[test]
+        fn should_not_overflow_a_multiplication() -> anyhow::Result<()> {
+            // Setup
+            let test_cases = [
+                (U256::MAX, U256::from(2)),
+                (U256::one() << 128, U256::one() << 128),
+            ];
+
+            for (lhs, rhs) in test_cases {
+                // Act
+                let res = wei_math(MathOperation::Mul, lhs, rhs);
+
+                // Assert
+                assert!(res.is_err(), "{lhs} * {rhs}");
+            }
+
+            // The largest product that fits
+            let res = wei_math(
+                MathOperation::Mul,
+                U256::one() << 128,
+                (U256::one() << 128) - 1,
+            )?;
+            assert_eq!(res, U256::MAX - ((U256::one() << 128) - 1));
+
+            Ok(())
+        }
+
+        #[test]
+        fn should_not_wrap_around_an_addition_or_subtraction() {
+            // Act
+            let add_res = wei_math(MathOperation::Add, U256::MAX, U256::one());
+            let sub_res = wei_math(MathOperation::Sub, U256::zero(), U256::one());
+
+            // Assert
+            assert!(add_res.is_err());
+            assert!(sub_res.is_err());
+        }
+
+        #[test]
+        fn should_not_divide_by_zero() {
+            // Setup
+            let test_cases = [MathOperation::Div, MathOperation::Mod];
+
+            for operation in test_cases {
+                // Act
+                let res = wei_math(operation, U256::one(), U256::zero());
+
+                // Assert
+                assert!(res.is_err(), "{operation:?}");
+            }
+        }
+    }
+
+    mod derive_mapping_slot {
+        use ethers::types::{Address, H256, U256};
+
+        use crate::cmd::utils::{derive_mapping_slot, MappingKey};
+
+        const HOLDER: &str = "0xf39Fd6e51aad88F6F4ce6aB8827279cffFb92266";
+        const SPENDER: &str = "0x70997970C51812dc3A010C7d01b50e0d17dc79C8";
+
+        #[test]
+        fn should_derive_the_slot_of_a_mapping_entry() -> anyhow::Result<()> {
+            // Setup
+            let test_cases = [
+                (
+                    0,
+                    MappingKey::Uint(U256::zero()),
+                    "0xad3228b676f7d3cd4284a5443f17f1962b36e491b30a40b2405849e597ba5fb5",
+                ),
+                (
+                    0,
+                    MappingKey::Address(HOLDER.parse()?),
+                    "0x723077b8a1b173adc35e5f0e7e3662fd1208212cb629f9c128551ea7168da722",
+                ),
+                (
+                    3,
+                    MappingKey::Uint(U256::one()),
+                    "0xa15bc60c955c405d20d9149c709e2460f1c2d9a497496a7f46004d1772c3054c",
+                ),
+                (
+                    2,
+                    MappingKey::Bytes32(H256::repeat_byte(0x11)),
+                    "0xcf3a25d1b2fbf5769a2f8891c95bc5b38555577eaa0f9a33d29f9759392fff3b",
+                ),
+            ];
+
+            for (base_slot, key, expected_slot) in test_cases {
+                // Act
+                let res = derive_mapping_slot(H256::from_low_u64_be(base_slot), &[key]);
+
+                // Assert
+                assert_eq!(res, expected_slot.parse::<H256>()?);
+            }
+
+            Ok(())
+        }
+
+        #[test]
+        fn should_derive_the_slot_of_a_nested_mapping_entry() -> anyhow::Result<()> {
+            // Arrange
+            let holder: Address = HOLDER.parse()?;
+            let spender: Address = SPENDER.parse()?;
+
+            // allowance[holder][spender] with allowance stored in slot 1
+            let expected_slot: H256 =
+                "0x7bb4c14a4642c37aac43229fec930a0666790858dbac8fc0f7b91e6a34742718".parse()?;
+
+            // Act
+            let res = derive_mapping_slot(
+                H256::from_low_u64_be(1),
+                &[MappingKey::Address(holder), MappingKey::Address(spender)],
+            );
+
+            // Assert
+            assert_eq!(res, expected_slot);
+
+            Ok(())
+        }
+
+        #[test]
+        fn should_return_the_base_slot_without_keys() {
+            // Arrange
+            let base_slot = H256::from_low_u64_be(5);
+
+            // Act
+            let res = derive_mapping_slot(base_slot, &[]);
+
+            // Assert
+            assert_eq!(res, base_slot);
+        }
+    }
+
+    mod compare_bytecode {
+        use crate::cmd::utils::{compare_bytecode, BytecodeVerification};
+
+        // Runtime code followed by a {"solc": 0.8.19} metadata map and its 2 bytes length
+        const CODE_WITH_METADATA: [u8; 16] = [
+            0x60, 0x2a, 0x00, 0x00, 0xa1, 0x64, 0x73, 0x6f, 0x6c, 0x63, 0x43, 0x00, 0x08, 0x13,
+            0x00, 0x0a,
+        ];
+
+        #[test]
+        fn should_match_identical_bytecode() {
+            // Act
+            let res = compare_bytecode(&CODE_WITH_METADATA, &CODE_WITH_METADATA, false);
+
+            // Assert
+            assert_eq!(
+                res,
+                BytecodeVerification {
+                    matches: true,
+                    first_mismatch_offset: None,
+                    deployed_length: 16,
+                    expected_length: 16,
+                }
+            );
+        }
+
+        #[test]
+        fn should_report_the_first_differing_byte() {
+            // Setup
+            let test_cases: [(&[u8], &[u8], usize); 3] = [
+                (&[0x60, 0x2a, 0x00], &[0x60, 0x2b, 0x00], 1),
+                (&[0x60, 0x2a], &[0x60, 0x2a, 0x00], 2),
+                (&[], &[0x60], 0),
+            ];
+
+            for (deployed, expected, expected_offset) in test_cases {
+                // Act
+                let res = compare_bytecode(deployed, expected, false);
+
+                // Assert
+                assert!(!res.matches);
+                assert_eq!(res.first_mismatch_offset, Some(expected_offset));
+            }
+        }
+
+        #[test]
+        fn should_ignore_the_trailing_metadata() {
+            // Arrange
+            let mut other_metadata = CODE_WITH_METADATA;
+            other_metadata[13] = 0x14;
+
+            // Act
+            let strict_res = compare_bytecode(&CODE_WITH_METADATA, &other_metadata, false);
+            let res = compare_bytecode(&CODE_WITH_METADATA, &other_metadata, true);
+
+            // Assert
+            assert_eq!(strict_res.first_mismatch_offset, Some(13));
+            assert!(res.matches);
+            assert_eq!(res.deployed_length, 4);
+        }
+
+        #[test]
+        fn should_compare_the_whole_bytecode_without_metadata() {
+            // Setup
+            let test_cases: [&[u8]; 3] = [
+                // Ends with PUSH1 0x02, no metadata
+                &[0x60, 0x2a, 0x60, 0x00, 0x52, 0x60, 0x02],
+                // The tail is not a cbor map
+                &[0x60, 0x2a, 0x00, 0x00, 0xa1, 0xa2, 0xa3, 0x00, 0x03],
+                // A cbor map without any solc key
+                &[0x60, 0x2a, 0xa1, 0x63, 0x66, 0x6f, 0x6f, 0xf5, 0x00, 0x06],
+            ];
+
+            for deployed in test_cases {
+                // Arrange
+                let mut expected = deployed.to_vec();
+                expected[0] = 0x61;
+
+                // Act
+                let res = compare_bytecode(deployed, &expected, true);
+
+                // Assert
+                assert!(!res.matches);
+                assert_eq!(res.first_mismatch_offset, Some(0));
+                assert_eq!(res.deployed_length, deployed.len());
+            }
+        }
+    }
+
+    mod verify_bytecode {
+        use ethers::types::Bytes;
+
+        use crate::cmd::{
+            helpers::test::{deploy_contract_helper, setup_test},
+            utils::verify_bytecode,
+        };
+
+        // Runtime code that returns 42
+        const RUNTIME_CODE: [u8; 10] = [0x60, 0x2a, 0x60, 0x00, 0x52, 0x60, 0x20, 0x60, 0x00, 0xf3];
+
+        // PUSH1 10, DUP1, PUSH1 11, PUSH1 0, CODECOPY, PUSH1 0, RETURN followed by the runtime code
+        fn init_code() -> Bytes {
+            let mut init_code = vec![
+                0x60, 0x0a, 0x80, 0x60, 0x0b, 0x60, 0x00, 0x39, 0x60, 0x00, 0xf3,
+            ];
+            init_code.extend_from_slice(&RUNTIME_CODE);
+
+            init_code.into()
+        }
+
+        #[tokio::test]
+        async fn should_verify_the_deployed_bytecode() -> anyhow::Result<()> {
+            // Arrange
+            let (node_provider, anvil) = setup_test().await?;
+
+            let deployer = *anvil.addresses().first().unwrap();
+            let contract = deploy_contract_helper(&node_provider, deployer, init_code()).await?;
+
+            // Act
+            let res = verify_bytecode(
+                &node_provider,
+                contract.into(),
+                RUNTIME_CODE.to_vec().into(),
+                false,
+                None,
+            )
+            .await;
+
+            // Assert
+            assert!(res.is_ok());
+            assert!(res.unwrap().matches);
+
+            Ok(())
+        }
+
+        #[tokio::test]
+        async fn should_not_verify_a_different_bytecode() -> anyhow::Result<()> {
+            // Arrange
+            let (node_provider, anvil) = setup_test().await?;
+
+            let deployer = *anvil.addresses().first().unwrap();
+            let contract = deploy_contract_helper(&node_provider, deployer, init_code()).await?;
+
+            let mut expected = RUNTIME_CODE;
+            expected[1] = 0x2b;
+
+            // Act
+            let res = verify_bytecode(
+                &node_provider,
+                contract.into(),
+                expected.to_vec().into(),
+                false,
+                None,
+            )
+            .await;
+
+            // Assert
+            assert!(res.is_ok());
+
+            let verification = res.unwrap();
+            assert!(!verification.matches);
+            assert_eq!(verification.first_mismatch_offset, Some(1));
+
+            Ok(())
+        }
+    }
+
+    mod decode_revert_reason {
+        use ethers::{
+            abi::{encode, Token},
+            types::U256,
+        };
+
+        use crate::cmd::utils::{decode_revert_reason, ERROR_STRING_SELECTOR, PANIC_SELECTOR};
+
+        fn with_selector(selector: [u8; 4], args: &[Token]) -> Vec<u8> {
+            [selector.to_vec(), encode(args)].concat()
+        }
+
+        #[test]
+        fn should_decode_the_revert_reason() {
+            // Setup
+            let test_cases = [
+                (
+                    with_selector(
+                        ERROR_STRING_SELECTOR,
+                        &[Token::String("Insufficient balance".into())],
+                    ),
+                    "Insufficient balance",
+                ),
+                (
+                    with_selector(PANIC_SELECTOR, &[Token::Uint(U256::from(0x11))]),
+                    "Panic(0x11)",
+                ),
+                (vec![0xde, 0xad, 0xbe, 0xef], "Custom error 0xdeadbeef"),
+                (vec![], "Reverted without a reason"),
+            ];
+
+            for (data, expected_res) in test_cases {
+                // Act
+                let res = decode_revert_reason(&data);
+
+                // Assert
+                assert_eq!(res, expected_res);
+            }
+        }
+    }
+
+    mod normalize_address {
+        use crate::cmd::utils::normalize_address;
+
+        const ADDRESS: &str = "0x7E5F4552091A69125d5DfCb7b8C2659029395Bdf";
+
+        #[test]
+        fn should_normalize_the_supported_address_forms() -> anyhow::Result<()> {
+            // Setup
+            let test_cases = [
+                ADDRESS.to_string(),
+                ADDRESS.to_lowercase(),
+                ADDRESS.to_uppercase().replace("0X", "0x"),
+                ADDRESS[2..].to_lowercase(),
+                format!("0x{}{}", "00".repeat(12), ADDRESS[2..].to_lowercase()),
+            ];
+
+            for value in test_cases {
+                // Act
+                let res = normalize_address(&value);
+
+                // Assert
+                assert_eq!(res?, ADDRESS, "{value}");
+            }
+
+            Ok(())
+        }
+
+        #[test]
+        fn should_reject_ambiguous_or_invalid_addresses() {
+            // Setup
+            let test_cases = [
+                format!("0x{}{}", "01".repeat(12), &ADDRESS[2..]),
+                ADDRESS.replace('E', "e"),
+                ADDRESS[..40].to_string(),
+                "0xnot-an-address".to_string(),
+            ];
+
+            for value in test_cases {
+                // Act
+                let res = normalize_address(&value);
+
+                // Assert
+                assert!(res.is_err(), "{value}");
+            }
+        }
+    }
+
+    mod checksum_address {
+        use crate::cmd::utils::checksum_address;
+
+        const ADDRESS: &str = "fB6916095ca1df60bB79Ce92cE3Ea74c37c5d359";
+
+        #[test]
+        fn should_checksum_the_address_in_any_case() -> anyhow::Result<()> {
+            // Setup
+            let test_cases = [
+                (ADDRESS.to_lowercase(), false),
+                (ADDRESS.to_uppercase(), false),
+                (ADDRESS.replace('B', "b"), false),
+                (ADDRESS.to_lowercase(), true),
+                (ADDRESS.to_string(), true),
+            ];
+
+            for (value, verify) in test_cases {
+                // Act
+                let res = checksum_address(&value, verify);
+
+                // Assert
+                assert_eq!(res?, format!("0x{ADDRESS}"), "{value}");
+            }
+
+            Ok(())
+        }
+
+        #[test]
+        fn should_reject_a_wrong_checksum_in_verify_mode() {
+            // Act
+            let res = checksum_address(&ADDRESS.replace('B', "b"), true);
+
+            // Assert
+            assert!(res.is_err_and(|err| err
+                .to_string()
+                .contains("fails the EIP-55 checksum validation")));
+        }
+    }
+
+    mod address_from_public_key {
+        use ethers::{
+            core::k256::elliptic_curve::sec1::ToEncodedPoint,
+            signers::{LocalWallet, Signer},
+            types::{Address, Bytes},
+        };
+
+        use crate::cmd::utils::address_from_public_key;
+
+        // Public key of the private key 0x01, i.e. the secp256k1 generator point
+        const PUBLIC_KEY_X: &str =
+            "79be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959f2815b16f81798";
+        const PUBLIC_KEY_Y: &str =
+            "483ada7726a3c4655da4fbfc0e1108a8fd17b448a68554199c47d08ffb10d4b8";
+        const ADDRESS: &str = "0x7E5F4552091A69125d5DfCb7b8C2659029395Bdf";
+
+        #[test]
+        fn should_derive_the_address_of_a_known_public_key() -> anyhow::Result<()> {
+            // Arrange
+            let expected_address: Address = ADDRESS.parse()?;
+
+            let public_keys = [
+                format!("0x04{PUBLIC_KEY_X}{PUBLIC_KEY_Y}"),
+                format!("0x02{PUBLIC_KEY_X}"),
+                format!("0x{PUBLIC_KEY_X}{PUBLIC_KEY_Y}"),
+            ];
+
+            for public_key in public_keys {
+                // Act
+                let res = address_from_public_key(public_key.parse()?);
+
+                // Assert
+                assert_eq!(res?, expected_address, "{public_key}");
+            }
+
+            Ok(())
+        }
+
+        #[test]
+        fn should_derive_the_address_of_a_wallet() -> anyhow::Result<()> {
+            // Arrange
+            let wallet = LocalWallet::new(&mut rand::thread_rng());
+            let public_key = wallet
+                .signer()
+                .verifying_key()
+                .to_encoded_point(true)
+                .as_bytes()
+                .to_vec();
+
+            // Act
+            let res = address_from_public_key(Bytes::from(public_key));
+
+            // Assert
+            assert_eq!(res?, wallet.address());
+
+            Ok(())
+        }
+
+        #[test]
+        fn should_not_derive_the_address_of_an_invalid_public_key() {
+            // Arrange
+            let public_keys = [
+                Bytes::from(vec![0x04; 65]),
+                Bytes::from(vec![0x02; 20]),
+                Bytes::new(),
+            ];
+
+            for public_key in public_keys {
+                // Act
+                let res = address_from_public_key(public_key);
+
+                // Assert
+                assert!(res.is_err());
+            }
+        }
+    }
+
+    mod signature_components {
+        use ethers::{
+            signers::{LocalWallet, Signer},
+            types::{Bytes, H256},
+        };
+
+        use crate::cmd::utils::{join_signature, split_signature, SignatureComponents};
+
+        const PRIV_KEY: &str = "ac0974bec39a17e36ba4a6b4d238ff944bacb478cbed5efcae784d7bf4f2ff80";
+
+        #[tokio::test]
+        async fn should_round_trip_a_signature() -> anyhow::Result<()> {
+            // Arrange
+            let wallet: LocalWallet = PRIV_KEY.parse()?;
+            let signature = wallet.sign_message("yaeth").await?;
+            let encoded: Bytes = signature.to_vec().into();
+
+            // Act
+            let components = split_signature(encoded.clone())?;
+            let res = join_signature(components.r, components.s, components.v);
+
+            // Assert
+            let mut r = H256::zero();
+            signature.r.to_big_endian(r.as_bytes_mut());
+            let mut s = H256::zero();
+            signature.s.to_big_endian(s.as_bytes_mut());
+
+            assert_eq!(
+                components,
+                SignatureComponents {
+                    r,
+                    s,
+                    v: signature.v
+                }
+            );
+            assert!(res.is_ok());
+            assert_eq!(res.unwrap(), encoded);
+
+            Ok(())
+        }
+
+        #[test]
+        fn should_normalize_both_v_encodings() -> anyhow::Result<()> {
+            // Arrange
+            let r = H256::repeat_byte(0x01);
+            let s = H256::repeat_byte(0x02);
+            let test_cases = [(0, 27), (1, 28), (27, 27), (28, 28)];
+
+            for (v, expected_v) in test_cases {
+                // Act
+                let joined = join_signature(r, s, v)?;
+                let mut encoded = joined.to_vec();
+                encoded[64] = v as u8;
+                let split = split_signature(encoded.into())?;
+
+                // Assert
+                assert_eq!(joined[64], expected_v as u8);
+                assert_eq!(
+                    split,
+                    SignatureComponents {
+                        r,
+                        s,
+                        v: expected_v
+                    }
+                );
+            }
+
+            Ok(())
+        }
+
+        #[test]
+        fn should_not_accept_invalid_signatures() {
+            // Act
+            let short_signature = split_signature(Bytes::from(vec![0; 64]));
+            let invalid_v = join_signature(H256::zero(), H256::zero(), 2);
+
+            // Assert
+            assert!(short_signature.is_err());
+            assert!(invalid_v.is_err());
+        }
+    }
 }
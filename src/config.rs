@@ -1,10 +1,35 @@
-use config::Config;
+use std::{sync::OnceLock, time::Duration};
+
+use config::{Config, Environment, Map};
+use reqwest::Client;
 use serde::Deserialize;
 
 #[derive(Deserialize, Debug)]
+#[serde(deny_unknown_fields)]
 pub struct CliConfig {
     priv_key: Option<String>,
+    #[serde(default)]
+    keystore: Option<String>,
+    #[serde(default)]
+    keystore_password: Option<String>,
+    #[serde(default)]
+    mnemonic: Option<String>,
+    #[serde(default)]
+    mnemonic_index: Option<u32>,
     rpc_url: String,
+    #[serde(default)]
+    ignore_tls_errors: bool,
+    max_block_range: u64,
+    #[serde(default)]
+    threads: Option<usize>,
+    #[serde(default)]
+    command_timeout: Option<u64>,
+    #[serde(default)]
+    timeout_secs: Option<u64>,
+    #[serde(default)]
+    max_retries: Option<u32>,
+    #[serde(skip)]
+    http_client: OnceLock<Client>,
 }
 
 impl CliConfig {
@@ -12,16 +37,100 @@ impl CliConfig {
         self.priv_key.clone()
     }
 
+    /// Path of the Web3 Secret Storage keystore holding the signing key.
+    pub fn keystore(&self) -> Option<&str> {
+        self.keystore.as_deref()
+    }
+
+    pub fn keystore_password(&self) -> Option<&str> {
+        self.keystore_password.as_deref()
+    }
+
+    /// Tells if the signing key is read from the keystore but no password was configured for it
+    /// by the flags, the environment or the config file.
+    pub fn is_missing_keystore_password(&self) -> bool {
+        self.keystore.is_some()
+            && self.keystore_password.is_none()
+            && self.priv_key.is_none()
+            && self.mnemonic.is_none()
+    }
+
+    /// Sets the password of the keystore, e.g. once prompted for.
+    pub fn with_keystore_password(mut self, password: String) -> Self {
+        self.keystore_password = Some(password);
+
+        self
+    }
+
+    /// BIP-39 mnemonic the signing key is derived from.
+    pub fn mnemonic(&self) -> Option<&str> {
+        self.mnemonic.as_deref()
+    }
+
+    /// Index of the account derived from the mnemonic at `m/44'/60'/0'/0/{index}`.
+    pub fn mnemonic_index(&self) -> u32 {
+        self.mnemonic_index.unwrap_or_default()
+    }
+
     pub fn rpc_url(&self) -> &str {
         self.rpc_url.as_str()
     }
+
+    pub fn ignore_tls_errors(&self) -> bool {
+        self.ignore_tls_errors
+    }
+
+    pub fn max_block_range(&self) -> u64 {
+        self.max_block_range
+    }
+
+    pub fn threads(&self) -> Option<usize> {
+        self.threads
+    }
+
+    /// Time limit overriding the default one of each command.
+    pub fn command_timeout(&self) -> Option<Duration> {
+        self.command_timeout.map(Duration::from_secs)
+    }
+
+    /// Time limit of each request sent to the node.
+    pub fn timeout(&self) -> Option<Duration> {
+        self.timeout_secs.map(Duration::from_secs)
+    }
+
+    /// Returns the http client of this config, built with `build` on first use so that the node
+    /// providers created from the same config share it instead of building one each.
+    pub fn http_client<E>(&self, build: impl FnOnce() -> Result<Client, E>) -> Result<Client, E> {
+        if let Some(client) = self.http_client.get() {
+            return Ok(client.clone());
+        }
+
+        let client = build()?;
+
+        Ok(self.http_client.get_or_init(|| client).clone())
+    }
+
+    /// Number of times a rate limited or failed request is retried.
+    pub fn max_retries(&self) -> Option<u32> {
+        self.max_retries
+    }
 }
 
 #[derive(Default)]
 pub struct ConfigOverrides {
     priv_key: Option<String>,
+    keystore: Option<String>,
+    keystore_password: Option<String>,
+    mnemonic: Option<String>,
+    mnemonic_index: Option<u32>,
     rpc_url: Option<String>,
     config_file: Option<String>,
+    ignore_tls_errors: bool,
+    max_block_range: Option<u64>,
+    threads: Option<usize>,
+    command_timeout: Option<u64>,
+    timeout_secs: Option<u64>,
+    max_retries: Option<u32>,
 }
 
 impl ConfigOverrides {
@@ -33,17 +142,133 @@ impl ConfigOverrides {
         Self {
             config_file,
             priv_key,
+            keystore: None,
+            keystore_password: None,
+            mnemonic: None,
+            mnemonic_index: None,
             rpc_url,
+            ignore_tls_errors: false,
+            max_block_range: None,
+            threads: None,
+            command_timeout: None,
+            timeout_secs: None,
+            max_retries: None,
         }
     }
+
+    pub fn with_keystore(mut self, keystore: Option<String>) -> Self {
+        self.keystore = keystore;
+
+        self
+    }
+
+    pub fn with_keystore_password(mut self, keystore_password: Option<String>) -> Self {
+        self.keystore_password = keystore_password;
+
+        self
+    }
+
+    pub fn with_mnemonic(mut self, mnemonic: Option<String>) -> Self {
+        self.mnemonic = mnemonic;
+
+        self
+    }
+
+    pub fn with_mnemonic_index(mut self, mnemonic_index: Option<u32>) -> Self {
+        self.mnemonic_index = mnemonic_index;
+
+        self
+    }
+
+    pub fn with_ignore_tls_errors(mut self, ignore_tls_errors: bool) -> Self {
+        self.ignore_tls_errors = ignore_tls_errors;
+
+        self
+    }
+
+    pub fn with_max_block_range(mut self, max_block_range: Option<u64>) -> Self {
+        self.max_block_range = max_block_range;
+
+        self
+    }
+
+    pub fn with_threads(mut self, threads: Option<usize>) -> Self {
+        self.threads = threads;
+
+        self
+    }
+
+    pub fn with_command_timeout(mut self, command_timeout: Option<u64>) -> Self {
+        self.command_timeout = command_timeout;
+
+        self
+    }
+
+    pub fn with_timeout_secs(mut self, timeout_secs: Option<u64>) -> Self {
+        self.timeout_secs = timeout_secs;
+
+        self
+    }
+
+    pub fn with_max_retries(mut self, max_retries: Option<u32>) -> Self {
+        self.max_retries = max_retries;
+
+        self
+    }
 }
 
 const DEFAULT_RPC_URL: &str = "http://localhost:8545";
+const DEFAULT_MAX_BLOCK_RANGE: u64 = 10_000;
+
+/// Prefix of the environment variables holding config values, e.g. `YAETH_RPC_URL`.
+const ENV_PREFIX: &str = "YAETH";
+
+/// Keys of the config values that can be set through the environment. Other variables sharing
+/// the prefix (e.g. `YAETH_LOG`) are ignored instead of being rejected as unknown config keys.
+const ENV_CONFIG_KEYS: [&str; 12] = [
+    "priv_key",
+    "keystore",
+    "keystore_password",
+    "mnemonic",
+    "mnemonic_index",
+    "rpc_url",
+    "ignore_tls_errors",
+    "max_block_range",
+    "threads",
+    "command_timeout",
+    "timeout_secs",
+    "max_retries",
+];
 
 pub fn get_config(overrides: ConfigOverrides) -> Result<CliConfig, config::ConfigError> {
+    get_config_with_environment(overrides, std::env::vars())
+}
+
+/// Keeps the environment variables holding a config value.
+fn config_environment(vars: impl IntoIterator<Item = (String, String)>) -> Environment {
+    let vars: Map<String, String> = vars
+        .into_iter()
+        .filter(|(name, _)| {
+            name.strip_prefix(ENV_PREFIX)
+                .and_then(|key| key.strip_prefix('_'))
+                .is_some_and(|key| ENV_CONFIG_KEYS.contains(&key.to_lowercase().as_str()))
+        })
+        .collect();
+
+    Environment::with_prefix(ENV_PREFIX).source(Some(vars))
+}
+
+/// Layers the config values by increasing precedence: defaults, config file, environment
+/// variables and cli flags.
+fn get_config_with_environment(
+    overrides: ConfigOverrides,
+    env_vars: impl IntoIterator<Item = (String, String)>,
+) -> Result<CliConfig, config::ConfigError> {
+    let environment = config_environment(env_vars);
     let mut builder = Config::builder();
 
     builder = builder.set_default("rpc_url", DEFAULT_RPC_URL)?;
+    builder = builder.set_default("max_block_range", DEFAULT_MAX_BLOCK_RANGE)?;
 
     if let Some(config_file) = overrides.config_file {
         let base_path = std::env::current_dir().expect("Failed to determine the current directory");
@@ -51,14 +276,56 @@ pub fn get_config(overrides: ConfigOverrides) -> Result<CliConfig, config::Confi
         builder = builder.add_source(config::File::from(base_path.join(config_file)));
     }
 
+    builder = builder.add_source(environment.ignore_empty(true));
+
     if let Some(priv_key) = overrides.priv_key {
         builder = builder.set_override("priv_key", priv_key)?;
     }
 
+    if let Some(keystore) = overrides.keystore {
+        builder = builder.set_override("keystore", keystore)?;
+    }
+
+    if let Some(keystore_password) = overrides.keystore_password {
+        builder = builder.set_override("keystore_password", keystore_password)?;
+    }
+
+    if let Some(mnemonic) = overrides.mnemonic {
+        builder = builder.set_override("mnemonic", mnemonic)?;
+    }
+
+    if let Some(mnemonic_index) = overrides.mnemonic_index {
+        builder = builder.set_override("mnemonic_index", mnemonic_index)?;
+    }
+
     if let Some(rpc_url) = overrides.rpc_url {
         builder = builder.set_override("rpc_url", rpc_url)?;
     }
 
+    if let Some(max_block_range) = overrides.max_block_range {
+        builder = builder.set_override("max_block_range", max_block_range)?;
+    }
+
+    if let Some(threads) = overrides.threads {
+        builder = builder.set_override("threads", threads as u64)?;
+    }
+
+    if let Some(command_timeout) = overrides.command_timeout {
+        builder = builder.set_override("command_timeout", command_timeout)?;
+    }
+
+    if let Some(timeout_secs) = overrides.timeout_secs {
+        builder = builder.set_override("timeout_secs", timeout_secs)?;
+    }
+
+    if let Some(max_retries) = overrides.max_retries {
+        builder = builder.set_override("max_retries", max_retries)?;
+    }
+
+    if overrides.ignore_tls_errors {
+        builder = builder.set_override("ignore_tls_errors", true)?;
+    }
+
     let cli_config = builder.build()?;
 
     cli_config.try_deserialize::<CliConfig>()
@@ -66,14 +333,22 @@ pub fn get_config(overrides: ConfigOverrides) -> Result<CliConfig, config::Confi
 
 #[cfg(test)]
 mod tests {
-    use super::{get_config, ConfigOverrides};
-    use crate::config::DEFAULT_RPC_URL;
+    use super::{get_config, get_config_with_environment, ConfigOverrides};
+    use crate::config::{DEFAULT_MAX_BLOCK_RANGE, DEFAULT_RPC_URL};
     use ethers::{core::rand::thread_rng, prelude::k256::ecdsa::SigningKey};
 
     const TEST_CONFIG_FILES_BASE_PATH: &str = "tests/config/";
     const FILE_CONFIG_PRIV_KEY: &str =
         "0xac0974bec39a17e36ba4a6b4d238ff944bacb478cbed5efcae784d7bf4f2ff80";
     const FILE_CONFIG_RPC_URL: &str = "https://eth-mainnet.g.alchemy.com/v2/someapikey";
+    const ENV_CONFIG_RPC_URL: &str = "https://eth-mainnet.g.alchemy.com/v2/someenvapikey";
+
+    // Reading a map instead of the process environment keeps parallel tests isolated
+    fn environment(vars: &[(&str, &str)]) -> Vec<(String, String)> {
+        vars.iter()
+            .map(|(name, value)| (name.to_string(), value.to_string()))
+            .collect()
+    }
 
     #[test]
     fn should_use_the_default_config_values_if_no_other_values_are_provided() {
@@ -87,7 +362,78 @@ mod tests {
         let res = res.unwrap();
 
         assert!(res.priv_key.is_none());
+        assert!(res.keystore.is_none());
+        assert!(res.keystore_password.is_none());
+        assert!(res.mnemonic.is_none());
+        assert_eq!(res.mnemonic_index(), 0);
         assert_eq!(res.rpc_url, DEFAULT_RPC_URL);
+        assert_eq!(res.max_block_range, DEFAULT_MAX_BLOCK_RANGE);
+        assert!(res.threads.is_none());
+        assert!(res.command_timeout.is_none());
+        assert!(res.timeout_secs.is_none());
+        assert!(res.max_retries.is_none());
+    }
+
+    #[test]
+    fn should_override_the_max_block_range() -> anyhow::Result<()> {
+        // Arrange
+        let overrides = ConfigOverrides::default().with_max_block_range(Some(50));
+
+        // Act
+        let res = get_config(overrides)?;
+
+        // Assert
+        assert_eq!(res.max_block_range(), 50);
+
+        Ok(())
+    }
+
+    #[test]
+    fn should_override_the_threads() -> anyhow::Result<()> {
+        // Arrange
+        let overrides = ConfigOverrides::default().with_threads(Some(4));
+
+        // Act
+        let res = get_config(overrides)?;
+
+        // Assert
+        assert_eq!(res.threads(), Some(4));
+
+        Ok(())
+    }
+
+    #[test]
+    fn should_override_the_command_timeout() -> anyhow::Result<()> {
+        // Arrange
+        let overrides = ConfigOverrides::default().with_command_timeout(Some(5));
+
+        // Act
+        let res = get_config(overrides)?;
+
+        // Assert
+        assert_eq!(
+            res.command_timeout(),
+            Some(std::time::Duration::from_secs(5))
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn should_override_the_request_timeout_and_retries() -> anyhow::Result<()> {
+        // Arrange
+        let overrides = ConfigOverrides::default()
+            .with_timeout_secs(Some(10))
+            .with_max_retries(Some(3));
+
+        // Act
+        let res = get_config(overrides)?;
+
+        // Assert
+        assert_eq!(res.timeout(), Some(std::time::Duration::from_secs(10)));
+        assert_eq!(res.max_retries(), Some(3));
+
+        Ok(())
     }
 
     #[test]
@@ -138,6 +484,174 @@ mod tests {
         assert_eq!(res.rpc_url, expected_rpc_url);
     }
 
+    #[test]
+    fn should_read_the_config_values_from_the_environment() -> anyhow::Result<()> {
+        // Arrange
+        let expected_priv_key = hex::encode(SigningKey::random(&mut thread_rng()).to_bytes());
+        let environment = environment(&[
+            ("YAETH_PRIV_KEY", &expected_priv_key),
+            ("YAETH_RPC_URL", ENV_CONFIG_RPC_URL),
+            ("YAETH_MAX_BLOCK_RANGE", "50"),
+            ("YAETH_IGNORE_TLS_ERRORS", "true"),
+            ("OTHER_RPC_URL", "http://localhost:1234"),
+        ]);
+
+        // Act
+        let res = get_config_with_environment(ConfigOverrides::default(), environment)?;
+
+        // Assert
+        assert_eq!(res.priv_key(), Some(expected_priv_key));
+        assert_eq!(res.rpc_url(), ENV_CONFIG_RPC_URL);
+        assert_eq!(res.max_block_range(), 50);
+        assert!(res.ignore_tls_errors());
+
+        Ok(())
+    }
+
+    #[test]
+    fn should_prefer_the_environment_over_the_file_and_the_flags_over_the_environment(
+    ) -> anyhow::Result<()> {
+        // Setup
+        let cli_rpc_url = "https://eth-mainnet.g.alchemy.com/v2/someotherapikey";
+        let test_cases = [(None, ENV_CONFIG_RPC_URL), (Some(cli_rpc_url), cli_rpc_url)];
+
+        for (rpc_url, expected_rpc_url) in test_cases {
+            // Arrange
+            let overrides = ConfigOverrides::new(
+                None,
+                rpc_url.map(String::from),
+                Some(format!("{TEST_CONFIG_FILES_BASE_PATH}config.json")),
+            );
+            let environment = environment(&[("YAETH_RPC_URL", ENV_CONFIG_RPC_URL)]);
+
+            // Act
+            let res = get_config_with_environment(overrides, environment)?;
+
+            // Assert
+            assert_eq!(res.rpc_url(), expected_rpc_url);
+            assert_eq!(res.priv_key().as_deref(), Some(FILE_CONFIG_PRIV_KEY));
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn should_tell_if_the_keystore_password_is_missing_from_the_resolved_config(
+    ) -> anyhow::Result<()> {
+        // Setup
+        let test_cases: [(&[(&str, &str)], bool); 4] = [
+            (&[("YAETH_KEYSTORE", "keystore.json")], true),
+            (
+                &[
+                    ("YAETH_KEYSTORE", "keystore.json"),
+                    ("YAETH_KEYSTORE_PASSWORD", "yaeth"),
+                ],
+                false,
+            ),
+            (
+                &[
+                    ("YAETH_KEYSTORE", "keystore.json"),
+                    ("YAETH_MNEMONIC", "test test test"),
+                ],
+                false,
+            ),
+            (&[], false),
+        ];
+
+        for (env_vars, expected_res) in test_cases {
+            // Arrange
+            let environment = environment(env_vars);
+
+            // Act
+            let res = get_config_with_environment(ConfigOverrides::default(), environment)?;
+
+            // Assert
+            assert_eq!(
+                res.is_missing_keystore_password(),
+                expected_res,
+                "{env_vars:?}"
+            );
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn should_not_miss_the_keystore_password_when_a_private_key_is_configured() -> anyhow::Result<()>
+    {
+        // Arrange
+        let overrides = ConfigOverrides::new(
+            None,
+            None,
+            Some(format!("{TEST_CONFIG_FILES_BASE_PATH}config.json")),
+        );
+        let environment = environment(&[("YAETH_KEYSTORE", "keystore.json")]);
+
+        // Act
+        let res = get_config_with_environment(overrides, environment)?;
+
+        // Assert
+        assert!(!res.is_missing_keystore_password());
+
+        Ok(())
+    }
+
+    #[test]
+    fn should_ignore_empty_environment_variables() -> anyhow::Result<()> {
+        // Arrange
+        let environment = environment(&[("YAETH_PRIV_KEY", ""), ("YAETH_RPC_URL", "")]);
+
+        // Act
+        let res = get_config_with_environment(ConfigOverrides::default(), environment)?;
+
+        // Assert
+        assert!(res.priv_key().is_none());
+        assert_eq!(res.rpc_url(), DEFAULT_RPC_URL);
+
+        Ok(())
+    }
+
+    #[test]
+    fn should_ignore_the_environment_variables_not_holding_a_config_value() -> anyhow::Result<()> {
+        // Arrange
+        let environment = environment(&[
+            ("YAETH_LOG", "debug"),
+            ("YAETH_", "1"),
+            ("YAETHRPC_URL", "http://localhost:1234"),
+            ("YAETH_RPC_URL", ENV_CONFIG_RPC_URL),
+        ]);
+
+        // Act
+        let res = get_config_with_environment(ConfigOverrides::default(), environment)?;
+
+        // Assert
+        assert_eq!(res.rpc_url(), ENV_CONFIG_RPC_URL);
+
+        Ok(())
+    }
+
+    #[test]
+    fn should_reject_unknown_config_keys() {
+        // Arrange
+        let overrides = ConfigOverrides::new(
+            None,
+            None,
+            Some(format!(
+                "{TEST_CONFIG_FILES_BASE_PATH}config_unknown_key.yaml"
+            )),
+        );
+
+        // Act
+        let res = get_config(overrides);
+
+        // Assert
+        assert!(res.is_err());
+
+        let err = res.unwrap_err().to_string();
+        assert!(err.contains("unknown field `rcp_url`"));
+        assert!(err.contains("rpc_url"));
+    }
+
     #[test]
     fn should_not_find_config_file() {
         // Arrange
@@ -1,32 +1,81 @@
-use crate::config::CliConfig;
+use crate::{
+    cmd::gas::FeeHistoryCache,
+    config::CliConfig,
+    run::{stdio_output, OutputCoordinator, StreamOutput},
+};
 use async_trait::async_trait;
 use ethers::{
     prelude::{
         k256::ecdsa::SigningKey, signer::SignerMiddlewareError, Middleware, SignerMiddleware,
     },
-    providers::{Http, MiddlewareError, PendingTransaction, Provider, ProviderError},
-    signers::{LocalWallet, Wallet},
-    types::{transaction::eip2718::TypedTransaction, Address, BlockId, Signature, U256},
+    providers::{
+        Http, HttpRateLimitRetryPolicy, JsonRpcClient, JsonRpcError, MiddlewareError,
+        PendingTransaction, Provider, ProviderError, PubsubClient, RetryClient, RetryClientBuilder,
+        RetryClientError, RpcError, Ws, WsClientError,
+    },
+    signers::{coins_bip39::English, LocalWallet, MnemonicBuilder, Signer, Wallet},
+    types::{
+        transaction::{eip2718::TypedTransaction, eip712::TypedData},
+        Address, BlockId, Bytes, GethDebugTracingOptions, Signature, Transaction, H256, U256, U64,
+    },
+    utils,
 };
-use std::future::Future;
+use futures::{channel::mpsc, stream, FutureExt, StreamExt};
+use reqwest::{Client, Url};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use serde_json::value::RawValue;
+use std::{future::Future, io::Write, panic::AssertUnwindSafe, sync::OnceLock, time::Duration};
 use thiserror::Error;
 use tokio::runtime;
 
+/// Maximum number of futures in flight when a command fans out without its own limit.
+pub const DEFAULT_CONCURRENCY: usize = 8;
+
 pub struct CommandExecutionContext {
     config: CliConfig,
     runtime: runtime::Runtime,
     node_provider: NodeProvider,
+    stream_output: StreamOutput,
+    fee_history_cache: Option<FeeHistoryCache>,
 }
 
 #[derive(Error, Debug)]
 pub enum ExecutionContextError {
     #[error("{0}")]
     ProviderConfigError(NodeProviderConfigError),
+
+    #[error("Failed to start the async runtime: {0}")]
+    RuntimeError(String),
+}
+
+/// Builds the runtime driving the commands. One-shot commands run on the current thread while
+/// long-running commands use a worker thread per core, so that concurrent streams don't starve.
+/// `threads` worker threads are used when requested.
+fn build_runtime(threads: Option<usize>, long_running: bool) -> std::io::Result<runtime::Runtime> {
+    match threads {
+        Some(threads) => runtime::Builder::new_multi_thread()
+            .worker_threads(threads.max(1))
+            .enable_all()
+            .build(),
+        None if long_running => runtime::Builder::new_multi_thread().enable_all().build(),
+        None => runtime::Builder::new_current_thread().enable_all().build(),
+    }
 }
 
 impl CommandExecutionContext {
+    /// Creates the context of a one-shot command.
     pub fn new(config: CliConfig) -> Result<Self, ExecutionContextError> {
-        let runtime = runtime::Runtime::new().unwrap();
+        Self::with_runtime(config, false)
+    }
+
+    /// Creates the context of a command that keeps running until interrupted.
+    pub fn new_long_running(config: CliConfig) -> Result<Self, ExecutionContextError> {
+        Self::with_runtime(config, true)
+    }
+
+    fn with_runtime(config: CliConfig, long_running: bool) -> Result<Self, ExecutionContextError> {
+        let runtime = build_runtime(config.threads(), long_running)
+            .map_err(|err| ExecutionContextError::RuntimeError(err.to_string()))?;
 
         let node_provider = runtime
             .block_on(NodeProvider::new(&config))
@@ -36,9 +85,25 @@ impl CommandExecutionContext {
             config,
             runtime,
             node_provider,
+            stream_output: StreamOutput::default(),
+            fee_history_cache: None,
         })
     }
 
+    /// Overrides where the streaming commands write their items
+    pub fn with_stream_output(mut self, stream_output: StreamOutput) -> Self {
+        self.stream_output = stream_output;
+
+        self
+    }
+
+    /// Reuses the fee history fetched by a command in the following ones with the same parameters
+    pub fn with_fee_history_cache(mut self, enabled: bool) -> Self {
+        self.fee_history_cache = enabled.then(FeeHistoryCache::default);
+
+        self
+    }
+
     pub fn execute<F>(&self, f: F) -> F::Output
     where
         F: Future,
@@ -46,6 +111,36 @@ impl CommandExecutionContext {
         self.runtime.block_on(f)
     }
 
+    /// Drives the provided futures with at most [`DEFAULT_CONCURRENCY`] of them in flight,
+    /// returning their results in order.
+    pub fn execute_many<I, F, T>(&self, futures: I) -> Vec<anyhow::Result<T>>
+    where
+        I: IntoIterator<Item = F>,
+        F: Future<Output = anyhow::Result<T>>,
+    {
+        self.execute_with_concurrency(futures, DEFAULT_CONCURRENCY)
+    }
+
+    /// Drives the provided futures with at most `limit` of them in flight, returning their
+    /// results in order. A panicking future is reported as an error without aborting the others.
+    pub fn execute_with_concurrency<I, F, T>(
+        &self,
+        futures: I,
+        limit: usize,
+    ) -> Vec<anyhow::Result<T>>
+    where
+        I: IntoIterator<Item = F>,
+        F: Future<Output = anyhow::Result<T>>,
+    {
+        let results = stream::iter(futures)
+            .map(|f| AssertUnwindSafe(f).catch_unwind())
+            .buffered(limit.max(1))
+            .map(|res| res.unwrap_or_else(|_| Err(anyhow::anyhow!("Task panicked"))))
+            .collect();
+
+        self.runtime.block_on(results)
+    }
+
     pub fn config(&self) -> &CliConfig {
         &self.config
     }
@@ -53,24 +148,332 @@ impl CommandExecutionContext {
     pub fn node_provider(&self) -> &NodeProvider {
         &self.node_provider
     }
+
+    pub fn stream_output(&self) -> &StreamOutput {
+        &self.stream_output
+    }
+
+    pub fn fee_history_cache(&self) -> Option<&FeeHistoryCache> {
+        self.fee_history_cache.as_ref()
+    }
+}
+
+static HTTP_CLIENT: OnceLock<Client> = OnceLock::new();
+
+/// Returns the http client shared by every provider in the process so that connections
+/// (and TLS sessions) are pooled instead of being established once per provider.
+fn shared_http_client() -> Client {
+    HTTP_CLIENT.get_or_init(Client::new).clone()
+}
+
+/// Returns the http client used to reach the provided url. Invalid certificates are only accepted
+/// for https urls when explicitly requested, in which case a warning is written to `warning_out`.
+/// Requests taking longer than `timeout` are aborted.
+fn http_client(
+    url: &Url,
+    ignore_tls_errors: bool,
+    timeout: Option<Duration>,
+    output: &OutputCoordinator<impl Write, impl Write>,
+) -> Result<Client, NodeProviderConfigError> {
+    let ignore_tls_errors = ignore_tls_errors && url.scheme() == "https";
+
+    if !ignore_tls_errors && timeout.is_none() {
+        return Ok(shared_http_client());
+    }
+
+    let mut builder = Client::builder();
+
+    if ignore_tls_errors {
+        // Failing to write the warning must not prevent the connection
+        let _ = output.progress(&format!(
+            "WARNING: TLS certificate verification is disabled for {url}. The connection is vulnerable to man-in-the-middle attacks."
+        ));
+
+        builder = builder.danger_accept_invalid_certs(true);
+    }
+
+    if let Some(timeout) = timeout {
+        builder = builder.timeout(timeout);
+    }
+
+    builder
+        .build()
+        .map_err(|err| NodeProviderConfigError::HttpClientError(err.to_string()))
+}
+
+/// Backoff before retrying a rate limited request, unless the node suggests a different one.
+const INITIAL_RETRY_BACKOFF: Duration = Duration::from_millis(500);
+
+/// Json rpc transport selected from the rpc url scheme. Http requests that are rate limited or
+/// fail because of connectivity issues are retried up to `max_retries` times.
+#[derive(Debug)]
+pub enum RpcTransport {
+    Http {
+        client: RetryClient<Http>,
+        max_retries: u32,
+        url: Url,
+        http_client: Client,
+    },
+    Ws(Ws),
+}
+
+impl RpcTransport {
+    async fn new(url: Url, config: &CliConfig) -> Result<Self, NodeProviderConfigError> {
+        match url.scheme() {
+            "ws" | "wss" => {
+                let ws = Ws::connect(url.as_str())
+                    .await
+                    .map_err(|err| NodeProviderConfigError::WsConnectionError(err.to_string()))?;
+
+                Ok(Self::Ws(ws))
+            }
+            _ => {
+                let http_client = config.http_client(|| {
+                    http_client(
+                        &url,
+                        config.ignore_tls_errors(),
+                        config.timeout(),
+                        stdio_output(),
+                    )
+                })?;
+
+                let max_retries = config.max_retries().unwrap_or_default();
+                let client = RetryClientBuilder::default()
+                    .rate_limit_retries(max_retries)
+                    .timeout_retries(max_retries)
+                    .initial_backoff(INITIAL_RETRY_BACKOFF)
+                    .build(
+                        Http::new_with_client(url.clone(), http_client.clone()),
+                        Box::<HttpRateLimitRetryPolicy>::default(),
+                    );
+
+                Ok(Self::Http {
+                    client,
+                    max_retries,
+                    url,
+                    http_client,
+                })
+            }
+        }
+    }
+
+    /// Sends a call of `method` for each of the provided params as a single json rpc batch,
+    /// returning the responses in the same order as the params.
+    pub async fn batch_request<T, R>(
+        &self,
+        method: &str,
+        params: &[T],
+    ) -> Result<Vec<Result<R, JsonRpcError>>, RpcTransportError>
+    where
+        T: Serialize,
+        R: DeserializeOwned,
+    {
+        let RpcTransport::Http {
+            url, http_client, ..
+        } = self
+        else {
+            return Err(RpcTransportError::BatchNotSupported);
+        };
+
+        let requests: Vec<_> = params
+            .iter()
+            .enumerate()
+            .map(|(id, params)| BatchRequest {
+                jsonrpc: "2.0",
+                id,
+                method,
+                params,
+            })
+            .collect();
+
+        let body = serde_json::to_vec(&requests)
+            .map_err(|err| RpcTransportError::BatchRequest(err.to_string()))?;
+
+        let res = http_client
+            .post(url.clone())
+            .header(reqwest::header::CONTENT_TYPE, "application/json")
+            .body(body)
+            .send()
+            .await
+            .map_err(|err| RpcTransportError::BatchRequest(err.to_string()))?
+            .bytes()
+            .await
+            .map_err(|err| RpcTransportError::BatchRequest(err.to_string()))?;
+
+        // Nodes without batch support usually answer with a single error object
+        let mut responses: Vec<BatchResponse<R>> =
+            serde_json::from_slice(&res).map_err(|_| RpcTransportError::BatchNotSupported)?;
+
+        if responses.len() != params.len() {
+            return Err(RpcTransportError::BatchRequest(format!(
+                "Expected {} responses, got {}",
+                params.len(),
+                responses.len()
+            )));
+        }
+
+        responses.sort_by_key(|res| res.id);
+
+        let mut results = Vec::with_capacity(responses.len());
+
+        for (idx, res) in responses.into_iter().enumerate() {
+            let result = match res {
+                BatchResponse { id, .. } if id != idx => {
+                    return Err(RpcTransportError::BatchRequest(format!(
+                        "Missing response for request {idx}"
+                    )))
+                }
+                BatchResponse {
+                    error: Some(err), ..
+                } => Err(err),
+                BatchResponse {
+                    result: Some(result),
+                    ..
+                } => Ok(result),
+                BatchResponse { id, .. } => {
+                    return Err(RpcTransportError::BatchRequest(format!(
+                        "Response {id} has neither a result nor an error"
+                    )))
+                }
+            };
+
+            results.push(result);
+        }
+
+        Ok(results)
+    }
+}
+
+#[derive(Serialize)]
+struct BatchRequest<'a, T> {
+    jsonrpc: &'static str,
+    id: usize,
+    method: &'a str,
+    params: &'a T,
+}
+
+#[derive(Deserialize)]
+struct BatchResponse<R> {
+    id: usize,
+    result: Option<R>,
+    error: Option<JsonRpcError>,
+}
+
+#[derive(Error, Debug)]
+pub enum RpcTransportError {
+    #[error("{0}")]
+    Http(ProviderError),
+
+    #[error(
+        "The node kept rate limiting the request after {0} retries. Raise the limit with --retries"
+    )]
+    RateLimited(u32),
+
+    #[error("{0}")]
+    Ws(WsClientError),
+
+    #[error("Subscriptions are only supported by websocket rpc endpoints")]
+    SubscriptionsNotSupported,
+
+    #[error("Batch requests are not supported by the rpc endpoint")]
+    BatchNotSupported,
+
+    #[error("Batch request failed: {0}")]
+    BatchRequest(String),
+}
+
+impl RpcError for RpcTransportError {
+    fn as_error_response(&self) -> Option<&JsonRpcError> {
+        match self {
+            RpcTransportError::Http(err) => RpcError::as_error_response(err),
+            RpcTransportError::Ws(err) => err.as_error_response(),
+            RpcTransportError::RateLimited(_)
+            | RpcTransportError::SubscriptionsNotSupported
+            | RpcTransportError::BatchNotSupported
+            | RpcTransportError::BatchRequest(_) => None,
+        }
+    }
+
+    fn as_serde_error(&self) -> Option<&serde_json::Error> {
+        match self {
+            RpcTransportError::Http(err) => RpcError::as_serde_error(err),
+            RpcTransportError::Ws(err) => err.as_serde_error(),
+            RpcTransportError::RateLimited(_)
+            | RpcTransportError::SubscriptionsNotSupported
+            | RpcTransportError::BatchNotSupported
+            | RpcTransportError::BatchRequest(_) => None,
+        }
+    }
+}
+
+impl From<RpcTransportError> for ProviderError {
+    fn from(err: RpcTransportError) -> Self {
+        ProviderError::JsonRpcClientError(Box::new(err))
+    }
+}
+
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+impl JsonRpcClient for RpcTransport {
+    type Error = RpcTransportError;
+
+    async fn request<T, R>(&self, method: &str, params: T) -> Result<R, Self::Error>
+    where
+        T: std::fmt::Debug + Serialize + Send + Sync,
+        R: DeserializeOwned + Send,
+    {
+        match self {
+            RpcTransport::Http {
+                client,
+                max_retries,
+                ..
+            } => client
+                .request(method, params)
+                .await
+                .map_err(|err| match err {
+                    RetryClientError::TimeoutError => RpcTransportError::RateLimited(*max_retries),
+                    err => RpcTransportError::Http(err.into()),
+                }),
+            RpcTransport::Ws(ws) => ws
+                .request(method, params)
+                .await
+                .map_err(RpcTransportError::Ws),
+        }
+    }
+}
+
+impl PubsubClient for RpcTransport {
+    type NotificationStream = mpsc::UnboundedReceiver<Box<RawValue>>;
+
+    fn subscribe<T: Into<U256>>(&self, id: T) -> Result<Self::NotificationStream, Self::Error> {
+        match self {
+            RpcTransport::Http { .. } => Err(RpcTransportError::SubscriptionsNotSupported),
+            RpcTransport::Ws(ws) => ws.subscribe(id).map_err(RpcTransportError::Ws),
+        }
+    }
+
+    fn unsubscribe<T: Into<U256>>(&self, id: T) -> Result<(), Self::Error> {
+        match self {
+            RpcTransport::Http { .. } => Err(RpcTransportError::SubscriptionsNotSupported),
+            RpcTransport::Ws(ws) => ws.unsubscribe(id).map_err(RpcTransportError::Ws),
+        }
+    }
 }
 
 #[derive(Debug)]
 pub enum NodeProvider {
-    Provider(Provider<Http>),
-    ProviderWithSigner(SignerMiddleware<Provider<Http>, Wallet<SigningKey>>),
+    Provider(Provider<RpcTransport>),
+    ProviderWithSigner(SignerMiddleware<Provider<RpcTransport>, Wallet<SigningKey>>),
 }
 
 impl NodeProvider {
     pub async fn new(config: &CliConfig) -> Result<Self, NodeProviderConfigError> {
-        let provider = Provider::try_from(config.rpc_url())
+        let url = Url::parse(config.rpc_url())
             .map_err(|err| NodeProviderConfigError::InvalidProviderUrl(err.to_string()))?;
 
-        let provider = if let Some(priv_key) = config.priv_key() {
-            let signer = priv_key
-                .parse::<LocalWallet>()
-                .map_err(|err| NodeProviderConfigError::InvalidPrivateKey(err.to_string()))?;
+        let provider = Provider::new(RpcTransport::new(url, config).await?);
 
+        let provider = if let Some(signer) = load_signer(config)? {
             let signer_middleware = SignerMiddleware::new_with_provider_chain(provider, signer)
                 .await
                 .map_err(|err| NodeProviderConfigError::ProviderWithSignerError(err.to_string()))?;
@@ -83,6 +486,11 @@ impl NodeProvider {
         Ok(provider)
     }
 
+    /// Tells if the node can push notifications through `eth_subscribe`.
+    pub fn supports_subscriptions(&self) -> bool {
+        matches!(self.inner().as_ref(), RpcTransport::Ws(_))
+    }
+
     /// Returns the current max priority fee per gas in wei.
     pub async fn get_max_priority_fee_per_gas(&self) -> anyhow::Result<U256> {
         let res = self.inner().request("eth_maxPriorityFeePerGas", ()).await?;
@@ -90,6 +498,75 @@ impl NodeProvider {
         Ok(res)
     }
 
+    /// Returns the trace of the transaction execution produced by the tracer selected in the
+    /// options.
+    pub async fn get_transaction_trace<R>(
+        &self,
+        hash: H256,
+        options: GethDebugTracingOptions,
+    ) -> anyhow::Result<R>
+    where
+        R: std::fmt::Debug + Serialize + DeserializeOwned + Send,
+    {
+        let res = self
+            .inner()
+            .request("debug_traceTransaction", (hash, options))
+            .await?;
+
+        Ok(res)
+    }
+
+    /// Returns the transaction at the provided index of the block with the provided identifier.
+    pub async fn get_transaction_by_block_and_index(
+        &self,
+        block_id: BlockId,
+        idx: U64,
+    ) -> anyhow::Result<Option<Transaction>> {
+        let idx = utils::serialize(&idx);
+
+        let res = match block_id {
+            BlockId::Hash(hash) => {
+                self.inner()
+                    .request(
+                        "eth_getTransactionByBlockHashAndIndex",
+                        [utils::serialize(&hash), idx],
+                    )
+                    .await?
+            }
+            BlockId::Number(number) => {
+                self.inner()
+                    .request(
+                        "eth_getTransactionByBlockNumberAndIndex",
+                        [utils::serialize(&number), idx],
+                    )
+                    .await?
+            }
+        };
+
+        Ok(res)
+    }
+
+    /// Signs the EIP-712 typed data with the configured private key if it belongs to `from`,
+    /// otherwise asks the node to sign it with one of its accounts.
+    pub async fn sign_typed_data(
+        &self,
+        data: &TypedData,
+        from: Address,
+    ) -> anyhow::Result<Signature> {
+        if let NodeProvider::ProviderWithSigner(signer_provider) = self {
+            if signer_provider.signer().address() == from {
+                return Ok(signer_provider.signer().sign_typed_data(data).await?);
+            }
+        }
+
+        let signature: Bytes = self
+            .inner()
+            .request("eth_signTypedData_v4", (from, data))
+            .await?;
+
+        Ok(Signature::try_from(signature.as_ref())?)
+    }
+
     /// Returns the current ethereum protocol version.
     pub async fn get_protocol_version(&self) -> anyhow::Result<U256> {
         let res = self.inner().request("eth_protocolVersion", ()).await?;
@@ -98,16 +575,76 @@ impl NodeProvider {
     }
 }
 
+/// Loads the signing key from the first configured source: an explicit private key, then a
+/// mnemonic, then a keystore. Without any of them the provider can't sign. A private key and a
+/// mnemonic are rejected together as they would likely select different accounts.
+fn load_signer(config: &CliConfig) -> Result<Option<LocalWallet>, NodeProviderConfigError> {
+    if config.priv_key().is_some() && config.mnemonic().is_some() {
+        return Err(NodeProviderConfigError::ConflictingSigners);
+    }
+
+    if let Some(priv_key) = config.priv_key() {
+        return priv_key
+            .parse::<LocalWallet>()
+            .map(Some)
+            // The parsing errors can quote the key, so they are not reported
+            .map_err(|_| NodeProviderConfigError::InvalidPrivateKey);
+    }
+
+    if let Some(mnemonic) = config.mnemonic() {
+        return MnemonicBuilder::<English>::default()
+            .phrase(mnemonic)
+            .index(config.mnemonic_index())
+            .and_then(|builder| builder.build())
+            .map(Some)
+            .map_err(|_| NodeProviderConfigError::InvalidMnemonic);
+    }
+
+    let Some(keystore) = config.keystore() else {
+        return Ok(None);
+    };
+
+    let password = config
+        .keystore_password()
+        .ok_or(NodeProviderConfigError::MissingKeystorePassword)?;
+
+    LocalWallet::decrypt_keystore(keystore, password)
+        .map(Some)
+        .map_err(|err| {
+            NodeProviderConfigError::InvalidKeystore(keystore.to_string(), err.to_string())
+        })
+}
+
 #[derive(Error, Debug)]
 pub enum NodeProviderConfigError {
     #[error("{0}")]
     InvalidProviderUrl(String),
 
-    #[error("{0}")]
-    InvalidPrivateKey(String),
+    #[error("Invalid private key format. Expected a 32 bytes hex encoded key")]
+    InvalidPrivateKey,
+
+    #[error("Invalid mnemonic. Expected a BIP-39 english phrase of 12, 15, 18, 21 or 24 words")]
+    InvalidMnemonic,
+
+    #[error(
+        "Provided both a private key and a mnemonic. Only one signer source must be provided."
+    )]
+    ConflictingSigners,
+
+    #[error("Missing keystore password. Provide it with --keystore-password")]
+    MissingKeystorePassword,
+
+    #[error("Failed to decrypt the keystore {0}: {1}")]
+    InvalidKeystore(String, String),
 
     #[error("{0}")]
     ProviderWithSignerError(String),
+
+    #[error("{0}")]
+    HttpClientError(String),
+
+    #[error("Failed to connect to the websocket endpoint: {0}")]
+    WsConnectionError(String),
 }
 
 #[derive(Error, Debug)]
@@ -116,7 +653,16 @@ pub enum NodeProviderError {
     ProviderError(ProviderError),
 
     #[error("{0}")]
-    ProviderWithSignerError(SignerMiddlewareError<Provider<Http>, Wallet<SigningKey>>),
+    ProviderWithSignerError(SignerMiddlewareError<Provider<RpcTransport>, Wallet<SigningKey>>),
+}
+
+impl NodeProviderError {
+    /// Returns the data returned by the contract if the node rejected the request because the
+    /// execution reverted.
+    pub fn revert_data(&self) -> Option<Bytes> {
+        self.as_error_response()
+            .and_then(JsonRpcError::as_revert_data)
+    }
 }
 
 impl MiddlewareError for NodeProviderError {
@@ -129,7 +675,7 @@ impl MiddlewareError for NodeProviderError {
     fn as_inner(&self) -> Option<&Self::Inner> {
         match self {
             NodeProviderError::ProviderError(err) => Some(err),
-            _ => None,
+            NodeProviderError::ProviderWithSignerError(err) => err.as_inner(),
         }
     }
 }
@@ -137,12 +683,13 @@ impl MiddlewareError for NodeProviderError {
 // Config taken from the trait impl from https://github.com/gakonst/ethers-rs
 #[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
 #[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+#[allow(mismatched_lifetime_syntaxes)]
 impl Middleware for NodeProvider {
     type Error = NodeProviderError;
 
-    type Provider = Http;
+    type Provider = RpcTransport;
 
-    type Inner = Provider<Http>;
+    type Inner = Provider<RpcTransport>;
 
     fn inner(&self) -> &Self::Inner {
         match self {
@@ -151,11 +698,18 @@ impl Middleware for NodeProvider {
         }
     }
 
+    fn default_sender(&self) -> Option<Address> {
+        match self {
+            NodeProvider::Provider(provider) => provider.default_sender(),
+            NodeProvider::ProviderWithSigner(signer_provider) => signer_provider.default_sender(),
+        }
+    }
+
     async fn send_transaction<T: Into<TypedTransaction> + Send + Sync>(
         &self,
         tx: T,
         block: Option<BlockId>,
-    ) -> Result<PendingTransaction<'_, Http>, Self::Error> {
+    ) -> Result<PendingTransaction<'_, RpcTransport>, Self::Error> {
         match self {
             NodeProvider::Provider(provider) => provider
                 .send_transaction(tx, block)
@@ -185,3 +739,656 @@ impl Middleware for NodeProvider {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        sync::atomic::{AtomicUsize, Ordering},
+        time::{Duration, Instant},
+    };
+
+    use ethers::{
+        providers::Middleware,
+        signers::{LocalWallet, Signer},
+        types::{
+            transaction::eip712::{Eip712, TypedData},
+            H256,
+        },
+    };
+    use futures::{future::LocalBoxFuture, FutureExt};
+    use serde_json::json;
+    use tokio::runtime::RuntimeFlavor;
+
+    use crate::{
+        cmd::helpers::test::{spawn_mock_rpc_server, spawn_mock_tls_rpc_server},
+        config::{get_config, ConfigOverrides},
+        context::{CommandExecutionContext, NodeProvider, DEFAULT_CONCURRENCY},
+    };
+
+    const MOCK_LATENCY: Duration = Duration::from_millis(200);
+
+    mod http_client {
+        use reqwest::Url;
+
+        use crate::{
+            config::{get_config, ConfigOverrides},
+            context::{http_client, NodeProvider},
+            run::OutputCoordinator,
+        };
+
+        const HTTPS_URL: &str = "https://localhost:8545";
+
+        #[test]
+        fn should_build_an_insecure_client_and_warn_for_https_urls() -> anyhow::Result<()> {
+            // Arrange
+            let url = Url::parse(HTTPS_URL)?;
+            let output = OutputCoordinator::new(Vec::new(), Vec::new());
+
+            // Act
+            let res = http_client(&url, true, None, &output);
+
+            // Assert
+            assert!(res.is_ok());
+
+            let (_, warning_out) = output.into_inner();
+            let warning = String::from_utf8(warning_out)?;
+            assert!(warning.contains("TLS certificate verification is disabled"));
+
+            Ok(())
+        }
+
+        #[test]
+        fn should_not_warn_without_the_flag_or_for_http_urls() -> anyhow::Result<()> {
+            // Setup
+            let test_cases = [(HTTPS_URL, false), ("http://localhost:8545", true)];
+
+            for (url, ignore_tls_errors) in test_cases {
+                // Arrange
+                let url = Url::parse(url)?;
+                let output = OutputCoordinator::new(Vec::new(), Vec::new());
+
+                // Act
+                let res = http_client(&url, ignore_tls_errors, None, &output);
+
+                // Assert
+                assert!(res.is_ok());
+
+                let (out, warning_out) = output.into_inner();
+                assert!(out.is_empty());
+                assert!(warning_out.is_empty());
+            }
+
+            Ok(())
+        }
+
+        #[tokio::test]
+        async fn should_create_a_provider_ignoring_tls_errors() -> anyhow::Result<()> {
+            // Arrange
+            let config = get_config(
+                ConfigOverrides::new(None, Some(HTTPS_URL.into()), None)
+                    .with_ignore_tls_errors(true),
+            )?;
+
+            // Act
+            let res = NodeProvider::new(&config).await;
+
+            // Assert
+            assert!(res.is_ok());
+            assert!(config.ignore_tls_errors());
+
+            Ok(())
+        }
+    }
+
+    mod rpc_transport {
+        use std::time::Duration;
+
+        use ethers::{providers::Middleware, utils::Anvil};
+        use serde_json::json;
+
+        use crate::{
+            cmd::helpers::test::{spawn_mock_rpc_error_server, spawn_mock_rpc_server},
+            config::{get_config, ConfigOverrides},
+            context::{NodeProvider, NodeProviderConfigError, RpcTransport},
+        };
+
+        #[tokio::test]
+        async fn should_use_a_websocket_transport_for_ws_urls() -> anyhow::Result<()> {
+            // Arrange
+            let anvil = Anvil::new().spawn();
+
+            let config = get_config(ConfigOverrides::new(None, Some(anvil.ws_endpoint()), None))?;
+
+            // Act
+            let node_provider = NodeProvider::new(&config).await?;
+            let res = node_provider.get_chainid().await;
+
+            // Assert
+            assert!(matches!(
+                node_provider.inner().as_ref(),
+                RpcTransport::Ws(_)
+            ));
+            assert!(res.is_ok());
+            assert_eq!(res.unwrap(), anvil.chain_id().into());
+
+            Ok(())
+        }
+
+        #[tokio::test]
+        async fn should_fail_if_the_websocket_endpoint_is_unreachable() -> anyhow::Result<()> {
+            // Arrange
+            let config = get_config(ConfigOverrides::new(
+                None,
+                Some("ws://127.0.0.1:1".into()),
+                None,
+            ))?;
+
+            // Act
+            let res = NodeProvider::new(&config).await;
+
+            // Assert
+            assert!(matches!(
+                res,
+                Err(NodeProviderConfigError::WsConnectionError(_))
+            ));
+
+            Ok(())
+        }
+
+        #[tokio::test]
+        async fn should_give_up_on_rate_limited_requests_after_the_max_retries(
+        ) -> anyhow::Result<()> {
+            // Arrange
+            let url = spawn_mock_rpc_error_server(json!({
+                "code": 429,
+                "message": "Too Many Requests",
+                "data": { "rate": { "backoff_seconds": 0 } },
+            }));
+
+            let config =
+                get_config(ConfigOverrides::new(None, Some(url), None).with_max_retries(Some(2)))?;
+            let node_provider = NodeProvider::new(&config).await?;
+
+            // Act
+            let res = node_provider.get_chainid().await;
+
+            // Assert
+            assert_eq!(
+                res.unwrap_err().to_string(),
+                "The node kept rate limiting the request after 2 retries. Raise the limit with --retries"
+            );
+
+            Ok(())
+        }
+
+        #[tokio::test]
+        async fn should_abort_requests_exceeding_the_timeout() -> anyhow::Result<()> {
+            // Arrange
+            let (url, _) = spawn_mock_rpc_server(json!("0x7a69"), Duration::from_secs(3));
+
+            let config =
+                get_config(ConfigOverrides::new(None, Some(url), None).with_timeout_secs(Some(1)))?;
+            let node_provider = NodeProvider::new(&config).await?;
+
+            // Act
+            let res = node_provider.get_chainid().await;
+
+            // Assert
+            assert!(res.unwrap_err().to_string().contains("timed out"));
+
+            Ok(())
+        }
+
+        #[tokio::test]
+        async fn should_use_an_http_transport_for_http_urls() -> anyhow::Result<()> {
+            // Arrange
+            let config = get_config(ConfigOverrides::default())?;
+
+            // Act
+            let res = NodeProvider::new(&config).await;
+
+            // Assert
+            assert!(res.is_ok());
+            assert!(matches!(
+                res.unwrap().inner().as_ref(),
+                RpcTransport::Http { .. }
+            ));
+
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn should_execute_the_futures_concurrently() -> anyhow::Result<()> {
+        // Arrange
+        let (url, _) = spawn_mock_rpc_server(json!("0x7a69"), MOCK_LATENCY);
+
+        let config = get_config(ConfigOverrides::new(None, Some(url), None))?;
+        let execution_context = CommandExecutionContext::new(config)?;
+        let node_provider = execution_context.node_provider();
+
+        let start = Instant::now();
+
+        // Act
+        let res = execution_context.execute_with_concurrency(
+            (0..3).map(|_| async { Ok(node_provider.get_chainid().await?) }),
+            3,
+        );
+
+        // Assert
+        assert_eq!(res.len(), 3);
+        assert!(res.iter().all(Result::is_ok));
+        assert!(start.elapsed() < MOCK_LATENCY * 2);
+
+        Ok(())
+    }
+
+    #[test]
+    fn should_execute_many_futures_concurrently_up_to_the_default_limit() -> anyhow::Result<()> {
+        // Arrange
+        let (url, _) = spawn_mock_rpc_server(json!("0x7a69"), MOCK_LATENCY);
+
+        let config = get_config(ConfigOverrides::new(None, Some(url), None))?;
+        let execution_context = CommandExecutionContext::new(config)?;
+        let node_provider = execution_context.node_provider();
+
+        let start = Instant::now();
+
+        // Act
+        let res = execution_context.execute_many(
+            (0..DEFAULT_CONCURRENCY).map(|_| async { Ok(node_provider.get_chainid().await?) }),
+        );
+
+        // Assert
+        assert_eq!(res.len(), DEFAULT_CONCURRENCY);
+        assert!(res.iter().all(Result::is_ok));
+        assert!(start.elapsed() < MOCK_LATENCY * 2);
+
+        Ok(())
+    }
+
+    #[test]
+    fn should_not_exceed_the_concurrency_limit() -> anyhow::Result<()> {
+        // Arrange
+        let config = get_config(ConfigOverrides::default())?;
+        let execution_context = CommandExecutionContext::new(config)?;
+
+        let in_flight = AtomicUsize::new(0);
+        let max_in_flight = AtomicUsize::new(0);
+
+        let futures = (0..6).map(|idx| {
+            let (in_flight, max_in_flight) = (&in_flight, &max_in_flight);
+
+            async move {
+                let running = in_flight.fetch_add(1, Ordering::SeqCst) + 1;
+                max_in_flight.fetch_max(running, Ordering::SeqCst);
+
+                tokio::time::sleep(Duration::from_millis(20)).await;
+                in_flight.fetch_sub(1, Ordering::SeqCst);
+
+                Ok(idx)
+            }
+        });
+
+        // Act
+        let res = execution_context.execute_with_concurrency(futures, 2);
+
+        // Assert
+        assert_eq!(
+            res.into_iter().collect::<anyhow::Result<Vec<_>>>()?,
+            vec![0, 1, 2, 3, 4, 5]
+        );
+        assert_eq!(max_in_flight.load(Ordering::SeqCst), 2);
+
+        Ok(())
+    }
+
+    #[test]
+    fn should_select_the_runtime_from_the_threads_config_and_the_command_kind() -> anyhow::Result<()>
+    {
+        // Setup
+        let (url, _) = spawn_mock_rpc_server(json!("0x7a69"), Duration::ZERO);
+        let test_cases = [
+            (None, false, RuntimeFlavor::CurrentThread),
+            (Some(4), false, RuntimeFlavor::MultiThread),
+            (None, true, RuntimeFlavor::MultiThread),
+            (Some(1), true, RuntimeFlavor::MultiThread),
+        ];
+
+        for (threads, long_running, expected_flavor) in test_cases {
+            // Arrange
+            let config = get_config(
+                ConfigOverrides::new(None, Some(url.clone()), None).with_threads(threads),
+            )?;
+            let execution_context = if long_running {
+                CommandExecutionContext::new_long_running(config)?
+            } else {
+                CommandExecutionContext::new(config)?
+            };
+
+            // Act
+            let res = execution_context.execute(execution_context.node_provider().get_chainid());
+
+            // Assert
+            assert_eq!(
+                execution_context.runtime.handle().runtime_flavor(),
+                expected_flavor
+            );
+            assert!(res.is_ok());
+            assert_eq!(res.unwrap(), 0x7a69.into());
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn should_isolate_panicking_futures() -> anyhow::Result<()> {
+        // Arrange
+        let config = get_config(ConfigOverrides::default())?;
+        let execution_context = CommandExecutionContext::new(config)?;
+
+        let futures: Vec<LocalBoxFuture<anyhow::Result<u64>>> = vec![
+            async { Ok(1) }.boxed_local(),
+            async { panic!("Something went wrong!") }.boxed_local(),
+            async { anyhow::bail!("Some error") }.boxed_local(),
+            async { Ok(4) }.boxed_local(),
+        ];
+
+        // Act
+        let res = execution_context.execute_many(futures);
+
+        // Assert
+        assert_eq!(res.len(), 4);
+        assert_eq!(res[0].as_ref().unwrap(), &1);
+        assert!(res[1]
+            .as_ref()
+            .unwrap_err()
+            .to_string()
+            .contains("panicked"));
+        assert!(res[2].is_err());
+        assert_eq!(res[3].as_ref().unwrap(), &4);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn should_reuse_the_connection_across_requests_and_providers() -> anyhow::Result<()> {
+        // Arrange
+        let (url, connections) = spawn_mock_tls_rpc_server(json!("0x7a69"));
+
+        let config = get_config(
+            ConfigOverrides::new(None, Some(url), None)
+                .with_ignore_tls_errors(true)
+                .with_timeout_secs(Some(5)),
+        )?;
+
+        let node_providers = [
+            NodeProvider::new(&config).await?,
+            NodeProvider::new(&config).await?,
+        ];
+
+        // Act
+        for node_provider in node_providers.iter().cycle().take(50) {
+            node_provider.get_chainid().await?;
+        }
+
+        // Assert
+        assert_eq!(connections.load(Ordering::SeqCst), 1);
+
+        Ok(())
+    }
+
+    mod load_signer {
+        use std::path::PathBuf;
+
+        use ethers::signers::{LocalWallet, Signer};
+
+        use crate::{
+            config::{get_config, ConfigOverrides},
+            context::{load_signer, NodeProviderConfigError},
+        };
+
+        const PASSWORD: &str = "yaeth";
+
+        fn keystore(name: &str) -> anyhow::Result<(LocalWallet, PathBuf)> {
+            let dir = std::env::temp_dir();
+            let file_name = format!("yaeth-keystore-{name}-{}", std::process::id());
+
+            let (wallet, _) = LocalWallet::new_keystore(
+                &dir,
+                &mut rand::thread_rng(),
+                PASSWORD,
+                Some(&file_name),
+            )?;
+
+            Ok((wallet, dir.join(file_name)))
+        }
+
+        #[test]
+        fn should_decrypt_the_keystore() -> anyhow::Result<()> {
+            // Arrange
+            let (wallet, path) = keystore("decrypt")?;
+            let config = get_config(
+                ConfigOverrides::default()
+                    .with_keystore(Some(path.display().to_string()))
+                    .with_keystore_password(Some(PASSWORD.into())),
+            )?;
+
+            // Act
+            let res = load_signer(&config);
+            std::fs::remove_file(path)?;
+
+            // Assert
+            assert_eq!(res?.map(|signer| signer.address()), Some(wallet.address()));
+
+            Ok(())
+        }
+
+        #[test]
+        fn should_prefer_the_private_key_over_the_keystore() -> anyhow::Result<()> {
+            // Arrange
+            let (_, path) = keystore("precedence")?;
+            let wallet = LocalWallet::new(&mut rand::thread_rng());
+            let priv_key = hex::encode(wallet.signer().to_bytes());
+            let config = get_config(
+                ConfigOverrides::new(Some(priv_key), None, None)
+                    .with_keystore(Some(path.display().to_string()))
+                    .with_keystore_password(Some(PASSWORD.into())),
+            )?;
+
+            // Act
+            let res = load_signer(&config);
+            std::fs::remove_file(path)?;
+
+            // Assert
+            assert_eq!(res?.map(|signer| signer.address()), Some(wallet.address()));
+
+            Ok(())
+        }
+
+        #[test]
+        fn should_not_decrypt_the_keystore_without_the_right_password() -> anyhow::Result<()> {
+            // Arrange
+            let (_, path) = keystore("password")?;
+            let keystore = Some(path.display().to_string());
+
+            let missing_password_config =
+                get_config(ConfigOverrides::default().with_keystore(keystore.clone()))?;
+            let wrong_password_config = get_config(
+                ConfigOverrides::default()
+                    .with_keystore(keystore)
+                    .with_keystore_password(Some("wrong".into())),
+            )?;
+
+            // Act
+            let missing_password_res = load_signer(&missing_password_config);
+            let wrong_password_res = load_signer(&wrong_password_config);
+            std::fs::remove_file(path)?;
+
+            // Assert
+            assert!(matches!(
+                missing_password_res,
+                Err(NodeProviderConfigError::MissingKeystorePassword)
+            ));
+            assert!(matches!(
+                wrong_password_res,
+                Err(NodeProviderConfigError::InvalidKeystore(_, _))
+            ));
+
+            Ok(())
+        }
+
+        const TEST_MNEMONIC: &str = "test test test test test test test test test test test junk";
+
+        #[test]
+        fn should_derive_the_account_of_the_mnemonic_index() -> anyhow::Result<()> {
+            // Setup
+            let test_cases = [
+                (None, "0xf39fd6e51aad88f6f4ce6ab8827279cfffb92266"),
+                (Some(1), "0x70997970c51812dc3a010c7d01b50e0d17dc79c8"),
+            ];
+
+            for (index, expected_address) in test_cases {
+                let config = get_config(
+                    ConfigOverrides::default()
+                        .with_mnemonic(Some(TEST_MNEMONIC.into()))
+                        .with_mnemonic_index(index),
+                )?;
+
+                // Act
+                let res = load_signer(&config);
+
+                // Assert
+                assert_eq!(
+                    res?.map(|signer| signer.address()),
+                    Some(expected_address.parse()?)
+                );
+            }
+
+            Ok(())
+        }
+
+        #[test]
+        fn should_not_combine_a_mnemonic_with_a_private_key() -> anyhow::Result<()> {
+            // Arrange
+            let wallet = LocalWallet::new(&mut rand::thread_rng());
+            let priv_key = hex::encode(wallet.signer().to_bytes());
+            let config = get_config(
+                ConfigOverrides::new(Some(priv_key), None, None)
+                    .with_mnemonic(Some(TEST_MNEMONIC.into())),
+            )?;
+
+            // Act
+            let res = load_signer(&config);
+
+            // Assert
+            assert!(matches!(
+                res,
+                Err(NodeProviderConfigError::ConflictingSigners)
+            ));
+
+            Ok(())
+        }
+
+        #[test]
+        fn should_not_leak_the_invalid_private_key_in_the_error() -> anyhow::Result<()> {
+            // Setup
+            let test_cases = [
+                "0xac0974bec39a17e36ba4a6b4d238ff944bacb478cbed5efcae784d7bf4f2ff8",
+                "ac0974bec39a17e36ba4a6b4d238ff944bacb478cbed5efcae784d7bf4f2ff80ff",
+                "0xac0974bec39a17e36ba4a6b4d238ff944bacb478cbed5efcae784d7bf4f2ffzz",
+            ];
+
+            for priv_key in test_cases {
+                // Arrange
+                let config = get_config(ConfigOverrides::new(Some(priv_key.into()), None, None))?;
+
+                // Act
+                let res = load_signer(&config);
+
+                // Assert
+                let err = res.unwrap_err();
+                assert!(matches!(err, NodeProviderConfigError::InvalidPrivateKey));
+
+                let err = err.to_string();
+                let chars: Vec<char> = priv_key.chars().collect();
+                let leaked = chars
+                    .windows(4)
+                    .map(|window| window.iter().collect::<String>())
+                    .find(|part| err.contains(part.as_str()));
+                assert_eq!(leaked, None, "{err}");
+            }
+
+            Ok(())
+        }
+
+        #[test]
+        fn should_not_leak_the_invalid_mnemonic_in_the_error() -> anyhow::Result<()> {
+            // Arrange
+            let mnemonic = "secret pumpkin ladder zebra";
+            let config =
+                get_config(ConfigOverrides::default().with_mnemonic(Some(mnemonic.into())))?;
+
+            // Act
+            let res = load_signer(&config);
+
+            // Assert
+            let err = res.unwrap_err();
+            assert!(matches!(err, NodeProviderConfigError::InvalidMnemonic));
+
+            let err = err.to_string();
+            assert!(mnemonic.split_whitespace().all(|word| !err.contains(word)));
+
+            Ok(())
+        }
+
+        #[test]
+        fn should_not_load_a_signer_without_a_key() -> anyhow::Result<()> {
+            // Arrange
+            let config = get_config(ConfigOverrides::default())?;
+
+            // Act
+            let res = load_signer(&config);
+
+            // Assert
+            assert!(res?.is_none());
+
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn should_sign_typed_data_with_the_configured_private_key() -> anyhow::Result<()> {
+        // Arrange
+        let wallet = LocalWallet::new(&mut rand::thread_rng());
+        let priv_key = hex::encode(wallet.signer().to_bytes());
+
+        let (url, _) = spawn_mock_rpc_server(json!("0x1"), Duration::ZERO);
+
+        let config = get_config(ConfigOverrides::new(Some(priv_key), Some(url), None))?;
+        let node_provider = NodeProvider::new(&config).await?;
+
+        let typed_data: TypedData = serde_json::from_value(json!({
+            "types": {
+                "EIP712Domain": [{ "name": "name", "type": "string" }],
+                "Mail": [{ "name": "contents", "type": "string" }],
+            },
+            "primaryType": "Mail",
+            "domain": { "name": "yaeth" },
+            "message": { "contents": "hello" },
+        }))?;
+
+        // Act
+        let res = node_provider
+            .sign_typed_data(&typed_data, wallet.address())
+            .await;
+
+        // Assert
+        assert!(res.is_ok());
+
+        let digest = H256::from(typed_data.encode_eip712()?);
+        assert_eq!(res.unwrap().recover(digest)?, wallet.address());
+
+        Ok(())
+    }
+}
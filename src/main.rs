@@ -1,3 +1,5 @@
-fn main() -> Result<(), anyhow::Error> {
+use std::process::ExitCode;
+
+fn main() -> ExitCode {
     yaeth_cli::run()
 }
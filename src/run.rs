@@ -1,16 +1,27 @@
-use std::fs::File;
+use std::{
+    ffi::OsString,
+    fs::{self, File, OpenOptions},
+    io::{self, Read, Stderr, Stdout, Write},
+    path::PathBuf,
+    process::ExitCode,
+    sync::{Mutex, OnceLock},
+};
 
-use clap::{builder::PossibleValue, command, Parser, Subcommand, ValueEnum};
+use clap::{builder::PossibleValue, Parser, Subcommand, ValueEnum};
 use serde::Serialize;
 
 use crate::{
     cli::{
         account::{self, AccountCommand, AccountNamespaceResult},
         block::{self, BlockCommand, BlockNamespaceResult},
+        event::{self, EventCommand, EventNamespaceResult},
         gas::{self, GasCommand, GasNamespaceResult},
+        spec::CommandSpec,
+        trace::{self, TraceCommand, TraceNamespaceResult},
         transaction::{self, TransactionCommand, TransactionNamespaceResult},
         utils::{self, UtilsCommand, UtilsNamespaceResult},
     },
+    cmd,
     config::{get_config, ConfigOverrides},
     context::CommandExecutionContext,
 };
@@ -21,14 +32,31 @@ use crate::{
     about = "An ether-rs wrapper to query the ethereum blockchain from a terminal",
     display_name = "yaeth",
     disable_help_subcommand = true,
+    arg_required_else_help = true,
     version
 )]
-struct EntryPoint {
+pub(crate) struct EntryPoint {
     /// Private key to use for signing transactions
     #[arg(short, long)]
     priv_key: Option<String>,
 
-    /// Rpc url to send requests to
+    /// Web3 Secret Storage keystore holding the key to use for signing transactions (ignored if a private key is provided)
+    #[arg(long, value_name = "PATH")]
+    keystore: Option<String>,
+
+    /// Password of the keystore, prompted for if omitted
+    #[arg(long, value_name = "PASSWORD", requires = "keystore")]
+    keystore_password: Option<String>,
+
+    /// BIP-39 mnemonic to derive the key to use for signing transactions from (can't be combined with a private key)
+    #[arg(long, value_name = "PHRASE", conflicts_with = "priv_key")]
+    mnemonic: Option<String>,
+
+    /// Index of the account derived from the mnemonic at m/44'/60'/0'/0/{index} (defaults to 0)
+    #[arg(long, value_name = "INDEX", requires = "mnemonic")]
+    mnemonic_index: Option<u32>,
+
+    /// Rpc url to send requests to (http(s):// or ws(s)://)
     #[arg(short, long)]
     rpc_url: Option<String>,
 
@@ -36,16 +64,60 @@ struct EntryPoint {
     #[arg(short, long, default_value = "console")]
     out: OutputFormat,
 
-    /// Optional name for the output file
+    /// Optional path of the output file, the extension of the output format is appended if missing
     #[arg(short, long, default_value = "out")]
     file: String,
 
+    /// Print single value results, like bytecode or hashes, without quotes or the json envelope
+    #[arg(long, alias = "raw")]
+    raw_output: bool,
+
+    /// Print the json result on a single line with its object keys sorted, so that the same result is always printed identically (only for the console, json and clipboard outputs)
+    #[arg(long)]
+    no_pretty: bool,
+
     /// Optional configuration file
     #[arg(short, long)]
     config_file: Option<String>,
 
+    /// Accept invalid TLS certificates (e.g. self-signed) from https rpc endpoints
+    #[arg(long, alias = "insecure")]
+    ignore_tls_errors: bool,
+
+    /// Refuse to scan block ranges larger than the provided number of blocks (defaults to 10000)
+    #[arg(long, value_name = "BLOCKS")]
+    max_block_range: Option<u64>,
+
+    /// Run the commands on a multi-threaded runtime with the provided number of worker threads (defaults to a single thread, or one per core for the follow and watch commands)
+    #[arg(long, value_name = "THREADS", value_parser = clap::value_parser!(u64).range(1..))]
+    threads: Option<u64>,
+
+    /// Abort the command if it does not complete within the provided number of seconds (overrides the default limit of each command)
+    #[arg(long, value_name = "SECONDS", value_parser = clap::value_parser!(u64).range(1..))]
+    command_timeout: Option<u64>,
+
+    /// Reuse the fee history fetched by a gas command in the following ones of a json command batch reading the same blocks
+    #[arg(long)]
+    fee_history_cache: bool,
+
+    /// Abort each request sent to the node if it does not complete within the provided number of seconds (http endpoints only)
+    #[arg(long, value_name = "SECONDS", value_parser = clap::value_parser!(u64).range(1..))]
+    timeout: Option<u64>,
+
+    /// Retry rate limited or failed requests with backoff up to the provided number of times (http endpoints only, defaults to 0)
+    #[arg(long, value_name = "RETRIES")]
+    retries: Option<u32>,
+
+    /// Abort before executing the command if the node chain id does not match the provided one
+    #[arg(long, value_name = "CHAIN_ID")]
+    assert_chain_id: Option<u64>,
+
+    /// Read the whole invocation from stdin as a json command spec, e.g. {"namespace": "block", "subcommand": "number"}, or an array of specs sharing the same options to run in turn
+    #[arg(long, exclusive = true)]
+    stdin_json: bool,
+
     #[command(subcommand)]
-    command: Command,
+    command: Option<Command>,
 }
 
 #[derive(Subcommand, Debug)]
@@ -63,19 +135,28 @@ enum Command {
     Transaction(TransactionCommand),
 
     /// Execute event related operations
-    #[command(subcommand)]
-    Event(NoSubCommand),
+    Event(EventCommand),
 
     /// Execute gas related operations
     Gas(GasCommand),
 
+    /// Execute tracing related operations
+    Trace(TraceCommand),
+
     /// Collection of utils
     Utils(UtilsCommand),
 }
 
-#[derive(Subcommand, Debug)]
-#[command()]
-pub enum NoSubCommand {}
+impl Command {
+    /// Tells if the command keeps running until interrupted.
+    fn is_long_running(&self) -> bool {
+        match self {
+            Command::Block(cmd) => cmd.is_long_running(),
+            Command::Event(cmd) => cmd.is_long_running(),
+            _ => false,
+        }
+    }
+}
 
 #[derive(Debug, Serialize)]
 #[serde(untagged)]
@@ -83,10 +164,23 @@ pub enum CliResult {
     BlockNamespace(BlockNamespaceResult),
     AccountNamespace(AccountNamespaceResult),
     TransactionNamespace(TransactionNamespaceResult),
+    EventNamespace(EventNamespaceResult),
     GasNamespace(GasNamespaceResult),
+    TraceNamespace(TraceNamespaceResult),
     UtilsNamespace(UtilsNamespaceResult),
 }
 
+impl CliResult {
+    /// Tells if the result was already written while the command was running.
+    fn is_streamed(&self) -> bool {
+        matches!(
+            self,
+            CliResult::EventNamespace(EventNamespaceResult::Followed)
+                | CliResult::BlockNamespace(BlockNamespaceResult::Watched)
+        )
+    }
+}
+
 #[derive(Debug, Clone)]
 pub enum OutputFormat {
     /// Output the cli result to the terminal
@@ -94,11 +188,30 @@ pub enum OutputFormat {
 
     /// Output the cli result to a json file
     Json,
+
+    /// Output the cli result to a yaml file
+    Yaml,
+
+    /// Output the cli result to a csv file
+    Csv,
+
+    /// Output the cli result to the terminal as a table
+    Table,
+
+    /// Copy the cli result to the system clipboard
+    Clipboard,
 }
 
 impl ValueEnum for OutputFormat {
     fn value_variants<'a>() -> &'a [Self] {
-        &[OutputFormat::Console, OutputFormat::Json]
+        &[
+            OutputFormat::Console,
+            OutputFormat::Json,
+            OutputFormat::Yaml,
+            OutputFormat::Csv,
+            OutputFormat::Table,
+            OutputFormat::Clipboard,
+        ]
     }
 
     fn to_possible_value(&self) -> Option<clap::builder::PossibleValue> {
@@ -106,50 +219,1752 @@ impl ValueEnum for OutputFormat {
             OutputFormat::Console => {
                 PossibleValue::new("console").help("Output the cli result to the terminal")
             }
-            OutputFormat::Json => {
-                PossibleValue::new("json").help("Output the cli result to a json file")
+            OutputFormat::Json => PossibleValue::new("json")
+                .help("Output the cli result to a json file and errors as json to stderr"),
+            OutputFormat::Yaml => {
+                PossibleValue::new("yaml").help("Output the cli result to a yaml file")
+            }
+            OutputFormat::Csv => {
+                PossibleValue::new("csv").help("Output the cli result to a csv file")
+            }
+            OutputFormat::Table => {
+                PossibleValue::new("table").help("Output the cli result to the terminal as a table")
+            }
+            OutputFormat::Clipboard => {
+                PossibleValue::new("clipboard").help("Copy the cli result to the system clipboard")
             }
         })
     }
 }
 
-fn format_output<T: Serialize>(
+impl OutputFormat {
+    /// Extensions of the file formats, the first one is appended to the output files missing it.
+    fn file_extensions(&self) -> &'static [&'static str] {
+        match self {
+            OutputFormat::Json => &["json"],
+            OutputFormat::Yaml => &["yaml", "yml"],
+            OutputFormat::Csv => &["csv"],
+            OutputFormat::Console | OutputFormat::Table | OutputFormat::Clipboard => &[],
+        }
+    }
+}
+
+/// Resolves the path of the output file, creating its parent directories. A path with the extension
+/// of the format is used as is, one with the extension of another format is rejected.
+fn output_path(output_file: &str, format: &OutputFormat) -> anyhow::Result<PathBuf> {
+    let mut path = PathBuf::from(output_file);
+
+    let extension = path
+        .extension()
+        .and_then(|extension| extension.to_str())
+        .map(str::to_lowercase);
+
+    match extension {
+        Some(extension) if format.file_extensions().contains(&extension.as_str()) => {}
+        Some(extension)
+            if OutputFormat::value_variants()
+                .iter()
+                .any(|other| other.file_extensions().contains(&extension.as_str())) =>
+        {
+            anyhow::bail!(
+                "The output file {output_file} has a .{extension} extension that does not match the selected output format"
+            )
+        }
+        _ => {
+            let mut file_name = path.file_name().unwrap_or_default().to_os_string();
+            file_name.push(format!(".{}", format.file_extensions()[0]));
+            path.set_file_name(file_name);
+        }
+    }
+
+    if let Some(parent) = path
+        .parent()
+        .filter(|parent| !parent.as_os_str().is_empty())
+    {
+        fs::create_dir_all(parent)?;
+    }
+
+    Ok(path)
+}
+
+/// Coordinates the progress and final result writes so that they never interleave: every write
+/// holds the same lock and the final result is written in one go after the progress output is flushed.
+pub struct OutputCoordinator<O: Write, E: Write> {
+    writers: Mutex<(O, E)>,
+}
+
+impl<O: Write, E: Write> OutputCoordinator<O, E> {
+    pub fn new(out: O, err: E) -> Self {
+        Self {
+            writers: Mutex::new((out, err)),
+        }
+    }
+
+    /// Writes a progress line to the error output.
+    pub fn progress(&self, line: &str) -> io::Result<()> {
+        let mut writers = self.writers.lock().unwrap_or_else(|err| err.into_inner());
+        let (_, err) = &mut *writers;
+
+        writeln!(err, "{line}")?;
+        err.flush()
+    }
+
+    /// Writes the error of a failed command to the error output.
+    pub fn fail(&self, error: &str) -> io::Result<()> {
+        let mut writers = self.writers.lock().unwrap_or_else(|err| err.into_inner());
+        let (_, err) = &mut *writers;
+
+        writeln!(err, "{error}")?;
+        err.flush()
+    }
+
+    #[cfg(test)]
+    pub fn into_inner(self) -> (O, E) {
+        self.writers
+            .into_inner()
+            .unwrap_or_else(|err| err.into_inner())
+    }
+
+    /// Writes the final result to the output once all the progress output has been flushed.
+    pub fn finish(&self, result: &str) -> io::Result<()> {
+        let mut writers = self.writers.lock().unwrap_or_else(|err| err.into_inner());
+        let (out, err) = &mut *writers;
+
+        err.flush()?;
+
+        out.write_all(format!("{result}\n").as_bytes())?;
+        out.flush()
+    }
+}
+
+static STDIO_OUTPUT: OnceLock<OutputCoordinator<Stdout, Stderr>> = OnceLock::new();
+
+/// Returns the coordinator shared by every command writing to the process stdout and stderr.
+pub fn stdio_output() -> &'static OutputCoordinator<Stdout, Stderr> {
+    STDIO_OUTPUT.get_or_init(|| OutputCoordinator::new(io::stdout(), io::stderr()))
+}
+
+/// Destination of the items written one document at a time by the streaming commands.
+#[derive(Debug, Clone)]
+pub struct StreamOutput {
+    format: OutputFormat,
+    output_file: String,
+}
+
+impl Default for StreamOutput {
+    fn default() -> Self {
+        Self::new(OutputFormat::Console, "out".into())
+    }
+}
+
+impl StreamOutput {
+    pub fn new(format: OutputFormat, output_file: String) -> Self {
+        Self {
+            format,
+            output_file,
+        }
+    }
+
+    /// Writes the item as a single json line to the terminal or appends it to the output file.
+    pub fn write<T: Serialize>(&self, item: &T) -> anyhow::Result<()> {
+        self.write_to(item, stdio_output())
+    }
+
+    fn write_to<T: Serialize, O: Write, E: Write>(
+        &self,
+        item: &T,
+        output: &OutputCoordinator<O, E>,
+    ) -> anyhow::Result<()> {
+        let line = serde_json::to_string(item)?;
+
+        match self.format {
+            OutputFormat::Console => output.finish(&line)?,
+            OutputFormat::Json => {
+                let mut file = OpenOptions::new()
+                    .create(true)
+                    .append(true)
+                    .open(output_path(&self.output_file, &self.format)?)?;
+
+                writeln!(file, "{line}")?
+            }
+            OutputFormat::Yaml => {
+                let mut file = OpenOptions::new()
+                    .create(true)
+                    .append(true)
+                    .open(output_path(&self.output_file, &self.format)?)?;
+
+                write!(file, "---\n{}", to_yaml(item)?)?
+            }
+            OutputFormat::Csv => {
+                anyhow::bail!("Streaming commands can't write their output to a csv file")
+            }
+            OutputFormat::Table => {
+                anyhow::bail!("Streaming commands can't write their output as a table")
+            }
+            OutputFormat::Clipboard => {
+                anyhow::bail!("Streaming commands can't copy their output to the clipboard")
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Table written by the csv output: a header row followed by the data rows.
+#[derive(Debug, PartialEq, Eq)]
+pub struct CsvTable {
+    headers: Vec<String>,
+    rows: Vec<Vec<String>>,
+}
+
+impl CsvTable {
+    pub fn new(headers: Vec<String>, rows: Vec<Vec<String>>) -> Self {
+        Self { headers, rows }
+    }
+
+    /// Flattens a serialized result keeping only its scalar fields: lists are written one row per
+    /// item and any other result as a single row.
+    fn from_value(value: &serde_json::Value) -> Self {
+        let (column, value) = unwrap_envelopes(value);
+
+        match value {
+            serde_json::Value::Array(items) => match items.first() {
+                Some(serde_json::Value::Object(first)) => {
+                    let headers = scalar_field_names(first);
+                    let rows = items
+                        .iter()
+                        .map(|item| {
+                            headers
+                                .iter()
+                                .map(|header| item.get(header).map(csv_cell).unwrap_or_default())
+                                .collect()
+                        })
+                        .collect();
+
+                    Self::new(headers, rows)
+                }
+                _ => Self::new(
+                    vec![column],
+                    items.iter().map(|item| vec![csv_cell(item)]).collect(),
+                ),
+            },
+            serde_json::Value::Object(object) => {
+                let headers = scalar_field_names(object);
+                let row = headers
+                    .iter()
+                    .map(|header| csv_cell(&object[header]))
+                    .collect();
+
+                Self::new(headers, vec![row])
+            }
+            value => Self::new(vec![column], vec![vec![csv_cell(value)]]),
+        }
+    }
+
+    /// Renders the table for the terminal: a single row with several columns is written as one
+    /// key/value row per field, anything else with one column per header.
+    fn to_text_table(&self) -> String {
+        let mut table = comfy_table::Table::new();
+
+        match self.rows.as_slice() {
+            [row] if self.headers.len() > 1 => {
+                table.set_header(["field", "value"]);
+
+                for (header, cell) in self.headers.iter().zip(row) {
+                    table.add_row([header, cell]);
+                }
+            }
+            rows => {
+                table.set_header(&self.headers);
+
+                for row in rows {
+                    table.add_row(row);
+                }
+            }
+        }
+
+        table.to_string()
+    }
+}
+
+impl std::fmt::Display for CsvTable {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for row in std::iter::once(&self.headers).chain(&self.rows) {
+            let line: Vec<String> = row.iter().map(|cell| escape_csv_cell(cell)).collect();
+
+            writeln!(f, "{}", line.join(","))?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Unwraps the result envelopes, e.g. `{"block": {...}}`, returning the name of the innermost one.
+fn unwrap_envelopes(mut value: &serde_json::Value) -> (String, &serde_json::Value) {
+    let mut column = String::from("value");
+
+    while let serde_json::Value::Object(object) = value {
+        match object.iter().next() {
+            Some((key, inner)) if object.len() == 1 => {
+                column = key.clone();
+                value = inner;
+            }
+            _ => break,
+        }
+    }
+
+    (column, value)
+}
+
+fn scalar_field_names(object: &serde_json::Map<String, serde_json::Value>) -> Vec<String> {
+    object
+        .iter()
+        .filter(|(_, value)| !value.is_array() && !value.is_object())
+        .map(|(key, _)| key.clone())
+        .collect()
+}
+
+fn csv_cell(value: &serde_json::Value) -> String {
+    match value {
+        serde_json::Value::Null => String::new(),
+        serde_json::Value::String(value) => value.clone(),
+        value => value.to_string(),
+    }
+}
+
+fn escape_csv_cell(cell: &str) -> String {
+    if cell.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", cell.replace('"', "\"\""))
+    } else {
+        cell.to_string()
+    }
+}
+
+/// Results that can be written by the csv and table outputs.
+pub trait CsvOutput {
+    fn csv_table(&self) -> anyhow::Result<CsvTable>;
+}
+
+impl CsvOutput for CliResult {
+    fn csv_table(&self) -> anyhow::Result<CsvTable> {
+        if let CliResult::EventNamespace(res) = self {
+            if let Some(table) = res.csv_table() {
+                return Ok(table);
+            }
+        }
+
+        Ok(CsvTable::from_value(&serde_json::to_value(self)?))
+    }
+}
+
+impl CsvOutput for serde_json::Value {
+    fn csv_table(&self) -> anyhow::Result<CsvTable> {
+        Ok(CsvTable::from_value(self))
+    }
+}
+
+fn format_output<T: Serialize + CsvOutput, O: Write, E: Write>(
     input: T,
     format: OutputFormat,
     output_file: String,
+    raw_output: bool,
+    no_pretty: bool,
+    output: &OutputCoordinator<O, E>,
 ) -> anyhow::Result<()> {
+    let content = if raw_output {
+        extract_raw_scalar(serde_json::to_value(&input)?)?
+    } else if no_pretty {
+        to_sorted_json(&input)?
+    } else if let OutputFormat::Yaml = format {
+        to_yaml(&input)?
+    } else if let OutputFormat::Csv = format {
+        input.csv_table()?.to_string()
+    } else if let OutputFormat::Table = format {
+        input.csv_table()?.to_text_table()
+    } else {
+        serde_json::to_string_pretty(&input)?
+    };
+
     match format {
-        OutputFormat::Console => println!("{}", serde_json::to_string_pretty(&input)?),
-        OutputFormat::Json => {
-            serde_json::to_writer_pretty(File::create(format!("{output_file}.json"))?, &input)?;
-            println!("Ok")
+        OutputFormat::Console | OutputFormat::Table => output.finish(&content)?,
+        OutputFormat::Json | OutputFormat::Yaml | OutputFormat::Csv => {
+            File::create(output_path(&output_file, &format)?)?.write_all(content.as_bytes())?;
+            output.finish("Ok")?
+        }
+        OutputFormat::Clipboard => {
+            copy_to_clipboard(content)?;
+            output.finish("Copied to clipboard")?
+        }
+    }
+
+    Ok(())
+}
+
+/// Serializes the result to yaml through a json value to avoid the tags of the result enums (e.g. `!block`).
+fn to_yaml<T: Serialize>(input: &T) -> anyhow::Result<String> {
+    Ok(serde_yaml::to_string(&serde_json::to_value(input)?)?)
+}
+
+/// Serializes the result on a single line with the keys of every object sorted. Going through a
+/// json value sorts the keys as its maps are ordered by key.
+fn to_sorted_json<T: Serialize>(input: &T) -> anyhow::Result<String> {
+    Ok(serde_json::to_string(&serde_json::to_value(input)?)?)
+}
+
+/// Unwraps the single value held by the result envelopes, e.g. `{"bytecode": "0x..."}`.
+fn extract_raw_scalar(mut value: serde_json::Value) -> anyhow::Result<String> {
+    loop {
+        value = match value {
+            serde_json::Value::String(value) => return Ok(value),
+            serde_json::Value::Number(value) => return Ok(value.to_string()),
+            serde_json::Value::Bool(value) => return Ok(value.to_string()),
+            serde_json::Value::Object(object) if object.len() == 1 => object
+                .into_iter()
+                .next()
+                .map(|(_, value)| value)
+                .unwrap_or_default(),
+            _ => {
+                anyhow::bail!("--raw-output is only supported by commands returning a single value")
+            }
+        }
+    }
+}
+
+#[cfg(feature = "clipboard")]
+fn copy_to_clipboard(content: String) -> anyhow::Result<()> {
+    arboard::Clipboard::new()?.set_text(content)?;
+
+    Ok(())
+}
+
+#[cfg(not(feature = "clipboard"))]
+fn copy_to_clipboard(_content: String) -> anyhow::Result<()> {
+    anyhow::bail!(
+        "Clipboard output is not supported by this build. Rebuild yaeth with the `clipboard` feature enabled"
+    )
+}
+
+fn assert_chain_id(
+    context: &CommandExecutionContext,
+    expected_chain_id: u64,
+) -> anyhow::Result<()> {
+    let chain_id = context.execute(cmd::utils::get_chain_id(context.node_provider()))?;
+
+    if chain_id != expected_chain_id.into() {
+        anyhow::bail!(
+            "Chain id mismatch. Expected chain id {expected_chain_id} but the node is connected to chain id {chain_id}"
+        );
+    }
+
+    Ok(())
+}
+
+/// Error of a failed command as written when the json output is selected.
+#[derive(Debug, Serialize)]
+struct CliError {
+    error: String,
+    context: Vec<String>,
+}
+
+impl From<&anyhow::Error> for CliError {
+    fn from(err: &anyhow::Error) -> Self {
+        Self {
+            error: err.to_string(),
+            context: err.chain().skip(1).map(ToString::to_string).collect(),
+        }
+    }
+}
+
+/// Writes the error according to the output format so that scripts reading the json output can
+/// parse the failures too. The other formats keep the usual error report.
+fn report_error<O: Write, E: Write>(
+    err: &anyhow::Error,
+    format: &OutputFormat,
+    output: &OutputCoordinator<O, E>,
+) -> anyhow::Result<()> {
+    let content = match format {
+        OutputFormat::Json => serde_json::to_string(&CliError::from(err))?,
+        _ => format!("Error: {err:?}"),
+    };
+
+    output.fail(&content)?;
+
+    Ok(())
+}
+
+/// Exit code of an invalid invocation, the same as the one used by clap.
+const USAGE_ERROR_EXIT_CODE: u8 = 2;
+
+/// Returns the json error envelope of an invocation rejected by clap if the json output is
+/// selected, so that scripts reading the json output can parse the usage errors too. Clap keeps
+/// its usual report otherwise, as well as the help and version output.
+fn json_usage_error(err: &clap::Error, args: &[OsString]) -> Option<CliError> {
+    if !err.use_stderr() || !matches!(selected_output_format(args), Some(OutputFormat::Json)) {
+        return None;
+    }
+
+    let rendered = err.render().to_string();
+    let mut lines = rendered
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty());
+
+    let error = lines.next().unwrap_or_default();
+
+    Some(CliError {
+        error: error.strip_prefix("error: ").unwrap_or(error).to_owned(),
+        context: lines.map(String::from).collect(),
+    })
+}
+
+/// Finds the output format requested by an invocation that clap could not parse.
+fn selected_output_format(args: &[OsString]) -> Option<OutputFormat> {
+    let mut args = args.iter().skip(1).map(|arg| arg.to_string_lossy());
+
+    while let Some(arg) = args.next() {
+        let value = match arg.as_ref() {
+            "--" => return None,
+            "-o" | "--out" => args.next()?.into_owned(),
+            arg => match arg
+                .strip_prefix("--out=")
+                .or_else(|| arg.strip_prefix("-o"))
+            {
+                Some(value) => value.to_owned(),
+                None => continue,
+            },
+        };
+
+        return OutputFormat::from_str(&value, false).ok();
+    }
+
+    None
+}
+
+/// Replaces the invocation with the ones described by the json command specs read from `reader`
+/// if --stdin-json is set.
+fn resolve_command_spec(cli: EntryPoint, reader: impl Read) -> anyhow::Result<Vec<EntryPoint>> {
+    if !cli.stdin_json {
+        return Ok(vec![cli]);
+    }
+
+    if cli.command.is_some() {
+        anyhow::bail!("--stdin-json can't be combined with a command");
+    }
+
+    let clis = CommandSpec::from_reader(reader)?
+        .iter()
+        .map(|spec| {
+            let cli = EntryPoint::try_parse_from(spec.to_args()?)?;
+            if cli.stdin_json {
+                anyhow::bail!("A json command spec can't enable --stdin-json");
+            }
+
+            Ok(cli)
+        })
+        .collect::<anyhow::Result<Vec<_>>>()?;
+
+    if let [first, _, ..] = clis.as_slice() {
+        if !matches!(first.out, OutputFormat::Console | OutputFormat::Table) {
+            anyhow::bail!("A json command batch can only print its results to the terminal");
+        }
+
+        if clis
+            .iter()
+            .any(|cli| cli.command.as_ref().is_some_and(Command::is_long_running))
+        {
+            anyhow::bail!("The follow and watch commands can't run in a json command batch");
         }
     }
 
+    Ok(clis)
+}
+
+pub fn run() -> ExitCode {
+    let args = transaction::migrate_deprecated_hash_arg(std::env::args_os());
+
+    let cli = match EntryPoint::try_parse_from(&args) {
+        Ok(cli) => cli,
+        Err(err) => {
+            let Some(cli_error) = json_usage_error(&err, &args) else {
+                err.exit();
+            };
+
+            if let Err(report_err) = serde_json::to_string(&cli_error)
+                .map_err(anyhow::Error::from)
+                .and_then(|content| Ok(stdio_output().fail(&content)?))
+            {
+                eprintln!("Error: {report_err:?}");
+            }
+
+            return ExitCode::from(USAGE_ERROR_EXIT_CODE);
+        }
+    };
+    let mut format = cli.out.clone();
+
+    let clis = resolve_command_spec(cli, io::stdin().lock());
+
+    if let Some(cli) = clis.as_ref().ok().and_then(|clis| clis.first()) {
+        format = cli.out.clone();
+    }
+
+    let Err(err) = clis.and_then(|clis| run_with_output(clis, stdio_output())) else {
+        return ExitCode::SUCCESS;
+    };
+
+    if report_error(&err, &format, stdio_output()).is_err() {
+        eprintln!("Error: {err:?}");
+    }
+
+    ExitCode::FAILURE
+}
+
+/// Runs the commands in turn, sharing the execution context, and so the node connection and the
+/// caches, between the ones that need a node.
+fn run_with_output<O: Write, E: Write>(
+    clis: Vec<EntryPoint>,
+    output: &OutputCoordinator<O, E>,
+) -> Result<(), anyhow::Error> {
+    let mut execution_context = None;
+
+    for cli in clis {
+        run_command(cli, &mut execution_context, output)?;
+    }
+
     Ok(())
 }
 
-pub fn run() -> Result<(), anyhow::Error> {
-    let cli = EntryPoint::parse();
+fn create_execution_context(
+    cli: &EntryPoint,
+    command: &Command,
+) -> anyhow::Result<CommandExecutionContext> {
+    let config_overrides = ConfigOverrides::new(
+        cli.priv_key.clone(),
+        cli.rpc_url.clone(),
+        cli.config_file.clone(),
+    )
+    .with_keystore(cli.keystore.clone())
+    .with_keystore_password(cli.keystore_password.clone())
+    .with_mnemonic(cli.mnemonic.clone())
+    .with_mnemonic_index(cli.mnemonic_index)
+    .with_ignore_tls_errors(cli.ignore_tls_errors)
+    .with_max_block_range(cli.max_block_range)
+    .with_threads(cli.threads.map(|threads| threads as usize))
+    .with_command_timeout(cli.command_timeout)
+    .with_timeout_secs(cli.timeout)
+    .with_max_retries(cli.retries);
 
-    let config_overrides = ConfigOverrides::new(cli.priv_key, cli.rpc_url, cli.config_file);
+    let mut config = get_config(config_overrides)?;
 
-    let config = get_config(config_overrides)?;
+    // The keystore and its password can come from the environment or the config file too
+    if config.is_missing_keystore_password() {
+        let password = rpassword::prompt_password_stderr("Keystore password: ")?;
+
+        config = config.with_keystore_password(password);
+    }
+
+    let execution_context = if command.is_long_running() {
+        CommandExecutionContext::new_long_running(config)?
+    } else {
+        CommandExecutionContext::new(config)?
+    }
+    .with_stream_output(StreamOutput::new(cli.out.clone(), cli.file.clone()))
+    .with_fee_history_cache(cli.fee_history_cache);
 
-    let execution_context = CommandExecutionContext::new(config)?;
+    if let Some(expected_chain_id) = cli.assert_chain_id {
+        assert_chain_id(&execution_context, expected_chain_id)?;
+    }
+
+    Ok(execution_context)
+}
+
+fn run_command<O: Write, E: Write>(
+    mut cli: EntryPoint,
+    execution_context: &mut Option<CommandExecutionContext>,
+    output: &OutputCoordinator<O, E>,
+) -> Result<(), anyhow::Error> {
+    let Some(command) = cli.command.take() else {
+        anyhow::bail!(
+            "Missing command. Provide a command or a json command spec with --stdin-json"
+        );
+    };
+
+    if cli.no_pretty
+        && matches!(
+            cli.out,
+            OutputFormat::Yaml | OutputFormat::Csv | OutputFormat::Table
+        )
+    {
+        let format = cli
+            .out
+            .to_possible_value()
+            .map(|value| value.get_name().to_owned())
+            .unwrap_or_default();
+
+        anyhow::bail!(
+            "--no-pretty only applies to the json printed results, it can't be combined with the {format} output"
+        );
+    }
 
-    let res = match cli.command {
-        Command::Block(cmd) => block::parse(&execution_context, cmd).map(CliResult::BlockNamespace),
+    // Pure conversions don't connect to the node so that they work offline
+    if let Command::Utils(cmd) = &command {
+        if cmd.is_offline() {
+            let res = utils::parse_offline(cmd).map(CliResult::UtilsNamespace)?;
+
+            return format_output(
+                res,
+                cli.out,
+                cli.file,
+                cli.raw_output,
+                cli.no_pretty,
+                output,
+            );
+        }
+    }
+
+    if let Command::Transaction(cmd) = &command {
+        if cmd.is_offline() {
+            let res = transaction::parse_offline(cmd).map(CliResult::TransactionNamespace)?;
+
+            return format_output(
+                res,
+                cli.out,
+                cli.file,
+                cli.raw_output,
+                cli.no_pretty,
+                output,
+            );
+        }
+    }
+
+    let execution_context = match execution_context {
+        Some(execution_context) => execution_context,
+        None => execution_context.insert(create_execution_context(&cli, &command)?),
+    };
+
+    let res = match command {
+        Command::Block(cmd) => block::parse(execution_context, cmd).map(CliResult::BlockNamespace),
         Command::Account(cmd) => {
-            account::parse(&execution_context, cmd).map(CliResult::AccountNamespace)
+            account::parse(execution_context, cmd).map(CliResult::AccountNamespace)
         }
         Command::Transaction(cmd) => {
-            transaction::parse(&execution_context, cmd).map(CliResult::TransactionNamespace)
+            transaction::parse(execution_context, cmd).map(CliResult::TransactionNamespace)
         }
-        Command::Event(_) => todo!(),
-        Command::Gas(cmd) => gas::parse(&execution_context, cmd).map(CliResult::GasNamespace),
-        Command::Utils(cmd) => utils::parse(&execution_context, cmd).map(CliResult::UtilsNamespace),
+        Command::Event(cmd) => event::parse(execution_context, cmd).map(CliResult::EventNamespace),
+        Command::Gas(cmd) => gas::parse(execution_context, cmd).map(CliResult::GasNamespace),
+        Command::Trace(cmd) => trace::parse(execution_context, cmd).map(CliResult::TraceNamespace),
+        Command::Utils(cmd) => utils::parse(execution_context, cmd).map(CliResult::UtilsNamespace),
     }?;
 
-    format_output(res, cli.out, cli.file)
+    if res.is_streamed() {
+        return Ok(());
+    }
+
+    format_output(
+        res,
+        cli.out,
+        cli.file,
+        cli.raw_output,
+        cli.no_pretty,
+        output,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        collections::HashMap,
+        ffi::OsString,
+        io::Write,
+        sync::{atomic::Ordering, Arc, Mutex},
+        thread,
+        time::Duration,
+    };
+
+    use clap::{Parser, ValueEnum};
+    use serde_json::json;
+
+    use ethers::{
+        providers::Middleware,
+        types::{Bytes, FeeHistory, Filter, TransactionRequest, H256, U256},
+        utils::{to_checksum, Anvil},
+    };
+
+    use crate::{
+        cli::{
+            account::AccountNamespaceResult, event::EventNamespaceResult,
+            transaction::TransactionNamespaceResult,
+        },
+        cmd::event::get_logs,
+        cmd::gas::{FEE_SUGGESTION_BLOCK_COUNT, FEE_SUGGESTION_PERCENTILES},
+        cmd::helpers::test::{
+            deploy_contract_helper, setup_test, spawn_counting_mock_rpc_server,
+            spawn_mock_rpc_server,
+        },
+        cmd::transaction::SendTxResult,
+        config::{get_config, ConfigOverrides},
+        context::CommandExecutionContext,
+    };
+
+    use super::{
+        assert_chain_id, format_output, json_usage_error, output_path, report_error,
+        resolve_command_spec, run_with_output, CliResult, CsvOutput, CsvTable, EntryPoint,
+        OutputCoordinator, OutputFormat, StreamOutput,
+    };
+
+    const ANVIL_CHAIN_ID: u64 = 31337;
+
+    fn setup_execution_context() -> anyhow::Result<CommandExecutionContext> {
+        let (url, _) = spawn_mock_rpc_server(json!(format!("{ANVIL_CHAIN_ID:#x}")), Duration::ZERO);
+
+        let config = get_config(ConfigOverrides::new(None, Some(url), None))?;
+
+        Ok(CommandExecutionContext::new(config)?)
+    }
+
+    #[test]
+    fn should_proceed_if_the_chain_id_matches() -> anyhow::Result<()> {
+        // Arrange
+        let execution_context = setup_execution_context()?;
+
+        // Act
+        let res = assert_chain_id(&execution_context, ANVIL_CHAIN_ID);
+
+        // Assert
+        assert!(res.is_ok());
+
+        Ok(())
+    }
+
+    #[test]
+    fn should_abort_if_the_chain_id_does_not_match() -> anyhow::Result<()> {
+        // Arrange
+        let execution_context = setup_execution_context()?;
+
+        // Act
+        let res = assert_chain_id(&execution_context, 1);
+
+        // Assert
+        assert!(res.is_err());
+        assert!(res.unwrap_err().to_string().contains("Chain id mismatch"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn should_run_the_utils_namespace_end_to_end() -> anyhow::Result<()> {
+        // Arrange
+        let anvil = Anvil::new().spawn();
+        let cli = EntryPoint::try_parse_from([
+            "yaeth",
+            "--rpc-url",
+            &anvil.endpoint(),
+            "--raw-output",
+            "utils",
+            "chain-id",
+        ])?;
+
+        let buffer = SharedBuffer::default();
+        let output = OutputCoordinator::new(buffer.clone(), buffer.clone());
+
+        // Act
+        let res = run_with_output(vec![cli], &output);
+
+        // Assert
+        assert!(res.is_ok());
+        assert_eq!(buffer.lines(), [format!("{ANVIL_CHAIN_ID:#x}")]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn should_only_treat_the_follow_and_watch_commands_as_long_running() -> anyhow::Result<()> {
+        // Setup
+        let test_cases = [
+            (vec!["block", "watch"], true),
+            (vec!["event", "logs", "--follow"], true),
+            (vec!["event", "new-blocks", "--follow"], true),
+            (vec!["event", "pending-txs", "--follow"], true),
+            (vec!["event", "logs"], false),
+            (vec!["event", "new-blocks"], false),
+            (vec!["block", "number"], false),
+            (vec!["utils", "chain-id"], false),
+        ];
+
+        for (args, expected) in test_cases {
+            // Arrange
+            let cli = EntryPoint::try_parse_from(["yaeth"].into_iter().chain(args.clone()))?;
+
+            // Act
+            let res = cli.command.map(|command| command.is_long_running());
+
+            // Assert
+            assert_eq!(res, Some(expected), "{args:?}");
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn should_run_the_command_of_a_json_spec() -> anyhow::Result<()> {
+        // Arrange
+        let (url, _) = spawn_mock_rpc_server(json!("0x10"), Duration::ZERO);
+        let spec = json!({
+            "options": {"rpc-url": url, "raw-output": true},
+            "namespace": "block",
+            "subcommand": "number"
+        })
+        .to_string();
+
+        let clis = resolve_command_spec(
+            EntryPoint::try_parse_from(["yaeth", "--stdin-json"])?,
+            spec.as_bytes(),
+        )?;
+
+        let buffer = SharedBuffer::default();
+        let output = OutputCoordinator::new(buffer.clone(), buffer.clone());
+
+        // Act
+        let res = run_with_output(clis, &output);
+
+        // Assert
+        assert!(res.is_ok());
+        assert_eq!(buffer.lines(), ["0x10"]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn should_share_the_fee_history_between_the_commands_of_a_json_batch() -> anyhow::Result<()> {
+        // Arrange
+        let fee_history = FeeHistory {
+            base_fee_per_gas: vec![U256::exp10(9); FEE_SUGGESTION_BLOCK_COUNT as usize + 1],
+            gas_used_ratio: vec![0.5; FEE_SUGGESTION_BLOCK_COUNT as usize],
+            oldest_block: U256::one(),
+            reward: vec![
+                vec![U256::from(100); FEE_SUGGESTION_PERCENTILES.len()];
+                FEE_SUGGESTION_BLOCK_COUNT as usize
+            ],
+        };
+        let (url, requests) = spawn_counting_mock_rpc_server(serde_json::to_value(fee_history)?);
+
+        let options = json!({"rpc-url": url, "no-pretty": true, "fee-history-cache": true});
+        let spec = json!([
+            {"options": options, "namespace": "gas", "subcommand": "average-price"},
+            {"options": options, "namespace": "gas", "subcommand": "suggest"},
+            {"options": options, "namespace": "gas", "subcommand": "average-price"}
+        ])
+        .to_string();
+
+        let clis = resolve_command_spec(
+            EntryPoint::try_parse_from(["yaeth", "--stdin-json"])?,
+            spec.as_bytes(),
+        )?;
+
+        let buffer = SharedBuffer::default();
+        let output = OutputCoordinator::new(buffer.clone(), buffer.clone());
+
+        // Act
+        let res = run_with_output(clis, &output);
+
+        // Assert
+        assert!(res.is_ok());
+        assert_eq!(buffer.lines().len(), 3);
+        assert_eq!(
+            buffer.lines()[0],
+            json!({ "averagePrice": U256::exp10(9) + 100 }).to_string()
+        );
+        assert_eq!(requests.load(Ordering::SeqCst), 1);
+
+        Ok(())
+    }
+
+    #[test]
+    fn should_reject_invalid_stdin_json_invocations() -> anyhow::Result<()> {
+        // Setup
+        let test_cases = [
+            (
+                vec!["yaeth", "--stdin-json", "utils", "chain-id"],
+                r#"{"namespace": "block", "subcommand": "number"}"#,
+            ),
+            (
+                vec!["yaeth", "--stdin-json"],
+                r#"{"options": {"stdin-json": true}, "namespace": "block", "subcommand": "number"}"#,
+            ),
+            (
+                vec!["yaeth", "--stdin-json"],
+                r#"{"namespace": "block", "subcommand": "unknown"}"#,
+            ),
+            (
+                vec!["yaeth", "--stdin-json"],
+                r#"[
+                    {"options": {"out": "json"}, "namespace": "block", "subcommand": "number"},
+                    {"options": {"out": "json"}, "namespace": "gas", "subcommand": "price"}
+                ]"#,
+            ),
+            (
+                vec!["yaeth", "--stdin-json"],
+                r#"[
+                    {"namespace": "block", "subcommand": "number"},
+                    {"namespace": "block", "subcommand": "watch"}
+                ]"#,
+            ),
+        ];
+
+        for (args, spec) in test_cases {
+            // Act
+            let res = resolve_command_spec(EntryPoint::try_parse_from(&args)?, spec.as_bytes());
+
+            // Assert
+            assert!(res.is_err(), "{args:?} {spec}");
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn should_recognize_the_clipboard_output_format() {
+        // Act
+        let res = OutputFormat::from_str("clipboard", false);
+
+        // Assert
+        assert!(matches!(res, Ok(OutputFormat::Clipboard)));
+    }
+
+    #[cfg(not(feature = "clipboard"))]
+    #[test]
+    fn should_not_support_the_clipboard_output_without_the_feature() {
+        // Arrange
+        let output = OutputCoordinator::new(Vec::new(), Vec::new());
+
+        // Act
+        let res = format_output(
+            json!("some result"),
+            OutputFormat::Clipboard,
+            "out".into(),
+            false,
+            false,
+            &output,
+        );
+
+        // Assert
+        assert!(res.is_err());
+        assert!(res.unwrap_err().to_string().contains("not supported"));
+    }
+
+    /// Writer shared by the output and error streams to observe the order of the writes
+    #[derive(Clone, Default)]
+    struct SharedBuffer(Arc<Mutex<Vec<u8>>>);
+
+    impl Write for SharedBuffer {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            // Write byte by byte to maximize the chances of interleaving
+            let mut inner = self.0.lock().unwrap();
+            inner.push(buf[0]);
+            drop(inner);
+
+            thread::yield_now();
+
+            Ok(1)
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    impl SharedBuffer {
+        fn lines(&self) -> Vec<String> {
+            String::from_utf8(self.0.lock().unwrap().clone())
+                .unwrap()
+                .lines()
+                .map(String::from)
+                .collect()
+        }
+    }
+
+    #[test]
+    fn should_report_errors_as_json_if_the_json_output_is_selected() -> anyhow::Result<()> {
+        // Arrange
+        let err = anyhow::anyhow!("connection refused").context("Failed to get the balance");
+
+        let out = SharedBuffer::default();
+        let err_out = SharedBuffer::default();
+        let output = OutputCoordinator::new(out.clone(), err_out.clone());
+
+        // Act
+        let res = report_error(&err, &OutputFormat::Json, &output);
+
+        // Assert
+        assert!(res.is_ok());
+        assert!(out.lines().is_empty());
+
+        let lines = err_out.lines();
+        assert_eq!(lines.len(), 1);
+        assert_eq!(
+            serde_json::from_str::<serde_json::Value>(&lines[0])?,
+            json!({
+                "error": "Failed to get the balance",
+                "context": ["connection refused"]
+            })
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn should_report_the_invalid_invocations_as_json_if_the_json_output_is_selected(
+    ) -> anyhow::Result<()> {
+        // Setup
+        let test_cases: [&[&str]; 3] = [
+            &["yaeth", "-o", "json", "block", "number", "--unknown"],
+            &["yaeth", "--out=json", "block", "number", "--unknown"],
+            &["yaeth", "-ojson", "block", "number", "--unknown"],
+        ];
+
+        for test_case in test_cases {
+            // Arrange
+            let args: Vec<OsString> = test_case.iter().map(Into::into).collect();
+            let err = EntryPoint::try_parse_from(&args).unwrap_err();
+
+            // Act
+            let res = json_usage_error(&err, &args);
+
+            // Assert
+            let res = res.unwrap();
+            assert_eq!(res.error, "unexpected argument '--unknown' found");
+            assert!(res.context.iter().any(|line| line.starts_with("Usage:")));
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn should_keep_the_usual_report_of_the_invalid_invocations_for_the_other_outputs() {
+        // Setup
+        let test_cases: [&[&str]; 4] = [
+            &["yaeth", "block", "number", "--unknown"],
+            &["yaeth", "-o", "yaml", "block", "number", "--unknown"],
+            &["yaeth", "-o", "json", "--help"],
+            &["yaeth", "-o", "json", "--version"],
+        ];
+
+        for test_case in test_cases {
+            // Arrange
+            let args: Vec<OsString> = test_case.iter().map(Into::into).collect();
+            let err = EntryPoint::try_parse_from(&args).unwrap_err();
+
+            // Act
+            let res = json_usage_error(&err, &args);
+
+            // Assert
+            assert!(res.is_none(), "{test_case:?}");
+        }
+    }
+
+    #[test]
+    fn should_keep_the_usual_error_report_for_the_other_outputs() {
+        // Arrange
+        let err = anyhow::anyhow!("connection refused").context("Failed to get the balance");
+
+        let err_out = SharedBuffer::default();
+        let output = OutputCoordinator::new(SharedBuffer::default(), err_out.clone());
+
+        // Act
+        let res = report_error(&err, &OutputFormat::Console, &output);
+
+        // Assert
+        assert!(res.is_ok());
+        assert_eq!(err_out.lines()[0], "Error: Failed to get the balance");
+        assert!(err_out
+            .lines()
+            .contains(&"    connection refused".to_string()));
+    }
+
+    #[test]
+    fn should_write_the_final_result_after_the_progress_lines() -> anyhow::Result<()> {
+        // Arrange
+        let buffer = SharedBuffer::default();
+        let output = OutputCoordinator::new(buffer.clone(), buffer.clone());
+
+        output.progress("fetched 1/2")?;
+        output.progress("fetched 2/2")?;
+
+        // Act
+        format_output(
+            json!({ "count": "0x2" }),
+            OutputFormat::Console,
+            "out".into(),
+            false,
+            false,
+            &output,
+        )?;
+
+        // Assert
+        assert_eq!(
+            buffer.lines(),
+            [
+                "fetched 1/2",
+                "fetched 2/2",
+                "{",
+                "  \"count\": \"0x2\"",
+                "}"
+            ]
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn should_write_the_result_to_a_yaml_file() -> anyhow::Result<()> {
+        // Arrange
+        let output_file = std::env::temp_dir()
+            .join(format!("yaeth-output-yaml-{}", std::process::id()))
+            .display()
+            .to_string();
+        let buffer = SharedBuffer::default();
+        let output = OutputCoordinator::new(buffer.clone(), buffer.clone());
+
+        let res = CliResult::AccountNamespace(AccountNamespaceResult::Bytecode(Bytes::from(vec![
+            0x60, 0x00,
+        ])));
+
+        // Act
+        format_output(
+            res,
+            OutputFormat::Yaml,
+            output_file.clone(),
+            false,
+            false,
+            &output,
+        )?;
+
+        // Assert
+        let path = format!("{output_file}.yaml");
+        let content = std::fs::read_to_string(&path)?;
+        std::fs::remove_file(path)?;
+
+        assert_eq!(content, "bytecode: '0x6000'\n");
+        assert_eq!(
+            serde_yaml::from_str::<serde_json::Value>(&content)?,
+            json!({ "bytecode": "0x6000" })
+        );
+        assert_eq!(buffer.lines(), ["Ok"]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn should_write_the_result_to_the_provided_path() -> anyhow::Result<()> {
+        // Arrange
+        let dir = std::env::temp_dir().join(format!("yaeth-output-dir-{}", std::process::id()));
+        let path = dir.join("nested").join("block.json");
+        let buffer = SharedBuffer::default();
+        let output = OutputCoordinator::new(buffer.clone(), buffer.clone());
+
+        // Act
+        format_output(
+            json!({ "number": "0x1" }),
+            OutputFormat::Json,
+            path.display().to_string(),
+            false,
+            true,
+            &output,
+        )?;
+
+        // Assert
+        let content = std::fs::read_to_string(&path)?;
+        std::fs::remove_dir_all(dir)?;
+
+        assert_eq!(content, r#"{"number":"0x1"}"#);
+
+        Ok(())
+    }
+
+    #[test]
+    fn should_resolve_the_output_path_from_the_format() -> anyhow::Result<()> {
+        // Setup
+        let test_cases = [
+            ("out", OutputFormat::Json, Some("out.json")),
+            ("out", OutputFormat::Csv, Some("out.csv")),
+            ("block.json", OutputFormat::Json, Some("block.json")),
+            ("block.YML", OutputFormat::Yaml, Some("block.YML")),
+            ("block.v2", OutputFormat::Yaml, Some("block.v2.yaml")),
+            ("block.yaml", OutputFormat::Json, None),
+            ("block.json", OutputFormat::Csv, None),
+        ];
+
+        for (output_file, format, expected_res) in test_cases {
+            // Act
+            let res = output_path(output_file, &format);
+
+            // Assert
+            match expected_res {
+                Some(expected_res) => assert_eq!(res?.display().to_string(), expected_res),
+                None => assert!(res.is_err(), "{output_file}"),
+            }
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn should_output_an_empty_log_set_as_an_empty_array() -> anyhow::Result<()> {
+        // Arrange
+        let buffer = SharedBuffer::default();
+        let output = OutputCoordinator::new(buffer.clone(), buffer.clone());
+
+        let res = CliResult::EventNamespace(EventNamespaceResult::Logs(vec![]));
+
+        // Act
+        format_output(
+            res,
+            OutputFormat::Console,
+            "out".into(),
+            false,
+            false,
+            &output,
+        )?;
+
+        // Assert
+        let output: serde_json::Value = serde_json::from_str(&buffer.lines().join("\n"))?;
+        assert_eq!(output, json!({ "logs": [] }));
+
+        Ok(())
+    }
+
+    // Fixture contract that emits an empty log on every call:
+    // constructor: PUSH1 0x06, DUP1, PUSH1 0x0b, PUSH1 0, CODECOPY, PUSH1 0, RETURN
+    // runtime: PUSH1 0, PUSH1 0, LOG0, STOP
+    fn emitter_init_code() -> Bytes {
+        Bytes::from(vec![
+            0x60, 0x06, 0x80, 0x60, 0x0b, 0x60, 0x00, 0x39, 0x60, 0x00, 0xf3, 0x60, 0x00, 0x60,
+            0x00, 0xa0, 0x00,
+        ])
+    }
+
+    #[tokio::test]
+    async fn should_write_one_csv_row_per_log() -> anyhow::Result<()> {
+        // Arrange
+        let (node_provider, anvil) = setup_test().await?;
+
+        let sender = *anvil.addresses().first().unwrap();
+        let emitter = deploy_contract_helper(&node_provider, sender, emitter_init_code()).await?;
+
+        for _ in 0..2 {
+            let tx = TransactionRequest::new().from(sender).to(emitter);
+            node_provider.send_transaction(tx, None).await?.await?;
+        }
+
+        let filter = Filter::new().address(emitter).from_block(0);
+        let logs = get_logs(&node_provider, filter, 10_000, None).await?;
+        let res = CliResult::EventNamespace(EventNamespaceResult::Logs(logs));
+
+        let output_file = std::env::temp_dir()
+            .join(format!("yaeth-output-csv-{}", std::process::id()))
+            .display()
+            .to_string();
+        let output = OutputCoordinator::new(Vec::new(), Vec::new());
+
+        // Act
+        format_output(
+            res,
+            OutputFormat::Csv,
+            output_file.clone(),
+            false,
+            false,
+            &output,
+        )?;
+
+        // Assert
+        let path = format!("{output_file}.csv");
+        let content = std::fs::read_to_string(&path)?;
+        std::fs::remove_file(path)?;
+
+        let rows: Vec<Vec<&str>> = content
+            .lines()
+            .map(|line| line.split(',').collect())
+            .collect();
+        assert_eq!(rows.len(), 3);
+        assert_eq!(rows[0][0], "block_number");
+        assert!(rows.iter().all(|row| row.len() == 10));
+        assert!(rows[1..]
+            .iter()
+            .all(|row| row[4] == to_checksum(&emitter, None)));
+
+        Ok(())
+    }
+
+    #[test]
+    fn should_write_a_single_csv_row_of_the_scalar_fields() {
+        // Arrange
+        let res = json!({
+            "block": {
+                "number": "0x1",
+                "extraData": "a,\"b\"",
+                "transactions": ["0xab"]
+            }
+        });
+
+        // Act
+        let table = res.csv_table().unwrap();
+
+        // Assert
+        assert_eq!(table.to_string(), "extraData,number\n\"a,\"\"b\"\"\",0x1\n");
+    }
+
+    #[test]
+    fn should_write_one_table_row_per_item() -> anyhow::Result<()> {
+        // Arrange
+        let buffer = SharedBuffer::default();
+        let output = OutputCoordinator::new(buffer.clone(), buffer.clone());
+
+        let res = json!({
+            "accounts": [
+                { "address": "0x01", "balance": "0x0" },
+                { "address": "0x02", "balance": "0x1" }
+            ]
+        });
+
+        // Act
+        format_output(
+            res,
+            OutputFormat::Table,
+            "out".into(),
+            false,
+            false,
+            &output,
+        )?;
+
+        // Assert
+        let lines = buffer.lines();
+        assert!(lines
+            .iter()
+            .any(|line| line.contains("address") && line.contains("balance")));
+        assert!(lines
+            .iter()
+            .any(|line| line.contains("0x01") && line.contains("0x0")));
+        assert!(lines
+            .iter()
+            .any(|line| line.contains("0x02") && line.contains("0x1")));
+
+        Ok(())
+    }
+
+    #[test]
+    fn should_write_a_scalar_result_as_key_value_table_rows() {
+        // Arrange
+        let res = json!({ "block": { "number": "0x1", "hash": "0xab" } });
+
+        // Act
+        let table = res.csv_table().unwrap().to_text_table();
+
+        // Assert
+        let lines: Vec<&str> = table.lines().collect();
+        assert!(lines
+            .iter()
+            .any(|line| line.contains("field") && line.contains("value")));
+        assert!(lines
+            .iter()
+            .any(|line| line.contains("hash") && line.contains("0xab")));
+        assert!(lines
+            .iter()
+            .any(|line| line.contains("number") && line.contains("0x1")));
+    }
+
+    #[test]
+    fn should_not_stream_as_a_table() {
+        // Arrange
+        let output = OutputCoordinator::new(Vec::new(), Vec::new());
+        let stream_output = StreamOutput::new(OutputFormat::Table, "out".into());
+
+        // Act
+        let res = stream_output.write_to(&json!({ "number": "0x1" }), &output);
+
+        // Assert
+        assert!(res.is_err());
+    }
+
+    #[test]
+    fn should_output_the_raw_bytecode_without_quotes() -> anyhow::Result<()> {
+        // Arrange
+        let buffer = SharedBuffer::default();
+        let output = OutputCoordinator::new(buffer.clone(), buffer.clone());
+
+        let res = CliResult::AccountNamespace(AccountNamespaceResult::Bytecode(Bytes::from(vec![
+            0x60, 0x80,
+        ])));
+
+        // Act
+        format_output(
+            res,
+            OutputFormat::Console,
+            "out".into(),
+            true,
+            false,
+            &output,
+        )?;
+
+        // Assert
+        assert_eq!(buffer.lines(), ["0x6080"]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn should_output_the_raw_sent_transaction_hash_without_quotes() -> anyhow::Result<()> {
+        // Arrange
+        let buffer = SharedBuffer::default();
+        let output = OutputCoordinator::new(buffer.clone(), buffer.clone());
+
+        let hash = H256::repeat_byte(0xab);
+        let res = CliResult::TransactionNamespace(TransactionNamespaceResult::SentTransaction(
+            SendTxResult::PendingTransaction(hash),
+        ));
+
+        // Act
+        format_output(
+            res,
+            OutputFormat::Console,
+            "out".into(),
+            true,
+            false,
+            &output,
+        )?;
+
+        // Assert
+        assert_eq!(buffer.lines(), [format!("{hash:?}")]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn should_not_output_a_raw_result_with_multiple_values() {
+        // Arrange
+        let output = OutputCoordinator::new(Vec::new(), Vec::new());
+
+        let res = CliResult::EventNamespace(EventNamespaceResult::Logs(vec![]));
+
+        // Act
+        let res = format_output(
+            res,
+            OutputFormat::Console,
+            "out".into(),
+            true,
+            false,
+            &output,
+        );
+
+        // Assert
+        assert!(res.is_err());
+        assert!(res
+            .unwrap_err()
+            .to_string()
+            .contains("only supported by commands returning a single value"));
+    }
+
+    #[test]
+    fn should_print_the_same_result_identically_without_pretty_printing() -> anyhow::Result<()> {
+        // Setup
+        #[derive(serde::Serialize)]
+        struct Balances {
+            total: u64,
+            balances: HashMap<String, u64>,
+        }
+
+        impl CsvOutput for Balances {
+            fn csv_table(&self) -> anyhow::Result<CsvTable> {
+                serde_json::to_value(self)?.csv_table()
+            }
+        }
+
+        let balances = |order: &[u64]| Balances {
+            total: 6,
+            balances: order.iter().map(|idx| (format!("0x{idx}"), *idx)).collect(),
+        };
+
+        let mut outputs = vec![];
+        for order in [[1, 2, 3], [3, 1, 2], [2, 3, 1]] {
+            let buffer = SharedBuffer::default();
+            let output = OutputCoordinator::new(buffer.clone(), buffer.clone());
+
+            // Act
+            format_output(
+                balances(&order),
+                OutputFormat::Console,
+                "out".into(),
+                false,
+                true,
+                &output,
+            )?;
+
+            outputs.push(buffer.lines());
+        }
+
+        // Assert
+        let expected = [r#"{"balances":{"0x1":1,"0x2":2,"0x3":3},"total":6}"#];
+        assert!(outputs.iter().all(|output| output == &expected));
+
+        Ok(())
+    }
+
+    #[test]
+    fn should_not_combine_no_pretty_with_the_non_json_outputs() -> anyhow::Result<()> {
+        // Setup
+        let test_cases = ["yaml", "csv", "table"];
+
+        for test_case in test_cases {
+            // Arrange
+            let cli = EntryPoint::try_parse_from([
+                "yaeth",
+                "-o",
+                test_case,
+                "--no-pretty",
+                "utils",
+                "to-gwei",
+                "1",
+            ])?;
+
+            let output = OutputCoordinator::new(Vec::new(), Vec::new());
+
+            // Act
+            let res = run_with_output(vec![cli], &output);
+
+            // Assert
+            assert_eq!(
+                res.unwrap_err().to_string(),
+                format!("--no-pretty only applies to the json printed results, it can't be combined with the {test_case} output")
+            );
+            let (out, _) = output.into_inner();
+            assert!(out.is_empty());
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn should_write_the_final_result_as_a_contiguous_block() -> anyhow::Result<()> {
+        // Arrange
+        let buffer = SharedBuffer::default();
+        let output = Arc::new(OutputCoordinator::new(buffer.clone(), buffer.clone()));
+
+        let result = (0..20)
+            .map(|idx| format!("result line {idx}"))
+            .collect::<Vec<_>>();
+
+        let progress_threads: Vec<_> = (0..4)
+            .map(|thread_idx| {
+                let output = output.clone();
+
+                thread::spawn(move || {
+                    for idx in 0..20 {
+                        output
+                            .progress(&format!("progress {thread_idx} {idx}"))
+                            .unwrap();
+                    }
+                })
+            })
+            .collect();
+
+        // Act
+        output.finish(&result.join("\n"))?;
+
+        for progress_thread in progress_threads {
+            progress_thread.join().unwrap();
+        }
+
+        // Assert
+        let lines = buffer.lines();
+        let result_start = lines
+            .iter()
+            .position(|line| line == "result line 0")
+            .unwrap();
+
+        assert_eq!(lines[result_start..result_start + result.len()], result);
+        assert!(lines
+            .iter()
+            .all(|line| line.starts_with("progress ") || line.starts_with("result line ")));
+
+        Ok(())
+    }
+
+    #[test]
+    fn should_stream_one_json_line_per_item() -> anyhow::Result<()> {
+        // Arrange
+        let buffer = SharedBuffer::default();
+        let output = OutputCoordinator::new(buffer.clone(), buffer.clone());
+        let stream_output = StreamOutput::new(OutputFormat::Console, "out".into());
+
+        // Act
+        stream_output.write_to(&json!({ "number": "0x1" }), &output)?;
+        stream_output.write_to(&json!({ "number": "0x2" }), &output)?;
+
+        // Assert
+        assert_eq!(
+            buffer.lines(),
+            [r#"{"number":"0x1"}"#, r#"{"number":"0x2"}"#]
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn should_append_the_streamed_items_to_the_output_file() -> anyhow::Result<()> {
+        // Arrange
+        let output_file = std::env::temp_dir()
+            .join(format!("yaeth-stream-{}", std::process::id()))
+            .display()
+            .to_string();
+        let output = OutputCoordinator::new(Vec::new(), Vec::new());
+        let stream_output = StreamOutput::new(OutputFormat::Json, output_file.clone());
+
+        // Act
+        stream_output.write_to(&json!({ "number": "0x1" }), &output)?;
+        stream_output.write_to(&json!({ "number": "0x2" }), &output)?;
+
+        // Assert
+        let path = format!("{output_file}.json");
+        let content = std::fs::read_to_string(&path)?;
+        std::fs::remove_file(path)?;
+
+        assert_eq!(content, "{\"number\":\"0x1\"}\n{\"number\":\"0x2\"}\n");
+
+        Ok(())
+    }
+
+    #[test]
+    fn should_append_the_streamed_items_as_yaml_documents() -> anyhow::Result<()> {
+        // Arrange
+        let output_file = std::env::temp_dir()
+            .join(format!("yaeth-stream-yaml-{}", std::process::id()))
+            .display()
+            .to_string();
+        let output = OutputCoordinator::new(Vec::new(), Vec::new());
+        let stream_output = StreamOutput::new(OutputFormat::Yaml, output_file.clone());
+
+        // Act
+        stream_output.write_to(&json!({ "number": "0x1" }), &output)?;
+        stream_output.write_to(&json!({ "number": "0x2" }), &output)?;
+
+        // Assert
+        let path = format!("{output_file}.yaml");
+        let content = std::fs::read_to_string(&path)?;
+        std::fs::remove_file(path)?;
+
+        assert_eq!(content, "---\nnumber: '0x1'\n---\nnumber: '0x2'\n");
+
+        Ok(())
+    }
+
+    #[test]
+    fn should_not_stream_to_the_clipboard() {
+        // Arrange
+        let output = OutputCoordinator::new(Vec::new(), Vec::new());
+        let stream_output = StreamOutput::new(OutputFormat::Clipboard, "out".into());
+
+        // Act
+        let res = stream_output.write_to(&json!({ "number": "0x1" }), &output);
+
+        // Assert
+        assert!(res.is_err());
+    }
 }